@@ -10,6 +10,8 @@ use crate::protocol::v1;
 use crate::protocol::v2;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::CommandPattern;
+use codex_protocol::protocol::ExecPolicyMatch;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::SandboxCommandAssessment;
@@ -143,6 +145,34 @@ client_request_definitions! {
         response: v2::GetAccountResponse,
     },
 
+    #[serde(rename = "playbook/list")]
+    #[ts(rename = "playbook/list")]
+    ListPlaybook {
+        params: v2::ListPlaybookParams,
+        response: v2::ListPlaybookResponse,
+    },
+
+    #[serde(rename = "playbook/search")]
+    #[ts(rename = "playbook/search")]
+    SearchPlaybook {
+        params: v2::SearchPlaybookParams,
+        response: v2::SearchPlaybookResponse,
+    },
+
+    #[serde(rename = "playbook/update")]
+    #[ts(rename = "playbook/update")]
+    UpdatePlaybookBullet {
+        params: v2::UpdatePlaybookBulletParams,
+        response: v2::UpdatePlaybookBulletResponse,
+    },
+
+    #[serde(rename = "playbook/delete")]
+    #[ts(rename = "playbook/delete")]
+    DeletePlaybookBullet {
+        params: v2::DeletePlaybookBulletParams,
+        response: v2::DeletePlaybookBulletResponse,
+    },
+
     /// DEPRECATED APIs below
     Initialize {
         params: v1::InitializeParams,
@@ -430,6 +460,13 @@ pub struct ExecCommandApprovalParams {
     pub cwd: PathBuf,
     pub reason: Option<String>,
     pub risk: Option<SandboxCommandAssessment>,
+    /// Exec policy rule that matched the command, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_match: Option<ExecPolicyMatch>,
+    /// Pattern remembered for this project if the client answers
+    /// `approved_for_project`. Absent when that answer is not offered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_approval_pattern: Option<CommandPattern>,
     pub parsed_cmd: Vec<ParsedCommand>,
 }
 
@@ -480,6 +517,7 @@ server_notification_definitions! {
     McpToolCallProgress => "item/mcpToolCall/progress" (v2::McpToolCallProgressNotification),
     AccountUpdated => "account/updated" (v2::AccountUpdatedNotification),
     AccountRateLimitsUpdated => "account/rateLimits/updated" (v2::AccountRateLimitsUpdatedNotification),
+    PlaybookUpdated => "playbook/updated" (v2::PlaybookUpdatedNotification),
 
     /// DEPRECATED NOTIFICATIONS below
     AuthStatusChange(v1::AuthStatusChangeNotification),
@@ -585,6 +623,8 @@ mod tests {
             cwd: PathBuf::from("/tmp"),
             reason: Some("because tests".to_string()),
             risk: None,
+            policy_match: None,
+            project_approval_pattern: None,
             parsed_cmd: vec![ParsedCommand::Unknown {
                 cmd: "echo hello".to_string(),
             }],
@@ -739,6 +779,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn serialize_playbook_delete() -> Result<()> {
+        let request = ClientRequest::DeletePlaybookBullet {
+            request_id: RequestId::Integer(7),
+            params: v2::DeletePlaybookBulletParams {
+                id: "bullet-1".to_string(),
+                archive: true,
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "playbook/delete",
+                "id": 7,
+                "params": {
+                    "id": "bullet-1",
+                    "archive": true
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
     #[test]
     fn serialize_list_models() -> Result<()> {
         let request = ClientRequest::ListModels {
@@ -306,6 +306,8 @@ pub struct SandboxSettings {
     #[serde(default)]
     pub writable_roots: Vec<PathBuf>,
     pub network_access: Option<bool>,
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
     pub exclude_tmpdir_env_var: Option<bool>,
     pub exclude_slash_tmp: Option<bool>,
 }
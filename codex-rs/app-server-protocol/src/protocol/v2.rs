@@ -138,6 +138,103 @@ pub struct UploadFeedbackResponse {
     pub thread_id: String,
 }
 
+/// A learned playbook entry (ACE bullet).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PlaybookBullet {
+    pub id: String,
+    /// Section title, e.g. "Tool Usage Tips".
+    pub section: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    /// Pinned entries are never decayed or archived by the optimizer.
+    pub pinned: bool,
+    /// Flagged by a user as wrong or harmful; excluded from retrieval.
+    pub flagged: bool,
+    pub confidence: f32,
+    pub success_count: u32,
+    pub failure_count: u32,
+    pub recall_count: u32,
+    /// RFC 3339 timestamp.
+    pub created_at: String,
+    /// RFC 3339 timestamp.
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ListPlaybookParams {
+    /// Only return entries in this section (matched by title).
+    pub section: Option<String>,
+    /// Optional page size; defaults to all remaining entries.
+    pub page_size: Option<usize>,
+    /// Opaque pagination cursor returned by a previous call.
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ListPlaybookResponse {
+    /// Grouped by section; pinned first, then most recently updated.
+    pub items: Vec<PlaybookBullet>,
+    /// Opaque cursor to pass to the next call to continue after the last item.
+    /// if None, there are no more items to return.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SearchPlaybookParams {
+    pub query: String,
+    /// Maximum number of results; defaults to 10.
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SearchPlaybookResponse {
+    /// Ranked by relevance.
+    pub items: Vec<PlaybookBullet>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct UpdatePlaybookBulletParams {
+    pub id: String,
+    /// New content; editing also clears a user flag.
+    pub content: Option<String>,
+    /// Pin or unpin; pinning also clears a user flag.
+    pub pinned: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct UpdatePlaybookBulletResponse {
+    pub bullet: PlaybookBullet,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct DeletePlaybookBulletParams {
+    pub id: String,
+    /// Move the entry to the archive directory instead of deleting it.
+    #[serde(default)]
+    pub archive: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct DeletePlaybookBulletResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -383,6 +480,19 @@ pub struct McpToolCallProgressNotification {
     pub message: String,
 }
 
+/// Sent after entries are added, updated or removed, either by learning in a
+/// conversation or through the `playbook/*` methods.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PlaybookUpdatedNotification {
+    /// Conversation whose turn changed the playbook; None for `playbook/*` calls.
+    pub conversation_id: Option<ConversationId>,
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
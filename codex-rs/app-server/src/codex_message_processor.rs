@@ -42,6 +42,7 @@ use codex_app_server_protocol::LoginChatGptCompleteNotification;
 use codex_app_server_protocol::LoginChatGptResponse;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::NewConversationResponse;
+use codex_app_server_protocol::PlaybookUpdatedNotification;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RemoveConversationSubscriptionResponse;
 use codex_app_server_protocol::RequestId;
@@ -88,6 +89,7 @@ use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::Op;
+use codex_core::protocol::PlaybookUpdatedEvent;
 use codex_core::protocol::ReviewDecision;
 use codex_core::read_head_for_summary;
 use codex_feedback::CodexFeedback;
@@ -292,6 +294,70 @@ impl CodexMessageProcessor {
             ClientRequest::UploadFeedback { request_id, params } => {
                 self.upload_feedback(request_id, params).await;
             }
+            #[cfg(feature = "ace")]
+            ClientRequest::ListPlaybook { request_id, params } => {
+                let result = crate::playbook::list_playbook(&self.config.codex_home, params).await;
+                self.send_result(request_id, result).await;
+            }
+            #[cfg(feature = "ace")]
+            ClientRequest::SearchPlaybook { request_id, params } => {
+                let result =
+                    crate::playbook::search_playbook(&self.config.codex_home, params).await;
+                self.send_result(request_id, result).await;
+            }
+            #[cfg(feature = "ace")]
+            ClientRequest::UpdatePlaybookBullet { request_id, params } => {
+                let result =
+                    crate::playbook::update_playbook_bullet(&self.config.codex_home, params).await;
+                self.send_playbook_change(request_id, result).await;
+            }
+            #[cfg(feature = "ace")]
+            ClientRequest::DeletePlaybookBullet { request_id, params } => {
+                let result =
+                    crate::playbook::delete_playbook_bullet(&self.config.codex_home, params).await;
+                self.send_playbook_change(request_id, result).await;
+            }
+            #[cfg(not(feature = "ace"))]
+            ClientRequest::ListPlaybook { request_id, .. }
+            | ClientRequest::SearchPlaybook { request_id, .. }
+            | ClientRequest::UpdatePlaybookBullet { request_id, .. }
+            | ClientRequest::DeletePlaybookBullet { request_id, .. } => {
+                self.send_unimplemented_error(request_id, "playbook").await;
+            }
+        }
+    }
+
+    #[cfg(feature = "ace")]
+    async fn send_result<T: serde::Serialize>(
+        &self,
+        request_id: RequestId,
+        result: std::result::Result<T, JSONRPCErrorError>,
+    ) {
+        match result {
+            Ok(response) => self.outgoing.send_response(request_id, response).await,
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    /// Replies to a `playbook/update` or `playbook/delete` request and, on
+    /// success, tells every client that the playbook changed.
+    #[cfg(feature = "ace")]
+    async fn send_playbook_change<T: serde::Serialize>(
+        &self,
+        request_id: RequestId,
+        result: std::result::Result<
+            (T, codex_app_server_protocol::PlaybookUpdatedNotification),
+            JSONRPCErrorError,
+        >,
+    ) {
+        match result {
+            Ok((response, notification)) => {
+                self.outgoing.send_response(request_id, response).await;
+                self.outgoing
+                    .send_server_notification(ServerNotification::PlaybookUpdated(notification))
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(request_id, error).await,
         }
     }
 
@@ -1745,6 +1811,8 @@ async fn apply_bespoke_event_handling(
             cwd,
             reason,
             risk,
+            policy_match,
+            project_approval_pattern,
             parsed_cmd,
         }) => {
             let params = ExecCommandApprovalParams {
@@ -1754,6 +1822,8 @@ async fn apply_bespoke_event_handling(
                 cwd,
                 reason,
                 risk,
+                policy_match,
+                project_approval_pattern,
                 parsed_cmd,
             };
             let rx = outgoing
@@ -1776,6 +1846,22 @@ async fn apply_bespoke_event_handling(
                     .await;
             }
         }
+        EventMsg::PlaybookUpdated(PlaybookUpdatedEvent {
+            added,
+            updated,
+            deleted,
+        }) => {
+            outgoing
+                .send_server_notification(ServerNotification::PlaybookUpdated(
+                    PlaybookUpdatedNotification {
+                        conversation_id: Some(conversation_id),
+                        added,
+                        updated,
+                        deleted,
+                    },
+                ))
+                .await;
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -34,6 +34,8 @@ mod fuzzy_file_search;
 mod message_processor;
 mod models;
 mod outgoing_message;
+#[cfg(feature = "ace")]
+mod playbook;
 
 /// Size of the bounded channels used to communicate between tasks. The value
 /// is a balance between throughput and memory usage – 128 messages should be
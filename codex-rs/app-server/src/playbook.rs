@@ -0,0 +1,178 @@
+//! `playbook/*` requests: browse and curate the personal ACE playbook.
+
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use codex_ace::BulletSection;
+use codex_app_server_protocol::DeletePlaybookBulletParams;
+use codex_app_server_protocol::DeletePlaybookBulletResponse;
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::ListPlaybookParams;
+use codex_app_server_protocol::ListPlaybookResponse;
+use codex_app_server_protocol::PlaybookBullet;
+use codex_app_server_protocol::PlaybookUpdatedNotification;
+use codex_app_server_protocol::SearchPlaybookParams;
+use codex_app_server_protocol::SearchPlaybookResponse;
+use codex_app_server_protocol::UpdatePlaybookBulletParams;
+use codex_app_server_protocol::UpdatePlaybookBulletResponse;
+use codex_core::ace::AceCliHandler;
+use codex_core::ace::BulletAction;
+use std::path::Path;
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+fn invalid_request(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+pub(crate) async fn list_playbook(
+    codex_home: &Path,
+    params: ListPlaybookParams,
+) -> Result<ListPlaybookResponse, JSONRPCErrorError> {
+    let ListPlaybookParams {
+        section,
+        page_size,
+        cursor,
+    } = params;
+    let section = match section.as_deref() {
+        Some(title) => Some(
+            BulletSection::from_title(title)
+                .ok_or_else(|| invalid_request(format!("unknown playbook section: {title}")))?,
+        ),
+        None => None,
+    };
+
+    let bullets = AceCliHandler::new(codex_home)
+        .list_bullets()
+        .await
+        .map_err(|err| internal_error(format!("failed to load playbook: {err}")))?;
+    let items: Vec<PlaybookBullet> = bullets
+        .iter()
+        .filter(|bullet| {
+            section
+                .as_ref()
+                .is_none_or(|section| &bullet.section == section)
+        })
+        .map(PlaybookBullet::from)
+        .collect();
+
+    let total = items.len();
+    let start = match cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .map_err(|_| invalid_request(format!("invalid cursor: {cursor}")))?,
+        None => 0,
+    };
+    if start > total {
+        return Err(invalid_request(format!(
+            "cursor {start} exceeds total entries {total}"
+        )));
+    }
+    let end = match page_size {
+        Some(page_size) => start.saturating_add(page_size.max(1)).min(total),
+        None => total,
+    };
+    let next_cursor = (end < total).then(|| end.to_string());
+
+    Ok(ListPlaybookResponse {
+        items: items[start..end].to_vec(),
+        next_cursor,
+    })
+}
+
+pub(crate) async fn search_playbook(
+    codex_home: &Path,
+    params: SearchPlaybookParams,
+) -> Result<SearchPlaybookResponse, JSONRPCErrorError> {
+    let SearchPlaybookParams { query, limit } = params;
+    let bullets = AceCliHandler::new(codex_home)
+        .search(&query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        .await
+        .map_err(|err| internal_error(format!("failed to search playbook: {err}")))?;
+    Ok(SearchPlaybookResponse {
+        items: bullets.iter().map(PlaybookBullet::from).collect(),
+    })
+}
+
+/// Applies the edit and returns the notification to broadcast alongside the response.
+pub(crate) async fn update_playbook_bullet(
+    codex_home: &Path,
+    params: UpdatePlaybookBulletParams,
+) -> Result<(UpdatePlaybookBulletResponse, PlaybookUpdatedNotification), JSONRPCErrorError> {
+    let UpdatePlaybookBulletParams {
+        id,
+        content,
+        pinned,
+    } = params;
+    let actions: Vec<BulletAction> = content
+        .map(BulletAction::Edit)
+        .into_iter()
+        .chain(pinned.map(BulletAction::SetPinned))
+        .collect();
+    if actions.is_empty() {
+        return Err(invalid_request(
+            "playbook/update requires `content` or `pinned`".to_string(),
+        ));
+    }
+
+    let handler = AceCliHandler::new(codex_home);
+    let mut updated = None;
+    for action in actions {
+        updated = handler
+            .apply_bullet_action(&id, action)
+            .await
+            .map_err(|err| invalid_request(format!("failed to update entry {id}: {err}")))?;
+    }
+    let bullet = updated
+        .as_ref()
+        .map(PlaybookBullet::from)
+        .ok_or_else(|| internal_error(format!("entry {id} disappeared during update")))?;
+
+    Ok((
+        UpdatePlaybookBulletResponse { bullet },
+        PlaybookUpdatedNotification {
+            conversation_id: None,
+            added: Vec::new(),
+            updated: vec![id],
+            deleted: Vec::new(),
+        },
+    ))
+}
+
+/// Deletes (or archives) the entry and returns the notification to broadcast.
+pub(crate) async fn delete_playbook_bullet(
+    codex_home: &Path,
+    params: DeletePlaybookBulletParams,
+) -> Result<(DeletePlaybookBulletResponse, PlaybookUpdatedNotification), JSONRPCErrorError> {
+    let DeletePlaybookBulletParams { id, archive } = params;
+    let action = if archive {
+        BulletAction::Archive
+    } else {
+        BulletAction::Delete
+    };
+    AceCliHandler::new(codex_home)
+        .apply_bullet_action(&id, action)
+        .await
+        .map_err(|err| invalid_request(format!("failed to delete entry {id}: {err}")))?;
+
+    Ok((
+        DeletePlaybookBulletResponse {},
+        PlaybookUpdatedNotification {
+            conversation_id: None,
+            added: Vec::new(),
+            updated: Vec::new(),
+            deleted: vec![id],
+        },
+    ))
+}
@@ -17,19 +17,23 @@ use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::CancelLoginChatGptParams;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientNotification;
+use codex_app_server_protocol::DeletePlaybookBulletParams;
 use codex_app_server_protocol::GetAuthStatusParams;
 use codex_app_server_protocol::InitializeParams;
 use codex_app_server_protocol::InterruptConversationParams;
 use codex_app_server_protocol::ListConversationsParams;
 use codex_app_server_protocol::ListModelsParams;
+use codex_app_server_protocol::ListPlaybookParams;
 use codex_app_server_protocol::LoginApiKeyParams;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::ResumeConversationParams;
+use codex_app_server_protocol::SearchPlaybookParams;
 use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
+use codex_app_server_protocol::UpdatePlaybookBulletParams;
 use codex_app_server_protocol::UploadFeedbackParams;
 
 use codex_app_server_protocol::JSONRPCError;
@@ -252,6 +256,42 @@ impl McpProcess {
         self.send_request("feedback/upload", params).await
     }
 
+    /// Send a `playbook/list` JSON-RPC request.
+    pub async fn send_list_playbook_request(
+        &mut self,
+        params: ListPlaybookParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("playbook/list", params).await
+    }
+
+    /// Send a `playbook/search` JSON-RPC request.
+    pub async fn send_search_playbook_request(
+        &mut self,
+        params: SearchPlaybookParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("playbook/search", params).await
+    }
+
+    /// Send a `playbook/update` JSON-RPC request.
+    pub async fn send_update_playbook_bullet_request(
+        &mut self,
+        params: UpdatePlaybookBulletParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("playbook/update", params).await
+    }
+
+    /// Send a `playbook/delete` JSON-RPC request.
+    pub async fn send_delete_playbook_bullet_request(
+        &mut self,
+        params: DeletePlaybookBulletParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("playbook/delete", params).await
+    }
+
     /// Send a `userInfo` JSON-RPC request.
     pub async fn send_user_info_request(&mut self) -> anyhow::Result<i64> {
         self.send_request("userInfo", None).await
@@ -27,6 +27,7 @@ use codex_core::protocol_config_types::ReasoningSummary;
 use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::CommandPattern;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
 use pretty_assertions::assert_eq;
@@ -275,6 +276,15 @@ async fn test_send_user_turn_changes_approval_policy_behavior() -> Result<()> {
             cwd: working_directory.clone(),
             reason: None,
             risk: None,
+            policy_match: None,
+            project_approval_pattern: Some(CommandPattern {
+                prefix: vec![
+                    "python3".to_string(),
+                    "-c".to_string(),
+                    "print(42)".to_string(),
+                ],
+                allow_extra_args: false,
+            }),
             parsed_cmd: vec![ParsedCommand::Unknown {
                 cmd: "python3 -c 'print(42)'".to_string()
             }],
@@ -424,6 +434,8 @@ async fn test_send_user_turn_updates_sandbox_and_cwd_between_turns() -> Result<(
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![first_cwd.clone()],
                 network_access: false,
+                network_allowlist: Vec::new(),
+                deny_read: Vec::new(),
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
             },
@@ -1,2 +1,3 @@
 // v2 test suite modules
 mod account;
+mod playbook;
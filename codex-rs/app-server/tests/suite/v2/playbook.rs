@@ -0,0 +1,192 @@
+use anyhow::Result;
+use anyhow::bail;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::DeletePlaybookBulletParams;
+use codex_app_server_protocol::DeletePlaybookBulletResponse;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::ListPlaybookParams;
+use codex_app_server_protocol::ListPlaybookResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ServerNotification;
+use codex_app_server_protocol::UpdatePlaybookBulletParams;
+use codex_app_server_protocol::UpdatePlaybookBulletResponse;
+use codex_core::ace::ACEConfig;
+use codex_core::ace::ACEConfigLoader;
+use codex_core::ace::Bullet;
+use codex_core::ace::BulletSection;
+use codex_core::ace::BulletStorage;
+use codex_core::ace::Playbook;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Points the ACE config at a playbook inside `codex_home` and seeds it with
+/// one entry per section in `entries`. Returns the ids in insertion order.
+async fn seed_playbook(
+    codex_home: &Path,
+    entries: &[(BulletSection, &str)],
+) -> Result<Vec<String>> {
+    let storage_path = codex_home.join("ace");
+    let config = ACEConfig {
+        storage_path: storage_path.to_string_lossy().into_owned(),
+        ..Default::default()
+    };
+    ACEConfigLoader::new(codex_home).save(&config).await?;
+
+    let mut playbook = Playbook::new();
+    let mut ids = Vec::new();
+    for (section, content) in entries {
+        let bullet = Bullet::new(section.clone(), content.to_string(), "session".to_string());
+        ids.push(bullet.id.clone());
+        playbook.add_bullet(bullet);
+    }
+    BulletStorage::new(&storage_path, config.max_entries)?
+        .save_playbook(&playbook)
+        .await?;
+    Ok(ids)
+}
+
+async fn list(mcp: &mut McpProcess, params: ListPlaybookParams) -> Result<ListPlaybookResponse> {
+    let id = mcp.send_list_playbook_request(params).await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(id)),
+    )
+    .await??;
+    to_response(resp)
+}
+
+#[tokio::test]
+async fn list_playbook_filters_by_section_and_paginates() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    seed_playbook(
+        codex_home.path(),
+        &[
+            (BulletSection::ToolUsageTips, "use rg instead of grep"),
+            (
+                BulletSection::ToolUsageTips,
+                "run cargo fmt before committing",
+            ),
+            (BulletSection::General, "the repo uses tokio"),
+        ],
+    )
+    .await?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let first = list(
+        &mut mcp,
+        ListPlaybookParams {
+            section: Some("Tool Usage Tips".to_string()),
+            page_size: Some(1),
+            cursor: None,
+        },
+    )
+    .await?;
+    assert_eq!(first.items.len(), 1);
+    assert_eq!(first.next_cursor.as_deref(), Some("1"));
+
+    let second = list(
+        &mut mcp,
+        ListPlaybookParams {
+            section: Some("Tool Usage Tips".to_string()),
+            page_size: Some(1),
+            cursor: first.next_cursor,
+        },
+    )
+    .await?;
+    assert_eq!(second.items.len(), 1);
+    assert_eq!(second.next_cursor, None);
+    assert_ne!(first.items[0].id, second.items[0].id);
+
+    let id = mcp
+        .send_list_playbook_request(ListPlaybookParams {
+            section: Some("Nonexistent".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(id)),
+    )
+    .await??;
+    assert_eq!(err.error.message, "unknown playbook section: Nonexistent");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn update_and_delete_playbook_bullet_notify() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let ids = seed_playbook(
+        codex_home.path(),
+        &[(BulletSection::ToolUsageTips, "use rg instead of grep")],
+    )
+    .await?;
+    let bullet_id = ids[0].clone();
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let id = mcp
+        .send_update_playbook_bullet_request(UpdatePlaybookBulletParams {
+            id: bullet_id.clone(),
+            content: Some("use rg --hidden instead of grep".to_string()),
+            pinned: Some(true),
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(id)),
+    )
+    .await??;
+    let UpdatePlaybookBulletResponse { bullet } = to_response(resp)?;
+    assert_eq!(bullet.content, "use rg --hidden instead of grep");
+    assert!(bullet.pinned);
+
+    let note = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("playbook/updated"),
+    )
+    .await??;
+    let parsed: ServerNotification = note.try_into()?;
+    let ServerNotification::PlaybookUpdated(payload) = parsed else {
+        bail!("unexpected notification: {parsed:?}");
+    };
+    assert_eq!(payload.updated, vec![bullet_id.clone()]);
+
+    let id = mcp
+        .send_delete_playbook_bullet_request(DeletePlaybookBulletParams {
+            id: bullet_id.clone(),
+            archive: false,
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(id)),
+    )
+    .await??;
+    let _ok: DeletePlaybookBulletResponse = to_response(resp)?;
+
+    let note = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("playbook/updated"),
+    )
+    .await??;
+    let parsed: ServerNotification = note.try_into()?;
+    let ServerNotification::PlaybookUpdated(payload) = parsed else {
+        bail!("unexpected notification: {parsed:?}");
+    };
+    assert_eq!(payload.deleted, vec![bullet_id]);
+
+    let remaining = list(&mut mcp, ListPlaybookParams::default()).await?;
+    assert_eq!(remaining.items, Vec::new());
+
+    Ok(())
+}
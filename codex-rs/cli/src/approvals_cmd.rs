@@ -0,0 +1,132 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::approval_rules::ProjectApprovalRules;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+
+/// Audit command patterns approved with "always allow in this project".
+///
+/// Subcommands:
+/// - `list`   — show the rules saved for the current project (or `--all`)
+/// - `revoke` — delete one rule by its number in `list`
+#[derive(Debug, clap::Parser)]
+pub struct ApprovalsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ApprovalsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ApprovalsSubcommand {
+    /// List approval rules saved for the current project.
+    List(ListArgs),
+
+    /// Revoke an approval rule of the current project.
+    Revoke(RevokeArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// List the rules of every project.
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RevokeArgs {
+    /// Number of the rule as shown by `codex approvals list`.
+    pub number: usize,
+}
+
+impl ApprovalsCli {
+    pub async fn run(self) -> Result<()> {
+        let ApprovalsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        let overrides = config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+            .await
+            .context("failed to load configuration")?;
+
+        match subcommand {
+            ApprovalsSubcommand::List(args) => run_list(&config, args),
+            ApprovalsSubcommand::Revoke(args) => run_revoke(&config, args),
+        }
+    }
+}
+
+fn run_list(config: &Config, list_args: ListArgs) -> Result<()> {
+    let projects = if list_args.all {
+        ProjectApprovalRules::load_all(&config.codex_home)
+            .context("failed to load approval rules")?
+    } else {
+        vec![load_current(config)?]
+    };
+
+    let mut printed_any = false;
+    for project in projects.iter().filter(|p| !p.rules().is_empty()) {
+        if printed_any {
+            println!();
+        }
+        printed_any = true;
+        println!("{}", project.project().display());
+        for (index, rule) in project.rules().iter().enumerate() {
+            println!(
+                "  {}. {}  (added {})",
+                index + 1,
+                rule.pattern,
+                rule.created_at.format("%Y-%m-%d")
+            );
+        }
+    }
+
+    if !printed_any {
+        match projects.first() {
+            Some(project) if !list_args.all => println!(
+                "No approval rules saved for {}.",
+                project.project().display()
+            ),
+            _ => println!("No approval rules saved yet."),
+        }
+    }
+
+    Ok(())
+}
+
+fn run_revoke(config: &Config, revoke_args: RevokeArgs) -> Result<()> {
+    let RevokeArgs { number } = revoke_args;
+    if number == 0 {
+        bail!("rule numbers start at 1; see `codex approvals list`");
+    }
+
+    let mut rules = load_current(config)?;
+    let revoked = rules
+        .revoke(number - 1)
+        .with_context(|| format!("failed to write {}", rules.path().display()))?;
+    match revoked {
+        Some(rule) => println!(
+            "Revoked `{}` for {}.",
+            rule.pattern,
+            rules.project().display()
+        ),
+        None => bail!(
+            "no approval rule #{number} for {}",
+            rules.project().display()
+        ),
+    }
+
+    Ok(())
+}
+
+fn load_current(config: &Config) -> Result<ProjectApprovalRules> {
+    ProjectApprovalRules::load(&config.codex_home, &config.cwd)
+        .context("failed to load approval rules")
+}
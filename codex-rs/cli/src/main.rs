@@ -25,8 +25,10 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod approvals_cmd;
 mod mcp_cmd;
 
+use crate::approvals_cmd::ApprovalsCli;
 use crate::mcp_cmd::McpCli;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -114,6 +116,9 @@ enum Subcommand {
     /// Inspect feature flags.
     Features(FeaturesCli),
 
+    /// List or revoke commands approved for a project.
+    Approvals(ApprovalsCli),
+
     /// [experimental] Manage ACE (Agentic Coding Environment) playbook.
     #[cfg(feature = "ace")]
     Ace {
@@ -151,6 +156,133 @@ enum AceSubcommand {
 
     /// Show ACE configuration
     Config,
+
+    /// Show missions and their todo history from recent sessions
+    Missions {
+        /// Number of most recent sessions to scan
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// Only show missions from this session id
+        #[arg(long)]
+        session: Option<String>,
+    },
+
+    /// Export the playbook to a JSON, JSONL or Markdown file
+    Export {
+        /// Destination file
+        #[arg(value_name = "FILE")]
+        output: PathBuf,
+
+        /// Output format (defaults to the file extension)
+        #[arg(long, value_enum)]
+        format: Option<PlaybookFormatArg>,
+
+        /// Export the repo-local playbook instead of the personal one
+        #[arg(long)]
+        repo: bool,
+    },
+
+    /// Import a playbook file, merging with existing entries
+    Import {
+        /// Source file
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+
+        /// Input format (defaults to the file extension)
+        #[arg(long, value_enum)]
+        format: Option<PlaybookFormatArg>,
+
+        /// What to do with entries that duplicate existing ones
+        #[arg(long, value_enum, default_value_t = ConflictPolicyArg::Skip)]
+        on_conflict: ConflictPolicyArg,
+
+        /// Import into the repo-local playbook instead of the personal one
+        #[arg(long)]
+        repo: bool,
+
+        /// Show what would change without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Scan the playbook and its archives for secrets
+    Scan {
+        /// Replace the secrets that were found with [REDACTED:<kind>]
+        #[arg(long)]
+        fix: bool,
+
+        /// Scan the repo-local playbook instead of the personal one
+        #[arg(long)]
+        repo: bool,
+    },
+
+    /// Show where an entry came from and the conversation turn that produced it
+    Explain {
+        /// Entry id (a unique prefix is enough)
+        bullet_id: String,
+
+        /// Look the entry up in the repo-local playbook instead of the personal one
+        #[arg(long)]
+        repo: bool,
+    },
+
+    /// Replay session rollouts against a scratch playbook and report retrieval metrics
+    Eval {
+        /// Directory searched recursively for *.jsonl rollouts
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Number of entries retrieved per turn
+        #[arg(long, default_value = "10")]
+        top_k: usize,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[cfg(feature = "ace")]
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum PlaybookFormatArg {
+    Json,
+    Jsonl,
+    #[value(alias = "md")]
+    Markdown,
+}
+
+#[cfg(feature = "ace")]
+impl From<PlaybookFormatArg> for codex_ace::transfer::PlaybookFormat {
+    fn from(value: PlaybookFormatArg) -> Self {
+        match value {
+            PlaybookFormatArg::Json => Self::Json,
+            PlaybookFormatArg::Jsonl => Self::Jsonl,
+            PlaybookFormatArg::Markdown => Self::Markdown,
+        }
+    }
+}
+
+#[cfg(feature = "ace")]
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ConflictPolicyArg {
+    /// Keep the existing entry
+    Skip,
+    /// Keep the existing content and merge counters, tags and tools
+    Merge,
+    /// Replace the existing entry with the imported one
+    Overwrite,
+}
+
+#[cfg(feature = "ace")]
+impl From<ConflictPolicyArg> for codex_ace::transfer::ConflictPolicy {
+    fn from(value: ConflictPolicyArg) -> Self {
+        match value {
+            ConflictPolicyArg::Skip => Self::Skip,
+            ConflictPolicyArg::Merge => Self::Merge,
+            ConflictPolicyArg::Overwrite => Self::Overwrite,
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -425,6 +557,13 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Approvals(mut approvals_cli)) => {
+            prepend_config_flags(
+                &mut approvals_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            approvals_cli.run().await?;
+        }
         Some(Subcommand::AppServer) => {
             codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
@@ -599,6 +738,38 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                     query: query.clone(),
                 },
                 AceSubcommand::Config => CoreAceCommand::Config,
+                AceSubcommand::Missions { limit, session } => {
+                    CoreAceCommand::Missions { limit, session }
+                }
+                AceSubcommand::Export {
+                    output,
+                    format,
+                    repo,
+                } => CoreAceCommand::Export {
+                    output,
+                    format: format.map(Into::into),
+                    repo,
+                },
+                AceSubcommand::Import {
+                    input,
+                    format,
+                    on_conflict,
+                    repo,
+                    dry_run,
+                } => CoreAceCommand::Import {
+                    input,
+                    format: format.map(Into::into),
+                    on_conflict: on_conflict.into(),
+                    repo,
+                    dry_run,
+                },
+                AceSubcommand::Scan { fix, repo } => CoreAceCommand::Scan { fix, repo },
+                AceSubcommand::Explain { bullet_id, repo } => {
+                    CoreAceCommand::Explain { bullet_id, repo }
+                }
+                AceSubcommand::Eval { dir, top_k, json } => {
+                    CoreAceCommand::Eval { dir, top_k, json }
+                }
             };
 
             // 执行命令
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use anyhow::Result;
+use codex_core::approval_rules::ProjectApprovalRules;
+use codex_core::protocol::CommandPattern;
+use predicates::str::contains;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path, cwd: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEACE_HOME", codex_home).current_dir(cwd);
+    Ok(cmd)
+}
+
+#[test]
+fn list_and_revoke_project_rules() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    let project_dir = project.path().canonicalize()?;
+
+    codex_command(codex_home.path(), &project_dir)?
+        .args(["approvals", "list"])
+        .assert()
+        .success()
+        .stdout(contains("No approval rules saved for"));
+
+    let mut rules = ProjectApprovalRules::load(codex_home.path(), &project_dir)?;
+    rules.add(CommandPattern {
+        prefix: vec!["cargo".to_string(), "test".to_string()],
+        allow_extra_args: true,
+    })?;
+
+    codex_command(codex_home.path(), &project_dir)?
+        .args(["approvals", "list"])
+        .assert()
+        .success()
+        .stdout(contains("1. cargo test *"));
+
+    codex_command(codex_home.path(), &project_dir)?
+        .args(["approvals", "revoke", "2"])
+        .assert()
+        .failure()
+        .stderr(contains("no approval rule #2"));
+
+    codex_command(codex_home.path(), &project_dir)?
+        .args(["approvals", "revoke", "1"])
+        .assert()
+        .success()
+        .stdout(contains("Revoked `cargo test *`"));
+
+    let rules = ProjectApprovalRules::load(codex_home.path(), &project_dir)?;
+    assert!(rules.rules().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,976 @@
+// 后台优化系统 - 无感知智能优化 Playbook
+//
+// 会话中每轮结束后记录注入的 bullets 及成败（交给 RecallTracker），
+// 并周期性地按 `BulletMetadata::calculate_dynamic_weight` 对 playbook 做维护：
+// - 去重：合并近似重复的 bullets（统计信息并入保留的一条）
+// - 衰减：长期未召回的 bullets 按实际经过时间降低重要性
+// - 降级：多次召回但动态权重很低（经常失败）的 bullets 降低重要性
+// - 归档：低价值 bullets 移出 playbook，写入 archive 目录
+//
+// 每次优化的结果写入存储目录下的 `optimizer_report.json`，供 `codex ace status` 展示。
+use crate::lightweight_index::PlaybookIndex;
+use crate::recall_tracker::RecallTracker;
+use crate::similarity::SimilarityCalculator;
+use crate::storage::BulletStorage;
+use crate::types::{Bullet, Playbook};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::{Duration, sleep};
+
+/// 优化报告文件名（位于存储目录）
+pub const OPTIMIZER_REPORT_FILENAME: &str = "optimizer_report.json";
+
+/// 去重相似度阈值
+const SIMILARITY_THRESHOLD: f32 = 0.85;
+
+/// 超过该天数未被召回的 bullet 才会衰减
+const DECAY_IDLE_DAYS: i64 = 14;
+
+/// 重要性衰减半衰期（天）
+const DECAY_HALF_LIFE_DAYS: f32 = 60.0;
+
+/// 衰减后的最低重要性
+const MIN_IMPORTANCE: f32 = 0.05;
+
+/// 至少被召回 N 次后才根据动态权重降级（避免样本过少）
+const DEMOTE_MIN_RECALLS: u32 = 3;
+
+/// 动态权重低于该值的 bullet 被降级
+const DEMOTE_WEIGHT_THRESHOLD: f32 = 0.4;
+
+/// 降级后的重要性上限
+const DEMOTED_IMPORTANCE: f32 = 0.2;
+
+/// 超过 30 天未召回且动态权重低于该值的 bullet 被归档
+const ARCHIVE_WEIGHT_THRESHOLD: f32 = 0.1;
+
+/// 后台优化器配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptimizerConfig {
+    /// 是否在会话中运行后台优化（关闭时仍记录召回）
+    pub enabled: bool,
+    /// 优化间隔(秒)
+    pub interval_secs: u64,
+    /// 是否启用去重
+    pub dedup_enabled: bool,
+    /// 是否启用清理（归档低价值 bullets）
+    pub cleanup_enabled: bool,
+    /// 每 N 次调用触发优化（0 表示只按间隔运行）
+    pub trigger_every_n_calls: u64,
+}
+
+impl Default for OptimizerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 300, // 5分钟
+            dedup_enabled: true,
+            cleanup_enabled: true,
+            trigger_every_n_calls: 100,
+        }
+    }
+}
+
+/// 后台优化器
+pub struct BackgroundOptimizer {
+    storage: Arc<RwLock<BulletStorage>>,
+    /// 检索索引（设置后写入同时更新索引）
+    index: Option<Arc<PlaybookIndex>>,
+    recall_tracker: RecallTracker,
+    call_count: Arc<AtomicU64>,
+    config: OptimizerConfig,
+    /// 串行化优化任务（定时任务与按调用次数触发的任务可能重叠）
+    running: Mutex<()>,
+}
+
+/// 单次优化的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    /// 完成时间
+    pub finished_at: DateTime<Utc>,
+    /// 耗时（毫秒）
+    pub duration_ms: u64,
+    /// 去重合并的 bullet 数
+    pub deduplicated: usize,
+    /// 重要性衰减的 bullet 数
+    pub decayed: usize,
+    /// 被降级的 bullet 数
+    pub demoted: usize,
+    /// 被归档的 bullet 数
+    pub archived: usize,
+    /// 归档文件路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_path: Option<PathBuf>,
+    /// 优化后的 bullet 总数
+    pub total_bullets: usize,
+}
+
+impl OptimizationReport {
+    /// 读取存储目录中最近一次的优化报告
+    pub async fn load(storage_dir: &Path) -> Option<Self> {
+        let content = tokio::fs::read_to_string(storage_dir.join(OPTIMIZER_REPORT_FILENAME))
+            .await
+            .ok()?;
+        serde_json::from_str(&content)
+            .inspect_err(|e| tracing::warn!("无法解析优化报告: {e}"))
+            .ok()
+    }
+
+    async fn save(&self, storage_dir: &Path) -> Result<()> {
+        let path = storage_dir.join(OPTIMIZER_REPORT_FILENAME);
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)
+            .await
+            .context("Failed to write optimizer report")?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .context("Failed to replace optimizer report")?;
+        Ok(())
+    }
+}
+
+/// 各维护步骤在 playbook 上的改动
+#[derive(Debug, Default)]
+struct PassResult {
+    deduplicated: usize,
+    decayed: usize,
+    demoted: usize,
+    archived: Vec<Bullet>,
+    total_bullets: usize,
+}
+
+impl BackgroundOptimizer {
+    /// 创建新的后台优化器
+    pub fn new(storage: Arc<RwLock<BulletStorage>>, config: OptimizerConfig) -> Self {
+        Self {
+            recall_tracker: RecallTracker::new(Arc::clone(&storage)),
+            storage,
+            index: None,
+            call_count: Arc::new(AtomicU64::new(0)),
+            config,
+            running: Mutex::new(()),
+        }
+    }
+
+    /// 通过检索索引写入，使召回统计和优化结果立即反映到检索中
+    pub fn with_index(mut self, index: Arc<PlaybookIndex>) -> Self {
+        self.recall_tracker = self.recall_tracker.with_index(Arc::clone(&index));
+        self.index = Some(index);
+        self
+    }
+
+    /// 启动后台优化任务
+    ///
+    /// 任务只持有弱引用，优化器（会话）释放后自动退出。
+    pub fn start(self: Arc<Self>) {
+        if !self.config.enabled {
+            tracing::debug!("后台优化已禁用");
+            return;
+        }
+
+        let interval = Duration::from_secs(self.config.interval_secs.max(1));
+        let optimizer = Arc::downgrade(&self);
+        tokio::spawn(async move {
+            loop {
+                // 等待指定间隔
+                sleep(interval).await;
+
+                let Some(optimizer) = optimizer.upgrade() else {
+                    break;
+                };
+                if let Err(e) = optimizer.optimize().await {
+                    tracing::error!("后台优化失败: {}", e);
+                }
+            }
+        });
+    }
+
+    /// 记录调用并可能触发优化
+    ///
+    /// # 参数
+    /// - `used_bullet_ids`: 本轮注入到上下文的 bullet ID
+    /// - `context`: 召回上下文（通常是用户查询摘要）
+    /// - `success`: 本轮是否成功
+    pub async fn record_call(
+        self: &Arc<Self>,
+        used_bullet_ids: Vec<String>,
+        context: String,
+        success: bool,
+    ) {
+        if !used_bullet_ids.is_empty()
+            && let Err(e) = self
+                .recall_tracker
+                .record_bullet_usage(used_bullet_ids, context, success)
+                .await
+        {
+            tracing::warn!("记录 bullet 召回失败: {e:#}");
+        }
+
+        let count = self.call_count.fetch_add(1, Ordering::Relaxed) + 1;
+
+        // 每 N 次调用触发优化
+        if self.config.enabled
+            && self.config.trigger_every_n_calls > 0
+            && count.is_multiple_of(self.config.trigger_every_n_calls)
+        {
+            tracing::info!("达到 {} 次调用，触发优化", count);
+
+            let optimizer = Arc::clone(self);
+            tokio::spawn(async move {
+                if let Err(e) = optimizer.optimize().await {
+                    tracing::error!("触发优化失败: {}", e);
+                }
+            });
+        }
+    }
+
+    /// 执行优化
+    ///
+    /// 所有改动在存储的排他锁内基于最新状态完成，然后归档被移除的 bullets 并写入报告。
+    pub async fn optimize(&self) -> Result<OptimizationReport> {
+        let _running = self.running.lock().await;
+        tracing::info!("开始后台优化...");
+        let start = std::time::Instant::now();
+
+        let config = self.config.clone();
+        let now = Utc::now();
+        let pass = move |playbook: &mut Playbook| Ok(Self::run_passes(playbook, &config, now));
+        let storage = self.storage().await;
+        let result = match &self.index {
+            Some(index) => index.rewrite_playbook(pass).await?,
+            None => storage.rewrite_playbook(pass).await?.1,
+        };
+
+        let archive_path = if result.archived.is_empty() {
+            None
+        } else {
+            Some(
+                storage
+                    .archive_bullets("optimizer", &result.archived)
+                    .await?,
+            )
+        };
+
+        let report = OptimizationReport {
+            finished_at: Utc::now(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            deduplicated: result.deduplicated,
+            decayed: result.decayed,
+            demoted: result.demoted,
+            archived: result.archived.len(),
+            archive_path,
+            total_bullets: result.total_bullets,
+        };
+        if let Err(e) = report.save(storage.base_dir()).await {
+            tracing::warn!("保存优化报告失败: {e:#}");
+        }
+
+        tracing::info!(
+            "后台优化完成: 去重 {} 条, 衰减 {} 条, 降级 {} 条, 归档 {} 条, 耗时 {:?}",
+            report.deduplicated,
+            report.decayed,
+            report.demoted,
+            report.archived,
+            start.elapsed()
+        );
+
+        Ok(report)
+    }
+
+    /// 在 playbook 上依次执行各维护步骤
+    ///
+    /// 有改动时递增 `playbook.version`，存储据此决定是否写入新快照。
+    fn run_passes(
+        playbook: &mut Playbook,
+        config: &OptimizerConfig,
+        now: DateTime<Utc>,
+    ) -> PassResult {
+        let version = playbook.version;
+        let mut result = PassResult::default();
+
+        // 1. 去重
+        if config.dedup_enabled {
+            result.deduplicated = Self::deduplicate_similar(playbook);
+        }
+
+        // 2. 衰减与降级（基于动态权重）
+        result.decayed = Self::decay_idle(playbook, now);
+        result.demoted = Self::demote_low_weight(playbook);
+
+        // 3. 归档低价值内容
+        if config.cleanup_enabled {
+            result.archived = Self::cleanup_low_value(playbook, now);
+        }
+
+        if (result.decayed > 0 || result.demoted > 0) && playbook.version == version {
+            playbook.version += 1;
+        }
+        result.total_bullets = playbook.metadata.total_bullets;
+        result
+    }
+
+    /// 相似内容去重（使用高级相似度算法）
+    ///
+    /// 先用归一化内容哈希识别完全重复，再用组合相似度检测近似重复。
+    /// 保留动态权重较高的一条，并吸收被删除一条的统计信息。
+    fn deduplicate_similar(playbook: &mut Playbook) -> usize {
+        let all_bullets: Vec<Bullet> = playbook.all_bullets().into_iter().cloned().collect();
+        if all_bullets.len() < 2 {
+            return 0;
+        }
+
+        tracing::debug!("开始去重检查，共 {} 个 bullets", all_bullets.len());
+
+        // 归一化所有内容
+        let normalized_contents: Vec<_> = all_bullets
+            .iter()
+            .map(|b| SimilarityCalculator::normalize_text(&b.content, true))
+            .collect();
+        let hashes: Vec<_> = all_bullets
+            .iter()
+            .map(|b| Self::calculate_content_hash(&b.content))
+            .collect();
+
+        let mut removed = HashSet::new();
+        let mut merges = Vec::new();
+
+        // 比较每对 bullet
+        for i in 0..all_bullets.len() {
+            if removed.contains(&i) {
+                continue; // 已经被标记删除，跳过
+            }
+
+            for j in (i + 1)..all_bullets.len() {
+                if removed.contains(&j) {
+                    continue;
+                }
+
+                let similarity = if hashes[i] == hashes[j] {
+                    1.0
+                } else {
+                    SimilarityCalculator::combined_similarity(
+                        &normalized_contents[i],
+                        &normalized_contents[j],
+                    )
+                };
+                if similarity < SIMILARITY_THRESHOLD {
+                    continue;
+                }
+
+                // 优先保留用户固定的；都未固定时比较权重，删除权重较低的
+                let (keep, drop) = match (
+                    all_bullets[i].metadata.pinned,
+                    all_bullets[j].metadata.pinned,
+                ) {
+                    (true, true) => continue,
+                    (true, false) => (i, j),
+                    (false, true) => (j, i),
+                    (false, false) => {
+                        let weight_i = all_bullets[i].metadata.calculate_dynamic_weight();
+                        let weight_j = all_bullets[j].metadata.calculate_dynamic_weight();
+                        if weight_i >= weight_j { (i, j) } else { (j, i) }
+                    }
+                };
+
+                tracing::debug!(
+                    "发现相似 bullets (相似度: {:.2}): 保留 '{}', 删除 '{}'",
+                    similarity,
+                    all_bullets[keep]
+                        .content
+                        .chars()
+                        .take(30)
+                        .collect::<String>(),
+                    all_bullets[drop]
+                        .content
+                        .chars()
+                        .take(30)
+                        .collect::<String>()
+                );
+
+                removed.insert(drop);
+                merges.push((all_bullets[keep].id.clone(), all_bullets[drop].id.clone()));
+                if drop == i {
+                    break;
+                }
+            }
+        }
+
+        // 按发现顺序合并，链式合并时统计信息逐级累加
+        for (keep_id, drop_id) in &merges {
+            if let Some(dropped) = playbook.find_bullet(drop_id).cloned() {
+                if let Some(kept) = playbook.find_bullet_mut(keep_id) {
+                    kept.absorb(&dropped);
+                }
+                playbook.remove_bullet(drop_id);
+            }
+        }
+
+        if !merges.is_empty() {
+            tracing::info!("去重完成，合并了 {} 个相似 bullets", merges.len());
+        }
+
+        merges.len()
+    }
+
+    /// 归一化内容哈希（识别完全重复的内容）
+    fn calculate_content_hash(content: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        SimilarityCalculator::normalize_text(content, true).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 衰减长期未召回的 bullets
+    ///
+    /// 按上次衰减以来实际经过的时间计算（半衰期 `DECAY_HALF_LIFE_DAYS`），
+    /// 每天最多执行一次，因此与优化频率无关。
+    fn decay_idle(playbook: &mut Playbook, now: DateTime<Utc>) -> usize {
+        let Some(last_decay_at) = playbook.metadata.last_decay_at else {
+            // 首次运行只记录基准时间
+            playbook.metadata.last_decay_at = Some(now);
+            playbook.version += 1;
+            return 0;
+        };
+
+        let elapsed_days = (now - last_decay_at).num_hours() as f32 / 24.0;
+        if elapsed_days < 1.0 {
+            return 0;
+        }
+
+        let factor = 0.5_f32.powf(elapsed_days / DECAY_HALF_LIFE_DAYS);
+        let mut decayed = 0;
+        for bullet in playbook.bullets.values_mut().flatten() {
+            let last_used = bullet.metadata.last_recall.unwrap_or(bullet.created_at);
+            if bullet.metadata.pinned
+                || (now - last_used).num_days() < DECAY_IDLE_DAYS
+                || bullet.metadata.importance <= MIN_IMPORTANCE
+            {
+                continue;
+            }
+
+            bullet.metadata.importance = (bullet.metadata.importance * factor).max(MIN_IMPORTANCE);
+            decayed += 1;
+        }
+
+        playbook.metadata.last_decay_at = Some(now);
+        playbook.version += 1;
+        decayed
+    }
+
+    /// 降级经常召回但动态权重很低（多为失败）的 bullets
+    fn demote_low_weight(playbook: &mut Playbook) -> usize {
+        let mut demoted = 0;
+        for bullet in playbook.bullets.values_mut().flatten() {
+            let metadata = &mut bullet.metadata;
+            if metadata.pinned
+                || metadata.recall_count < DEMOTE_MIN_RECALLS
+                || metadata.importance <= DEMOTED_IMPORTANCE
+            {
+                continue;
+            }
+
+            let weight = metadata.calculate_dynamic_weight();
+            if weight < DEMOTE_WEIGHT_THRESHOLD {
+                tracing::debug!(
+                    "降级 bullet {} (权重: {:.3}, 成功率: {:.0}%)",
+                    bullet.id,
+                    weight,
+                    metadata.success_rate * 100.0
+                );
+                metadata.importance = DEMOTED_IMPORTANCE;
+                demoted += 1;
+            }
+        }
+        demoted
+    }
+
+    /// 移除低价值内容，返回被移除的 bullets（由调用方归档）
+    fn cleanup_low_value(playbook: &mut Playbook, now: DateTime<Utc>) -> Vec<Bullet> {
+        let to_remove: Vec<String> = playbook
+            .all_bullets()
+            .into_iter()
+            .filter(|bullet| Self::is_low_value(bullet, now))
+            .map(|bullet| bullet.id.clone())
+            .collect();
+
+        let mut removed = Vec::new();
+        for id in &to_remove {
+            if let Some(bullet) = playbook.find_bullet(id).cloned() {
+                tracing::debug!(
+                    "归档低价值 bullet: {} (recall: {}, success_rate: {:.0}%, age: {} days)",
+                    bullet.id,
+                    bullet.metadata.recall_count,
+                    bullet.metadata.success_rate * 100.0,
+                    (now - bullet.created_at).num_days()
+                );
+                playbook.remove_bullet(id);
+                removed.push(bullet);
+            }
+        }
+        removed
+    }
+
+    /// 判断是否应该删除某个 bullet
+    fn should_remove(&self, bullet: &Bullet) -> bool {
+        Self::is_low_value(bullet, Utc::now())
+    }
+
+    fn is_low_value(bullet: &Bullet, now: DateTime<Utc>) -> bool {
+        // 0. 用户固定的永不归档
+        if bullet.metadata.pinned {
+            return false;
+        }
+
+        // 1. 保护最近使用的
+        if let Some(last_recall) = bullet.metadata.last_recall {
+            let days_since = (now - last_recall).num_days();
+            if days_since < 7 {
+                return false; // 7天内使用过，保留
+            }
+
+            // 2. 长期未再召回且动态权重已很低
+            if days_since > 30
+                && bullet.metadata.calculate_dynamic_weight() < ARCHIVE_WEIGHT_THRESHOLD
+            {
+                return true;
+            }
+        }
+
+        // 3. 从未被召回且创建超过30天
+        if bullet.metadata.recall_count == 0 {
+            let age_days = (now - bullet.created_at).num_days();
+            if age_days > 30 {
+                return true;
+            }
+        }
+
+        // 4. 失败率太高（> 80%）且召回次数 > 5
+        if bullet.metadata.recall_count > 5 && bullet.metadata.success_rate < 0.2 {
+            return true;
+        }
+
+        // 5. 内容太短且重要性低
+        if bullet.content.len() < 30 && bullet.metadata.importance < 0.3 {
+            return true;
+        }
+
+        false
+    }
+
+    async fn storage(&self) -> BulletStorage {
+        self.storage.read().await.clone()
+    }
+
+    /// 获取优化统计
+    pub async fn get_stats(&self) -> Result<OptimizerStats> {
+        let storage = self.storage().await;
+        let playbook = storage.load_playbook().await?;
+        let all_bullets = playbook.all_bullets();
+
+        let mut stats = OptimizerStats {
+            total_bullets: all_bullets.len(),
+            ..Default::default()
+        };
+
+        let mut weight_sum = 0.0;
+        let mut recall_sum = 0;
+        let mut success_sum = 0.0;
+
+        for bullet in all_bullets {
+            let weight = bullet.metadata.calculate_dynamic_weight();
+            weight_sum += weight;
+            recall_sum += bullet.metadata.recall_count as i32;
+            success_sum += bullet.metadata.success_rate;
+
+            if bullet.metadata.recall_count >= DEMOTE_MIN_RECALLS
+                && bullet.metadata.importance <= DEMOTED_IMPORTANCE
+            {
+                stats.demoted += 1;
+            }
+
+            // 统计各个年龄段
+            let age_days = (Utc::now() - bullet.created_at).num_days();
+            if age_days < 7 {
+                stats.bullets_last_week += 1;
+            } else if age_days < 30 {
+                stats.bullets_last_month += 1;
+            } else {
+                stats.bullets_older += 1;
+            }
+
+            // 统计召回频率
+            if bullet.metadata.recall_count == 0 {
+                stats.never_recalled += 1;
+            } else if bullet.metadata.recall_count < 5 {
+                stats.low_recall += 1;
+            } else {
+                stats.high_recall += 1;
+            }
+        }
+
+        if stats.total_bullets > 0 {
+            stats.avg_weight = weight_sum / stats.total_bullets as f32;
+            stats.avg_recall = recall_sum as f32 / stats.total_bullets as f32;
+            stats.avg_success_rate = success_sum / stats.total_bullets as f32;
+        }
+
+        stats.call_count = self.call_count.load(Ordering::Relaxed);
+        stats.last_run = OptimizationReport::load(storage.base_dir()).await;
+
+        Ok(stats)
+    }
+}
+
+/// 优化器统计信息
+#[derive(Debug, Default)]
+pub struct OptimizerStats {
+    pub total_bullets: usize,
+    pub avg_weight: f32,
+    pub avg_recall: f32,
+    pub avg_success_rate: f32,
+    pub bullets_last_week: usize,
+    pub bullets_last_month: usize,
+    pub bullets_older: usize,
+    pub never_recalled: usize,
+    pub low_recall: usize,
+    pub high_recall: usize,
+    /// 当前处于降级状态的 bullet 数
+    pub demoted: usize,
+    pub call_count: u64,
+    /// 最近一次优化的结果
+    pub last_run: Option<OptimizationReport>,
+}
+
+impl OptimizerStats {
+    /// 格式化为可读的字符串
+    pub fn format(&self) -> String {
+        let last_run = match &self.last_run {
+            Some(report) => format!(
+                "{} (去重 {}, 衰减 {}, 降级 {}, 归档 {})",
+                report.finished_at.format("%Y-%m-%d %H:%M"),
+                report.deduplicated,
+                report.decayed,
+                report.demoted,
+                report.archived
+            ),
+            None => "尚未运行".to_string(),
+        };
+
+        format!(
+            "📊 LAPS 优化器统计
+━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+总 Bullets 数: {}
+平均权重: {:.3}
+平均召回次数: {:.1}
+平均成功率: {:.1}%
+已降级: {}
+
+年龄分布:
+  - 最近一周: {}
+  - 最近一月: {}
+  - 更早: {}
+
+召回频率分布:
+  - 从未召回: {}
+  - 低频召回 (1-4): {}
+  - 高频召回 (5+): {}
+
+总调用次数: {}
+最近一次优化: {}
+",
+            self.total_bullets,
+            self.avg_weight,
+            self.avg_recall,
+            self.avg_success_rate * 100.0,
+            self.demoted,
+            self.bullets_last_week,
+            self.bullets_last_month,
+            self.bullets_older,
+            self.never_recalled,
+            self.low_recall,
+            self.high_recall,
+            self.call_count,
+            last_run
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BulletMetadata, BulletSection, DeltaContext};
+
+    #[tokio::test]
+    async fn test_content_hash() {
+        let content1 = "这是测试内容";
+        let content2 = "这是测试内容"; // 相同
+        let content3 = "这是不同内容";
+
+        let hash1 = BackgroundOptimizer::calculate_content_hash(content1);
+        let hash2 = BackgroundOptimizer::calculate_content_hash(content2);
+        let hash3 = BackgroundOptimizer::calculate_content_hash(content3);
+
+        assert_eq!(hash1, hash2);
+        assert_ne!(hash1, hash3);
+    }
+
+    #[tokio::test]
+    async fn test_should_remove_never_recalled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_path = temp_dir.path().to_path_buf();
+
+        let storage = Arc::new(RwLock::new(
+            BulletStorage::new(&storage_path, 1000).unwrap(),
+        ));
+        let optimizer = BackgroundOptimizer::new(storage, OptimizerConfig::default());
+
+        // 创建一个从未被召回且很旧的 bullet
+        let mut bullet = Bullet {
+            id: "test-1".to_string(),
+            content: "测试内容".to_string(),
+            section: BulletSection::General,
+            created_at: Utc::now() - chrono::Duration::days(35),
+            updated_at: Utc::now(),
+            source_session_id: "test".to_string(),
+            metadata: BulletMetadata::default(),
+            tags: vec![],
+            code_content: None,
+        };
+
+        bullet.metadata.recall_count = 0;
+
+        assert!(optimizer.should_remove(&bullet));
+    }
+
+    #[tokio::test]
+    async fn test_pinned_bullet_is_never_removed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(RwLock::new(
+            BulletStorage::new(temp_dir.path(), 1000).unwrap(),
+        ));
+        let optimizer = BackgroundOptimizer::new(storage, OptimizerConfig::default());
+
+        let mut bullet = Bullet::new(BulletSection::General, "短".to_string(), "test".to_string());
+        bullet.created_at = Utc::now() - chrono::Duration::days(35);
+        bullet.metadata.importance = 0.1;
+        assert!(optimizer.should_remove(&bullet));
+
+        bullet.metadata.pinned = true;
+        assert!(!optimizer.should_remove(&bullet));
+    }
+
+    #[tokio::test]
+    async fn test_should_remove_high_failure_rate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_path = temp_dir.path().to_path_buf();
+
+        let storage = Arc::new(RwLock::new(
+            BulletStorage::new(&storage_path, 1000).unwrap(),
+        ));
+        let optimizer = BackgroundOptimizer::new(storage, OptimizerConfig::default());
+
+        // 创建一个失败率很高的 bullet
+        let mut bullet = Bullet {
+            id: "test-2".to_string(),
+            content: "测试内容".to_string(),
+            section: BulletSection::General,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            source_session_id: "test".to_string(),
+            metadata: BulletMetadata::default(),
+            tags: vec![],
+            code_content: None,
+        };
+
+        bullet.metadata.recall_count = 10;
+        bullet.metadata.success_count = 1;
+        bullet.metadata.failure_count = 9;
+        bullet.metadata.success_rate = 0.1;
+
+        assert!(optimizer.should_remove(&bullet));
+    }
+
+    #[tokio::test]
+    async fn test_should_not_remove_recent() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_path = temp_dir.path().to_path_buf();
+
+        let storage = Arc::new(RwLock::new(
+            BulletStorage::new(&storage_path, 1000).unwrap(),
+        ));
+        let optimizer = BackgroundOptimizer::new(storage, OptimizerConfig::default());
+
+        // 创建一个最近使用的 bullet
+        let mut bullet = Bullet {
+            id: "test-3".to_string(),
+            content: "测试内容".to_string(),
+            section: BulletSection::General,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            source_session_id: "test".to_string(),
+            metadata: BulletMetadata::default(),
+            tags: vec![],
+            code_content: None,
+        };
+
+        bullet.metadata.last_recall = Some(Utc::now() - chrono::Duration::days(3));
+        bullet.metadata.recall_count = 5;
+
+        assert!(!optimizer.should_remove(&bullet));
+    }
+
+    #[tokio::test]
+    async fn test_optimizer_stats() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_path = temp_dir.path().to_path_buf();
+
+        let storage = BulletStorage::new(&storage_path, 1000).unwrap();
+
+        // 创建一些测试 bullets
+        let mut delta = DeltaContext::new("test-session".to_string());
+
+        for i in 0..10 {
+            let mut bullet = Bullet {
+                id: format!("test-{}", i),
+                content: format!("测试内容 {}", i),
+                section: BulletSection::General,
+                created_at: Utc::now() - chrono::Duration::days(i as i64),
+                updated_at: Utc::now(),
+                source_session_id: "test".to_string(),
+                metadata: BulletMetadata::default(),
+                tags: vec![],
+                code_content: None,
+            };
+
+            bullet.metadata.recall_count = i;
+            bullet.metadata.importance = 0.5 + (i as f32 * 0.05);
+
+            delta.new_bullets.push(bullet);
+        }
+
+        storage.merge_delta(delta).await.unwrap();
+
+        let storage_arc = Arc::new(RwLock::new(storage));
+        let optimizer = BackgroundOptimizer::new(storage_arc, OptimizerConfig::default());
+
+        let stats = optimizer.get_stats().await.unwrap();
+
+        assert_eq!(stats.total_bullets, 10);
+        assert!(stats.avg_recall > 0.0);
+    }
+
+    #[test]
+    fn test_decay_uses_elapsed_time_since_last_decay() {
+        let now = Utc::now();
+        let half_life_later = now + chrono::Duration::days(60);
+        let mut playbook = Playbook::new();
+
+        let mut idle = Bullet::new(
+            BulletSection::General,
+            "Prefer rg over grep for large repositories".to_string(),
+            "session-1".to_string(),
+        );
+        idle.created_at = now - chrono::Duration::days(90);
+        idle.metadata.importance = 0.8;
+        let idle_id = idle.id.clone();
+        playbook.add_bullet(idle);
+
+        let mut active = Bullet::new(
+            BulletSection::General,
+            "Run cargo fmt before committing changes".to_string(),
+            "session-1".to_string(),
+        );
+        active.metadata.importance = 0.8;
+        active.metadata.last_recall = Some(half_life_later - chrono::Duration::days(1));
+        let active_id = active.id.clone();
+        playbook.add_bullet(active);
+
+        // 首次运行只记录基准时间
+        assert_eq!(BackgroundOptimizer::decay_idle(&mut playbook, now), 0);
+        assert_eq!(playbook.metadata.last_decay_at, Some(now));
+
+        // 同一天内再次运行不衰减
+        let later = now + chrono::Duration::hours(2);
+        assert_eq!(BackgroundOptimizer::decay_idle(&mut playbook, later), 0);
+
+        // 经过一个半衰期后重要性减半，近期召回的不受影响
+        assert_eq!(
+            BackgroundOptimizer::decay_idle(&mut playbook, half_life_later),
+            1
+        );
+        let idle = playbook.find_bullet(&idle_id).unwrap();
+        assert!((idle.metadata.importance - 0.4).abs() < 1e-3);
+        let active = playbook.find_bullet(&active_id).unwrap();
+        assert_eq!(active.metadata.importance, 0.8);
+    }
+
+    #[tokio::test]
+    async fn test_optimize_demotes_archives_and_reports() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = BulletStorage::new(temp_dir.path(), 1000).unwrap();
+
+        // 多次召回但总是失败 -> 降级
+        let mut failing = Bullet::new(
+            BulletSection::ToolUsageTips,
+            "Use the legacy deploy script to publish preview builds".to_string(),
+            "session-1".to_string(),
+        );
+        for _ in 0..3 {
+            failing
+                .metadata
+                .record_recall("deploy preview".to_string(), false);
+        }
+        let failing_id = failing.id.clone();
+
+        // 从未召回且很旧 -> 归档
+        let mut stale = Bullet::new(
+            BulletSection::General,
+            "Old advice about a tool nobody uses anymore".to_string(),
+            "session-0".to_string(),
+        );
+        stale.created_at = Utc::now() - chrono::Duration::days(45);
+        let stale_id = stale.id.clone();
+
+        let mut delta = DeltaContext::new("session-1".to_string());
+        delta.new_bullets.push(failing);
+        delta.new_bullets.push(stale);
+        storage.merge_delta(delta).await.unwrap();
+
+        let storage = Arc::new(RwLock::new(storage));
+        let optimizer = BackgroundOptimizer::new(Arc::clone(&storage), OptimizerConfig::default());
+        let report = optimizer.optimize().await.unwrap();
+
+        assert_eq!(report.demoted, 1);
+        assert_eq!(report.archived, 1);
+        assert_eq!(report.total_bullets, 1);
+
+        let storage = storage.read().await.clone();
+        let playbook = storage.load_playbook().await.unwrap();
+        let demoted = playbook.find_bullet(&failing_id).unwrap();
+        assert_eq!(demoted.metadata.importance, DEMOTED_IMPORTANCE);
+        assert!(playbook.find_bullet(&stale_id).is_none());
+
+        // 被归档的 bullet 写入了 archive 目录
+        let archive_path = report.archive_path.clone().unwrap();
+        let archived: Vec<Bullet> =
+            serde_json::from_str(&std::fs::read_to_string(archive_path).unwrap()).unwrap();
+        assert_eq!(archived[0].id, stale_id);
+
+        // 报告可被 status 读取；再次运行不会重复降级
+        let stats = optimizer.get_stats().await.unwrap();
+        assert_eq!(stats.demoted, 1);
+        assert_eq!(stats.last_run.unwrap().demoted, 1);
+        let report = optimizer.optimize().await.unwrap();
+        assert_eq!(report.demoted, 0);
+        assert_eq!(report.archived, 0);
+    }
+}
@@ -49,6 +49,13 @@ extract_tools = true
 # Extract error handling strategies
 extract_errors = true
 
+# Extraction mode: "rule" (regex-based, works offline) or "llm" (asks the
+# configured model for structured insights, falls back to "rule" on failure)
+mode = "rule"
+
+# Timeout in seconds for a single LLM reflector request
+llm_timeout_secs = 30
+
 # ============================================================================
 # Context Settings (Knowledge Retrieval)
 # ============================================================================
@@ -62,6 +69,75 @@ include_all_successes = true
 
 # Maximum characters for context injection
 max_context_chars = 4000
+
+# Maximum tokens ACE may inject per turn, counted with the model's tokenizer
+max_context_tokens = 1500
+
+# Per-section token quotas; sections not listed share the overall budget.
+# Code snippets fall back to a one-line summary when the full code does not fit.
+[ace.context.section_quotas]
+CodeSnippetsAndTemplates = 600
+
+# ============================================================================
+# Embedding Settings (Semantic Retrieval)
+# ============================================================================
+
+[ace.embedding]
+# Blend embedding similarity into retrieval so paraphrases are recalled
+enabled = false
+
+# "ollama" (local Ollama server, uses the built-in oss provider) or
+# "hashing" (deterministic, offline, no semantic understanding)
+provider = "ollama"
+
+# Ollama embedding model
+model = "nomic-embed-text"
+
+# Vector size for the "hashing" provider
+dimensions = 256
+
+# Weight of cosine similarity in the hybrid score (rest is keyword score)
+weight = 0.5
+
+# Minimum cosine similarity for bullets recalled by embeddings alone
+min_similarity = 0.45
+
+# ============================================================================
+# Scope Settings (Per-Project / Per-Language Knowledge)
+# ============================================================================
+
+[ace.scope]
+# Stamp learned bullets with the project (git remote) and language of the
+# session, and filter/boost retrieval by the current session's scope
+enabled = true
+
+# Only inject bullets learned in the current project (plus unscoped ones)
+project_only = false
+
+# Repo-local playbook layer, relative to the repository root. Commit it to
+# share curated bullets with your team. Set to "" to disable.
+repo_playbook_dir = ".codeace/playbook"
+
+# ============================================================================
+# Redaction Settings (Secrets and PII)
+# ============================================================================
+
+[ace.redaction]
+# Replace API keys, tokens, credentials and internal hostnames with
+# [REDACTED:<kind>] before anything is written to the playbook
+enabled = true
+
+# Also redact random-looking strings (mixed case + digits, high Shannon entropy)
+detect_high_entropy = true
+entropy_threshold = 4.0
+min_entropy_length = 24
+
+# Hostnames ending in these suffixes are treated as internal
+internal_host_suffixes = ["internal", "corp", "lan", "intranet", "local"]
+
+# Extra regexes to redact. If a pattern has a capture group named "secret",
+# only that group is replaced, e.g. 'employee-id: (?P<secret>\d+)'
+patterns = []
 "#;
 
 /// ACE configuration TOML representation (for serialization/deserialization)
@@ -199,6 +275,10 @@ mod tests {
         assert!(config.reflector.extract_patterns);
         assert!(config.reflector.extract_tools);
         assert!(config.reflector.extract_errors);
+        assert_eq!(config.reflector.mode, crate::ReflectorMode::Rule);
+        assert_eq!(config.embedding, crate::types::EmbeddingConfig::default());
+        assert_eq!(config.scope, crate::types::ScopeConfig::default());
+        assert_eq!(config.context, crate::types::ContextConfig::default());
 
         // File should be created
         assert!(loader.config_path().exists());
@@ -244,6 +324,11 @@ max_context_chars = 2000
         assert_eq!(config.context.max_recent_entries, 5);
         assert!(!config.context.include_all_successes);
         assert_eq!(config.context.max_context_chars, 2000);
+        assert_eq!(config.context.max_context_tokens, 1500);
+        assert_eq!(
+            config.context.section_quotas,
+            crate::types::ContextConfig::default().section_quotas
+        );
     }
 
     #[tokio::test]
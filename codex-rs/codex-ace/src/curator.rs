@@ -6,6 +6,8 @@
 use super::bullet_formatter::BulletContentBuilder;
 use super::code_analyzer::CodeAnalyzer;
 use super::content_classifier::ContentClassifier;
+use super::knowledge_scope::KnowledgeScope;
+use super::similarity::SimilarityCalculator;
 use super::types::Applicability;
 use super::types::Bullet;
 use super::types::BulletCodeContent;
@@ -13,7 +15,9 @@ use super::types::BulletMetadata;
 use super::types::BulletSection;
 use super::types::CuratorConfig;
 use super::types::DeltaContext;
+use super::types::DeltaOperation;
 use super::types::InsightCategory;
+use super::types::Playbook;
 use super::types::RawInsight;
 use super::types::SourceType;
 use anyhow::Result;
@@ -24,6 +28,9 @@ use regex::Regex;
 pub struct CuratorMVP {
     config: CuratorConfig,
     code_analyzer: CodeAnalyzer,
+
+    /// 当前会话的范围（设置后新 bullet 会打上范围）
+    scope: Option<KnowledgeScope>,
 }
 
 impl CuratorMVP {
@@ -31,17 +38,41 @@ impl CuratorMVP {
         Self {
             config,
             code_analyzer: CodeAnalyzer::new(),
+            scope: None,
         }
     }
 
-    /// 处理 insights，生成 delta
+    /// 设置会话范围，新生成的 bullets 据此打上项目/语言范围
+    pub fn with_scope(mut self, scope: KnowledgeScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// 处理 insights，生成 delta（不与已有 playbook 比较）
     ///
-    /// 这是 Curator 的核心方法，它接收一组 RawInsights，
-    /// 为每个 insight 生成对应的 Bullet，并返回 DeltaContext。
+    /// 等价于对空 playbook 调用 `process_insights_with_playbook`。
     pub async fn process_insights(
         &self,
         insights: Vec<RawInsight>,
         session_id: String,
+    ) -> Result<DeltaContext> {
+        self.process_insights_with_playbook(insights, session_id, &Playbook::new())
+            .await
+    }
+
+    /// 处理 insights，生成 delta
+    ///
+    /// 这是 Curator 的核心方法（grow-and-refine）：为每个 insight 生成候选
+    /// Bullet，并与 playbook 中同一 section 的已有 bullets 比较：
+    /// - 相似度 >= `update_threshold`：强化已有 bullet（Update）
+    /// - 相似度 >= `merge_threshold`：用新内容刷新已有 bullet（Merge）
+    /// - 其余同样相似的已有 bullets 并入保留的 bullet 后删除（Delete）
+    /// - 否则作为新 bullet 加入（Add）
+    pub async fn process_insights_with_playbook(
+        &self,
+        insights: Vec<RawInsight>,
+        session_id: String,
+        playbook: &Playbook,
     ) -> Result<DeltaContext> {
         let start = std::time::Instant::now();
         let mut delta = DeltaContext::new(session_id.clone());
@@ -76,18 +107,161 @@ impl CuratorMVP {
 
         delta.metadata.insights_processed = validated_insights.len();
 
-        // 3. 为每个验证通过的 insight 生成 bullet
+        // 3. 为每个验证通过的 insight 生成 bullet，并与已有 bullets 比较
         for insight in validated_insights {
+            let insight_content = insight.content.clone();
             let bullet = self.create_bullet_from_insight(insight, &session_id)?;
-            delta.new_bullets.push(bullet);
+
+            if self.config.dedup_enabled {
+                self.refine_into_delta(&mut delta, bullet, &insight_content, playbook);
+            } else {
+                delta.operations.push(DeltaOperation::Add {
+                    bullet_id: bullet.id.clone(),
+                });
+                delta.new_bullets.push(bullet);
+            }
         }
 
         delta.metadata.new_bullets_count = delta.new_bullets.len();
+        delta.metadata.updated_bullets_count = delta.updated_bullets.len();
+        delta.metadata.deleted_bullets_count = delta.deleted_bullet_ids.len();
         delta.metadata.processing_time_ms = start.elapsed().as_millis() as u64;
 
         Ok(delta)
     }
 
+    /// 将候选 bullet 与已有 bullets 比较，生成 add/update/merge/delete 操作
+    fn refine_into_delta(
+        &self,
+        delta: &mut DeltaContext,
+        candidate: Bullet,
+        insight_content: &str,
+        playbook: &Playbook,
+    ) {
+        let candidate_text = SimilarityCalculator::normalize_text(insight_content, true);
+
+        // 收集同 section 中足够相似的 bullets（已有的 + 本批次新增的）
+        let mut matches: Vec<(String, f32)> = playbook
+            .bullets_by_section(&candidate.section)
+            .into_iter()
+            .filter(|b| !delta.deleted_bullet_ids.contains(&b.id))
+            .filter(|b| Self::scopes_compatible(&candidate, b))
+            .chain(
+                delta
+                    .new_bullets
+                    .iter()
+                    .filter(|b| b.section == candidate.section),
+            )
+            .filter_map(|existing| {
+                let existing_text = SimilarityCalculator::normalize_text(
+                    Self::core_content(&existing.content),
+                    true,
+                );
+                let similarity =
+                    SimilarityCalculator::combined_similarity_v2(&candidate_text, &existing_text);
+                (similarity >= self.config.merge_threshold)
+                    .then(|| (existing.id.clone(), similarity))
+            })
+            .collect();
+
+        if matches.is_empty() {
+            delta.operations.push(DeltaOperation::Add {
+                bullet_id: candidate.id.clone(),
+            });
+            delta.new_bullets.push(candidate);
+            return;
+        }
+
+        matches.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        let (target_id, best_similarity) = matches[0].clone();
+
+        // 先把冗余的 bullets 从 delta 中取出（它们会被并入保留的 bullet）
+        let mut redundant = Vec::new();
+        for (id, _) in matches.iter().skip(1) {
+            if let Some(bullet) = Self::take_from_delta(delta, playbook, id) {
+                redundant.push(bullet);
+            }
+        }
+
+        let Some(mut target) = Self::take_from_delta(delta, playbook, &target_id) else {
+            delta.new_bullets.push(candidate);
+            return;
+        };
+        let target_is_new = playbook.find_bullet(&target_id).is_none();
+
+        // 强化 / 合并保留的 bullet
+        if best_similarity >= self.config.update_threshold {
+            delta.operations.push(DeltaOperation::Update {
+                bullet_id: target_id.clone(),
+                similarity: best_similarity,
+            });
+        } else {
+            target.content = candidate.content.clone();
+            if candidate.code_content.is_some() {
+                target.code_content = candidate.code_content.clone();
+            }
+            delta.operations.push(DeltaOperation::Merge {
+                target_id: target_id.clone(),
+                similarity: best_similarity,
+            });
+        }
+        delta.metadata.merged_bullets_count += 1;
+        target.absorb(&candidate);
+        target.increment_reference();
+        target.metadata.confidence =
+            (target.metadata.confidence + self.config.confidence_boost).min(1.0);
+
+        // 冗余 bullets 并入保留的 bullet 后删除
+        for bullet in redundant {
+            target.absorb(&bullet);
+            if playbook.find_bullet(&bullet.id).is_some() {
+                delta.deleted_bullet_ids.push(bullet.id.clone());
+            }
+            delta.operations.push(DeltaOperation::Delete {
+                bullet_id: bullet.id,
+                merged_into: target_id.clone(),
+            });
+        }
+
+        if target_is_new {
+            delta.new_bullets.push(target);
+        } else {
+            delta.updated_bullets.push(target);
+        }
+    }
+
+    /// 语言范围冲突的 bullets 不互相合并（例如 Python 与 Rust 项目中学到的同类经验）
+    fn scopes_compatible(candidate: &Bullet, existing: &Bullet) -> bool {
+        match (&candidate.metadata.scope, &existing.metadata.scope) {
+            (Some(candidate_scope), Some(existing_scope)) => {
+                candidate_scope.language_compatible(&existing_scope.language)
+            }
+            _ => true,
+        }
+    }
+
+    /// 从 delta（若已修改）或 playbook 中取出 bullet 的最新版本
+    fn take_from_delta(delta: &mut DeltaContext, playbook: &Playbook, id: &str) -> Option<Bullet> {
+        if let Some(pos) = delta.new_bullets.iter().position(|b| b.id == id) {
+            return Some(delta.new_bullets.remove(pos));
+        }
+        if let Some(pos) = delta.updated_bullets.iter().position(|b| b.id == id) {
+            return Some(delta.updated_bullets.remove(pos));
+        }
+        playbook.find_bullet(id).cloned()
+    }
+
+    /// 提取 bullet 的核心内容（解决思路部分），避免模板字段干扰相似度
+    fn core_content(content: &str) -> &str {
+        const MARKER: &str = "**Solution Approach**:\n";
+        let Some(start) = content.find(MARKER) else {
+            return content;
+        };
+        let rest = &content[start + MARKER.len()..];
+        let end = rest.find("\n\n**").unwrap_or(rest.len());
+        rest[..end].trim()
+    }
+
     /// 从 insight 创建 bullet
     fn create_bullet_from_insight(&self, insight: RawInsight, session_id: &str) -> Result<Bullet> {
         // 决定 section
@@ -246,6 +420,13 @@ impl CuratorMVP {
             last_recall: None,
             recall_contexts: Vec::new(),
             success_rate,
+            scope: self
+                .scope
+                .as_ref()
+                .map(|scope| KnowledgeScope::for_bullet(&insight.content, scope)),
+            pinned: false,
+            // 由 ACEPlugin 在合并前按会话和回合填写
+            provenance: None,
         };
 
         Ok(metadata)
@@ -365,7 +546,7 @@ impl CuratorMVP {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ace::types::InsightContext;
+    use crate::types::InsightContext;
 
     fn create_test_insight(
         content: &str,
@@ -589,6 +770,220 @@ mod tests {
         assert_eq!(delta.metadata.new_bullets_count, 0);
     }
 
+    #[tokio::test]
+    async fn test_curator_updates_duplicate_bullet() {
+        let curator = CuratorMVP::new(CuratorConfig::default());
+        let content = "使用 cargo test 命令可以运行项目的所有测试";
+
+        let first = curator
+            .process_insights(
+                vec![create_test_insight(
+                    content,
+                    InsightCategory::ToolUsage,
+                    true,
+                )],
+                "session-1".to_string(),
+            )
+            .await
+            .unwrap();
+        let mut playbook = Playbook::new();
+        let mut existing = first.new_bullets[0].clone();
+        existing.metadata.confidence = 0.5;
+        let existing_id = existing.id.clone();
+        playbook.add_bullet(existing);
+
+        let delta = curator
+            .process_insights_with_playbook(
+                vec![create_test_insight(
+                    content,
+                    InsightCategory::ToolUsage,
+                    false,
+                )],
+                "session-2".to_string(),
+                &playbook,
+            )
+            .await
+            .unwrap();
+
+        assert!(delta.new_bullets.is_empty());
+        assert_eq!(delta.updated_bullets.len(), 1);
+        assert_eq!(delta.metadata.merged_bullets_count, 1);
+
+        let updated = &delta.updated_bullets[0];
+        assert_eq!(updated.id, existing_id);
+        assert_eq!(updated.metadata.reference_count, 1);
+        assert_eq!(updated.metadata.success_count, 1);
+        assert_eq!(updated.metadata.failure_count, 1);
+        assert!(updated.metadata.confidence > 0.5);
+        assert!(matches!(
+            delta.operations.as_slice(),
+            [DeltaOperation::Update { bullet_id, .. }] if *bullet_id == existing_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_curator_stamps_scope_and_keeps_languages_apart() {
+        use crate::knowledge_scope::Domain;
+        use crate::knowledge_scope::Language;
+
+        let content = "使用 pytest -x 命令可以在第一个失败时停止测试运行";
+        let python_scope = KnowledgeScope::new(Domain::Generic, Language::Python)
+            .with_project("github.com/team/monorepo".to_string());
+        let python_curator = CuratorMVP::new(CuratorConfig::default()).with_scope(python_scope);
+
+        let first = python_curator
+            .process_insights(
+                vec![create_test_insight(
+                    content,
+                    InsightCategory::ToolUsage,
+                    true,
+                )],
+                "session-1".to_string(),
+            )
+            .await
+            .unwrap();
+        let scope = first.new_bullets[0].metadata.scope.clone().unwrap();
+        assert_eq!(scope.language, Language::Python);
+        assert_eq!(scope.project.as_deref(), Some("github.com/team/monorepo"));
+
+        let mut playbook = Playbook::new();
+        playbook.add_bullet(first.new_bullets[0].clone());
+
+        // 同样的经验在 Rust 项目中学到时不与 Python bullet 合并
+        let rust_curator = CuratorMVP::new(CuratorConfig::default()).with_scope(
+            KnowledgeScope::new(Domain::Generic, Language::Rust)
+                .with_project("github.com/team/service".to_string()),
+        );
+        let delta = rust_curator
+            .process_insights_with_playbook(
+                vec![create_test_insight(
+                    content,
+                    InsightCategory::ToolUsage,
+                    true,
+                )],
+                "session-2".to_string(),
+                &playbook,
+            )
+            .await
+            .unwrap();
+        assert!(delta.updated_bullets.is_empty());
+        assert_eq!(delta.new_bullets.len(), 1);
+        assert_eq!(
+            delta.new_bullets[0]
+                .metadata
+                .scope
+                .as_ref()
+                .unwrap()
+                .language,
+            Language::Rust
+        );
+    }
+
+    #[tokio::test]
+    async fn test_curator_merges_similar_bullet() {
+        let curator = CuratorMVP::new(CuratorConfig {
+            update_threshold: 1.1,
+            ..Default::default()
+        });
+
+        let first = curator
+            .process_insights(
+                vec![create_test_insight(
+                    "Run cargo test --workspace before committing changes to the Rust project",
+                    InsightCategory::ToolUsage,
+                    true,
+                )],
+                "session-1".to_string(),
+            )
+            .await
+            .unwrap();
+        let mut playbook = Playbook::new();
+        let existing_id = first.new_bullets[0].id.clone();
+        playbook.add_bullet(first.new_bullets[0].clone());
+
+        let delta = curator
+            .process_insights_with_playbook(
+                vec![create_test_insight(
+                    "Run cargo test --workspace before pushing changes to the Rust project",
+                    InsightCategory::ToolUsage,
+                    true,
+                )],
+                "session-2".to_string(),
+                &playbook,
+            )
+            .await
+            .unwrap();
+
+        assert!(delta.new_bullets.is_empty());
+        assert_eq!(delta.updated_bullets.len(), 1);
+        let merged = &delta.updated_bullets[0];
+        assert_eq!(merged.id, existing_id);
+        // 合并后内容刷新为较新的洞察
+        assert!(merged.content.contains("pushing changes"));
+        assert_eq!(merged.metadata.success_count, 2);
+        assert!(matches!(
+            delta.operations.as_slice(),
+            [DeltaOperation::Merge { target_id, .. }] if *target_id == existing_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_curator_deletes_redundant_bullets() {
+        let curator = CuratorMVP::new(CuratorConfig::default());
+        let content = "执行测试流程时应该先运行单元测试再运行集成测试";
+
+        let mut playbook = Playbook::new();
+        for session in ["session-1", "session-2"] {
+            let delta = curator
+                .process_insights(
+                    vec![create_test_insight(content, InsightCategory::Pattern, true)],
+                    session.to_string(),
+                )
+                .await
+                .unwrap();
+            playbook.add_bullet(delta.new_bullets[0].clone());
+        }
+        assert_eq!(playbook.metadata.total_bullets, 2);
+
+        let delta = curator
+            .process_insights_with_playbook(
+                vec![create_test_insight(content, InsightCategory::Pattern, true)],
+                "session-3".to_string(),
+                &playbook,
+            )
+            .await
+            .unwrap();
+
+        assert!(delta.new_bullets.is_empty());
+        assert_eq!(delta.updated_bullets.len(), 1);
+        assert_eq!(delta.deleted_bullet_ids.len(), 1);
+        assert_eq!(delta.metadata.deleted_bullets_count, 1);
+        assert_ne!(delta.updated_bullets[0].id, delta.deleted_bullet_ids[0]);
+        // 保留的 bullet 吸收了被删除 bullet 的统计信息
+        assert_eq!(delta.updated_bullets[0].metadata.success_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_curator_dedups_within_batch() {
+        let curator = CuratorMVP::new(CuratorConfig::default());
+        let content = "使用 cargo test 命令可以运行项目的所有测试";
+
+        let delta = curator
+            .process_insights(
+                vec![
+                    create_test_insight(content, InsightCategory::ToolUsage, true),
+                    create_test_insight(content, InsightCategory::ToolUsage, true),
+                ],
+                "test-session".to_string(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(delta.new_bullets.len(), 1);
+        assert_eq!(delta.new_bullets[0].metadata.reference_count, 1);
+        assert_eq!(delta.new_bullets[0].metadata.success_count, 2);
+    }
+
     #[tokio::test]
     async fn test_curator_processing_time() {
         let curator = CuratorMVP::new(CuratorConfig::default());
@@ -0,0 +1,363 @@
+//! 向量检索 - 可插拔的 Embedder 与向量缓存
+//!
+//! 关键词检索无法识别同义改写（"flaky test" 与 "intermittent CI failure"），
+//! 因此为 bullets 提供可选的向量索引：
+//! - `Embedder`: 文本 → 向量的抽象
+//! - `HashingEmbedder`: 确定性的特征哈希向量，无需网络，用于测试和离线环境
+//! - Ollama 实现位于 `codex-ollama` crate（它依赖 codex-core），由前端创建后
+//!   经 `ConversationManager::with_ace_embedder` 随会话传入
+//! - `EmbeddingCache`: 按 bullet ID + 内容哈希缓存向量并持久化到存储目录，
+//!   避免每次会话启动都重新计算
+
+use super::similarity::SimilarityCalculator;
+use super::types::Bullet;
+use super::types::EmbeddingConfig;
+use super::types::EmbeddingProvider;
+use anyhow::Context;
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 单次请求最多发送的文本数
+const EMBED_BATCH_SIZE: usize = 32;
+
+/// 参与向量计算的 bullet 内容最大字符数
+const MAX_EMBED_CHARS: usize = 2000;
+
+/// 文本向量化接口
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// 提供者标识（区分缓存，例如 `ollama:nomic-embed-text`）
+    fn id(&self) -> String;
+
+    /// 批量计算向量，返回顺序与输入一致
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// 根据配置选择 embedder（未启用或不可用时返回 None）
+///
+/// `ollama_embedder` 为前端按 `provider = "ollama"` 创建的 embedder，其他提供者忽略它。
+pub fn embedder_from_config(
+    config: &EmbeddingConfig,
+    ollama_embedder: Option<Arc<dyn Embedder>>,
+) -> Option<Arc<dyn Embedder>> {
+    if !config.enabled {
+        return None;
+    }
+
+    match config.provider {
+        EmbeddingProvider::Hashing => Some(Arc::new(HashingEmbedder::new(config.dimensions))),
+        EmbeddingProvider::Ollama => {
+            if ollama_embedder.is_none() {
+                tracing::warn!(
+                    "ACE embedding provider is ollama but no embedder was provided, using keyword retrieval only"
+                );
+            }
+            ollama_embedder
+        }
+    }
+}
+
+/// 确定性的特征哈希 embedder
+///
+/// 将归一化后的词和字符三元组哈希到固定维度（带符号），再做 L2 归一化。
+/// 能捕获词形变化和部分词重叠，但不理解语义；主要用于测试和无模型环境。
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    /// 创建 hashing embedder
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+        let normalized = SimilarityCalculator::normalize_text(text, true);
+
+        for token in normalized.split_whitespace() {
+            add_feature(&mut vector, token, 1.0);
+
+            // 字符三元组，捕获词形变化（test / tests / testing）
+            let chars: Vec<char> = format!("#{token}#").chars().collect();
+            for window in chars.windows(3) {
+                let gram: String = window.iter().collect();
+                add_feature(&mut vector, &gram, 0.5);
+            }
+        }
+
+        l2_normalize(&mut vector);
+        vector
+    }
+}
+
+#[async_trait]
+impl Embedder for HashingEmbedder {
+    fn id(&self) -> String {
+        format!("hashing:{}", self.dimensions)
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_one(text)).collect())
+    }
+}
+
+fn add_feature(vector: &mut [f32], feature: &str, weight: f32) {
+    let hash = fnv1a(feature.as_bytes());
+    let index = (hash % vector.len() as u64) as usize;
+    let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    vector[index] += sign * weight;
+}
+
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+}
+
+/// FNV-1a 64 位哈希（跨版本稳定，可用于持久化）
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// 余弦相似度（维度不一致或零向量时返回 0）
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let mut dot = 0.0;
+    let mut norm_a = 0.0;
+    let mut norm_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a.sqrt() * norm_b.sqrt())
+    }
+}
+
+/// bullet 参与向量计算的文本（内容 + 标签）
+fn embedding_text(bullet: &Bullet) -> String {
+    let mut text: String = bullet.content.chars().take(MAX_EMBED_CHARS).collect();
+    if !bullet.tags.is_empty() {
+        text.push('\n');
+        text.push_str(&bullet.tags.join(" "));
+    }
+    text
+}
+
+/// bullet 向量缓存
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EmbeddingCache {
+    /// 生成这些向量的 embedder（切换模型后缓存失效）
+    embedder_id: String,
+
+    entries: HashMap<String, CachedEmbedding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedEmbedding {
+    content_hash: u64,
+    vector: Vec<f32>,
+}
+
+impl EmbeddingCache {
+    /// 从磁盘加载缓存（不存在、损坏或 embedder 不一致时返回空缓存）
+    pub async fn load(path: &Path, embedder_id: &str) -> Self {
+        let empty = Self {
+            embedder_id: embedder_id.to_string(),
+            entries: HashMap::new(),
+        };
+
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(_) => return empty,
+        };
+
+        match serde_json::from_str::<Self>(&content) {
+            Ok(cache) if cache.embedder_id == embedder_id => cache,
+            Ok(_) => {
+                tracing::info!("Embedder changed, discarding cached bullet embeddings");
+                empty
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse embedding cache, rebuilding: {e}");
+                empty
+            }
+        }
+    }
+
+    /// 原子写入缓存
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self).context("Failed to serialize embedding cache")?;
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, json)
+            .await
+            .context("Failed to write embedding cache")?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .context("Failed to replace embedding cache")?;
+        Ok(())
+    }
+
+    /// 获取 bullet 的向量
+    pub fn vector(&self, bullet_id: &str) -> Option<&[f32]> {
+        self.entries
+            .get(bullet_id)
+            .map(|entry| entry.vector.as_slice())
+    }
+
+    /// 已缓存的向量数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 移除 bullet 的向量
+    pub fn remove(&mut self, bullet_id: &str) -> bool {
+        self.entries.remove(bullet_id).is_some()
+    }
+
+    /// 仅保留给定 ID 的向量
+    pub fn retain_ids(&mut self, ids: &HashSet<&str>) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|id, _| ids.contains(id.as_str()));
+        before != self.entries.len()
+    }
+
+    /// 为缺失或内容已变化的 bullets 计算向量
+    ///
+    /// 返回是否有新向量写入缓存。
+    pub async fn ensure(&mut self, embedder: &dyn Embedder, bullets: &[&Bullet]) -> Result<bool> {
+        let pending: Vec<(&Bullet, u64)> = bullets
+            .iter()
+            .map(|bullet| (*bullet, fnv1a(embedding_text(bullet).as_bytes())))
+            .filter(|(bullet, hash)| {
+                self.entries
+                    .get(&bullet.id)
+                    .is_none_or(|entry| entry.content_hash != *hash)
+            })
+            .collect();
+
+        if pending.is_empty() {
+            return Ok(false);
+        }
+
+        tracing::debug!("Embedding {} bullets with {}", pending.len(), embedder.id());
+
+        for chunk in pending.chunks(EMBED_BATCH_SIZE) {
+            let texts: Vec<String> = chunk
+                .iter()
+                .map(|(bullet, _)| embedding_text(bullet))
+                .collect();
+            let vectors = embedder.embed(&texts).await?;
+            anyhow::ensure!(
+                vectors.len() == chunk.len(),
+                "Embedder returned {} vectors for {} inputs",
+                vectors.len(),
+                chunk.len()
+            );
+
+            for ((bullet, content_hash), vector) in chunk.iter().zip(vectors) {
+                self.entries.insert(
+                    bullet.id.clone(),
+                    CachedEmbedding {
+                        content_hash: *content_hash,
+                        vector,
+                    },
+                );
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BulletSection;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_hashing_embedder_is_deterministic() {
+        let embedder = HashingEmbedder::new(64);
+        let texts = vec!["run cargo test".to_string(), "run cargo test".to_string()];
+        let vectors = embedder.embed(&texts).await.unwrap();
+
+        assert_eq!(vectors[0], vectors[1]);
+        assert_eq!(vectors[0].len(), 64);
+        assert!((cosine_similarity(&vectors[0], &vectors[1]) - 1.0).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn test_hashing_embedder_ranks_related_text_higher() {
+        let embedder = HashingEmbedder::new(256);
+        let texts = vec![
+            "flaky tests in CI".to_string(),
+            "retry the flaky test job".to_string(),
+            "docker image layers".to_string(),
+        ];
+        let vectors = embedder.embed(&texts).await.unwrap();
+
+        let related = cosine_similarity(&vectors[0], &vectors[1]);
+        let unrelated = cosine_similarity(&vectors[0], &vectors[2]);
+        assert!(related > unrelated);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_cache_reuses_unchanged_vectors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("embeddings.json");
+        let embedder = HashingEmbedder::new(32);
+
+        let mut bullet = Bullet::new(
+            BulletSection::General,
+            "Use cargo nextest".to_string(),
+            "session-1".to_string(),
+        );
+        let mut cache = EmbeddingCache::load(&path, &embedder.id()).await;
+        assert!(cache.ensure(&embedder, &[&bullet]).await.unwrap());
+        assert!(!cache.ensure(&embedder, &[&bullet]).await.unwrap());
+        cache.save(&path).await.unwrap();
+
+        // 重新加载后命中缓存
+        let mut cache = EmbeddingCache::load(&path, &embedder.id()).await;
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.ensure(&embedder, &[&bullet]).await.unwrap());
+
+        // 内容变化后重新计算
+        bullet.content = "Use cargo nextest with retries".to_string();
+        assert!(cache.ensure(&embedder, &[&bullet]).await.unwrap());
+
+        // 切换 embedder 后缓存失效
+        let cache = EmbeddingCache::load(&path, "hashing:64").await;
+        assert!(cache.is_empty());
+    }
+}
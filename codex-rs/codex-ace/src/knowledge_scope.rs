@@ -1,5 +1,31 @@
 // 跨领域知识图谱 - 智能管理跨项目、跨语言、跨行业的知识
+//
+// 会话开始时根据 cwd、git remote 和项目标记文件检测当前范围；
+// Curator 用它为新 bullet 打上范围，检索时据此过滤和加权。
+use crate::types::Bullet;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// `match_score` 的最大值
+const MAX_MATCH_SCORE: f32 = 4.5;
+
+/// 范围完全匹配时检索分数的最大加成比例
+const SCOPE_BOOST: f32 = 0.5;
+
+/// 项目标记文件 -> 语言
+const LANGUAGE_MARKERS: &[(&str, Language)] = &[
+    ("Cargo.toml", Language::Rust),
+    ("pyproject.toml", Language::Python),
+    ("setup.py", Language::Python),
+    ("requirements.txt", Language::Python),
+    ("tsconfig.json", Language::TypeScript),
+    ("package.json", Language::JavaScript),
+    ("go.mod", Language::Go),
+    ("pom.xml", Language::Java),
+    ("build.gradle", Language::Java),
+    ("build.gradle.kts", Language::Java),
+    ("CMakeLists.txt", Language::Cpp),
+];
 
 /// 领域分类
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -31,8 +57,19 @@ pub enum Language {
     Multi(Vec<String>), // 多语言（使用 String 避免递归）
 }
 
+impl Language {
+    /// 具体语言名称（`Generic` 为空）
+    fn names(&self) -> Vec<String> {
+        match self {
+            Language::Generic => Vec::new(),
+            Language::Multi(languages) => languages.clone(),
+            language => vec![format!("{language:?}")],
+        }
+    }
+}
+
 /// 知识范围 - 定义知识的适用范围
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct KnowledgeScope {
     pub domain: Domain,
     pub language: Language,
@@ -49,6 +86,46 @@ pub struct Context {
     pub query: String,
 }
 
+/// 检索范围 - 当前会话的范围及过滤策略
+#[derive(Debug, Clone)]
+pub struct RetrievalScope {
+    /// 当前会话的范围
+    pub session: KnowledgeScope,
+    /// 是否只注入当前项目（及未绑定项目）的 bullets
+    pub project_only: bool,
+}
+
+impl RetrievalScope {
+    /// 按会话范围调整 bullet 的检索分数
+    ///
+    /// 语言冲突（或 `project_only` 下项目不同）的 bullet 返回 `None`；
+    /// 其余按 `match_score` 加权，最多提高 `SCOPE_BOOST`。未打范围的旧 bullet 视为通用。
+    pub fn adjust_score(&self, bullet: &Bullet, query: &str, score: f32) -> Option<f32> {
+        let default_scope = KnowledgeScope::default();
+        let scope = bullet.metadata.scope.as_ref().unwrap_or(&default_scope);
+
+        if !scope.language_compatible(&self.session.language) {
+            return None;
+        }
+        if self.project_only
+            && let (Some(bullet_project), Some(session_project)) =
+                (&scope.project, &self.session.project)
+            && bullet_project != session_project
+        {
+            return None;
+        }
+
+        let context = Context {
+            domain: KnowledgeScope::detect_domain(query),
+            language: self.session.language.clone(),
+            project: self.session.project.clone(),
+            query: query.to_string(),
+        };
+        let match_ratio = (scope.match_score(&context) / MAX_MATCH_SCORE).min(1.0);
+        Some(score * (1.0 + SCOPE_BOOST * match_ratio))
+    }
+}
+
 impl Default for KnowledgeScope {
     fn default() -> Self {
         Self::new(Domain::Generic, Language::Generic)
@@ -78,6 +155,98 @@ impl KnowledgeScope {
         self
     }
 
+    /// 由工作目录和 git 信息确定范围
+    ///
+    /// 本 crate 不读取 git，调用方负责找到仓库根目录和 remote。
+    ///
+    /// - 项目：git remote（归一化为 `host/owner/repo`），没有 remote 时使用仓库目录名
+    /// - 语言：仓库根目录和 cwd 下的项目标记文件（`Cargo.toml`、`pyproject.toml` 等）
+    pub fn from_repo(cwd: &Path, repo_root: Option<&Path>, remote: Option<&str>) -> Self {
+        let project = remote.map(Self::normalize_remote).or_else(|| {
+            repo_root
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().to_string())
+        });
+
+        let mut dirs = vec![cwd];
+        if let Some(root) = repo_root
+            && root != cwd
+        {
+            dirs.push(root);
+        }
+
+        let mut scope = Self::new(Domain::Generic, Self::detect_language_in_dirs(&dirs));
+        scope.project = project;
+        scope
+    }
+
+    /// 新 bullet 的范围：项目和语言沿用会话范围，领域从内容检测
+    ///
+    /// 会话语言未知时才从内容检测语言（内容检测容易受自然语言干扰）。
+    pub fn for_bullet(content: &str, session: &KnowledgeScope) -> Self {
+        let language = match session.language {
+            Language::Generic => Self::detect_language(content),
+            ref language => language.clone(),
+        };
+        Self {
+            domain: Self::detect_domain(content),
+            language,
+            project: session.project.clone(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// 语言是否兼容（任一方为通用，或语言集合有交集）
+    pub fn language_compatible(&self, other: &Language) -> bool {
+        let ours = self.language.names();
+        let theirs = other.names();
+        ours.is_empty() || theirs.is_empty() || ours.iter().any(|name| theirs.contains(name))
+    }
+
+    /// 将 git remote 归一化为 `host/owner/repo`，使 https 与 ssh 地址一致
+    fn normalize_remote(url: &str) -> String {
+        let url = url.trim().trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url);
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let without_user = without_scheme
+            .split_once('@')
+            .map_or(without_scheme, |(_, rest)| rest);
+        // scp 风格：host:owner/repo
+        let normalized = match without_user.split_once(':') {
+            Some((host, path)) if !path.starts_with(|c: char| c.is_ascii_digit()) => {
+                format!("{host}/{path}")
+            }
+            Some((host, port_and_path)) => {
+                let path = port_and_path.split_once('/').map_or("", |(_, path)| path);
+                format!("{host}/{path}")
+            }
+            None => without_user.to_string(),
+        };
+        normalized.to_lowercase()
+    }
+
+    /// 根据项目标记文件检测语言，检测到多种时返回 `Language::Multi`
+    fn detect_language_in_dirs(dirs: &[&Path]) -> Language {
+        let mut languages: Vec<Language> = Vec::new();
+        for dir in dirs {
+            for (marker, language) in LANGUAGE_MARKERS {
+                if dir.join(marker).exists() && !languages.contains(language) {
+                    languages.push(language.clone());
+                }
+            }
+        }
+        // TypeScript 项目同时有 package.json
+        if languages.contains(&Language::TypeScript) {
+            languages.retain(|language| *language != Language::JavaScript);
+        }
+
+        match languages.len() {
+            0 => Language::Generic,
+            1 => languages.remove(0),
+            _ => Language::Multi(languages.iter().flat_map(Language::names).collect()),
+        }
+    }
+
     /// 自动检测领域
     pub fn detect_domain(content: &str) -> Domain {
         let content_lower = content.to_lowercase();
@@ -517,4 +686,113 @@ mod tests {
         assert!(score_rust > 1.0);
         assert!(score_python > 1.0);
     }
+
+    #[test]
+    fn test_normalize_remote() {
+        let expected = "github.com/uu114/codeace";
+        for url in [
+            "https://github.com/UU114/codeACE.git",
+            "git@github.com:UU114/codeACE.git",
+            "ssh://git@github.com:22/UU114/codeACE",
+            "https://github.com/UU114/codeACE/",
+        ] {
+            assert_eq!(KnowledgeScope::normalize_remote(url), expected, "{url}");
+        }
+    }
+
+    #[test]
+    fn test_detect_language_from_markers() {
+        let rust_dir = tempfile::tempdir().unwrap();
+        std::fs::write(rust_dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(
+            KnowledgeScope::detect_language_in_dirs(&[rust_dir.path()]),
+            Language::Rust
+        );
+
+        let ts_dir = tempfile::tempdir().unwrap();
+        std::fs::write(ts_dir.path().join("package.json"), "{}").unwrap();
+        std::fs::write(ts_dir.path().join("tsconfig.json"), "{}").unwrap();
+        assert_eq!(
+            KnowledgeScope::detect_language_in_dirs(&[ts_dir.path()]),
+            Language::TypeScript
+        );
+
+        // 多语言仓库
+        assert_eq!(
+            KnowledgeScope::detect_language_in_dirs(&[rust_dir.path(), ts_dir.path()]),
+            Language::Multi(vec!["Rust".to_string(), "TypeScript".to_string()])
+        );
+
+        let empty_dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            KnowledgeScope::detect_language_in_dirs(&[empty_dir.path()]),
+            Language::Generic
+        );
+    }
+
+    #[test]
+    fn test_retrieval_scope_filters_and_boosts() {
+        use crate::types::BulletSection;
+
+        let session = KnowledgeScope::new(Domain::Generic, Language::Rust)
+            .with_project("github.com/team/service".to_string());
+        let retrieval = RetrievalScope {
+            session: session.clone(),
+            project_only: false,
+        };
+
+        let bullet = |scope: Option<KnowledgeScope>| {
+            let mut bullet = Bullet::new(
+                BulletSection::General,
+                "Run the test suite before committing".to_string(),
+                "session-1".to_string(),
+            );
+            bullet.metadata.scope = scope;
+            bullet
+        };
+
+        // 其他语言的 bullet 被过滤
+        let python = bullet(Some(
+            KnowledgeScope::new(Domain::Generic, Language::Python)
+                .with_project("github.com/team/monorepo".to_string()),
+        ));
+        assert_eq!(retrieval.adjust_score(&python, "run tests", 1.0), None);
+
+        // 同项目同语言的 bullet 加权高于未打范围的旧 bullet
+        let same_project = bullet(Some(KnowledgeScope::for_bullet(
+            "Run the test suite before committing",
+            &session,
+        )));
+        let legacy = bullet(None);
+        let boosted = retrieval
+            .adjust_score(&same_project, "run tests", 1.0)
+            .unwrap();
+        let unscoped = retrieval.adjust_score(&legacy, "run tests", 1.0).unwrap();
+        assert!(boosted > unscoped);
+        assert!(unscoped >= 1.0);
+
+        // project_only 下其他项目的 bullet 被过滤
+        let other_project = bullet(Some(
+            KnowledgeScope::new(Domain::Generic, Language::Rust)
+                .with_project("github.com/team/cli".to_string()),
+        ));
+        assert!(
+            retrieval
+                .adjust_score(&other_project, "run tests", 1.0)
+                .is_some()
+        );
+        let project_only = RetrievalScope {
+            session,
+            project_only: true,
+        };
+        assert_eq!(
+            project_only.adjust_score(&other_project, "run tests", 1.0),
+            None
+        );
+        assert!(
+            project_only
+                .adjust_score(&legacy, "run tests", 1.0)
+                .is_some()
+        );
+    }
 }
@@ -2,12 +2,21 @@
 //!
 //! 提供基于内存的快速索引和搜索功能，无需外部数据库。
 
-use crate::ace::similarity::SimilarityCalculator;
-use crate::ace::types::{Bullet, BulletSection, Playbook};
+use crate::embedding::Embedder;
+use crate::embedding::EmbeddingCache;
+use crate::embedding::cosine_similarity;
+use crate::knowledge_scope::RetrievalScope;
+use crate::similarity::SimilarityCalculator;
+use crate::storage::BulletStorage;
+use crate::storage::StorageRevision;
+use crate::types::{Bullet, BulletSection, DeltaContext, EmbeddingConfig, Playbook};
+use anyhow::Result;
 use lru::LruCache;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::Mutex;
 
 /// 英文停用词表（与 storage.rs 保持一致）
 const STOP_WORDS: &[&str] = &[
@@ -140,7 +149,7 @@ impl LightweightIndex {
                 .push(bullet.id.clone());
 
             // 3. 关键词索引
-            let keywords = Self::extract_keywords(&bullet.content);
+            let keywords = Self::bullet_keywords(bullet);
             for keyword in keywords {
                 index
                     .keywords
@@ -214,6 +223,16 @@ impl LightweightIndex {
         keywords
     }
 
+    /// 提取 bullet 的索引关键词（内容 + 标签 + 相关工具）
+    fn bullet_keywords(bullet: &Bullet) -> Vec<String> {
+        let mut text = bullet.content.clone();
+        for extra in bullet.tags.iter().chain(&bullet.metadata.related_tools) {
+            text.push(' ');
+            text.push_str(extra);
+        }
+        Self::extract_keywords(&text)
+    }
+
     /// 计算文本相似度（使用高级相似度算法）
     ///
     /// 使用组合相似度算法，结合：
@@ -249,22 +268,20 @@ impl LightweightIndex {
     /// # 返回
     /// 排序后的 bullets 列表
     pub fn search(&mut self, query: &str, limit: usize) -> Vec<Arc<Bullet>> {
-        // 1. 提取查询关键词
-        let query_keywords = Self::extract_keywords(query);
-        if query_keywords.is_empty() {
-            tracing::warn!("查询关键词为空，返回空结果");
-            return Vec::new();
-        }
-
-        let mut candidates = HashSet::new();
-
-        // 2. 从倒排索引获取候选
-        for keyword in &query_keywords {
-            if let Some(bullet_ids) = self.keywords.get(keyword) {
-                candidates.extend(bullet_ids.clone());
-            }
-        }
+        self.search_scoped(query, limit, None)
+    }
 
+    /// 按会话范围搜索 bullets
+    ///
+    /// 与 `search` 相同，但会过滤与会话范围冲突的 bullets，并按范围匹配度加权。
+    pub fn search_scoped(
+        &mut self,
+        query: &str,
+        limit: usize,
+        scope: Option<&RetrievalScope>,
+    ) -> Vec<Arc<Bullet>> {
+        // 1-2. 提取查询关键词，从倒排索引获取候选
+        let candidates = self.keyword_candidates(query);
         if candidates.is_empty() {
             tracing::debug!("未找到匹配的 bullets");
             return Vec::new();
@@ -273,7 +290,7 @@ impl LightweightIndex {
         tracing::debug!("找到 {} 个候选 bullets", candidates.len());
 
         // 3. 计算相关性分数
-        let mut scored_results: Vec<(Arc<Bullet>, f32)> = candidates
+        let scored_results: Vec<(Arc<Bullet>, f32)> = candidates
             .iter()
             .filter_map(|id| {
                 // 先检查缓存
@@ -285,24 +302,129 @@ impl LightweightIndex {
                 self.by_id.get(id).cloned()
             })
             .map(|bullet| {
-                // 计算文本相似度分数
-                let text_score = Self::text_similarity(query, &bullet.content);
+                let score = Self::keyword_score(query, &bullet);
+                (bullet, score)
+            })
+            .collect();
 
-                // 计算动态权重分数（归一化到 0-1）
-                let weight_score = bullet.metadata.calculate_dynamic_weight();
-                let normalized_weight = (weight_score / 5.0).min(1.0); // 假设最大权重为 5
+        // 4-5. 排序、更新缓存并返回
+        self.take_top(scored_results, query, scope, limit)
+    }
 
-                // 综合分数：文本相似度占60%，权重占40%
-                let final_score = text_score * 0.6 + normalized_weight * 0.4;
+    /// 混合检索：关键词候选 ∪ 向量近邻，按加权分数排序
+    ///
+    /// 最终分数 = 关键词得分 × (1 - weight) + 余弦相似度 × weight。
+    /// 未命中关键词的 bullet 需要余弦相似度不低于 `min_similarity` 才会入选，
+    /// 从而召回同义改写（"flaky test" 与 "intermittent CI failure"）。
+    ///
+    /// # 参数
+    /// - `query`: 查询文本
+    /// - `query_vector`: 查询向量
+    /// - `embeddings`: bullet 向量缓存
+    /// - `weight`: 余弦相似度权重（0.0 - 1.0）
+    /// - `min_similarity`: 仅向量召回时的最低相似度
+    /// - `limit`: 返回的最大数量
+    /// - `scope`: 会话范围（可选，用于过滤和加权）
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_hybrid(
+        &mut self,
+        query: &str,
+        query_vector: &[f32],
+        embeddings: &EmbeddingCache,
+        weight: f32,
+        min_similarity: f32,
+        limit: usize,
+        scope: Option<&RetrievalScope>,
+    ) -> Vec<Arc<Bullet>> {
+        let weight = weight.clamp(0.0, 1.0);
+        let candidates = self.keyword_candidates(query);
+
+        let scored_results: Vec<(Arc<Bullet>, f32)> = self
+            .by_id
+            .iter()
+            .filter_map(|(id, bullet)| {
+                let cosine = embeddings
+                    .vector(id)
+                    .map(|vector| cosine_similarity(query_vector, vector))
+                    .unwrap_or(0.0);
+                let keyword_hit = candidates.contains(id);
+                if !keyword_hit && cosine < min_similarity {
+                    return None;
+                }
 
-                (bullet, final_score)
+                let keyword_score = if keyword_hit {
+                    Self::keyword_score(query, bullet)
+                } else {
+                    0.0
+                };
+                let score = keyword_score * (1.0 - weight) + cosine * weight;
+                Some((Arc::clone(bullet), score))
             })
             .collect();
 
-        // 4. 排序（降序）
+        tracing::debug!(
+            "混合检索: {} 个关键词候选, {} 个入选",
+            candidates.len(),
+            scored_results.len()
+        );
+
+        self.take_top(scored_results, query, scope, limit)
+    }
+
+    /// 从倒排索引获取关键词候选 ID
+    fn keyword_candidates(&self, query: &str) -> HashSet<String> {
+        let query_keywords = Self::extract_keywords(query);
+        if query_keywords.is_empty() {
+            tracing::warn!("查询关键词为空，返回空结果");
+            return HashSet::new();
+        }
+
+        let mut candidates = HashSet::new();
+        for keyword in &query_keywords {
+            if let Some(bullet_ids) = self.keywords.get(keyword) {
+                candidates.extend(bullet_ids.iter().cloned());
+            }
+        }
+        candidates
+    }
+
+    /// 关键词得分（0.0 - 1.0）
+    fn keyword_score(query: &str, bullet: &Bullet) -> f32 {
+        // 计算文本相似度分数
+        let text_score = Self::text_similarity(query, &bullet.content);
+
+        // 计算动态权重分数（归一化到 0-1）
+        let weight_score = bullet.metadata.calculate_dynamic_weight();
+        let normalized_weight = (weight_score / 5.0).min(1.0); // 假设最大权重为 5
+
+        // 综合分数：文本相似度占60%，权重占40%
+        text_score * 0.6 + normalized_weight * 0.4
+    }
+
+    /// 去掉被标记的 bullets，按会话范围过滤/加权后，按分数降序取前 N 个，并放入热缓存
+    fn take_top(
+        &mut self,
+        mut scored_results: Vec<(Arc<Bullet>, f32)>,
+        query: &str,
+        scope: Option<&RetrievalScope>,
+        limit: usize,
+    ) -> Vec<Arc<Bullet>> {
+        // 被用户标记的 bullets 在复查前不再返回
+        scored_results.retain(|(bullet, _)| !bullet.metadata.flagged);
+
+        if let Some(scope) = scope {
+            scored_results = scored_results
+                .into_iter()
+                .filter_map(|(bullet, score)| {
+                    scope
+                        .adjust_score(&bullet, query, score)
+                        .map(|score| (bullet, score))
+                })
+                .collect();
+        }
+
         scored_results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-        // 5. 更新缓存并返回
         let results: Vec<Arc<Bullet>> = scored_results
             .into_iter()
             .take(limit)
@@ -375,7 +497,7 @@ impl LightweightIndex {
             .or_default()
             .push(bullet_id.clone());
 
-        let keywords = Self::extract_keywords(&bullet.content);
+        let keywords = Self::bullet_keywords(&bullet);
         for keyword in keywords {
             self.keywords
                 .entry(keyword)
@@ -398,7 +520,7 @@ impl LightweightIndex {
             }
 
             // 从关键词索引移除
-            let keywords = Self::extract_keywords(&bullet.content);
+            let keywords = Self::bullet_keywords(&bullet);
             for keyword in keywords {
                 if let Some(ids) = self.keywords.get_mut(&keyword) {
                     ids.remove(bullet_id);
@@ -446,10 +568,273 @@ pub struct IndexStatistics {
     pub cache_size: usize,
 }
 
+/// 常驻内存的 playbook 索引
+///
+/// 由 `ACEPlugin` 在整个会话期间持有，替代每轮都全量读取、线性扫描的
+/// `BulletStorage::query_bullets`：
+/// - 首次查询时从 storage 构建索引
+/// - 本进程的 delta 通过 `merge_delta` 增量更新索引
+/// - 每次查询前比较存储 revision，发现其他会话/进程写入时重新构建
+/// - 配置了 embedder 时，同步维护 bullet 向量并使用混合评分
+pub struct PlaybookIndex {
+    storage: Arc<BulletStorage>,
+    state: Mutex<IndexState>,
+
+    /// 向量检索（可选）
+    semantic: Option<SemanticSearch>,
+}
+
+struct SemanticSearch {
+    embedder: Arc<dyn Embedder>,
+    cache_path: PathBuf,
+    weight: f32,
+    min_similarity: f32,
+}
+
+struct IndexState {
+    index: LightweightIndex,
+
+    /// 索引对应的存储 revision（None 表示尚未构建或已失效）
+    revision: Option<StorageRevision>,
+
+    /// bullet 向量（启用向量检索时使用）
+    embeddings: EmbeddingCache,
+
+    /// 向量缓存是否已从磁盘加载
+    embeddings_loaded: bool,
+}
+
+impl PlaybookIndex {
+    /// 创建索引（延迟到首次查询时构建）
+    pub fn new(storage: Arc<BulletStorage>) -> Self {
+        Self {
+            storage,
+            state: Mutex::new(IndexState {
+                index: LightweightIndex::new(),
+                revision: None,
+                embeddings: EmbeddingCache::default(),
+                embeddings_loaded: false,
+            }),
+            semantic: None,
+        }
+    }
+
+    /// 启用向量检索
+    ///
+    /// # 参数
+    /// - `embedder`: 向量提供者
+    /// - `cache_path`: 向量缓存文件路径
+    /// - `config`: 混合评分参数
+    pub fn with_embedder(
+        mut self,
+        embedder: Arc<dyn Embedder>,
+        cache_path: PathBuf,
+        config: &EmbeddingConfig,
+    ) -> Self {
+        tracing::info!("ACE semantic retrieval enabled ({})", embedder.id());
+        self.semantic = Some(SemanticSearch {
+            embedder,
+            cache_path,
+            weight: config.weight,
+            min_similarity: config.min_similarity,
+        });
+        self
+    }
+
+    /// 搜索相关 bullets
+    ///
+    /// 启用向量检索时使用混合评分；查询向量计算失败（例如 Ollama 未运行）
+    /// 时回退到纯关键词检索。
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Bullet>> {
+        self.search_scoped(query, limit, None).await
+    }
+
+    /// 按会话范围搜索相关 bullets（过滤语言冲突的 bullets，并按范围匹配度加权）
+    pub async fn search_scoped(
+        &self,
+        query: &str,
+        limit: usize,
+        scope: Option<&RetrievalScope>,
+    ) -> Result<Vec<Bullet>> {
+        let mut guard = self.state.lock().await;
+        self.refresh_if_stale(&mut guard).await?;
+
+        let query_vector = match &self.semantic {
+            Some(semantic) => Self::query_vector(semantic, query).await,
+            None => None,
+        };
+
+        let state = &mut *guard;
+        let results = match (&self.semantic, query_vector) {
+            (Some(semantic), Some(query_vector)) => state.index.search_hybrid(
+                query,
+                &query_vector,
+                &state.embeddings,
+                semantic.weight,
+                semantic.min_similarity,
+                limit,
+                scope,
+            ),
+            _ => state.index.search_scoped(query, limit, scope),
+        };
+
+        Ok(results
+            .into_iter()
+            .map(|bullet| bullet.as_ref().clone())
+            .collect())
+    }
+
+    /// 合并 delta 到存储，并增量更新索引
+    ///
+    /// 如果合并期间有其他写入（revision 不连续）或触发了归档，
+    /// 则将索引标记为失效，下次查询时重新构建。
+    pub async fn merge_delta(&self, delta: DeltaContext) -> Result<()> {
+        let outcome = self.storage.merge_delta_tracked(delta.clone()).await?;
+
+        let mut state = self.state.lock().await;
+        if outcome.archived || state.revision.as_ref() != Some(&outcome.revision_before) {
+            state.revision = None;
+            return Ok(());
+        }
+
+        for id in &delta.deleted_bullet_ids {
+            state.index.remove_bullet(id);
+        }
+        for bullet in delta.new_bullets {
+            state.index.add_bullet(bullet);
+        }
+        for bullet in delta.updated_bullets {
+            // 存储中不存在的 bullet 不会被合并，索引同样跳过
+            if state.index.by_id.contains_key(&bullet.id) {
+                state.index.remove_bullet(&bullet.id);
+                state.index.add_bullet(bullet);
+            }
+        }
+        state.revision = Some(outcome.revision_after);
+        self.sync_embeddings(&mut state).await;
+
+        Ok(())
+    }
+
+    /// 在存储中原地修改 bullets（如记录召回），并增量更新索引
+    ///
+    /// 返回修改后的 bullets；不存在的 ID 会被跳过。
+    pub async fn update_bullets_with<F>(
+        &self,
+        session_id: String,
+        ids: Vec<String>,
+        f: F,
+    ) -> Result<Vec<Bullet>>
+    where
+        F: FnMut(&mut Bullet) + Send + 'static,
+    {
+        let (outcome, updated) = self.storage.update_bullets_with(session_id, ids, f).await?;
+
+        let mut state = self.state.lock().await;
+        if state.revision.as_ref() != Some(&outcome.revision_before) {
+            state.revision = None;
+            return Ok(updated);
+        }
+
+        for bullet in &updated {
+            state.index.remove_bullet(&bullet.id);
+            state.index.add_bullet(bullet.clone());
+        }
+        state.revision = Some(outcome.revision_after);
+
+        Ok(updated)
+    }
+
+    /// 重写整个 playbook（后台优化等维护任务）
+    ///
+    /// 发生变更时索引失效，在下次查询时重新构建。
+    pub async fn rewrite_playbook<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Playbook) -> Result<T> + Send + 'static,
+    {
+        let (outcome, output) = self.storage.rewrite_playbook(f).await?;
+        if outcome.revision_after != outcome.revision_before {
+            self.state.lock().await.revision = None;
+        }
+        Ok(output)
+    }
+
+    /// 底层存储
+    pub fn storage(&self) -> &Arc<BulletStorage> {
+        &self.storage
+    }
+
+    /// 获取索引统计信息
+    pub async fn statistics(&self) -> IndexStatistics {
+        self.state.lock().await.index.statistics()
+    }
+
+    async fn refresh_if_stale(&self, state: &mut IndexState) -> Result<()> {
+        let revision = self.storage.revision().await?;
+        if state.revision.as_ref() == Some(&revision) {
+            return Ok(());
+        }
+
+        let playbook = self.storage.load_playbook().await?;
+        state.index = LightweightIndex::build_from_playbook(&playbook);
+        state.revision = Some(revision);
+        self.sync_embeddings(state).await;
+        Ok(())
+    }
+
+    /// 为新增/变化的 bullets 计算向量，移除已删除 bullets 的向量
+    ///
+    /// 失败时仅记录警告：缺少向量的 bullet 仍可通过关键词召回。
+    async fn sync_embeddings(&self, state: &mut IndexState) {
+        let Some(semantic) = &self.semantic else {
+            return;
+        };
+
+        if !state.embeddings_loaded {
+            state.embeddings =
+                EmbeddingCache::load(&semantic.cache_path, &semantic.embedder.id()).await;
+            state.embeddings_loaded = true;
+        }
+
+        let bullets: Vec<&Bullet> = state
+            .index
+            .by_id
+            .values()
+            .map(|bullet| bullet.as_ref())
+            .collect();
+        let ids: HashSet<&str> = bullets.iter().map(|bullet| bullet.id.as_str()).collect();
+        let mut changed = state.embeddings.retain_ids(&ids);
+
+        match state
+            .embeddings
+            .ensure(semantic.embedder.as_ref(), &bullets)
+            .await
+        {
+            Ok(embedded) => changed |= embedded,
+            Err(e) => tracing::warn!("Failed to embed bullets, keyword retrieval only: {e:#}"),
+        }
+
+        if changed && let Err(e) = state.embeddings.save(&semantic.cache_path).await {
+            tracing::warn!("Failed to save embedding cache: {e:#}");
+        }
+    }
+
+    async fn query_vector(semantic: &SemanticSearch, query: &str) -> Option<Vec<f32>> {
+        match semantic.embedder.embed(&[query.to_string()]).await {
+            Ok(mut vectors) => vectors.pop(),
+            Err(e) => {
+                tracing::warn!("Failed to embed query, keyword retrieval only: {e:#}");
+                None
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ace::types::{Bullet, BulletSection, Playbook};
+    use crate::types::{Bullet, BulletSection, Playbook};
 
     fn create_test_playbook() -> Playbook {
         let mut playbook = Playbook::new();
@@ -626,4 +1011,102 @@ mod tests {
         assert!(stats.total_keywords > 0);
         assert!(stats.total_sections > 0);
     }
+
+    #[tokio::test]
+    async fn test_playbook_index_incremental_merge() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(BulletStorage::new(temp_dir.path(), 100).unwrap());
+        let index = PlaybookIndex::new(Arc::clone(&storage));
+
+        assert!(index.search("cargo", 5).await.unwrap().is_empty());
+
+        let bullet = Bullet::new(
+            BulletSection::ToolUsageTips,
+            "使用 cargo nextest 加速测试".to_string(),
+            "test-session".to_string(),
+        );
+        let bullet_id = bullet.id.clone();
+        let mut delta = DeltaContext::new("test-session".to_string());
+        delta.new_bullets.push(bullet);
+        index.merge_delta(delta).await.unwrap();
+
+        // 增量更新后索引保持有效，无需重建
+        assert!(index.state.lock().await.revision.is_some());
+        let results = index.search("cargo nextest", 5).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, bullet_id);
+
+        let mut delta = DeltaContext::new("test-session".to_string());
+        delta.deleted_bullet_ids.push(bullet_id);
+        index.merge_delta(delta).await.unwrap();
+        assert!(index.search("cargo nextest", 5).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_playbook_index_detects_external_writes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(BulletStorage::new(temp_dir.path(), 100).unwrap());
+        let index = PlaybookIndex::new(Arc::clone(&storage));
+        assert!(index.search("docker", 5).await.unwrap().is_empty());
+
+        // 另一个会话通过独立的 storage 写入
+        let other = BulletStorage::new(temp_dir.path(), 100).unwrap();
+        let mut bullet = Bullet::new(
+            BulletSection::General,
+            "多阶段构建减小镜像体积".to_string(),
+            "other-session".to_string(),
+        );
+        bullet.tags = vec!["docker".to_string()];
+        let mut delta = DeltaContext::new("other-session".to_string());
+        delta.new_bullets.push(bullet);
+        other.merge_delta(delta).await.unwrap();
+
+        // 标签同样被索引
+        let results = index.search("docker", 5).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_playbook_index_hybrid_recalls_without_keyword_overlap() {
+        use crate::embedding::HashingEmbedder;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(BulletStorage::new(temp_dir.path(), 100).unwrap());
+        let config = EmbeddingConfig {
+            enabled: true,
+            min_similarity: 0.2,
+            ..Default::default()
+        };
+        let index = PlaybookIndex::new(Arc::clone(&storage)).with_embedder(
+            Arc::new(HashingEmbedder::new(256)),
+            temp_dir.path().join("embeddings.json"),
+            &config,
+        );
+
+        let related = Bullet::new(
+            BulletSection::General,
+            "Add retries".to_string(),
+            "test-session".to_string(),
+        );
+        let related_id = related.id.clone();
+        let mut delta = DeltaContext::new("test-session".to_string());
+        delta.new_bullets.push(related);
+        delta.new_bullets.push(Bullet::new(
+            BulletSection::General,
+            "Docker image layers".to_string(),
+            "test-session".to_string(),
+        ));
+        index.merge_delta(delta).await.unwrap();
+
+        // 关键词检索无法匹配（retrying → retry，retries → retrie）
+        let mut keyword_only =
+            LightweightIndex::build_from_playbook(&storage.load_playbook().await.unwrap());
+        assert!(keyword_only.search("retrying", 5).is_empty());
+
+        // 向量召回
+        let results = index.search("retrying", 5).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, related_id);
+        assert!(temp_dir.path().join("embeddings.json").exists());
+    }
 }
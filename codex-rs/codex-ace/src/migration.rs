@@ -0,0 +1,288 @@
+//! 旧版 SimpleStorage playbook 迁移
+//!
+//! 早期的 `codex-ace` 使用 `<storage_path>/playbook.jsonl` 保存整段对话条目
+//! （每行一个 `PlaybookEntry`）。迁移时把其中的洞察和策略转换为 bullets 合并进
+//! `BulletStorage`，然后将旧文件重命名为 `playbook.jsonl.migrated`，保证只迁移一次。
+
+use crate::storage::BulletStorage;
+use crate::types::Bullet;
+use crate::types::BulletSection;
+use crate::types::DeltaContext;
+use crate::types::SourceType;
+use anyhow::Context;
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// 旧版 playbook 文件名
+pub const LEGACY_PLAYBOOK_FILE: &str = "playbook.jsonl";
+
+/// 迁移完成后旧文件的新名字
+pub const MIGRATED_PLAYBOOK_FILE: &str = "playbook.jsonl.migrated";
+
+/// 迁移生成的 delta 使用的会话标识
+const MIGRATION_SESSION_ID: &str = "simple-storage-migration";
+
+/// 旧版条目（只保留迁移需要的字段）
+#[derive(Debug, Deserialize)]
+struct LegacyEntry {
+    #[serde(default)]
+    timestamp: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    session_id: String,
+
+    #[serde(default)]
+    insights: Vec<LegacyInsight>,
+
+    #[serde(default)]
+    learned_strategies: Vec<String>,
+
+    #[serde(default)]
+    tools_used: Vec<String>,
+
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyInsight {
+    content: String,
+    category: LegacyCategory,
+    #[serde(default)]
+    importance: f32,
+}
+
+#[derive(Debug, Deserialize)]
+enum LegacyCategory {
+    ToolUsage,
+    Pattern,
+    Solution,
+    Knowledge,
+    ErrorHandling,
+}
+
+impl LegacyCategory {
+    fn section(&self) -> BulletSection {
+        match self {
+            LegacyCategory::ToolUsage => BulletSection::ToolUsageTips,
+            LegacyCategory::Pattern => BulletSection::StrategiesAndRules,
+            LegacyCategory::Solution => BulletSection::TroubleshootingAndPitfalls,
+            LegacyCategory::Knowledge => BulletSection::General,
+            LegacyCategory::ErrorHandling => BulletSection::ErrorHandlingPatterns,
+        }
+    }
+
+    fn source_type(&self) -> SourceType {
+        match self {
+            LegacyCategory::Pattern => SourceType::PatternRecognition,
+            LegacyCategory::ErrorHandling => SourceType::ErrorResolution,
+            _ => SourceType::SuccessExecution,
+        }
+    }
+}
+
+/// 迁移结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// 成功解析的旧条目数
+    pub entries: usize,
+
+    /// 无法解析而跳过的行数
+    pub skipped_lines: usize,
+
+    /// 写入的 bullets 数
+    pub bullets: usize,
+
+    /// 旧文件重命名后的路径
+    pub migrated_file: PathBuf,
+}
+
+/// 存储目录下是否还有待迁移的旧版 playbook
+pub fn has_legacy_playbook(storage_dir: &Path) -> bool {
+    storage_dir.join(LEGACY_PLAYBOOK_FILE).is_file()
+}
+
+/// 将旧版 playbook 迁移到 `storage`（存储目录即 `storage.base_dir()`）
+///
+/// 没有旧文件时返回 `None`。同一分类下内容相同的洞察只保留一条。
+pub async fn migrate_simple_storage(storage: &BulletStorage) -> Result<Option<MigrationReport>> {
+    let legacy_path = storage.base_dir().join(LEGACY_PLAYBOOK_FILE);
+    if !legacy_path.is_file() {
+        return Ok(None);
+    }
+
+    let text = tokio::fs::read_to_string(&legacy_path)
+        .await
+        .with_context(|| format!("Failed to read {}", legacy_path.display()))?;
+
+    let mut report = MigrationReport::default();
+    let mut delta = DeltaContext::new(MIGRATION_SESSION_ID.to_string());
+    let mut seen = HashSet::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: LegacyEntry = match serde_json::from_str(line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping unreadable line {} in {}: {e}",
+                    index + 1,
+                    legacy_path.display()
+                );
+                report.skipped_lines += 1;
+                continue;
+            }
+        };
+        report.entries += 1;
+
+        let insights = entry
+            .insights
+            .iter()
+            .map(|insight| {
+                (
+                    insight.category.section(),
+                    insight.content.as_str(),
+                    insight.importance,
+                    insight.category.source_type(),
+                )
+            })
+            .chain(entry.learned_strategies.iter().map(|strategy| {
+                (
+                    BulletSection::StrategiesAndRules,
+                    strategy.as_str(),
+                    0.5,
+                    SourceType::SuccessExecution,
+                )
+            }));
+        for (section, content, importance, source_type) in insights {
+            let content = content.trim();
+            if content.is_empty() || !seen.insert((section.clone(), content.to_string())) {
+                continue;
+            }
+            delta.new_bullets.push(legacy_bullet(
+                &entry,
+                section,
+                content,
+                importance,
+                source_type,
+            ));
+        }
+    }
+
+    report.bullets = delta.new_bullets.len();
+    delta.metadata.new_bullets_count = report.bullets;
+    storage.merge_delta(delta).await?;
+
+    report.migrated_file = storage.base_dir().join(MIGRATED_PLAYBOOK_FILE);
+    tokio::fs::rename(&legacy_path, &report.migrated_file)
+        .await
+        .with_context(|| format!("Failed to rename {}", legacy_path.display()))?;
+
+    tracing::info!(
+        "Migrated {} legacy playbook entries into {} bullets",
+        report.entries,
+        report.bullets
+    );
+    Ok(Some(report))
+}
+
+fn legacy_bullet(
+    entry: &LegacyEntry,
+    section: BulletSection,
+    content: &str,
+    importance: f32,
+    source_type: SourceType,
+) -> Bullet {
+    let session_id = if entry.session_id.is_empty() {
+        MIGRATION_SESSION_ID.to_string()
+    } else {
+        entry.session_id.clone()
+    };
+    let mut bullet = Bullet::new(section, content.to_string(), session_id);
+    if let Some(timestamp) = entry.timestamp {
+        bullet.created_at = timestamp;
+        bullet.updated_at = timestamp;
+    }
+    bullet.metadata.importance = importance.clamp(0.0, 1.0);
+    bullet.metadata.source_type = source_type;
+    bullet.metadata.related_tools = entry.tools_used.clone();
+    bullet.tags = entry.tags.clone();
+    bullet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_migrate_simple_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let legacy = [
+            serde_json::json!({
+                "id": "1",
+                "timestamp": "2025-01-01T00:00:00Z",
+                "session_id": "s1",
+                "user_query": "build the project",
+                "assistant_response": "done",
+                "execution_success": true,
+                "insights": [
+                    {"content": "Run cargo build before cargo test", "category": "ToolUsage", "importance": 0.8},
+                    {"content": "Retry flaky network calls", "category": "ErrorHandling", "importance": 0.6}
+                ],
+                "patterns": [],
+                "learned_strategies": ["Keep commits small"],
+                "tools_used": ["cargo"],
+                "error_messages": [],
+                "tags": ["rust"]
+            })
+            .to_string(),
+            "not json".to_string(),
+            serde_json::json!({
+                "session_id": "s2",
+                "insights": [
+                    {"content": "Run cargo build before cargo test", "category": "ToolUsage", "importance": 0.8}
+                ]
+            })
+            .to_string(),
+        ]
+        .join("\n");
+        std::fs::write(temp_dir.path().join(LEGACY_PLAYBOOK_FILE), legacy).unwrap();
+
+        let storage = BulletStorage::new(temp_dir.path(), 100).unwrap();
+        assert!(has_legacy_playbook(temp_dir.path()));
+        let report = migrate_simple_storage(&storage).await.unwrap().unwrap();
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.skipped_lines, 1);
+        assert_eq!(report.bullets, 3);
+        assert!(report.migrated_file.is_file());
+        assert!(!has_legacy_playbook(temp_dir.path()));
+
+        let playbook = storage.load_playbook().await.unwrap();
+        let mut sections: Vec<BulletSection> = playbook
+            .bullets
+            .values()
+            .flatten()
+            .map(|bullet| bullet.section.clone())
+            .collect();
+        sections.sort();
+        assert_eq!(
+            sections,
+            vec![
+                BulletSection::StrategiesAndRules,
+                BulletSection::ErrorHandlingPatterns,
+                BulletSection::ToolUsageTips,
+            ]
+        );
+
+        // 已迁移，再次调用不做任何事
+        assert_eq!(migrate_simple_storage(&storage).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! Bullet 来源追溯
+//!
+//! 新 bullet 的元数据中记录生成它的会话 ID、回合 ID 和 rollout 文件
+//! （见 [`BulletProvenance`](super::types::BulletProvenance)）。
+//! 这里从 rollout（JSONL）中还原该回合的对话和工具调用，供 `codex ace explain` 展示，
+//! 便于评审学到的规则是否可信。
+
+use anyhow::Context;
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use std::path::Path;
+
+/// 回合中的一条记录
+#[derive(Debug, Clone, PartialEq)]
+pub enum TurnEntry {
+    /// 用户消息
+    User(String),
+
+    /// 模型回复
+    Assistant(String),
+
+    /// 工具调用
+    ToolCall { name: String, arguments: String },
+
+    /// 工具输出
+    ToolOutput(String),
+}
+
+/// rollout 中的一个用户回合
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnTranscript {
+    /// 回合 ID（旧 rollout 没有记录时为 None）
+    pub turn_id: Option<String>,
+
+    /// 回合开始时间（rollout 行的时间戳）
+    pub started_at: String,
+
+    /// 按顺序排列的记录
+    pub entries: Vec<TurnEntry>,
+}
+
+impl TurnTranscript {
+    fn started_before(&self, time: DateTime<Utc>) -> bool {
+        // 无法解析的时间戳视为满足条件
+        !DateTime::parse_from_rfc3339(&self.started_at).is_ok_and(|started_at| started_at > time)
+    }
+}
+
+/// 把 rollout 拆分为用户回合
+///
+/// 每条用户消息开始一个新回合；回合 ID 取其后第一条 `turn_context` 中的 `turn_id`。
+/// 第一条用户消息之前的内容（会话元数据、恢复前的历史）被忽略。
+pub fn split_turns(lines: impl IntoIterator<Item = RolloutLine>) -> Vec<TurnTranscript> {
+    let mut turns: Vec<TurnTranscript> = Vec::new();
+    for line in lines {
+        match line.item {
+            RolloutItem::EventMsg(EventMsg::UserMessage(event)) => turns.push(TurnTranscript {
+                turn_id: None,
+                started_at: line.timestamp,
+                entries: vec![TurnEntry::User(event.message)],
+            }),
+            item => {
+                let Some(turn) = turns.last_mut() else {
+                    continue;
+                };
+                match item {
+                    RolloutItem::TurnContext(context) => {
+                        if turn.turn_id.is_none() {
+                            turn.turn_id = context.turn_id;
+                        }
+                    }
+                    RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+                        turn.entries.push(TurnEntry::Assistant(event.message));
+                    }
+                    RolloutItem::ResponseItem(item) => turn.entries.extend(tool_entry(item)),
+                    _ => {}
+                }
+            }
+        }
+    }
+    turns
+}
+
+/// 工具调用和输出；其他响应项（消息已由事件记录）忽略
+fn tool_entry(item: ResponseItem) -> Option<TurnEntry> {
+    match item {
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => Some(TurnEntry::ToolCall { name, arguments }),
+        ResponseItem::CustomToolCall { name, input, .. } => Some(TurnEntry::ToolCall {
+            name,
+            arguments: input,
+        }),
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => Some(TurnEntry::ToolCall {
+            name: "local_shell".to_string(),
+            arguments: exec.command.join(" "),
+        }),
+        ResponseItem::FunctionCallOutput { output, .. } => {
+            Some(TurnEntry::ToolOutput(output.content))
+        }
+        ResponseItem::CustomToolCallOutput { output, .. } => Some(TurnEntry::ToolOutput(output)),
+        _ => None,
+    }
+}
+
+/// 查找生成 bullet 的回合
+///
+/// 恢复的会话会从头编号，同一 rollout 中回合 ID 可能重复，
+/// 因此取 `created_at` 之前开始的最后一个匹配回合。
+/// 没有回合 ID（旧 bullet）时取 `created_at` 之前开始的最后一个回合。
+pub fn find_turn(
+    turns: Vec<TurnTranscript>,
+    turn_id: Option<&str>,
+    created_at: DateTime<Utc>,
+) -> Option<TurnTranscript> {
+    let mut matching: Vec<TurnTranscript> = turns
+        .into_iter()
+        .filter(|turn| turn_id.is_none_or(|id| turn.turn_id.as_deref() == Some(id)))
+        .collect();
+    match matching
+        .iter()
+        .rposition(|turn| turn.started_before(created_at))
+    {
+        Some(index) => Some(matching.swap_remove(index)),
+        // 按 ID 匹配但时间对不上（例如时钟不一致）时退回第一个匹配回合
+        None if turn_id.is_some() && !matching.is_empty() => Some(matching.swap_remove(0)),
+        None => None,
+    }
+}
+
+/// 读取 rollout 文件并查找回合
+///
+/// 无法解析的行被跳过。
+pub async fn load_turn(
+    rollout_path: &Path,
+    turn_id: Option<&str>,
+    created_at: DateTime<Utc>,
+) -> Result<Option<TurnTranscript>> {
+    let contents = tokio::fs::read_to_string(rollout_path)
+        .await
+        .with_context(|| format!("Failed to read rollout {}", rollout_path.display()))?;
+    let lines = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok());
+    Ok(find_turn(split_turns(lines), turn_id, created_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::AskForApproval;
+    use codex_protocol::protocol::SandboxPolicy;
+    use codex_protocol::protocol::TurnContextItem;
+    use codex_protocol::protocol::UserMessageEvent;
+    use std::path::PathBuf;
+
+    fn line(timestamp: &str, item: RolloutItem) -> RolloutLine {
+        RolloutLine {
+            timestamp: timestamp.to_string(),
+            item,
+        }
+    }
+
+    fn user(timestamp: &str, message: &str) -> RolloutLine {
+        line(
+            timestamp,
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: message.to_string(),
+                images: None,
+            })),
+        )
+    }
+
+    fn turn_context(timestamp: &str, turn_id: &str) -> RolloutLine {
+        line(
+            timestamp,
+            RolloutItem::TurnContext(TurnContextItem {
+                cwd: PathBuf::from("/repo"),
+                approval_policy: AskForApproval::Never,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                model: "gpt-5".to_string(),
+                effort: None,
+                summary: Default::default(),
+                turn_id: Some(turn_id.to_string()),
+            }),
+        )
+    }
+
+    fn rollout() -> Vec<RolloutLine> {
+        vec![
+            user("2025-01-01T10:00:00.000Z", "run the tests"),
+            turn_context("2025-01-01T10:00:00.100Z", "1"),
+            line(
+                "2025-01-01T10:00:01.000Z",
+                RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                    id: None,
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":["cargo","test"]}"#.to_string(),
+                    call_id: "call-1".to_string(),
+                }),
+            ),
+            line(
+                "2025-01-01T10:00:05.000Z",
+                RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                    call_id: "call-1".to_string(),
+                    output: FunctionCallOutputPayload {
+                        content: "test result: ok".to_string(),
+                        ..Default::default()
+                    },
+                }),
+            ),
+            line(
+                "2025-01-01T10:00:06.000Z",
+                RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                    message: "All tests pass".to_string(),
+                })),
+            ),
+            // 恢复后的会话重新从 1 编号
+            user("2025-01-02T09:00:00.000Z", "fix the build"),
+            turn_context("2025-01-02T09:00:00.100Z", "1"),
+        ]
+    }
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_split_turns_collects_messages_and_tool_calls() {
+        let turns = split_turns(rollout());
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].turn_id.as_deref(), Some("1"));
+        assert_eq!(
+            turns[0].entries,
+            vec![
+                TurnEntry::User("run the tests".to_string()),
+                TurnEntry::ToolCall {
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":["cargo","test"]}"#.to_string(),
+                },
+                TurnEntry::ToolOutput("test result: ok".to_string()),
+                TurnEntry::Assistant("All tests pass".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_turn_disambiguates_repeated_ids_by_time() {
+        let first = find_turn(
+            split_turns(rollout()),
+            Some("1"),
+            at("2025-01-01T10:00:10Z"),
+        )
+        .unwrap();
+        assert_eq!(
+            first.entries[0],
+            TurnEntry::User("run the tests".to_string())
+        );
+
+        let second = find_turn(
+            split_turns(rollout()),
+            Some("1"),
+            at("2025-01-02T09:05:00Z"),
+        )
+        .unwrap();
+        assert_eq!(
+            second.entries[0],
+            TurnEntry::User("fix the build".to_string())
+        );
+
+        // 没有回合 ID 时取创建时间之前的最后一个回合
+        let legacy = find_turn(split_turns(rollout()), None, at("2025-01-01T12:00:00Z")).unwrap();
+        assert_eq!(legacy.started_at, "2025-01-01T10:00:00.000Z");
+
+        assert_eq!(
+            find_turn(
+                split_turns(rollout()),
+                Some("7"),
+                at("2025-01-03T00:00:00Z")
+            ),
+            None
+        );
+    }
+}
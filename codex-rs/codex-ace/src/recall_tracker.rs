@@ -2,18 +2,25 @@
 //!
 //! 负责记录 bullet 的使用情况，更新召回统计和动态权重。
 
-use crate::ace::storage::BulletStorage;
-use crate::ace::types::Bullet;
+use crate::lightweight_index::PlaybookIndex;
+use crate::storage::BulletStorage;
+use crate::types::Bullet;
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// 召回记录写入 journal 时使用的会话标识
+const RECALL_SESSION_ID: &str = "recall-tracker";
+
 /// 召回记录器
 ///
 /// 跟踪 bullet 的使用情况，维护召回统计数据。
 pub struct RecallTracker {
     /// Storage 引用
     storage: Arc<RwLock<BulletStorage>>,
+
+    /// 检索索引（设置后召回统计同时增量更新到索引）
+    index: Option<Arc<PlaybookIndex>>,
 }
 
 impl RecallTracker {
@@ -22,12 +29,22 @@ impl RecallTracker {
     /// # 参数
     /// - `storage`: Storage 的共享引用
     pub fn new(storage: Arc<RwLock<BulletStorage>>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            index: None,
+        }
+    }
+
+    /// 通过检索索引写入召回统计，使动态权重的变化立即影响检索排序
+    pub fn with_index(mut self, index: Arc<PlaybookIndex>) -> Self {
+        self.index = Some(index);
+        self
     }
 
     /// 记录 bullet 使用
     ///
     /// 当 bullets 被召回使用时调用此方法，更新每个 bullet 的统计信息。
+    /// 更新在存储锁内基于最新状态完成，多个会话同时记录不会互相覆盖。
     ///
     /// # 参数
     /// - `bullet_ids`: 使用的 bullet ID 列表
@@ -42,31 +59,43 @@ impl RecallTracker {
         context: String,
         success: bool,
     ) -> Result<()> {
-        let storage = self.storage.write().await;
+        // 记录召回
+        let recall_context = context.clone();
+        let record = move |bullet: &mut Bullet| {
+            bullet
+                .metadata
+                .record_recall(recall_context.clone(), success);
+        };
 
-        // 从 storage 加载 playbook
-        let mut playbook = storage.load_playbook().await?;
+        let updated = match &self.index {
+            Some(index) => {
+                index
+                    .update_bullets_with(RECALL_SESSION_ID.to_string(), bullet_ids.clone(), record)
+                    .await?
+            }
+            None => {
+                let storage = self.storage.read().await.clone();
+                storage
+                    .update_bullets_with(RECALL_SESSION_ID.to_string(), bullet_ids.clone(), record)
+                    .await?
+                    .1
+            }
+        };
 
-        // 记录每个 bullet 的使用
+        for bullet in &updated {
+            tracing::debug!(
+                "Recorded bullet {} recall, total: {}, success rate: {:.2}%",
+                bullet.id,
+                bullet.metadata.recall_count,
+                bullet.metadata.success_rate * 100.0
+            );
+        }
         for bullet_id in &bullet_ids {
-            if let Some(bullet) = playbook.find_bullet_mut(bullet_id) {
-                // 记录召回
-                bullet.metadata.record_recall(context.clone(), success);
-
-                tracing::debug!(
-                    "Recorded bullet {} recall, total: {}, success rate: {:.2}%",
-                    bullet_id,
-                    bullet.metadata.recall_count,
-                    bullet.metadata.success_rate * 100.0
-                );
-            } else {
+            if !updated.iter().any(|bullet| &bullet.id == bullet_id) {
                 tracing::warn!("Bullet {} does not exist, cannot record recall", bullet_id);
             }
         }
 
-        // Save updated playbook
-        storage.save_playbook(&playbook).await?;
-
         tracing::info!(
             "Recall recording complete: {} bullets, context: {}, success: {}",
             bullet_ids.len(),
@@ -201,7 +230,7 @@ impl RecallStatistics {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ace::types::{Bullet, BulletSection, Playbook};
+    use crate::types::{Bullet, BulletSection, Playbook};
     use tempfile::TempDir;
 
     async fn create_test_tracker() -> (RecallTracker, TempDir) {
@@ -18,7 +18,7 @@ impl SimilarityCalculator {
     ///
     /// # 示例
     /// ```
-    /// use codex_core::ace::similarity::SimilarityCalculator;
+    /// use codex_ace::similarity::SimilarityCalculator;
     ///
     /// let distance = SimilarityCalculator::levenshtein_distance("kitten", "sitting");
     /// assert_eq!(distance, 3);
@@ -73,7 +73,7 @@ impl SimilarityCalculator {
     ///
     /// # 示例
     /// ```
-    /// use codex_core::ace::similarity::SimilarityCalculator;
+    /// use codex_ace::similarity::SimilarityCalculator;
     ///
     /// let score = SimilarityCalculator::similarity_score("hello", "hello");
     /// assert_eq!(score, 1.0);
@@ -106,7 +106,7 @@ impl SimilarityCalculator {
     ///
     /// # 示例
     /// ```
-    /// use codex_core::ace::similarity::SimilarityCalculator;
+    /// use codex_ace::similarity::SimilarityCalculator;
     ///
     /// let score = SimilarityCalculator::ngram_similarity("hello", "hallo", 2);
     /// assert!(score > 0.5);
@@ -153,7 +153,7 @@ impl SimilarityCalculator {
     ///
     /// # 示例
     /// ```
-    /// use codex_core::ace::similarity::SimilarityCalculator;
+    /// use codex_ace::similarity::SimilarityCalculator;
     ///
     /// let ngrams = SimilarityCalculator::extract_ngrams("hello", 2);
     /// // 结果应包含: "he", "el", "ll", "lo"
@@ -0,0 +1,714 @@
+//! Playbook 导入/导出（团队共享格式）
+//!
+//! 支持三种格式：
+//! - JSON：带格式标识和版本号的完整文档，无损
+//! - JSONL：首行为头部，其后每行一个 bullet，适合提交到 git 后按行 diff
+//! - Markdown：按分类分组的列表，元数据放在 HTML 注释里，便于手工编辑
+//!
+//! 导入时与已有 bullets 去重（ID 相同或内容相似度达到阈值），
+//! 冲突按 [`ConflictPolicy`] 处理；`source_session_id` 原样保留以追溯来源。
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+
+use super::knowledge_scope::KnowledgeScope;
+use super::similarity::SimilarityCalculator;
+use super::types::Bullet;
+use super::types::BulletCodeContent;
+use super::types::BulletSection;
+use super::types::Playbook;
+use super::types::SourceType;
+
+/// 导出文件中的格式标识
+pub const PLAYBOOK_FORMAT: &str = "codex-ace-playbook";
+
+/// 当前导出格式版本；导入时拒绝更高版本
+pub const PLAYBOOK_FORMAT_VERSION: u32 = 1;
+
+/// 内容相似度达到该阈值即视为重复（与后台优化器一致）
+const DUPLICATE_THRESHOLD: f32 = 0.85;
+
+/// Markdown 中元数据注释的前缀
+const MARKDOWN_META_PREFIX: &str = "<!-- ace:";
+
+/// 导出/导入格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybookFormat {
+    Json,
+    Jsonl,
+    Markdown,
+}
+
+impl PlaybookFormat {
+    /// 根据文件扩展名推断格式
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(Self::Json),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// 导入时与已有 bullet 冲突（ID 相同或内容近似重复）的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// 保留已有 bullet，丢弃导入项（重复导入同一文件是幂等的）
+    #[default]
+    Skip,
+    /// 保留已有内容，吸收导入项的计数、标签和工具
+    Merge,
+    /// 用导入项的内容、分类和元数据覆盖已有 bullet（保留已有 ID）
+    Overwrite,
+}
+
+/// 导入结果统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub added: usize,
+    pub merged: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+}
+
+impl ImportReport {
+    /// 是否修改了 playbook
+    pub fn has_changes(&self) -> bool {
+        self.added + self.merged + self.overwritten > 0
+    }
+}
+
+/// JSON 文档 / JSONL 首行的头部
+#[derive(Debug, Serialize, Deserialize)]
+struct PlaybookHeader {
+    format: String,
+    version: u32,
+    exported_at: DateTime<Utc>,
+}
+
+impl PlaybookHeader {
+    fn current() -> Self {
+        Self {
+            format: PLAYBOOK_FORMAT.to_string(),
+            version: PLAYBOOK_FORMAT_VERSION,
+            exported_at: Utc::now(),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.format != PLAYBOOK_FORMAT {
+            bail!(
+                "Not a playbook export: expected format \"{PLAYBOOK_FORMAT}\", found \"{}\"",
+                self.format
+            );
+        }
+        if self.version > PLAYBOOK_FORMAT_VERSION {
+            bail!(
+                "Playbook format version {} is newer than supported version {PLAYBOOK_FORMAT_VERSION}",
+                self.version
+            );
+        }
+        Ok(())
+    }
+}
+
+/// JSON 格式的完整文档
+#[derive(Debug, Serialize, Deserialize)]
+struct PlaybookDocument {
+    #[serde(flatten)]
+    header: PlaybookHeader,
+    bullets: Vec<Bullet>,
+}
+
+/// Markdown 中每个 bullet 附带的元数据
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MarkdownMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    importance: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<KnowledgeScope>,
+    /// 代码块对应的文件路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    /// 代码块是摘要而非完整代码
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    code_summary: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+}
+
+/// 导出 bullets
+///
+/// 按分类、创建时间排序以保证输出稳定；最近召回的原始查询和本机 rollout 路径不导出。
+pub fn export_bullets(bullets: &[Bullet], format: PlaybookFormat) -> Result<String> {
+    let mut bullets: Vec<Bullet> = bullets
+        .iter()
+        .cloned()
+        .map(|mut bullet| {
+            bullet.metadata.recall_contexts.clear();
+            if let Some(provenance) = bullet.metadata.provenance.as_mut() {
+                provenance.rollout_path = None;
+            }
+            bullet
+        })
+        .collect();
+    bullets.sort_by(|a, b| {
+        a.section
+            .cmp(&b.section)
+            .then_with(|| a.created_at.cmp(&b.created_at))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    match format {
+        PlaybookFormat::Json => {
+            let document = PlaybookDocument {
+                header: PlaybookHeader::current(),
+                bullets,
+            };
+            let mut out = serde_json::to_string_pretty(&document)?;
+            out.push('\n');
+            Ok(out)
+        }
+        PlaybookFormat::Jsonl => {
+            let mut out = serde_json::to_string(&PlaybookHeader::current())?;
+            out.push('\n');
+            for bullet in &bullets {
+                out.push_str(&serde_json::to_string(bullet)?);
+                out.push('\n');
+            }
+            Ok(out)
+        }
+        PlaybookFormat::Markdown => Ok(render_markdown(&bullets)),
+    }
+}
+
+/// 解析导出文件
+///
+/// `default_source` 用于没有来源信息的条目（手写的 Markdown bullet）。
+pub fn parse_bullets(
+    input: &str,
+    format: PlaybookFormat,
+    default_source: &str,
+) -> Result<Vec<Bullet>> {
+    match format {
+        PlaybookFormat::Json => {
+            let document: PlaybookDocument =
+                serde_json::from_str(input).context("Invalid playbook JSON")?;
+            document.header.validate()?;
+            Ok(document.bullets)
+        }
+        PlaybookFormat::Jsonl => {
+            let mut lines = input
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty());
+            let Some((_, first)) = lines.next() else {
+                bail!("Empty playbook JSONL file");
+            };
+            let header: PlaybookHeader =
+                serde_json::from_str(first).context("Invalid playbook JSONL header")?;
+            header.validate()?;
+
+            lines
+                .map(|(idx, line)| {
+                    serde_json::from_str(line)
+                        .with_context(|| format!("Invalid bullet on line {}", idx + 1))
+                })
+                .collect()
+        }
+        PlaybookFormat::Markdown => parse_markdown(input, default_source),
+    }
+}
+
+/// 将导入的 bullets 合并到 playbook
+///
+/// 依次处理每个导入项：ID 已存在或与已有 bullet 内容近似重复时按 `policy`
+/// 处理，否则原样加入。已加入的导入项也参与后续去重，文件内部的重复同样会被合并。
+pub fn merge_into_playbook(
+    playbook: &mut Playbook,
+    incoming: Vec<Bullet>,
+    policy: ConflictPolicy,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+
+    for bullet in incoming {
+        let Some(existing) = find_conflict(playbook, &bullet) else {
+            playbook.add_bullet(bullet);
+            report.added += 1;
+            continue;
+        };
+
+        match policy {
+            ConflictPolicy::Skip => report.skipped += 1,
+            ConflictPolicy::Merge => {
+                let mut merged = existing;
+                merged.absorb(&bullet);
+                playbook.update_bullet(merged);
+                report.merged += 1;
+            }
+            ConflictPolicy::Overwrite => {
+                let mut replacement = bullet;
+                replacement.id = existing.id.clone();
+                replacement.created_at = existing.created_at;
+                replacement.updated_at = Utc::now();
+                // 分类可能变化，先删除再加入
+                playbook.remove_bullet(&existing.id);
+                playbook.add_bullet(replacement);
+                report.overwritten += 1;
+            }
+        }
+    }
+
+    report
+}
+
+/// 查找与导入项冲突的已有 bullet：先按 ID，再按内容相似度
+fn find_conflict(playbook: &Playbook, bullet: &Bullet) -> Option<Bullet> {
+    if let Some(existing) = playbook.find_bullet(&bullet.id) {
+        return Some(existing.clone());
+    }
+
+    let normalized = SimilarityCalculator::normalize_text(&bullet.content, true);
+    playbook
+        .all_bullets()
+        .into_iter()
+        .map(|existing| {
+            let other = SimilarityCalculator::normalize_text(&existing.content, true);
+            let score = SimilarityCalculator::combined_similarity(&normalized, &other);
+            (existing, score)
+        })
+        .filter(|(_, score)| *score >= DUPLICATE_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(existing, _)| existing.clone())
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+/// 渲染 Markdown：每个分类一个二级标题，每个 bullet 一个列表项，
+/// 续行和代码块缩进两个空格，元数据在最后一行的 HTML 注释中
+fn render_markdown(bullets: &[Bullet]) -> String {
+    let mut out = format!("<!-- {PLAYBOOK_FORMAT} v{PLAYBOOK_FORMAT_VERSION} -->\n");
+    out.push_str("# ACE Playbook\n");
+
+    let mut current_section: Option<&BulletSection> = None;
+    for bullet in bullets {
+        if current_section != Some(&bullet.section) {
+            out.push_str(&format!("\n## {}\n\n", bullet.section.title()));
+            current_section = Some(&bullet.section);
+        }
+
+        let mut lines = bullet.content.trim().lines();
+        out.push_str(&format!("- {}\n", lines.next().unwrap_or_default()));
+        for line in lines {
+            push_indented(&mut out, line);
+        }
+
+        let mut meta = MarkdownMeta {
+            id: Some(bullet.id.clone()),
+            source: Some(bullet.source_session_id.clone()),
+            created_at: Some(bullet.created_at),
+            tags: bullet.tags.clone(),
+            tools: bullet.metadata.related_tools.clone(),
+            importance: Some(bullet.metadata.importance),
+            scope: bullet.metadata.scope.clone(),
+            pinned: bullet.metadata.pinned,
+            ..Default::default()
+        };
+
+        if let Some(code) = &bullet.code_content {
+            let (language, code) = match code {
+                BulletCodeContent::Full {
+                    language,
+                    code,
+                    file_path,
+                } => {
+                    meta.file = file_path.clone();
+                    (language, code)
+                }
+                BulletCodeContent::Summary {
+                    language,
+                    summary,
+                    file_path,
+                    ..
+                } => {
+                    meta.file = Some(file_path.clone());
+                    meta.code_summary = true;
+                    (language, summary)
+                }
+            };
+            push_indented(&mut out, &format!("```{language}"));
+            for line in code.lines() {
+                push_indented(&mut out, line);
+            }
+            push_indented(&mut out, "```");
+        }
+
+        // MarkdownMeta 的序列化不会失败
+        let meta = serde_json::to_string(&meta).unwrap_or_default();
+        push_indented(&mut out, &format!("{MARKDOWN_META_PREFIX} {meta} -->"));
+    }
+
+    out
+}
+
+fn push_indented(out: &mut String, line: &str) {
+    if line.is_empty() {
+        out.push('\n');
+    } else {
+        out.push_str("  ");
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// 解析中的 bullet
+struct PendingBullet {
+    section: BulletSection,
+    content: Vec<String>,
+    code_language: Option<String>,
+    code: Vec<String>,
+    in_code: bool,
+    meta: Option<MarkdownMeta>,
+}
+
+impl PendingBullet {
+    fn into_bullet(self, default_source: &str) -> Option<Bullet> {
+        let content = self.content.join("\n").trim().to_string();
+        if content.is_empty() {
+            return None;
+        }
+
+        let manual = self.meta.is_none();
+        let meta = self.meta.unwrap_or_default();
+        let mut bullet = Bullet::new(
+            self.section,
+            content,
+            meta.source.unwrap_or_else(|| default_source.to_string()),
+        );
+        if let Some(id) = meta.id {
+            bullet.id = id;
+        }
+        if let Some(created_at) = meta.created_at {
+            bullet.created_at = created_at;
+        }
+        if let Some(importance) = meta.importance {
+            bullet.metadata.importance = importance;
+        }
+        if manual {
+            bullet.metadata.source_type = SourceType::ManualEntry;
+        }
+        bullet.tags = meta.tags;
+        bullet.metadata.related_tools = meta.tools;
+        bullet.metadata.scope = meta.scope;
+        bullet.metadata.pinned = meta.pinned;
+
+        if let Some(language) = self.code_language {
+            let code = self.code.join("\n");
+            bullet.code_content = Some(match meta.file {
+                Some(file_path) if meta.code_summary => BulletCodeContent::Summary {
+                    language,
+                    summary: code,
+                    file_path,
+                    key_lines: None,
+                },
+                file_path => BulletCodeContent::Full {
+                    language,
+                    code,
+                    file_path,
+                },
+            });
+        }
+
+        Some(bullet)
+    }
+}
+
+/// 解析 Markdown
+///
+/// 容忍手工编辑：未知标题归入通用分类，没有元数据注释的列表项视为手动添加的新条目。
+fn parse_markdown(input: &str, default_source: &str) -> Result<Vec<Bullet>> {
+    let mut bullets = Vec::new();
+    let mut section = BulletSection::General;
+    let mut pending: Option<PendingBullet> = None;
+
+    let flush = |pending: &mut Option<PendingBullet>, bullets: &mut Vec<Bullet>| {
+        if let Some(bullet) = pending.take().and_then(|p| p.into_bullet(default_source)) {
+            bullets.push(bullet);
+        }
+    };
+
+    for (idx, raw_line) in input.lines().enumerate() {
+        let line = raw_line.trim_end();
+
+        // 缩进行属于当前 bullet
+        if let Some(current) = pending.as_mut() {
+            let continuation = line.strip_prefix("  ").or_else(|| line.strip_prefix('\t'));
+            if current.in_code {
+                match continuation {
+                    Some(code_line) if code_line.trim_start().starts_with("```") => {
+                        current.in_code = false;
+                    }
+                    Some(code_line) => current.code.push(code_line.to_string()),
+                    None if line.is_empty() => current.code.push(String::new()),
+                    None => bail!("Unterminated code block before line {}", idx + 1),
+                }
+                continue;
+            }
+
+            if line.is_empty() {
+                current.content.push(String::new());
+                continue;
+            }
+
+            if let Some(text) = continuation {
+                let text = text.trim_start();
+                if let Some(meta) = text
+                    .strip_prefix(MARKDOWN_META_PREFIX)
+                    .and_then(|rest| rest.strip_suffix("-->"))
+                {
+                    let meta: MarkdownMeta = serde_json::from_str(meta.trim())
+                        .with_context(|| format!("Invalid bullet metadata on line {}", idx + 1))?;
+                    current.meta = Some(meta);
+                } else if let Some(language) = text.strip_prefix("```") {
+                    if current.code_language.is_some() {
+                        bail!("Only one code block per bullet (line {})", idx + 1);
+                    }
+                    current.code_language = Some(language.trim().to_string());
+                    current.in_code = true;
+                } else {
+                    current.content.push(text.to_string());
+                }
+                continue;
+            }
+        }
+
+        if let Some(title) = line.strip_prefix("## ") {
+            flush(&mut pending, &mut bullets);
+            section = BulletSection::from_title(title.trim()).unwrap_or(BulletSection::General);
+        } else if let Some(text) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            flush(&mut pending, &mut bullets);
+            pending = Some(PendingBullet {
+                section: section.clone(),
+                content: vec![text.trim().to_string()],
+                code_language: None,
+                code: Vec::new(),
+                in_code: false,
+                meta: None,
+            });
+        } else if !line.is_empty() {
+            // 标题、说明文字等非列表内容
+            flush(&mut pending, &mut bullets);
+        }
+    }
+
+    if pending.as_ref().is_some_and(|p| p.in_code) {
+        bail!("Unterminated code block at end of file");
+    }
+    flush(&mut pending, &mut bullets);
+
+    Ok(bullets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn bullet(section: BulletSection, content: &str) -> Bullet {
+        Bullet::new(section, content.to_string(), "session-1".to_string())
+    }
+
+    fn sample_bullets() -> Vec<Bullet> {
+        let mut rules = bullet(
+            BulletSection::StrategiesAndRules,
+            "Run cargo fmt before committing\nand fix clippy warnings",
+        );
+        rules.tags = vec!["rust".to_string()];
+        rules.metadata.related_tools = vec!["cargo".to_string()];
+        rules.metadata.pinned = true;
+        rules.metadata.recall_contexts = vec!["private prompt".to_string()];
+
+        let mut snippet = bullet(
+            BulletSection::CodeSnippetsAndTemplates,
+            "Async test template",
+        );
+        snippet.code_content = Some(BulletCodeContent::Full {
+            language: "rust".to_string(),
+            code: "#[tokio::test]\nasync fn it_works() {\n\n    assert!(true);\n}".to_string(),
+            file_path: Some("src/lib.rs".to_string()),
+        });
+
+        vec![
+            rules,
+            snippet,
+            bullet(BulletSection::ToolUsageTips, "Prefer rg over grep"),
+        ]
+    }
+
+    fn summary(bullets: &[Bullet]) -> Vec<(String, BulletSection, String, bool)> {
+        let mut out: Vec<_> = bullets
+            .iter()
+            .map(|b| {
+                (
+                    b.id.clone(),
+                    b.section.clone(),
+                    b.content.clone(),
+                    b.metadata.pinned,
+                )
+            })
+            .collect();
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn roundtrips_all_formats() {
+        let bullets = sample_bullets();
+        for format in [
+            PlaybookFormat::Json,
+            PlaybookFormat::Jsonl,
+            PlaybookFormat::Markdown,
+        ] {
+            let exported = export_bullets(&bullets, format).unwrap();
+            assert!(!exported.contains("private prompt"));
+
+            let parsed = parse_bullets(&exported, format, "import:test").unwrap();
+            assert_eq!(summary(&parsed), summary(&bullets), "{format:?}");
+
+            let snippet = parsed
+                .iter()
+                .find(|b| b.section == BulletSection::CodeSnippetsAndTemplates)
+                .unwrap();
+            match &snippet.code_content {
+                Some(BulletCodeContent::Full {
+                    code, file_path, ..
+                }) => {
+                    assert!(code.contains("assert!(true);"));
+                    assert_eq!(file_path.as_deref(), Some("src/lib.rs"));
+                }
+                other => panic!("expected full code for {format:?}, got {other:?}"),
+            }
+            assert!(parsed.iter().all(|b| b.source_session_id == "session-1"));
+        }
+    }
+
+    #[test]
+    fn parses_hand_written_markdown() {
+        let input = "# Team playbook\n\nIntro text.\n\n## Tool Usage Tips\n\n- Use `rg` for search\n  across large repos\n- Run `just fmt`\n\n## Misc notes\n\n* Document public APIs\n";
+        let parsed = parse_bullets(input, PlaybookFormat::Markdown, "import:team.md").unwrap();
+
+        let contents: Vec<_> = parsed
+            .iter()
+            .map(|b| (b.section.clone(), b.content.as_str()))
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                (
+                    BulletSection::ToolUsageTips,
+                    "Use `rg` for search\nacross large repos"
+                ),
+                (BulletSection::ToolUsageTips, "Run `just fmt`"),
+                (BulletSection::General, "Document public APIs"),
+            ]
+        );
+        assert!(
+            parsed
+                .iter()
+                .all(|b| b.source_session_id == "import:team.md"
+                    && b.metadata.source_type == SourceType::ManualEntry)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_format_and_newer_versions() {
+        let newer = format!(
+            "{{\"format\":\"{PLAYBOOK_FORMAT}\",\"version\":{},\"exported_at\":\"2025-01-01T00:00:00Z\",\"bullets\":[]}}",
+            PLAYBOOK_FORMAT_VERSION + 1
+        );
+        assert!(parse_bullets(&newer, PlaybookFormat::Json, "x").is_err());
+
+        let other = "{\"format\":\"something-else\",\"version\":1,\"exported_at\":\"2025-01-01T00:00:00Z\"}\n";
+        assert!(parse_bullets(other, PlaybookFormat::Jsonl, "x").is_err());
+    }
+
+    #[test]
+    fn merge_dedupes_by_id_and_similarity() {
+        let mut playbook = Playbook::new();
+        let existing = bullet(
+            BulletSection::ToolUsageTips,
+            "Prefer rg over grep for searching code",
+        );
+        let existing_id = existing.id.clone();
+        playbook.add_bullet(existing.clone());
+
+        let mut same_id = existing.clone();
+        same_id.content = "Something rewritten".to_string();
+        let mut near_duplicate = bullet(
+            BulletSection::ToolUsageTips,
+            "Prefer rg over grep for searching code.",
+        );
+        near_duplicate.metadata.success_count = 3;
+        near_duplicate.tags = vec!["search".to_string()];
+        let fresh = bullet(BulletSection::General, "Keep PRs small");
+
+        let incoming = vec![same_id.clone(), near_duplicate.clone(), fresh.clone()];
+
+        let mut skipped = playbook.clone();
+        let report = merge_into_playbook(&mut skipped, incoming.clone(), ConflictPolicy::Skip);
+        assert_eq!(
+            report,
+            ImportReport {
+                added: 1,
+                skipped: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            skipped.find_bullet(&existing_id).unwrap().content,
+            existing.content
+        );
+
+        let mut merged = playbook.clone();
+        let report = merge_into_playbook(
+            &mut merged,
+            vec![near_duplicate.clone(), fresh.clone()],
+            ConflictPolicy::Merge,
+        );
+        assert_eq!((report.added, report.merged), (1, 1));
+        let merged_bullet = merged.find_bullet(&existing_id).unwrap();
+        assert_eq!(merged_bullet.metadata.success_count, 3);
+        assert_eq!(merged_bullet.tags, vec!["search".to_string()]);
+        assert_eq!(merged.all_bullets().len(), 2);
+
+        let mut overwritten = playbook.clone();
+        let mut moved = same_id;
+        moved.section = BulletSection::StrategiesAndRules;
+        let report = merge_into_playbook(&mut overwritten, vec![moved], ConflictPolicy::Overwrite);
+        assert_eq!(report.overwritten, 1);
+        let replaced = overwritten.find_bullet(&existing_id).unwrap();
+        assert_eq!(replaced.content, "Something rewritten");
+        assert_eq!(replaced.section, BulletSection::StrategiesAndRules);
+        assert_eq!(overwritten.all_bullets().len(), 1);
+    }
+}
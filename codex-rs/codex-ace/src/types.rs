@@ -1,96 +1,687 @@
-//! ACE框架的核心数据结构
+//! ACE 框架的核心数据结构 - Bullet-based 架构
 //!
-//! MVP版本，专注于简单和实用。
+//! 基于 Agentic Context Engineering 论文实现，采用细粒度的 bullet 管理。
 
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use super::background_optimizer::OptimizerConfig;
+use super::knowledge_scope::KnowledgeScope;
+use super::redaction::RedactionConfig;
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::protocol::PlaybookFeedbackRating;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use uuid::Uuid;
 
-/// Playbook条目 - MVP版本
+/// 每次用户点赞/点踩对置信度的调整幅度
+const USER_FEEDBACK_CONFIDENCE_STEP: f32 = 0.2;
+
+// ============================================================================
+// Bullet 数据结构（核心单元）
+// ============================================================================
+
+/// 一条可执行的规则/策略/知识点
 ///
-/// 每个条目代表一次对话的学习结果。
+/// Bullet 是 ACE 系统的核心存储单元，每个 bullet 代表一条独立的、
+/// 可引用的知识点或策略。
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlaybookEntry {
-    /// 唯一标识符
+pub struct Bullet {
+    /// 唯一标识符（UUID）
     pub id: String,
 
-    /// 时间戳
-    pub timestamp: DateTime<Utc>,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
 
-    /// 会话ID
-    pub session_id: String,
+    /// 最后更新时间
+    pub updated_at: DateTime<Utc>,
 
-    /// 用户查询
-    pub user_query: String,
+    /// 来源会话ID（首次创建时）
+    pub source_session_id: String,
 
-    /// 助手响应（截断到合理长度）
-    pub assistant_response: String,
+    /// 所属分类（structured sections）
+    pub section: BulletSection,
 
-    /// 执行是否成功
-    pub execution_success: bool,
+    /// 具体内容（markdown 格式）
+    pub content: String,
 
-    /// 提取的洞察
-    pub insights: Vec<Insight>,
+    /// 代码内容（如果包含代码）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_content: Option<BulletCodeContent>,
 
-    /// 识别的模式
-    pub patterns: Vec<String>,
+    /// 元数据（细粒度跟踪）
+    pub metadata: BulletMetadata,
 
-    /// 学到的策略
-    pub learned_strategies: Vec<String>,
+    /// 关联的标签（用于检索）
+    pub tags: Vec<String>,
+}
 
-    /// 使用的工具
-    pub tools_used: Vec<String>,
+/// 代码内容（分级保存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BulletCodeContent {
+    /// 完整代码（用于核心代码、小文件）
+    Full {
+        /// 编程语言
+        language: String,
+        /// 完整代码
+        code: String,
+        /// 文件路径（如果有）
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file_path: Option<String>,
+    },
+
+    /// 摘要+引用（用于大文件、辅助代码）
+    Summary {
+        /// 编程语言
+        language: String,
+        /// 代码摘要（函数签名、关键类型等）
+        summary: String,
+        /// 文件路径
+        file_path: String,
+        /// 关键行号范围
+        #[serde(skip_serializing_if = "Option::is_none")]
+        key_lines: Option<Vec<(usize, usize)>>,
+    },
+}
 
-    /// 错误信息（如果有）
-    pub error_messages: Vec<String>,
+/// 分类（参考论文 Figure 3）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BulletSection {
+    /// 策略和硬性规则
+    StrategiesAndRules,
 
-    /// 标签（用于快速过滤）
-    pub tags: Vec<String>,
+    /// 可用的代码片段和模板
+    CodeSnippetsAndTemplates,
+
+    /// 故障排查和陷阱
+    TroubleshootingAndPitfalls,
+
+    /// API 使用指南
+    ApiUsageGuides,
+
+    /// 错误处理模式
+    ErrorHandlingPatterns,
+
+    /// 工具使用技巧
+    ToolUsageTips,
+
+    /// 其他通用知识
+    General,
 }
 
-impl PlaybookEntry {
-    /// 创建一个新的空条目
-    pub fn new(user_query: String, assistant_response: String) -> Self {
+impl BulletSection {
+    /// 全部分类（按展示顺序）
+    pub const ALL: [BulletSection; 7] = [
+        BulletSection::StrategiesAndRules,
+        BulletSection::CodeSnippetsAndTemplates,
+        BulletSection::TroubleshootingAndPitfalls,
+        BulletSection::ApiUsageGuides,
+        BulletSection::ErrorHandlingPatterns,
+        BulletSection::ToolUsageTips,
+        BulletSection::General,
+    ];
+
+    /// 展示用标题
+    pub fn title(&self) -> &'static str {
+        match self {
+            BulletSection::StrategiesAndRules => "Strategies and Rules",
+            BulletSection::CodeSnippetsAndTemplates => "Code Snippets and Templates",
+            BulletSection::TroubleshootingAndPitfalls => "Troubleshooting and Pitfalls",
+            BulletSection::ApiUsageGuides => "API Usage Guides",
+            BulletSection::ErrorHandlingPatterns => "Error Handling Patterns",
+            BulletSection::ToolUsageTips => "Tool Usage Tips",
+            BulletSection::General => "General Knowledge",
+        }
+    }
+
+    /// 按展示用标题或枚举名解析（不区分大小写）
+    pub fn from_title(title: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|section| {
+            section.title().eq_ignore_ascii_case(title)
+                || format!("{section:?}").eq_ignore_ascii_case(title)
+        })
+    }
+}
+
+/// 细粒度元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulletMetadata {
+    /// 重要性评分（0.0 - 1.0）
+    pub importance: f32,
+
+    /// 来源类型
+    pub source_type: SourceType,
+
+    /// 适用性范围
+    pub applicability: Applicability,
+
+    /// 引用次数（被 context loader 使用）
+    pub reference_count: u32,
+
+    /// 成功应用次数
+    pub success_count: u32,
+
+    /// 失败应用次数
+    pub failure_count: u32,
+
+    /// 相关工具/语言
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub related_tools: Vec<String>,
+
+    /// 相关文件模式（glob）
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub related_file_patterns: Vec<String>,
+
+    /// 置信度（0.0 - 1.0），Curator 重复提取时提高，用户反馈调整；参与动态权重
+    pub confidence: f32,
+
+    // ============ LAPS 新增字段 ============
+    /// 被召回次数
+    #[serde(default)]
+    pub recall_count: u32,
+
+    /// 最后召回时间
+    #[serde(default)]
+    pub last_recall: Option<DateTime<Utc>>,
+
+    /// 最近召回的上下文（保留最近10次）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recall_contexts: Vec<String>,
+
+    /// 成功率（0.0 - 1.0，基于 success_count 和 failure_count）
+    #[serde(default)]
+    pub success_rate: f32,
+
+    /// 适用范围（项目/语言/领域），Curator 生成时根据会话范围填写
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<KnowledgeScope>,
+
+    /// 用户固定：不会被后台优化器衰减、降级或归档，去重时优先保留
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+
+    /// 用户标记为错误或有害：复查（编辑、固定或点赞）前不再注入
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub flagged: bool,
+
+    /// 生成该 bullet 的会话和回合（`codex ace explain` 据此打开原始对话）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<BulletProvenance>,
+}
+
+/// Bullet 的来源：生成它的会话、回合和 rollout 文件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BulletProvenance {
+    /// 会话 ID（与 rollout 中的 ConversationId 一致）
+    pub conversation_id: String,
+
+    /// 回合 ID（rollout 中 `turn_context` 记录的 `turn_id`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+
+    /// 会话 rollout 文件（本机路径，导出时去掉）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollout_path: Option<PathBuf>,
+}
+
+/// 来源类型
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum SourceType {
+    /// 从成功执行中提取
+    SuccessExecution,
+
+    /// 从错误解决中提取
+    ErrorResolution,
+
+    /// 从模式识别中提取
+    PatternRecognition,
+
+    /// 用户手动添加（`/playbook-add` 或导入手写条目）
+    ManualEntry,
+}
+
+/// 适用性范围
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Applicability {
+    /// 适用的编程语言（空表示通用）
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub languages: Vec<String>,
+
+    /// 适用的工具
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub tools: Vec<String>,
+
+    /// 适用的操作系统
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub platforms: Vec<String>,
+
+    /// 适用的项目类型
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub project_types: Vec<String>,
+}
+
+impl Bullet {
+    /// 创建新 bullet
+    pub fn new(section: BulletSection, content: String, source_session_id: String) -> Self {
+        let now = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
-            timestamp: Utc::now(),
-            session_id: Uuid::new_v4().to_string(),
-            user_query,
-            assistant_response: truncate_string(&assistant_response, 2000),
-            execution_success: false,
-            insights: Vec::new(),
-            patterns: Vec::new(),
-            learned_strategies: Vec::new(),
-            tools_used: Vec::new(),
-            error_messages: Vec::new(),
+            created_at: now,
+            updated_at: now,
+            source_session_id,
+            section,
+            content,
+            code_content: None,
+            metadata: BulletMetadata::default(),
             tags: Vec::new(),
         }
     }
 
-    /// 判断是否为有价值的条目
-    pub fn is_valuable(&self) -> bool {
-        !self.insights.is_empty()
-            || !self.patterns.is_empty()
-            || !self.learned_strategies.is_empty()
-            || self.execution_success
+    /// 增加引用计数
+    pub fn increment_reference(&mut self) {
+        self.metadata.reference_count += 1;
+        self.updated_at = Utc::now();
+    }
+
+    /// 记录成功应用
+    pub fn record_success(&mut self) {
+        self.metadata.success_count += 1;
+        self.updated_at = Utc::now();
+    }
+
+    /// 记录失败应用
+    pub fn record_failure(&mut self) {
+        self.metadata.failure_count += 1;
+        self.updated_at = Utc::now();
+    }
+
+    /// 计算成功率（0.0 - 1.0）
+    pub fn success_rate(&self) -> f32 {
+        let total = self.metadata.success_count + self.metadata.failure_count;
+        if total == 0 {
+            0.0
+        } else {
+            self.metadata.success_count as f32 / total as f32
+        }
+    }
+
+    /// 吸收另一个 bullet 的统计信息（合并近似重复的 bullets 时使用）
+    ///
+    /// 累加计数、合并标签和工具，保留较高的重要性，不修改内容。
+    pub fn absorb(&mut self, other: &Bullet) {
+        let metadata = &mut self.metadata;
+        metadata.reference_count += other.metadata.reference_count;
+        metadata.success_count += other.metadata.success_count;
+        metadata.failure_count += other.metadata.failure_count;
+        metadata.recall_count += other.metadata.recall_count;
+        metadata.last_recall = metadata.last_recall.max(other.metadata.last_recall);
+        metadata.importance = metadata.importance.max(other.metadata.importance);
+        if metadata.scope.is_none() {
+            metadata.scope = other.metadata.scope.clone();
+        }
+        metadata.pinned |= other.metadata.pinned;
+        metadata.flagged |= other.metadata.flagged;
+
+        merge_unique(&mut metadata.related_tools, &other.metadata.related_tools);
+        merge_unique(
+            &mut metadata.related_file_patterns,
+            &other.metadata.related_file_patterns,
+        );
+        merge_unique(
+            &mut metadata.applicability.languages,
+            &other.metadata.applicability.languages,
+        );
+        merge_unique(
+            &mut metadata.applicability.tools,
+            &other.metadata.applicability.tools,
+        );
+        merge_unique(&mut self.tags, &other.tags);
+        self.tags.sort();
+
+        let total = metadata.success_count + metadata.failure_count;
+        metadata.success_rate = if total > 0 {
+            metadata.success_count as f32 / total as f32
+        } else {
+            0.0
+        };
+        self.updated_at = Utc::now();
+    }
+}
+
+/// app-server 和 MCP server 对外暴露的 bullet
+impl From<&Bullet> for codex_app_server_protocol::PlaybookBullet {
+    fn from(bullet: &Bullet) -> Self {
+        Self {
+            id: bullet.id.clone(),
+            section: bullet.section.title().to_string(),
+            content: bullet.content.clone(),
+            tags: bullet.tags.clone(),
+            pinned: bullet.metadata.pinned,
+            flagged: bullet.metadata.flagged,
+            confidence: bullet.metadata.confidence,
+            success_count: bullet.metadata.success_count,
+            failure_count: bullet.metadata.failure_count,
+            recall_count: bullet.metadata.recall_count,
+            created_at: bullet.created_at.to_rfc3339(),
+            updated_at: bullet.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// 将 `extra` 中尚不存在的元素追加到 `target`
+fn merge_unique(target: &mut Vec<String>, extra: &[String]) {
+    for item in extra {
+        if !target.contains(item) {
+            target.push(item.clone());
+        }
+    }
+}
+
+impl Default for BulletMetadata {
+    fn default() -> Self {
+        Self {
+            importance: 0.5,
+            source_type: SourceType::PatternRecognition,
+            applicability: Applicability::default(),
+            reference_count: 0,
+            success_count: 0,
+            failure_count: 0,
+            related_tools: Vec::new(),
+            related_file_patterns: Vec::new(),
+            confidence: 1.0,
+            // LAPS 新增字段
+            recall_count: 0,
+            last_recall: None,
+            recall_contexts: Vec::new(),
+            success_rate: 0.0,
+            scope: None,
+            pinned: false,
+            flagged: false,
+            provenance: None,
+        }
     }
 }
 
-/// 洞察 - 从对话中提取的知识
+impl BulletMetadata {
+    /// 【LAPS】计算动态权重
+    ///
+    /// 基于多个因子计算 bullet 的动态权重：
+    /// - 召回频率（对数增长，避免过度偏向）
+    /// - 成功率（质量指标）
+    /// - 时效性（最近使用的更重要）
+    ///
+    /// # 返回
+    /// 动态权重值（0.0 - 无上限，通常在 0.0 - 5.0 之间）
+    pub fn calculate_dynamic_weight(&self) -> f32 {
+        // 召回频率因子（对数增长）
+        // 使用 ln(1 + recall_count) 避免对高频内容过度偏向
+        let recall_factor = (1.0 + self.recall_count as f32).ln();
+
+        // 成功率因子（0.5 - 1.0）
+        // 即使没有使用记录，也给予基础权重 0.5
+        let total = self.success_count + self.failure_count;
+        let success_rate = if total > 0 {
+            self.success_count as f32 / total as f32
+        } else {
+            0.5 // 默认50%
+        };
+        let success_factor = 0.5 + success_rate * 0.5;
+
+        // 时效性因子（0.5 - 1.0）
+        // 最近使用的内容权重更高
+        let recency_factor = if let Some(last_recall) = self.last_recall {
+            let days_since = (Utc::now() - last_recall).num_days();
+            // 使用指数衰减，7天内保持高权重
+            let decay = 1.0 / (1.0 + (days_since as f32) * 0.01);
+            0.5 + decay * 0.5
+        } else {
+            0.5 // 从未被召回，给予基础权重
+        };
+
+        // 综合权重 = 基础重要性 × 召回频率 × 成功率 × 时效性 × 置信度
+        self.importance * recall_factor * success_factor * recency_factor * self.confidence
+    }
+
+    /// 【LAPS】记录召回
+    ///
+    /// 当 bullet 被召回使用时调用此方法，更新统计信息。
+    ///
+    /// # 参数
+    /// - `context`: 召回时的上下文描述
+    /// - `success`: 是否成功应用
+    pub fn record_recall(&mut self, context: String, success: bool) {
+        // 增加召回计数
+        self.recall_count += 1;
+
+        // 更新最后召回时间
+        self.last_recall = Some(Utc::now());
+
+        // 保留最近10次上下文
+        self.recall_contexts.push(context);
+        if self.recall_contexts.len() > 10 {
+            self.recall_contexts.remove(0);
+        }
+
+        // 更新成功/失败计数
+        if success {
+            self.success_count += 1;
+        } else {
+            self.failure_count += 1;
+        }
+
+        self.update_success_rate();
+    }
+
+    /// 记录用户对注入内容的反馈
+    ///
+    /// 点赞和点踩分别计为一次成功和失败，并按 `USER_FEEDBACK_CONFIDENCE_STEP` 调整置信度；
+    /// 标记计为一次失败并停止注入，点赞视为复查通过。
+    pub fn record_user_feedback(&mut self, rating: PlaybookFeedbackRating) {
+        match rating {
+            PlaybookFeedbackRating::Upvote => {
+                self.success_count += 1;
+                self.confidence = (self.confidence + USER_FEEDBACK_CONFIDENCE_STEP).min(1.0);
+                self.flagged = false;
+            }
+            PlaybookFeedbackRating::Downvote => {
+                self.failure_count += 1;
+                self.confidence = (self.confidence - USER_FEEDBACK_CONFIDENCE_STEP).max(0.0);
+            }
+            PlaybookFeedbackRating::Flag => {
+                self.failure_count += 1;
+                self.flagged = true;
+            }
+        }
+        self.update_success_rate();
+    }
+
+    /// 根据成功/失败计数重新计算成功率
+    fn update_success_rate(&mut self) {
+        let total = self.success_count + self.failure_count;
+        self.success_rate = if total > 0 {
+            self.success_count as f32 / total as f32
+        } else {
+            0.0
+        };
+    }
+}
+
+// ============================================================================
+// Playbook 结构（bullet 集合）
+// ============================================================================
+
+/// Playbook - bullet 的有序集合
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Insight {
+pub struct Playbook {
+    /// 版本号（用于追踪变更）
+    pub version: u32,
+
+    /// 最后更新时间
+    pub last_updated: DateTime<Utc>,
+
+    /// 所有 bullets（按 section 分组）
+    pub bullets: HashMap<BulletSection, Vec<Bullet>>,
+
+    /// 全局元数据
+    pub metadata: PlaybookMetadata,
+}
+
+/// Playbook 元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybookMetadata {
+    /// 总 bullet 数
+    pub total_bullets: usize,
+
+    /// 按 section 统计
+    pub section_counts: HashMap<BulletSection, usize>,
+
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+
+    /// 来源会话数
+    pub total_sessions: usize,
+
+    /// 后台优化器最近一次衰减的时间（用于按实际经过时间计算衰减）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_decay_at: Option<DateTime<Utc>>,
+}
+
+impl Playbook {
+    /// 创建空 playbook
+    pub fn new() -> Self {
+        Self {
+            version: 1,
+            last_updated: Utc::now(),
+            bullets: HashMap::new(),
+            metadata: PlaybookMetadata {
+                total_bullets: 0,
+                section_counts: HashMap::new(),
+                created_at: Utc::now(),
+                total_sessions: 0,
+                last_decay_at: None,
+            },
+        }
+    }
+
+    /// 添加 bullet
+    pub fn add_bullet(&mut self, bullet: Bullet) {
+        let section = bullet.section.clone();
+        self.bullets
+            .entry(section.clone())
+            .or_default()
+            .push(bullet);
+
+        self.metadata.total_bullets += 1;
+        *self.metadata.section_counts.entry(section).or_insert(0) += 1;
+        self.version += 1;
+        self.last_updated = Utc::now();
+    }
+
+    /// 查找 bullet
+    pub fn find_bullet(&self, id: &str) -> Option<&Bullet> {
+        self.bullets.values().flatten().find(|b| b.id == id)
+    }
+
+    /// 查找 bullet（可变引用）
+    pub fn find_bullet_mut(&mut self, id: &str) -> Option<&mut Bullet> {
+        self.bullets.values_mut().flatten().find(|b| b.id == id)
+    }
+
+    /// 更新 bullet（返回是否成功）
+    pub fn update_bullet(&mut self, updated: Bullet) -> bool {
+        for bullets in self.bullets.values_mut() {
+            if let Some(pos) = bullets.iter().position(|b| b.id == updated.id) {
+                bullets[pos] = updated;
+                self.version += 1;
+                self.last_updated = Utc::now();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 删除 bullet（返回是否成功）
+    pub fn remove_bullet(&mut self, id: &str) -> bool {
+        for (section, bullets) in self.bullets.iter_mut() {
+            if let Some(pos) = bullets.iter().position(|b| b.id == id) {
+                bullets.remove(pos);
+                self.metadata.total_bullets = self.metadata.total_bullets.saturating_sub(1);
+                if let Some(count) = self.metadata.section_counts.get_mut(section) {
+                    *count = count.saturating_sub(1);
+                }
+                self.version += 1;
+                self.last_updated = Utc::now();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 获取所有 bullets（扁平化）
+    pub fn all_bullets(&self) -> Vec<&Bullet> {
+        self.bullets.values().flatten().collect()
+    }
+
+    /// 按 section 获取 bullets
+    pub fn bullets_by_section(&self, section: &BulletSection) -> Vec<&Bullet> {
+        self.bullets
+            .get(section)
+            .map(|v| v.iter().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for Playbook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// RawInsight（Reflector 输出）
+// ============================================================================
+
+/// Reflector 的原始输出（未结构化）
+#[derive(Debug, Clone)]
+pub struct RawInsight {
     /// 洞察内容
     pub content: String,
 
     /// 类别
     pub category: InsightCategory,
 
-    /// 重要性（0.0 - 1.0）
+    /// 重要性
     pub importance: f32,
+
+    /// 来源上下文
+    pub context: InsightContext,
+}
+
+/// 洞察上下文（帮助 Curator 生成 metadata）
+#[derive(Debug, Clone)]
+pub struct InsightContext {
+    /// 用户查询
+    pub user_query: String,
+
+    /// 助手响应片段
+    pub assistant_response_snippet: String,
+
+    /// 执行结果
+    pub execution_success: bool,
+
+    /// 使用的工具
+    pub tools_used: Vec<String>,
+
+    /// 错误信息
+    pub error_message: Option<String>,
+
+    /// 会话ID
+    pub session_id: String,
 }
 
 /// 洞察类别
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum InsightCategory {
     /// 工具使用
     ToolUsage,
@@ -108,8 +699,118 @@ pub enum InsightCategory {
     ErrorHandling,
 }
 
-/// 执行结果
+// ============================================================================
+// DeltaContext（Curator 输出）
+// ============================================================================
+
+/// 增量上下文更新（Curator 输出）
 #[derive(Debug, Clone)]
+pub struct DeltaContext {
+    /// 会话ID
+    pub session_id: String,
+
+    /// 新增的 bullets
+    pub new_bullets: Vec<Bullet>,
+
+    /// 需要更新的 bullets（metadata 变化，合并时内容也可能变化）
+    pub updated_bullets: Vec<Bullet>,
+
+    /// 需要删除的 bullet ID（已合并进其他 bullet）
+    pub deleted_bullet_ids: Vec<String>,
+
+    /// Curator 执行的操作记录
+    pub operations: Vec<DeltaOperation>,
+
+    /// 生成时间
+    pub generated_at: DateTime<Utc>,
+
+    /// 元数据
+    pub metadata: DeltaMetadata,
+}
+
+/// Delta 元数据
+#[derive(Debug, Clone)]
+pub struct DeltaMetadata {
+    /// 处理的 insights 数量
+    pub insights_processed: usize,
+
+    /// 生成的新 bullets 数量
+    pub new_bullets_count: usize,
+
+    /// 更新的 bullets 数量
+    pub updated_bullets_count: usize,
+
+    /// 合并的 insights 数量（并入已有 bullet 而非新增）
+    pub merged_bullets_count: usize,
+
+    /// 删除的 bullets 数量
+    pub deleted_bullets_count: usize,
+
+    /// 处理耗时（毫秒）
+    pub processing_time_ms: u64,
+}
+
+/// Curator 对 playbook 的操作（grow-and-refine）
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeltaOperation {
+    /// 新增 bullet
+    Add { bullet_id: String },
+
+    /// 强化已有 bullet（内容几乎相同，仅更新 metadata）
+    Update { bullet_id: String, similarity: f32 },
+
+    /// 将新洞察合并进已有 bullet（内容相近，刷新内容并合并 metadata）
+    Merge { target_id: String, similarity: f32 },
+
+    /// 删除冗余 bullet（其统计信息已并入 `merged_into`）
+    Delete {
+        bullet_id: String,
+        merged_into: String,
+    },
+}
+
+impl DeltaContext {
+    /// 创建空 delta
+    pub fn new(session_id: String) -> Self {
+        Self {
+            session_id,
+            new_bullets: Vec::new(),
+            updated_bullets: Vec::new(),
+            deleted_bullet_ids: Vec::new(),
+            operations: Vec::new(),
+            generated_at: Utc::now(),
+            metadata: DeltaMetadata {
+                insights_processed: 0,
+                new_bullets_count: 0,
+                updated_bullets_count: 0,
+                merged_bullets_count: 0,
+                deleted_bullets_count: 0,
+                processing_time_ms: 0,
+            },
+        }
+    }
+
+    /// 为新增的 bullets 记录来源（更新的 bullets 保留原有来源）
+    pub fn set_provenance(&mut self, provenance: &BulletProvenance) {
+        for bullet in &mut self.new_bullets {
+            bullet.metadata.provenance = Some(provenance.clone());
+        }
+    }
+
+    /// 是否为空（无变更）
+    pub fn is_empty(&self) -> bool {
+        self.new_bullets.is_empty()
+            && self.updated_bullets.is_empty()
+            && self.deleted_bullet_ids.is_empty()
+    }
+}
+
+// ============================================================================
+// 执行结果
+// ============================================================================
+
+/// 执行结果
+#[derive(Debug, Clone, Default)]
 pub struct ExecutionResult {
     /// 是否成功
     pub success: bool,
@@ -128,23 +829,17 @@ pub struct ExecutionResult {
 
     /// 是否重试成功
     pub retry_success: bool,
-}
 
-impl Default for ExecutionResult {
-    fn default() -> Self {
-        Self {
-            success: false,
-            output: None,
-            error: None,
-            tools_used: Vec::new(),
-            errors: Vec::new(),
-            retry_success: false,
-        }
-    }
+    /// 本轮最终产生的 diff（如果有）
+    pub diff: Option<String>,
 }
 
+// ============================================================================
+// 配置
+// ============================================================================
+
 /// ACE配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ACEConfig {
     /// 是否启用
     pub enabled: bool,
@@ -160,6 +855,22 @@ pub struct ACEConfig {
 
     /// Context配置
     pub context: ContextConfig,
+
+    /// 向量检索配置（可选）
+    #[serde(default)]
+    pub embedding: EmbeddingConfig,
+
+    /// 后台优化器配置
+    #[serde(default)]
+    pub optimizer: OptimizerConfig,
+
+    /// 项目/语言范围配置
+    #[serde(default)]
+    pub scope: ScopeConfig,
+
+    /// 写入 playbook 前的敏感信息脱敏配置
+    #[serde(default)]
+    pub redaction: RedactionConfig,
 }
 
 impl Default for ACEConfig {
@@ -170,12 +881,16 @@ impl Default for ACEConfig {
             max_entries: 500,
             reflector: ReflectorConfig::default(),
             context: ContextConfig::default(),
+            embedding: EmbeddingConfig::default(),
+            optimizer: OptimizerConfig::default(),
+            scope: ScopeConfig::default(),
+            redaction: RedactionConfig::default(),
         }
     }
 }
 
 /// Reflector配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ReflectorConfig {
     /// 提取模式
     pub extract_patterns: bool,
@@ -185,6 +900,18 @@ pub struct ReflectorConfig {
 
     /// 提取错误处理
     pub extract_errors: bool,
+
+    /// 提取模式（rule: 正则规则；llm: 调用模型，离线时回退到 rule）
+    #[serde(default)]
+    pub mode: ReflectorMode,
+
+    /// LLM 模式下单次请求的超时时间（秒）
+    #[serde(default = "default_llm_timeout_secs")]
+    pub llm_timeout_secs: u64,
+}
+
+fn default_llm_timeout_secs() -> u64 {
+    30
 }
 
 impl Default for ReflectorConfig {
@@ -193,12 +920,26 @@ impl Default for ReflectorConfig {
             extract_patterns: true,
             extract_tools: true,
             extract_errors: true,
+            mode: ReflectorMode::default(),
+            llm_timeout_secs: default_llm_timeout_secs(),
         }
     }
 }
 
+/// Reflector 提取模式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReflectorMode {
+    /// 基于正则规则的提取（ReflectorMVP）
+    #[default]
+    Rule,
+
+    /// 基于模型结构化输出的提取（LlmReflector）
+    Llm,
+}
+
 /// Context配置
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ContextConfig {
     /// 最近条目数
     pub max_recent_entries: usize,
@@ -208,6 +949,22 @@ pub struct ContextConfig {
 
     /// 最大字符数
     pub max_context_chars: usize,
+
+    /// 每轮注入的最大 token 数（按当前模型的 tokenizer 计数）
+    #[serde(default = "default_max_context_tokens")]
+    pub max_context_tokens: usize,
+
+    /// 各分类最多占用的 token 数，未列出的分类只受总预算限制
+    #[serde(default = "default_section_quotas")]
+    pub section_quotas: BTreeMap<BulletSection, usize>,
+}
+
+fn default_max_context_tokens() -> usize {
+    1500
+}
+
+fn default_section_quotas() -> BTreeMap<BulletSection, usize> {
+    BTreeMap::from([(BulletSection::CodeSnippetsAndTemplates, 600)])
 }
 
 impl Default for ContextConfig {
@@ -216,17 +973,350 @@ impl Default for ContextConfig {
             max_recent_entries: 10,
             include_all_successes: true,
             max_context_chars: 4000,
+            max_context_tokens: default_max_context_tokens(),
+            section_quotas: default_section_quotas(),
+        }
+    }
+}
+
+/// 向量检索配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EmbeddingConfig {
+    /// 是否启用向量检索（关闭时仅使用关键词检索）
+    pub enabled: bool,
+
+    /// 向量提供者
+    pub provider: EmbeddingProvider,
+
+    /// Ollama 向量模型
+    pub model: String,
+
+    /// hashing 提供者的向量维度
+    pub dimensions: usize,
+
+    /// 混合评分中余弦相似度的权重（0.0 - 1.0），其余为关键词得分
+    pub weight: f32,
+
+    /// 仅通过向量召回的 bullet 需要达到的最低余弦相似度
+    pub min_similarity: f32,
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: EmbeddingProvider::default(),
+            model: "nomic-embed-text".to_string(),
+            dimensions: 256,
+            weight: 0.5,
+            min_similarity: 0.45,
+        }
+    }
+}
+
+/// 向量提供者
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// 本地 Ollama 服务（embedder 由前端创建并随会话传入）
+    #[default]
+    Ollama,
+
+    /// 确定性的特征哈希向量（无需网络，主要用于测试）
+    Hashing,
+}
+
+/// 范围配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ScopeConfig {
+    /// 是否为 bullets 打上项目/语言范围，并在检索时按会话范围过滤和加权
+    pub enabled: bool,
+
+    /// 只注入当前项目（及未绑定项目）的 bullets
+    pub project_only: bool,
+
+    /// 仓库本地 playbook 目录（相对仓库根目录，可提交到 git 与团队共享），空字符串表示禁用
+    pub repo_playbook_dir: String,
+}
+
+impl Default for ScopeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            project_only: false,
+            repo_playbook_dir: ".codeace/playbook".to_string(),
+        }
+    }
+}
+
+/// Curator配置
+#[derive(Debug, Clone)]
+pub struct CuratorConfig {
+    /// 最小重要性阈值
+    pub min_importance: f32,
+
+    /// 是否自动分类
+    pub auto_categorize: bool,
+
+    /// 是否生成标签
+    pub generate_tags: bool,
+
+    /// 是否与已有 bullets 比较并执行 update/merge/delete
+    pub dedup_enabled: bool,
+
+    /// 相似度不低于此值时视为同一条知识，仅强化已有 bullet
+    pub update_threshold: f32,
+
+    /// 相似度不低于此值时合并进已有 bullet（刷新内容）
+    pub merge_threshold: f32,
+
+    /// 每次强化/合并时增加的置信度
+    pub confidence_boost: f32,
+}
+
+impl Default for CuratorConfig {
+    fn default() -> Self {
+        Self {
+            min_importance: 0.5,
+            auto_categorize: true,
+            generate_tags: true,
+            dedup_enabled: true,
+            update_threshold: 0.9,
+            merge_threshold: 0.75,
+            confidence_boost: 0.05,
         }
     }
 }
 
+// ============================================================================
 // 辅助函数
+// ============================================================================
 
-/// 截断字符串到指定长度
-fn truncate_string(s: &str, max_len: usize) -> String {
+/// 截断字符串到指定长度（安全处理 UTF-8 边界）
+pub fn truncate_string(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len])
+        // 找到安全的 UTF-8 字符边界
+        let mut boundary = max_len;
+        while boundary > 0 && !s.is_char_boundary(boundary) {
+            boundary -= 1;
+        }
+
+        if boundary == 0 {
+            // 如果连第一个字符都放不下，返回空字符串
+            "...".to_string()
+        } else {
+            format!("{}...", &s[..boundary])
+        }
+    }
+}
+
+// ============================================================================
+// 对话精华提取（Essence Extraction）
+// ============================================================================
+
+/// 一次对话的精华总结
+///
+/// 目标：压缩并提取精华，让历史上下文膨胀得慢一些
+/// 一次用户对话通常只生成一条精炼的 insight (200-800 字符)
+#[derive(Debug, Clone)]
+pub struct ConversationSummary {
+    /// 用户要求（原始 query）
+    pub user_request: String,
+
+    /// 任务类型
+    pub task_type: TaskType,
+
+    /// 最终状态
+    pub final_state: FinalState,
+
+    /// 提取的精华信息
+    pub essence: TaskEssence,
+}
+
+/// 任务类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskType {
+    /// 代码实现
+    CodeImplementation,
+
+    /// 问题修复
+    BugFix,
+
+    /// 测试运行
+    Testing,
+
+    /// 重构
+    Refactoring,
+
+    /// 配置修改
+    Configuration,
+
+    /// 文档编写
+    Documentation,
+
+    /// 其他
+    Other,
+}
+
+/// 最终状态
+#[derive(Debug, Clone)]
+pub enum FinalState {
+    /// 成功完成
+    Completed {
+        /// 简短总结（一句话）
+        summary: String,
+    },
+
+    /// 部分完成
+    PartiallyCompleted {
+        /// 完成了什么
+        completed: String,
+        /// 未完成什么
+        remaining: String,
+    },
+
+    /// 失败未解决
+    Failed {
+        /// 问题描述
+        problem: String,
+        /// 后续计划
+        next_steps: Vec<String>,
+    },
+}
+
+/// 任务精华（只保留最终结果）
+#[derive(Debug, Clone)]
+pub struct TaskEssence {
+    /// 做了什么（最终结果，不记录中间过程）
+    pub what_was_done: String,
+
+    /// 为什么这么做
+    pub why: Option<String>,
+
+    /// 最终代码（只保留最后成功的版本）
+    pub final_code: Vec<CodeBlock>,
+
+    /// 解决了什么问题
+    pub problem_solved: Option<String>,
+
+    /// 修改的文件
+    pub modified_files: Vec<String>,
+
+    /// 关键决策（只保留最后的决策）
+    pub key_decisions: Vec<String>,
+}
+
+/// 代码块（最终版本）
+#[derive(Debug, Clone)]
+pub struct CodeBlock {
+    /// 编程语言
+    pub language: String,
+
+    /// 代码内容
+    pub code: String,
+
+    /// 文件路径（如果有）
+    pub file_path: Option<String>,
+
+    /// 简短描述（如："异步函数实现，45 行"）
+    pub description: String,
+}
+
+/// 文件修改类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModificationType {
+    /// 新建
+    Created,
+
+    /// 修改
+    Modified,
+
+    /// 删除
+    Deleted,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_string_ascii() {
+        // ASCII 字符串
+        let result = truncate_string("hello world", 5);
+        assert_eq!(result, "hello...");
+
+        let result = truncate_string("hello", 10);
+        assert_eq!(result, "hello");
+    }
+
+    #[test]
+    fn test_truncate_string_utf8() {
+        // 中文字符（每个字符 3 字节）
+        let result = truncate_string("你好世界", 6); // 正好 2 个中文字符
+        assert_eq!(result, "你好...");
+
+        // 边界不对齐的情况
+        let result = truncate_string("你好世界", 7); // 7 字节落在第 3 个字符中间
+        assert_eq!(result, "你好..."); // 应该截断到安全边界
+
+        // 混合字符
+        let result = truncate_string("Hello你好World", 10);
+        assert!(result.contains("..."));
+        assert!(!result.contains("�")); // 不应该有乱码
+    }
+
+    #[test]
+    fn test_truncate_string_emoji() {
+        // Emoji（4 字节）
+        let result = truncate_string("😀😁😂", 4);
+        assert_eq!(result, "😀...");
+
+        let result = truncate_string("😀😁😂", 5); // 落在第 2 个 emoji 中间
+        assert_eq!(result, "😀..."); // 应该截断到第 1 个
+    }
+
+    #[test]
+    fn test_truncate_string_edge_cases() {
+        // 空字符串
+        let result = truncate_string("", 10);
+        assert_eq!(result, "");
+
+        // 极小的 max_len
+        let result = truncate_string("你好", 1);
+        assert_eq!(result, "..."); // 无法容纳任何字符
+
+        // max_len 为 0
+        let result = truncate_string("hello", 0);
+        assert_eq!(result, "...");
+    }
+
+    #[test]
+    fn test_record_user_feedback() {
+        let mut metadata = BulletMetadata {
+            recall_count: 3,
+            ..Default::default()
+        };
+        let baseline = metadata.calculate_dynamic_weight();
+
+        metadata.record_user_feedback(PlaybookFeedbackRating::Downvote);
+        metadata.record_user_feedback(PlaybookFeedbackRating::Downvote);
+        assert_eq!(metadata.failure_count, 2);
+        assert!((metadata.confidence - 0.6).abs() < 1e-6);
+        assert!(metadata.calculate_dynamic_weight() < baseline);
+
+        metadata.record_user_feedback(PlaybookFeedbackRating::Flag);
+        assert!(metadata.flagged);
+        assert_eq!(metadata.failure_count, 3);
+
+        // 点赞视为复查通过
+        metadata.record_user_feedback(PlaybookFeedbackRating::Upvote);
+        assert!(!metadata.flagged);
+        assert_eq!(metadata.success_count, 1);
+        assert!((metadata.confidence - 0.8).abs() < 1e-6);
+        assert_eq!(metadata.success_rate, 0.25);
     }
 }
@@ -92,8 +92,9 @@ async fn main() -> anyhow::Result<()> {
     let test_query = "如何运行rust测试";
     println!("\n   测试查询: \"{}\"", test_query);
 
-    match plugin.pre_execute(test_query) {
-        Some(context) => {
+    match plugin.pre_execute(test_query).await.first() {
+        Some(contribution) => {
+            let context = &contribution.content;
             println!("   ✅ pre_execute返回了上下文!");
             println!("   上下文长度: {} 字符", context.len());
             println!("   上下文预览:");
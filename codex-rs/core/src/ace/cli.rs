@@ -4,11 +4,36 @@
 
 use anyhow::Context;
 use anyhow::Result;
+use anyhow::bail;
 use std::path::Path;
 use std::path::PathBuf;
 
+use super::background_optimizer::OptimizationReport;
 use super::config_loader::ACEConfigLoader;
+use super::eval;
+use super::eval::EvalOptions;
+use super::migration::migrate_simple_storage;
+use super::provenance;
+use super::provenance::TurnEntry;
+use super::provenance::TurnTranscript;
+use super::redaction::RedactionConfig;
+use super::redaction::Redactor;
 use super::storage::BulletStorage;
+use super::storage::StorageStats;
+use super::transfer;
+use super::transfer::ConflictPolicy;
+use super::transfer::ImportReport;
+use super::transfer::PlaybookFormat;
+use super::types::ACEConfig;
+use super::types::Bullet;
+use super::types::BulletMetadata;
+use super::types::truncate_string;
+use crate::RolloutRecorder;
+use crate::mission::MissionContext;
+use crate::mission::MissionManager;
+use crate::rollout::find_conversation_path_by_id_str;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::protocol::InitialHistory;
 
 /// ACE CLI commands
 #[derive(Debug, Clone)]
@@ -30,9 +55,119 @@ pub enum AceCommand {
 
     /// Display configuration information
     Config,
+
+    /// Display missions and their todo history recorded in session rollouts
+    Missions {
+        /// Number of most recent sessions to scan
+        limit: usize,
+        /// Only show this session (conversation id)
+        session: Option<String>,
+    },
+
+    /// Export the playbook to a shareable file
+    Export {
+        /// Destination file
+        output: PathBuf,
+        /// Output format; inferred from the file extension when `None`
+        format: Option<PlaybookFormat>,
+        /// Export the repo-local playbook instead of the personal one
+        repo: bool,
+    },
+
+    /// Import bullets from an exported file
+    Import {
+        /// Source file
+        input: PathBuf,
+        /// Input format; inferred from the file extension when `None`
+        format: Option<PlaybookFormat>,
+        /// How to handle bullets that duplicate existing ones
+        on_conflict: ConflictPolicy,
+        /// Import into the repo-local playbook instead of the personal one
+        repo: bool,
+        /// Report what would change without writing
+        dry_run: bool,
+    },
+
+    /// Scan the playbook and its archives for secrets
+    Scan {
+        /// Scrub the secrets that were found
+        fix: bool,
+        /// Scan the repo-local playbook instead of the personal one
+        repo: bool,
+    },
+
+    /// Show where an entry came from and the conversation turn that produced it
+    Explain {
+        /// Entry id or a unique prefix of it
+        bullet_id: String,
+        /// Look the entry up in the repo-local playbook instead of the personal one
+        repo: bool,
+    },
+
+    /// Replay session rollouts against a scratch playbook and report retrieval metrics
+    Eval {
+        /// Directory searched recursively for `*.jsonl` rollouts
+        dir: PathBuf,
+        /// Number of entries retrieved per turn
+        top_k: usize,
+        /// Print the report as JSON
+        json: bool,
+    },
+}
+
+/// Result of [`AceCliHandler::explain`]
+#[derive(Debug, Clone)]
+pub struct BulletExplanation {
+    pub bullet: Bullet,
+    /// Rollout of the originating session, if it is still on disk
+    pub rollout_path: Option<PathBuf>,
+    /// The originating turn, if it was found in the rollout
+    pub turn: Option<TurnTranscript>,
+}
+
+/// Result of [`AceCliHandler::scan`]; never contains the secrets themselves
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// Number of bullets scanned
+    pub bullets_scanned: usize,
+    /// Bullets with secrets: (id, detector kinds)
+    pub bullets: Vec<(String, Vec<&'static str>)>,
+    /// Archive files with secrets: (path, number of findings)
+    pub archives: Vec<(PathBuf, usize)>,
+    /// Whether the findings were scrubbed
+    pub fixed: bool,
+}
+
+/// ACE status returned by [`AceCliHandler::status`]
+#[derive(Debug, Clone)]
+pub struct AceStatus {
+    pub enabled: bool,
+    pub storage_path: String,
+    pub max_entries: usize,
+    pub optimizer_enabled: bool,
+    pub stats: StorageStats,
+    /// Most recent background optimizer run, if any
+    pub last_optimization: Option<OptimizationReport>,
+}
+
+/// In-place edit of a single bullet (used by the TUI playbook browser)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulletAction {
+    /// Pin or unpin; pinned bullets are never decayed, demoted or archived by the optimizer.
+    /// Pinning also clears a user flag.
+    SetPinned(bool),
+    /// Replace the bullet content and clear a user flag
+    Edit(String),
+    /// Delete without archiving
+    Delete,
+    /// Move the bullet to the archive directory
+    Archive,
 }
 
 /// CLI command handler
+///
+/// The `handle_*` methods print to stdout for `codex ace`; the other public
+/// methods return structured results for embedding (e.g. in the TUI).
 pub struct AceCliHandler {
     codex_home: std::path::PathBuf,
 }
@@ -53,35 +188,372 @@ impl AceCliHandler {
             AceCommand::Clear { no_archive } => self.handle_clear(no_archive).await,
             AceCommand::Search { query } => self.handle_search(&query).await,
             AceCommand::Config => self.handle_config().await,
+            AceCommand::Missions { limit, session } => {
+                self.handle_missions(limit, session.as_deref()).await
+            }
+            AceCommand::Export {
+                output,
+                format,
+                repo,
+            } => self.handle_export(&output, format, repo).await,
+            AceCommand::Import {
+                input,
+                format,
+                on_conflict,
+                repo,
+                dry_run,
+            } => {
+                self.handle_import(&input, format, on_conflict, repo, dry_run)
+                    .await
+            }
+            AceCommand::Scan { fix, repo } => self.handle_scan(fix, repo).await,
+            AceCommand::Explain { bullet_id, repo } => self.handle_explain(&bullet_id, repo).await,
+            AceCommand::Eval { dir, top_k, json } => self.handle_eval(&dir, top_k, json).await,
         }
     }
 
-    /// Handle status command
-    pub async fn handle_status(&self) -> Result<()> {
-        // Load configuration
-        let config_loader = ACEConfigLoader::new(&self.codex_home);
-        let config = config_loader
+    async fn load_config(&self) -> Result<ACEConfig> {
+        ACEConfigLoader::new(&self.codex_home)
             .load_or_create()
             .await
-            .context("Failed to load ACE config")?;
+            .context("Failed to load ACE config")
+    }
+
+    /// Load configuration and open the playbook storage
+    async fn open_storage(&self) -> Result<(ACEConfig, BulletStorage)> {
+        let config = self.load_config().await?;
 
-        // Load storage
         let storage_path = shellexpand::tilde(&config.storage_path).to_string();
         let storage = BulletStorage::new(PathBuf::from(storage_path), config.max_entries)
             .context("Failed to open ACE storage")?;
+        // Playbooks written by the old SimpleStorage are converted on first access
+        migrate_simple_storage(&storage)
+            .await
+            .context("Failed to migrate legacy ACE playbook")?;
+
+        Ok((config, storage))
+    }
+
+    /// Open the repo-local playbook shared through git
+    ///
+    /// Resolved from the git root of the current directory and
+    /// `scope.repo_playbook_dir`; created when missing.
+    async fn open_repo_storage(&self) -> Result<BulletStorage> {
+        let config = self.load_config().await?;
+        if config.scope.repo_playbook_dir.is_empty() {
+            bail!("Repo playbook is disabled (scope.repo_playbook_dir is empty)");
+        }
+
+        let cwd = std::env::current_dir()?;
+        let repo_root = crate::git_info::get_git_repo_root(&cwd).unwrap_or(cwd);
+        let path = repo_root.join(&config.scope.repo_playbook_dir);
+        BulletStorage::new(&path, usize::MAX)
+            .with_context(|| format!("Failed to open repo playbook {}", path.display()))
+    }
+
+    async fn open_target_storage(&self, repo: bool) -> Result<BulletStorage> {
+        if repo {
+            self.open_repo_storage().await
+        } else {
+            Ok(self.open_storage().await?.1)
+        }
+    }
+
+    /// Write the playbook to `output`; returns the number of exported bullets
+    pub async fn export(&self, output: &Path, format: PlaybookFormat, repo: bool) -> Result<usize> {
+        let storage = self.open_target_storage(repo).await?;
+        let playbook = storage.load_playbook().await?;
+        let bullets: Vec<Bullet> = playbook.all_bullets().into_iter().cloned().collect();
+
+        let contents = transfer::export_bullets(&bullets, format)?;
+        tokio::fs::write(output, contents)
+            .await
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        Ok(bullets.len())
+    }
+
+    /// Merge bullets from `input` into the playbook
+    ///
+    /// With `dry_run` the merge is computed against the current playbook but
+    /// nothing is written.
+    pub async fn import(
+        &self,
+        input: &Path,
+        format: PlaybookFormat,
+        on_conflict: ConflictPolicy,
+        repo: bool,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let contents = tokio::fs::read_to_string(input)
+            .await
+            .with_context(|| format!("Failed to read {}", input.display()))?;
+        let file_name = input
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let bullets = transfer::parse_bullets(&contents, format, &format!("import:{file_name}"))
+            .with_context(|| format!("Failed to parse {}", input.display()))?;
+
+        let storage = self.open_target_storage(repo).await?;
+        if dry_run {
+            let mut playbook = storage.load_playbook().await?;
+            return Ok(transfer::merge_into_playbook(
+                &mut playbook,
+                bullets,
+                on_conflict,
+            ));
+        }
+
+        let (_, report) = storage
+            .rewrite_playbook(move |playbook| {
+                Ok(transfer::merge_into_playbook(
+                    playbook,
+                    bullets,
+                    on_conflict,
+                ))
+            })
+            .await?;
+        Ok(report)
+    }
+
+    /// Look up an entry and the conversation turn that produced it
+    ///
+    /// `bullet_id` may be a unique prefix. The rollout is located through the
+    /// recorded provenance, falling back to searching the sessions directory
+    /// by conversation id.
+    pub async fn explain(&self, bullet_id: &str, repo: bool) -> Result<BulletExplanation> {
+        let storage = self.open_target_storage(repo).await?;
+        let playbook = storage.load_playbook().await?;
+        let matches: Vec<&Bullet> = playbook
+            .all_bullets()
+            .into_iter()
+            .filter(|bullet| bullet.id.starts_with(bullet_id))
+            .collect();
+        let bullet = match matches.as_slice() {
+            [bullet] => (*bullet).clone(),
+            [] => bail!("No entry found with id {bullet_id}"),
+            _ => match matches.iter().find(|bullet| bullet.id == bullet_id) {
+                Some(bullet) => (*bullet).clone(),
+                None => bail!("Id {bullet_id} matches {} entries", matches.len()),
+            },
+        };
+
+        let provenance = bullet.metadata.provenance.as_ref();
+        let conversation_id = provenance.map_or(bullet.source_session_id.as_str(), |provenance| {
+            provenance.conversation_id.as_str()
+        });
+        let rollout_path = match provenance
+            .and_then(|provenance| provenance.rollout_path.clone())
+            .filter(|path| path.exists())
+        {
+            Some(path) => Some(path),
+            None => find_conversation_path_by_id_str(&self.codex_home, conversation_id)
+                .await
+                .ok()
+                .flatten(),
+        };
+
+        let turn_id = provenance.and_then(|provenance| provenance.turn_id.as_deref());
+        let turn = match &rollout_path {
+            Some(path) => provenance::load_turn(path, turn_id, bullet.created_at).await?,
+            None => None,
+        };
+
+        Ok(BulletExplanation {
+            bullet,
+            rollout_path,
+            turn,
+        })
+    }
+
+    /// Find secrets in the playbook and its archive files
+    ///
+    /// Uses the configured detectors even when redaction is disabled for
+    /// learning. With `fix`, bullets are rewritten under the storage lock
+    /// (which also compacts the journal) and archive files are rewritten in place.
+    pub async fn scan(&self, fix: bool, repo: bool) -> Result<ScanReport> {
+        let config = self.load_config().await?;
+        let redactor = Redactor::new(&RedactionConfig {
+            enabled: true,
+            ..config.redaction
+        })?;
+        let redactor = std::sync::Arc::new(redactor);
+        let storage = self.open_target_storage(repo).await?;
+
+        let mut report = ScanReport {
+            fixed: fix,
+            ..Default::default()
+        };
+
+        let playbook = storage.load_playbook().await?;
+        for bullet in playbook.all_bullets() {
+            report.bullets_scanned += 1;
+            let kinds = redactor.redact_bullet(&mut bullet.clone());
+            if !kinds.is_empty() {
+                report.bullets.push((bullet.id.clone(), kinds));
+            }
+        }
+        report.bullets.sort();
+
+        if fix && !report.bullets.is_empty() {
+            let redactor = std::sync::Arc::clone(&redactor);
+            storage
+                .rewrite_playbook(move |playbook| {
+                    let mut redacted: Vec<Bullet> =
+                        playbook.all_bullets().into_iter().cloned().collect();
+                    redacted.retain_mut(|bullet| !redactor.redact_bullet(bullet).is_empty());
+                    for bullet in redacted {
+                        playbook.update_bullet(bullet);
+                    }
+                    Ok(())
+                })
+                .await?;
+        }
+
+        let archive_dir = storage.base_dir().join("archive");
+        let mut entries = tokio::fs::read_dir(&archive_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let contents = tokio::fs::read_to_string(&path).await?;
+            let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&contents) else {
+                tracing::warn!("Skipping unreadable archive {}", path.display());
+                continue;
+            };
+            let count = redactor.redact_json(&mut value);
+            if count == 0 {
+                continue;
+            }
+            if fix {
+                tokio::fs::write(&path, serde_json::to_string_pretty(&value)?)
+                    .await
+                    .with_context(|| format!("Failed to rewrite {}", path.display()))?;
+            }
+            report.archives.push((path, count));
+        }
+        report.archives.sort();
+
+        Ok(report)
+    }
 
-        // Get statistics
+    /// Collect ACE status and statistics
+    pub async fn status(&self) -> Result<AceStatus> {
+        let (config, storage) = self.open_storage().await?;
         let stats = storage.get_stats().await?;
+        let last_optimization = OptimizationReport::load(storage.base_dir()).await;
+
+        Ok(AceStatus {
+            enabled: config.enabled,
+            storage_path: config.storage_path,
+            max_entries: config.max_entries,
+            optimizer_enabled: config.optimizer.enabled,
+            stats,
+            last_optimization,
+        })
+    }
+
+    /// All bullets, grouped by section; pinned first, then most recently updated
+    pub async fn list_bullets(&self) -> Result<Vec<Bullet>> {
+        let (_, storage) = self.open_storage().await?;
+        let playbook = storage.load_playbook().await?;
+        let mut bullets: Vec<Bullet> = playbook.all_bullets().into_iter().cloned().collect();
+        bullets.sort_by(|a, b| {
+            a.section
+                .cmp(&b.section)
+                .then_with(|| b.metadata.pinned.cmp(&a.metadata.pinned))
+                .then_with(|| b.updated_at.cmp(&a.updated_at))
+        });
+        Ok(bullets)
+    }
+
+    /// Most recently updated bullets
+    pub async fn recent_bullets(&self, limit: usize) -> Result<Vec<Bullet>> {
+        let mut bullets = self.list_bullets().await?;
+        bullets.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        bullets.truncate(limit);
+        Ok(bullets)
+    }
+
+    /// Search bullets by content
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Bullet>> {
+        let (_, storage) = self.open_storage().await?;
+        storage.query_bullets(query, limit).await
+    }
+
+    /// Apply an in-place edit to a bullet
+    ///
+    /// Returns the updated bullet, or `None` when it was deleted or archived.
+    pub async fn apply_bullet_action(
+        &self,
+        id: &str,
+        action: BulletAction,
+    ) -> Result<Option<Bullet>> {
+        let (_, storage) = self.open_storage().await?;
+        let ids = vec![id.to_string()];
+
+        match action {
+            BulletAction::SetPinned(pinned) => {
+                let (_, updated) = storage
+                    .update_bullets_with(String::new(), ids, move |bullet| {
+                        bullet.metadata.pinned = pinned;
+                        bullet.metadata.flagged &= !pinned;
+                    })
+                    .await?;
+                match updated.into_iter().next() {
+                    Some(bullet) => Ok(Some(bullet)),
+                    None => bail!("Bullet {id} not found"),
+                }
+            }
+            BulletAction::Edit(content) => {
+                let content = content.trim().to_string();
+                if content.is_empty() {
+                    bail!("Bullet content cannot be empty");
+                }
+                let (_, updated) = storage
+                    .update_bullets_with(String::new(), ids, move |bullet| {
+                        bullet.content = content.clone();
+                        bullet.metadata.flagged = false;
+                        bullet.updated_at = chrono::Utc::now();
+                    })
+                    .await?;
+                match updated.into_iter().next() {
+                    Some(bullet) => Ok(Some(bullet)),
+                    None => bail!("Bullet {id} not found"),
+                }
+            }
+            BulletAction::Delete => {
+                if storage.remove_bullets(ids).await?.is_empty() {
+                    bail!("Bullet {id} not found");
+                }
+                Ok(None)
+            }
+            BulletAction::Archive => {
+                let removed = storage.remove_bullets(ids).await?;
+                if removed.is_empty() {
+                    bail!("Bullet {id} not found");
+                }
+                storage.archive_bullets("manual", &removed).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Handle status command
+    pub async fn handle_status(&self) -> Result<()> {
+        let status = self.status().await?;
+        let stats = &status.stats;
 
         // Display status
         println!("📚 ACE (Agentic Coding Environment) Status\n");
         println!("Configuration:");
         println!(
             "  Enabled: {}",
-            if config.enabled { "✅ Yes" } else { "❌ No" }
+            if status.enabled { "✅ Yes" } else { "❌ No" }
         );
-        println!("  Storage: {}", config.storage_path);
-        println!("  Max entries: {}", config.max_entries);
+        println!("  Storage: {}", status.storage_path);
+        println!("  Max entries: {}", status.max_entries);
         println!();
 
         println!("Playbook Statistics:");
@@ -108,6 +580,38 @@ impl AceCliHandler {
             println!();
         }
 
+        println!("Recall:");
+        println!(
+            "  Recalled bullets: {} / {}",
+            stats.recalled_bullets, stats.total_bullets
+        );
+        println!("  Total recalls: {}", stats.total_recalls);
+        println!();
+
+        println!("Background Optimizer:");
+        println!(
+            "  Enabled: {}",
+            if status.optimizer_enabled {
+                "✅ Yes"
+            } else {
+                "❌ No"
+            }
+        );
+        match &status.last_optimization {
+            Some(report) => {
+                println!(
+                    "  Last run: {}",
+                    report.finished_at.format("%Y-%m-%d %H:%M:%S UTC")
+                );
+                println!("  Deduplicated: {}", report.deduplicated);
+                println!("  Decayed: {}", report.decayed);
+                println!("  Demoted: {}", report.demoted);
+                println!("  Archived: {}", report.archived);
+            }
+            None => println!("  Last run: never"),
+        }
+        println!();
+
         if stats.total_bullets > 0 {
             println!(
                 "Overall Success Rate: {:.1}%",
@@ -120,17 +624,7 @@ impl AceCliHandler {
 
     /// Handle show command
     pub async fn handle_show(&self, limit: usize) -> Result<()> {
-        // Load configuration
-        let config_loader = ACEConfigLoader::new(&self.codex_home);
-        let config = config_loader.load_or_create().await?;
-
-        // Load storage
-        let storage_path = shellexpand::tilde(&config.storage_path).to_string();
-        let storage = BulletStorage::new(PathBuf::from(storage_path), config.max_entries)?;
-
-        // Load all bullets
-        let playbook = storage.load_playbook().await?;
-        let bullets: Vec<_> = playbook.all_bullets().into_iter().cloned().collect();
+        let bullets = self.list_bullets().await?;
 
         if bullets.is_empty() {
             println!("📭 No learning entries found yet.");
@@ -150,10 +644,11 @@ impl AceCliHandler {
 
         for (i, bullet) in sorted_bullets.iter().take(limit).enumerate() {
             println!(
-                "{}. [{:?}] {}",
+                "{}. [{:?}] {}{}",
                 i + 1,
                 bullet.section,
-                bullet.updated_at.format("%Y-%m-%d %H:%M")
+                bullet.updated_at.format("%Y-%m-%d %H:%M"),
+                bullet_markers(&bullet.metadata)
             );
 
             // Display content (truncated)
@@ -163,6 +658,7 @@ impl AceCliHandler {
                 bullet.content.clone()
             };
             println!("   {content}");
+            println!("   Id: {}", bullet.id);
 
             // Display tools
             if !bullet.metadata.related_tools.is_empty() {
@@ -195,13 +691,7 @@ impl AceCliHandler {
 
     /// Handle clear command
     pub async fn handle_clear(&self, no_archive: bool) -> Result<()> {
-        // Load configuration
-        let config_loader = ACEConfigLoader::new(&self.codex_home);
-        let config = config_loader.load_or_create().await?;
-
-        // Load storage
-        let storage_path = shellexpand::tilde(&config.storage_path).to_string();
-        let storage = BulletStorage::new(PathBuf::from(storage_path), config.max_entries)?;
+        let (_, storage) = self.open_storage().await?;
 
         // Get current entry count
         let playbook = storage.load_playbook().await?;
@@ -252,16 +742,7 @@ impl AceCliHandler {
 
     /// Handle search command
     pub async fn handle_search(&self, query: &str) -> Result<()> {
-        // Load configuration
-        let config_loader = ACEConfigLoader::new(&self.codex_home);
-        let config = config_loader.load_or_create().await?;
-
-        // Load storage
-        let storage_path = shellexpand::tilde(&config.storage_path).to_string();
-        let storage = BulletStorage::new(PathBuf::from(storage_path), config.max_entries)?;
-
-        // Search
-        let results = storage.query_bullets(query, 20).await?;
+        let results = self.search(query, 20).await?;
 
         if results.is_empty() {
             println!("🔍 No results found for '{query}'");
@@ -289,6 +770,249 @@ impl AceCliHandler {
         Ok(())
     }
 
+    /// Handle export command
+    pub async fn handle_export(
+        &self,
+        output: &Path,
+        format: Option<PlaybookFormat>,
+        repo: bool,
+    ) -> Result<()> {
+        let format = resolve_format(output, format)?;
+        let count = self.export(output, format, repo).await?;
+        println!("📤 Exported {count} entries to {}", output.display());
+        Ok(())
+    }
+
+    /// Handle import command
+    pub async fn handle_import(
+        &self,
+        input: &Path,
+        format: Option<PlaybookFormat>,
+        on_conflict: ConflictPolicy,
+        repo: bool,
+        dry_run: bool,
+    ) -> Result<()> {
+        let format = resolve_format(input, format)?;
+        let report = self
+            .import(input, format, on_conflict, repo, dry_run)
+            .await?;
+
+        if dry_run {
+            println!("🔎 Dry run: {} (nothing written)", input.display());
+        } else {
+            println!("📥 Imported {}", input.display());
+        }
+        println!("   Added:       {}", report.added);
+        println!("   Merged:      {}", report.merged);
+        println!("   Overwritten: {}", report.overwritten);
+        println!("   Skipped:     {}", report.skipped);
+        Ok(())
+    }
+
+    /// Handle scan command
+    pub async fn handle_scan(&self, fix: bool, repo: bool) -> Result<()> {
+        let report = self.scan(fix, repo).await?;
+
+        println!(
+            "🔒 Scanned {} entries and their archives",
+            report.bullets_scanned
+        );
+        if report.bullets.is_empty() && report.archives.is_empty() {
+            println!("✅ No secrets found.");
+            return Ok(());
+        }
+
+        for (id, kinds) in &report.bullets {
+            println!("   Entry {id}: {}", summarize_kinds(kinds));
+        }
+        for (path, count) in &report.archives {
+            println!("   Archive {}: {count} finding(s)", path.display());
+        }
+
+        if report.fixed {
+            println!("✅ Secrets replaced with [REDACTED:<kind>].");
+        } else {
+            println!("\nRun `codex ace scan --fix` to scrub them.");
+        }
+        Ok(())
+    }
+
+    /// Handle explain command
+    pub async fn handle_explain(&self, bullet_id: &str, repo: bool) -> Result<()> {
+        let BulletExplanation {
+            bullet,
+            rollout_path,
+            turn,
+        } = self.explain(bullet_id, repo).await?;
+        let metadata = &bullet.metadata;
+
+        println!("🔎 Entry {}{}", bullet.id, bullet_markers(metadata));
+        println!("   Section: {}", bullet.section.title());
+        println!(
+            "   Created: {}  Updated: {}",
+            bullet.created_at.format("%Y-%m-%d %H:%M"),
+            bullet.updated_at.format("%Y-%m-%d %H:%M")
+        );
+        println!(
+            "   Source: {:?}, importance {:.2}, confidence {:.2}",
+            metadata.source_type, metadata.importance, metadata.confidence
+        );
+        println!(
+            "   Applied: {} succeeded, {} failed, recalled {} times",
+            metadata.success_count, metadata.failure_count, metadata.recall_count
+        );
+        println!();
+        for line in bullet.content.lines() {
+            println!("   {line}");
+        }
+        println!();
+
+        println!("📜 Provenance");
+        match &metadata.provenance {
+            Some(provenance) => {
+                println!("   Session: {}", provenance.conversation_id);
+                println!(
+                    "   Turn: {}",
+                    provenance.turn_id.as_deref().unwrap_or("unknown")
+                );
+            }
+            None => {
+                println!(
+                    "   Session: {} (recorded before provenance tracking)",
+                    bullet.source_session_id
+                );
+            }
+        }
+        match &rollout_path {
+            Some(path) => println!("   Rollout: {}", path.display()),
+            None => {
+                println!("   Rollout: not found");
+                println!("\nThe originating session is no longer available on this machine.");
+                return Ok(());
+            }
+        }
+
+        let Some(turn) = turn else {
+            println!("\nThe originating turn was not found in the rollout.");
+            return Ok(());
+        };
+        println!("\n💬 Originating turn (started {})", turn.started_at);
+        for entry in &turn.entries {
+            print_turn_entry(entry);
+        }
+
+        Ok(())
+    }
+
+    /// Handle eval command
+    pub async fn handle_eval(&self, dir: &Path, top_k: usize, json: bool) -> Result<()> {
+        let report = eval::evaluate(dir, &EvalOptions { top_k }).await?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+
+        println!(
+            "📊 Replayed {} rollouts ({} turns) from {}",
+            report.rollouts,
+            report.turns,
+            dir.display()
+        );
+        if report.skipped_rollouts > 0 {
+            println!("   Skipped {} unreadable rollouts", report.skipped_rollouts);
+        }
+        println!(
+            "   Hit rate:   {:.1}% ({} of {} turns with retrieval)",
+            report.hit_rate * 100.0,
+            report.turns_with_hit,
+            report.turns_with_retrieval
+        );
+        println!(
+            "   Precision:  {:.1}% ({} of {} retrieved entries)",
+            report.precision * 100.0,
+            report.useful_bullets,
+            report.retrieved_bullets
+        );
+        println!(
+            "   Injected:   {:.0} tokens/turn ({} total)",
+            report.injected_tokens_per_turn, report.injected_tokens
+        );
+        println!(
+            "   Playbook:   {} entries, {} near-duplicates ({:.1}%)",
+            report.final_bullets,
+            report.duplicate_bullets,
+            report.duplication_ratio * 100.0
+        );
+        let growth: Vec<String> = report
+            .playbook_growth
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        println!("   Growth:     {}", growth.join(" → "));
+        Ok(())
+    }
+
+    /// Handle missions command
+    pub async fn handle_missions(&self, limit: usize, session: Option<&str>) -> Result<()> {
+        let paths = match session {
+            Some(id) => vec![
+                find_conversation_path_by_id_str(&self.codex_home, id)
+                    .await?
+                    .with_context(|| format!("No session found with id {id}"))?,
+            ],
+            None => {
+                RolloutRecorder::list_conversations(&self.codex_home, limit, None, &[], None, "")
+                    .await?
+                    .items
+                    .into_iter()
+                    .map(|item| item.path)
+                    .collect()
+            }
+        };
+
+        let mut sessions_with_missions = 0;
+        for path in paths {
+            let history = match RolloutRecorder::get_rollout_history(&path).await {
+                Ok(history) => history,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable rollout {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let Some(manager) = MissionManager::from_rollout_items(&history.get_rollout_items())
+            else {
+                continue;
+            };
+            let missions: Vec<&MissionContext> = manager.missions().collect();
+            if missions.is_empty() {
+                continue;
+            }
+
+            if sessions_with_missions == 0 {
+                println!("🎯 ACE Missions\n");
+            }
+            sessions_with_missions += 1;
+
+            let session_id = match &history {
+                InitialHistory::Resumed(resumed) => resumed.conversation_id.to_string(),
+                _ => "unknown".to_string(),
+            };
+            println!("Session {session_id}");
+            println!("   {}", path.display());
+            for (i, mission) in missions.iter().enumerate() {
+                print_mission(i + 1, mission);
+            }
+            println!();
+        }
+
+        if sessions_with_missions == 0 {
+            println!("📭 No missions recorded yet.");
+            println!("\nMissions are created when the agent plans its work with todos.");
+        }
+
+        Ok(())
+    }
+
     /// Handle config command
     pub async fn handle_config(&self) -> Result<()> {
         let config_loader = ACEConfigLoader::new(&self.codex_home);
@@ -317,6 +1041,10 @@ impl AceCliHandler {
             config.context.include_all_successes
         );
         println!("max_context_chars = {}", config.context.max_context_chars);
+        println!("max_context_tokens = {}", config.context.max_context_tokens);
+        for (section, tokens) in &config.context.section_quotas {
+            println!("section_quotas.{section:?} = {tokens}");
+        }
         println!();
 
         println!("To edit: {}", config_loader.config_path().display());
@@ -325,9 +1053,98 @@ impl AceCliHandler {
     }
 }
 
+/// " 📌 🚩" suffix for pinned and flagged entries
+fn bullet_markers(metadata: &BulletMetadata) -> String {
+    let mut markers = String::new();
+    if metadata.pinned {
+        markers.push_str(" 📌");
+    }
+    if metadata.flagged {
+        markers.push_str(" 🚩");
+    }
+    markers
+}
+
+/// Print a mission with its todos
+fn print_mission(index: usize, mission: &MissionContext) {
+    println!("{}. [{:?}] {}", index, mission.status, mission.description);
+    println!(
+        "   Created: {}  Updated: {}",
+        mission.created_at.format("%Y-%m-%d %H:%M"),
+        mission.updated_at.format("%Y-%m-%d %H:%M")
+    );
+    for todo in &mission.todos {
+        let status_symbol = match todo.status {
+            StepStatus::Completed => "✅",
+            StepStatus::InProgress => "🔄",
+            StepStatus::Pending => "⏳",
+        };
+        let mut details = vec![format!("added {}", todo.created_at.format("%H:%M"))];
+        if let Some(completed_at) = todo.completed_at {
+            details.push(format!("completed {}", completed_at.format("%H:%M")));
+        }
+        if todo.reflected {
+            details.push("reflected".to_string());
+        }
+        println!("   {status_symbol} {} ({})", todo.step, details.join(", "));
+    }
+}
+
+/// Longest text shown for a single turn entry
+const TURN_ENTRY_PREVIEW_LEN: usize = 600;
+
+fn print_turn_entry(entry: &TurnEntry) {
+    let (symbol, text) = match entry {
+        TurnEntry::User(message) => ("👤", message.clone()),
+        TurnEntry::Assistant(message) => ("🤖", message.clone()),
+        TurnEntry::ToolCall { name, arguments } => ("🔧", format!("{name} {arguments}")),
+        TurnEntry::ToolOutput(output) => ("  ↳", output.clone()),
+    };
+    let text = truncate_string(text.trim(), TURN_ENTRY_PREVIEW_LEN);
+    let mut lines = text.lines();
+    println!("   {symbol} {}", lines.next().unwrap_or_default());
+    for line in lines {
+        println!("      {line}");
+    }
+}
+
+/// "github_token x2, env_assignment"
+fn summarize_kinds(kinds: &[&'static str]) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for &kind in kinds {
+        match counts.iter_mut().find(|(existing, _)| *existing == kind) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((kind, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(kind, count)| {
+            if count > 1 {
+                format!("{kind} x{count}")
+            } else {
+                kind.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn resolve_format(path: &Path, format: Option<PlaybookFormat>) -> Result<PlaybookFormat> {
+    match format.or_else(|| PlaybookFormat::from_path(path)) {
+        Some(format) => Ok(format),
+        None => bail!(
+            "Cannot infer format from {}; pass --format json|jsonl|markdown",
+            path.display()
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ace::types::BulletSection;
+    use crate::ace::types::DeltaContext;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -349,4 +1166,72 @@ mod tests {
         let result = handler.handle_status().await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_bullet_actions() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = AceCliHandler::new(temp_dir.path());
+        let (_, storage) = handler.open_storage().await.unwrap();
+
+        let mut delta = DeltaContext::new("session-1".to_string());
+        for content in ["Run cargo fmt before committing", "Prefer rg over grep"] {
+            delta.new_bullets.push(Bullet::new(
+                BulletSection::ToolUsageTips,
+                content.to_string(),
+                "session-1".to_string(),
+            ));
+        }
+        let first_id = delta.new_bullets[0].id.clone();
+        let second_id = delta.new_bullets[1].id.clone();
+        storage.merge_delta(delta).await.unwrap();
+
+        let pinned = handler
+            .apply_bullet_action(&second_id, BulletAction::SetPinned(true))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pinned.metadata.pinned);
+        assert_eq!(handler.list_bullets().await.unwrap()[0].id, second_id);
+
+        let edited = handler
+            .apply_bullet_action(
+                &first_id,
+                BulletAction::Edit("  Run just fmt  ".to_string()),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(edited.content, "Run just fmt");
+
+        handler
+            .apply_bullet_action(&first_id, BulletAction::Archive)
+            .await
+            .unwrap();
+        handler
+            .apply_bullet_action(&second_id, BulletAction::Delete)
+            .await
+            .unwrap();
+        assert!(handler.list_bullets().await.unwrap().is_empty());
+        assert!(
+            handler
+                .apply_bullet_action(&first_id, BulletAction::Delete)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missions_without_sessions() {
+        let temp_dir = TempDir::new().unwrap();
+        let handler = AceCliHandler::new(temp_dir.path());
+
+        // No sessions recorded yet
+        assert!(handler.handle_missions(10, None).await.is_ok());
+
+        // Unknown session id is an error
+        let result = handler
+            .handle_missions(10, Some("00000000-0000-0000-0000-000000000000"))
+            .await;
+        assert!(result.is_err());
+    }
 }
@@ -1,7 +1,358 @@
-//! 上下文管理器（占位符）
+//! 上下文渲染
 //!
-//! 注意：在 Bullet-based 架构中，上下文加载已集成到 mod.rs 的 ACEPlugin 中。
-//! 此文件作为模块占位符保留。
+//! 把检索到的 bullets 渲染为注入模型的上下文，受 token 预算约束：
+//! - 按检索排名依次选择 bullet，总量不超过 `max_context_tokens` 和 `max_context_chars`
+//! - 每个分类不超过 `section_quotas` 中配置的配额
+//! - 代码优先完整展示，放不下时退回一行摘要，仍放不下则跳过该 bullet
+//!
+//! 输出按 `BulletSection` 的顺序分组，组内保持检索排名。
+
+use super::types::Bullet;
+use super::types::BulletCodeContent;
+use super::types::BulletSection;
+use super::types::ContextConfig;
+use crate::hooks::TokenCounter;
+use std::collections::BTreeMap;
+
+/// 上下文标题
+const CONTEXT_HEADER: &str = "# 📚 ACE Playbook Context\n\n";
+
+/// 渲染结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedContext {
+    /// 上下文文本
+    pub text: String,
+
+    /// 文本的 token 数
+    pub tokens: usize,
+
+    /// 实际注入的 bullet ID（按检索排名）
+    pub bullet_ids: Vec<String>,
+}
+
+/// 代码的展示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CodeRendering {
+    /// 完整代码（`Summary` 变体展示完整摘要）
+    Full,
+
+    /// 只展示语言、行数和文件路径
+    Compact,
+}
+
+/// 已选中的 bullet
+struct Selected {
+    section: BulletSection,
+    rendered: String,
+}
+
+/// 按预算渲染 bullets
+///
+/// `bullets` 须按检索排名排序。没有任何 bullet 放得下时返回 None。
+pub fn render_context(
+    bullets: &[Bullet],
+    config: &ContextConfig,
+    counter: &TokenCounter,
+) -> Option<RenderedContext> {
+    if bullets.is_empty() || config.max_context_tokens == 0 {
+        return None;
+    }
+
+    let header = format_header(bullets.len());
+    let mut remaining_tokens = config
+        .max_context_tokens
+        .checked_sub(counter.count(&header))?;
+    let mut remaining_chars = config.max_context_chars.checked_sub(header.len())?;
+    let mut section_used: BTreeMap<BulletSection, usize> = BTreeMap::new();
+    let mut selected: Vec<Selected> = Vec::new();
+    let mut bullet_ids = Vec::new();
+
+    for bullet in bullets {
+        let heading_cost = if section_used.contains_key(&bullet.section) {
+            None
+        } else {
+            let heading = format_section_heading(&bullet.section);
+            Some((counter.count(&heading), heading.len()))
+        };
+        let section_remaining = config.section_quotas.get(&bullet.section).map(|quota| {
+            quota.saturating_sub(section_used.get(&bullet.section).copied().unwrap_or(0))
+        });
+
+        let renderings: &[CodeRendering] = if bullet.code_content.is_some() {
+            &[CodeRendering::Full, CodeRendering::Compact]
+        } else {
+            &[CodeRendering::Full]
+        };
+        let choice = renderings.iter().find_map(|&rendering| {
+            let rendered = format_bullet(bullet, rendering);
+            let (heading_tokens, heading_chars) = heading_cost.unwrap_or((0, 0));
+            let tokens = counter.count(&rendered) + heading_tokens;
+            let chars = rendered.len() + heading_chars;
+            let fits = tokens <= remaining_tokens
+                && chars <= remaining_chars
+                && section_remaining.is_none_or(|quota| tokens <= quota);
+            fits.then_some((rendered, tokens, chars))
+        });
+
+        let Some((rendered, tokens, chars)) = choice else {
+            tracing::debug!(
+                "Skipping bullet {} ({}): over context budget",
+                bullet.id,
+                bullet.section.title()
+            );
+            continue;
+        };
+
+        remaining_tokens -= tokens;
+        remaining_chars -= chars;
+        *section_used.entry(bullet.section.clone()).or_default() += tokens;
+        bullet_ids.push(bullet.id.clone());
+        selected.push(Selected {
+            section: bullet.section.clone(),
+            rendered,
+        });
+    }
+
+    if selected.is_empty() {
+        return None;
+    }
+
+    let mut text = format_header(selected.len());
+    for section in section_used.keys() {
+        text.push_str(&format_section_heading(section));
+        for item in selected.iter().filter(|item| &item.section == section) {
+            text.push_str(&item.rendered);
+        }
+    }
+
+    Some(RenderedContext {
+        tokens: counter.count(&text),
+        text,
+        bullet_ids,
+    })
+}
+
+fn format_header(count: usize) -> String {
+    format!("{CONTEXT_HEADER}Found {count} relevant strategies:\n\n")
+}
+
+fn format_section_heading(section: &BulletSection) -> String {
+    format!("## {}\n\n", section.title())
+}
+
+/// 格式化单个 bullet
+fn format_bullet(bullet: &Bullet, rendering: CodeRendering) -> String {
+    let mut output = format!("- {}\n", bullet.content);
+
+    // 显示相关工具
+    if !bullet.metadata.related_tools.is_empty() {
+        output.push_str(&format!(
+            "  - Tools: {}\n",
+            bullet.metadata.related_tools.join(", ")
+        ));
+    }
+
+    // 显示成功率
+    let total = bullet.metadata.success_count + bullet.metadata.failure_count;
+    if total > 0 {
+        let success_rate = (bullet.metadata.success_count as f32 / total as f32) * 100.0;
+        output.push_str(&format!("  - Success rate: {success_rate:.0}%\n"));
+    }
+
+    if let Some(code) = &bullet.code_content {
+        output.push_str(&format_code(code, rendering));
+    }
+
+    output.push('\n');
+    output
+}
+
+/// 格式化代码内容
+fn format_code(code: &BulletCodeContent, rendering: CodeRendering) -> String {
+    match (code, rendering) {
+        (
+            BulletCodeContent::Full {
+                language,
+                code,
+                file_path,
+            },
+            CodeRendering::Full,
+        ) => {
+            let location = file_path
+                .as_deref()
+                .map(|path| format!(" (`{path}`)"))
+                .unwrap_or_default();
+            format!(
+                "  - Code{location}:\n{}\n",
+                indent_block(&format!("```{language}\n{}\n```", code.trim_end()))
+            )
+        }
+        (
+            BulletCodeContent::Full {
+                language,
+                code,
+                file_path,
+            },
+            CodeRendering::Compact,
+        ) => {
+            let location = file_path
+                .as_deref()
+                .map(|path| format!(" in `{path}`"))
+                .unwrap_or_default();
+            format!(
+                "  - Code: {language}, {} lines{location} (omitted to fit the context budget)\n",
+                code.lines().count()
+            )
+        }
+        (
+            BulletCodeContent::Summary {
+                language,
+                summary,
+                file_path,
+                key_lines,
+            },
+            CodeRendering::Full,
+        ) => format!(
+            "  - Code summary ({language}, `{file_path}`{}):\n{}\n",
+            format_key_lines(key_lines.as_deref()),
+            indent_block(summary.trim_end())
+        ),
+        (
+            BulletCodeContent::Summary {
+                language,
+                file_path,
+                key_lines,
+                ..
+            },
+            CodeRendering::Compact,
+        ) => format!(
+            "  - Code: {language} in `{file_path}`{}\n",
+            format_key_lines(key_lines.as_deref())
+        ),
+    }
+}
+
+fn format_key_lines(key_lines: Option<&[(usize, usize)]>) -> String {
+    match key_lines {
+        Some(ranges) if !ranges.is_empty() => {
+            let ranges: Vec<String> = ranges
+                .iter()
+                .map(|(start, end)| format!("{start}-{end}"))
+                .collect();
+            format!(", lines {}", ranges.join(", "))
+        }
+        _ => String::new(),
+    }
+}
+
+/// 每行缩进 4 个空格，使代码块位于列表项内
+fn indent_block(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("    {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bullet(section: BulletSection, content: &str) -> Bullet {
+        Bullet::new(section, content.to_string(), "session".to_string())
+    }
+
+    fn code_bullet(lines: usize) -> Bullet {
+        let mut bullet = bullet(
+            BulletSection::CodeSnippetsAndTemplates,
+            "Retry helper for flaky network calls",
+        );
+        bullet.code_content = Some(BulletCodeContent::Full {
+            language: "rust".to_string(),
+            code: (0..lines)
+                .map(|i| format!("let value_{i} = retry(|| fetch({i}))?;"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            file_path: Some("src/net.rs".to_string()),
+        });
+        bullet
+    }
+
+    fn config(max_context_tokens: usize) -> ContextConfig {
+        ContextConfig {
+            max_context_tokens,
+            section_quotas: BTreeMap::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sections_follow_enum_order_and_keep_rank() {
+        let bullets = vec![
+            bullet(BulletSection::ToolUsageTips, "Use rg instead of grep"),
+            bullet(
+                BulletSection::StrategiesAndRules,
+                "Run tests before committing",
+            ),
+            bullet(BulletSection::ToolUsageTips, "Prefer fd over find"),
+        ];
+        let counter = TokenCounter::approximate();
+        let rendered = render_context(&bullets, &config(1000), &counter).unwrap();
+
+        let rules = rendered.text.find("## Strategies and Rules").unwrap();
+        let tools = rendered.text.find("## Tool Usage Tips").unwrap();
+        assert!(rules < tools);
+        assert!(rendered.text.find("Use rg").unwrap() < rendered.text.find("Prefer fd").unwrap());
+        assert_eq!(
+            rendered.bullet_ids,
+            bullets.iter().map(|b| b.id.clone()).collect::<Vec<_>>()
+        );
+        assert_eq!(rendered.tokens, counter.count(&rendered.text));
+    }
+
+    #[test]
+    fn test_code_falls_back_to_summary_under_section_quota() {
+        let bullets = vec![code_bullet(40)];
+        let counter = TokenCounter::approximate();
+
+        let roomy = render_context(&bullets, &config(2000), &counter).unwrap();
+        assert!(roomy.text.contains("```rust"));
+
+        let mut tight = config(2000);
+        tight
+            .section_quotas
+            .insert(BulletSection::CodeSnippetsAndTemplates, 60);
+        let rendered = render_context(&bullets, &tight, &counter).unwrap();
+        assert!(!rendered.text.contains("```"));
+        assert!(rendered.text.contains("rust, 40 lines in `src/net.rs`"));
+
+        tight
+            .section_quotas
+            .insert(BulletSection::CodeSnippetsAndTemplates, 5);
+        assert_eq!(render_context(&bullets, &tight, &counter), None);
+    }
+
+    #[test]
+    fn test_budget_skips_bullets_that_do_not_fit() {
+        let long = "x".repeat(2000);
+        let bullets = vec![
+            bullet(BulletSection::General, "Short tip one"),
+            bullet(BulletSection::General, &long),
+            bullet(BulletSection::General, "Short tip two"),
+        ];
+        let counter = TokenCounter::approximate();
+        let rendered = render_context(&bullets, &config(100), &counter).unwrap();
 
-// 暂时导出空类型以保持模块结构
-// 实际上下文加载逻辑在 mod.rs 中的 ACEPlugin::pre_execute()
+        assert!(rendered.tokens <= 100);
+        assert!(rendered.text.contains("Found 2 relevant strategies"));
+        assert_eq!(
+            rendered.bullet_ids,
+            vec![bullets[0].id.clone(), bullets[2].id.clone()]
+        );
+    }
+}
@@ -0,0 +1,548 @@
+//! 离线评估
+//!
+//! 按顺序重放一批 rollout（JSONL），逐回合模拟 ACE 的检索和学习，
+//! 在不调用模型的情况下衡量检索排序（`BulletStorage::query_bullets`）的效果：
+//! - 回合开始时用用户消息检索 top-k bullets，按默认上下文预算渲染并统计注入的 token 数
+//! - 回合结束后用规则版 Reflector 和 Curator 学习，合并到临时 playbook
+//! - 检索到的 bullet 如果被同一回合的学习结果强化（Curator 判定为同一条知识），视为有用
+//!
+//! 评估在临时目录中进行，不读写用户的 playbook；相同输入得到相同的指标，可以放在 CI 中比较。
+
+use super::MAX_INJECTED_BULLETS;
+use super::context::render_context;
+use super::curator::CuratorMVP;
+use super::execution_result_for;
+use super::recall_tracker::RecallTracker;
+use super::redaction::Redactor;
+use super::reflector::Reflector;
+use super::reflector::ReflectorMVP;
+use super::similarity::SimilarityCalculator;
+use super::storage::BulletStorage;
+use super::types::ACEConfig;
+use super::types::ContextConfig;
+use super::types::CuratorConfig;
+use super::types::Playbook;
+use super::types::truncate_string;
+use crate::RolloutRecorder;
+use crate::hooks::TokenCounter;
+use crate::hooks::ToolCallRecord;
+use crate::hooks::TurnOutcome;
+use anyhow::Context;
+use anyhow::Result;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 评估选项
+#[derive(Debug, Clone)]
+pub struct EvalOptions {
+    /// 每个回合检索的 bullet 数
+    pub top_k: usize,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        Self {
+            top_k: MAX_INJECTED_BULLETS,
+        }
+    }
+}
+
+/// 评估结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EvalReport {
+    /// 重放的 rollout 数
+    pub rollouts: usize,
+
+    /// 无法读取而跳过的 rollout 数
+    pub skipped_rollouts: usize,
+
+    /// 重放的回合数
+    pub turns: usize,
+
+    /// 检索到 bullet 的回合数
+    pub turns_with_retrieval: usize,
+
+    /// 至少有一个检索结果被判定有用的回合数
+    pub turns_with_hit: usize,
+
+    /// 检索到的 bullet 总数
+    pub retrieved_bullets: usize,
+
+    /// 被判定有用的检索结果数
+    pub useful_bullets: usize,
+
+    /// 命中率：`turns_with_hit / turns_with_retrieval`
+    pub hit_rate: f64,
+
+    /// 精确率：`useful_bullets / retrieved_bullets`
+    pub precision: f64,
+
+    /// 注入的 token 总数
+    pub injected_tokens: usize,
+
+    /// 平均每回合注入的 token 数
+    pub injected_tokens_per_turn: f64,
+
+    /// 每个 rollout 重放后的 playbook 大小
+    pub playbook_growth: Vec<usize>,
+
+    /// 最终的 bullet 数
+    pub final_bullets: usize,
+
+    /// 与同分类中其他 bullet 近似重复的 bullet 数
+    pub duplicate_bullets: usize,
+
+    /// 重复率：`duplicate_bullets / final_bullets`
+    pub duplication_ratio: f64,
+}
+
+impl EvalReport {
+    fn finish(&mut self, playbook: &Playbook, threshold: f32) {
+        self.hit_rate = ratio(self.turns_with_hit, self.turns_with_retrieval);
+        self.precision = ratio(self.useful_bullets, self.retrieved_bullets);
+        self.injected_tokens_per_turn = ratio(self.injected_tokens, self.turns);
+        self.final_bullets = playbook.all_bullets().len();
+        self.duplicate_bullets = count_duplicates(playbook, threshold);
+        self.duplication_ratio = ratio(self.duplicate_bullets, self.final_bullets);
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// 统计近似重复的 bullet（只在同一分类内比较）
+fn count_duplicates(playbook: &Playbook, threshold: f32) -> usize {
+    let bullets = playbook.all_bullets();
+    let mut duplicates = HashSet::new();
+    for (i, a) in bullets.iter().enumerate() {
+        for b in bullets.iter().skip(i + 1) {
+            if a.section == b.section
+                && SimilarityCalculator::combined_similarity_v2(&a.content, &b.content) >= threshold
+            {
+                duplicates.insert(a.id.as_str());
+                duplicates.insert(b.id.as_str());
+            }
+        }
+    }
+    duplicates.len()
+}
+
+/// 递归收集目录中的 rollout 文件，按路径排序
+pub fn collect_rollouts(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut rollouts = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read directory {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("jsonl") {
+                rollouts.push(path);
+            }
+        }
+    }
+    rollouts.sort();
+    Ok(rollouts)
+}
+
+/// 把 rollout 还原为回合结果
+///
+/// 与 [`provenance::split_turns`](super::provenance::split_turns) 相同，每条用户消息开始一个新回合。
+/// 工具调用按 call_id 与其输出配对，没有输出的调用被忽略。
+/// rollout 不记录成功的工具输出的 `success` 字段，因此多数调用的结果为 None。
+pub fn turn_outcomes(items: &[RolloutItem]) -> Vec<TurnOutcome> {
+    let mut outcomes: Vec<TurnOutcome> = Vec::new();
+    // call_id -> (工具名称, 参数)
+    let mut pending_calls: HashMap<String, (String, String)> = HashMap::new();
+
+    for item in items {
+        if let RolloutItem::EventMsg(EventMsg::UserMessage(event)) = item {
+            pending_calls.clear();
+            outcomes.push(TurnOutcome {
+                query: event.message.clone(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(outcome) = outcomes.last_mut() else {
+            continue;
+        };
+
+        match item {
+            RolloutItem::TurnContext(context) => {
+                if outcome.turn_id.is_empty()
+                    && let Some(turn_id) = &context.turn_id
+                {
+                    outcome.turn_id = turn_id.clone();
+                }
+            }
+            RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => {
+                outcome.response = Some(event.message.clone());
+            }
+            RolloutItem::EventMsg(EventMsg::Error(event)) => {
+                outcome.errors.push(event.message.clone());
+            }
+            RolloutItem::EventMsg(EventMsg::TurnAborted(_)) => outcome.aborted = true,
+            // TurnDiff 是回合开始以来的累计 diff，保留最后一个
+            RolloutItem::EventMsg(EventMsg::TurnDiff(event)) => {
+                outcome.diff = Some(event.unified_diff.clone());
+            }
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            }) => {
+                pending_calls.insert(call_id.clone(), (name.clone(), arguments.clone()));
+            }
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            }) => {
+                pending_calls.insert(call_id.clone(), (name.clone(), input.clone()));
+            }
+            RolloutItem::ResponseItem(ResponseItem::LocalShellCall {
+                id,
+                call_id,
+                action,
+                ..
+            }) => {
+                if let Some(call_id) = call_id.as_ref().or(id.as_ref()) {
+                    let arguments = serde_json::to_string(action).unwrap_or_default();
+                    pending_calls.insert(call_id.clone(), ("local_shell".to_string(), arguments));
+                }
+            }
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput { call_id, output }) => {
+                if let Some((name, arguments)) = pending_calls.remove(call_id) {
+                    outcome.tool_calls.push(ToolCallRecord {
+                        call_id: call_id.clone(),
+                        name,
+                        arguments,
+                        success: output.success,
+                        output: output.content.clone(),
+                    });
+                }
+            }
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCallOutput { call_id, output }) => {
+                if let Some((name, arguments)) = pending_calls.remove(call_id) {
+                    outcome.tool_calls.push(ToolCallRecord {
+                        call_id: call_id.clone(),
+                        name,
+                        arguments,
+                        success: None,
+                        output: output.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    outcomes
+}
+
+/// 模拟 ACE 的一个会话序列
+struct Simulator {
+    storage: Arc<RwLock<BulletStorage>>,
+    recall_tracker: RecallTracker,
+    reflector: ReflectorMVP,
+    curator: CuratorMVP,
+    redactor: Redactor,
+    context_config: ContextConfig,
+    counter: TokenCounter,
+    top_k: usize,
+}
+
+impl Simulator {
+    fn new(storage_dir: &Path, options: &EvalOptions) -> Result<Self> {
+        let config = ACEConfig::default();
+        let storage = Arc::new(RwLock::new(BulletStorage::new(
+            storage_dir,
+            config.max_entries,
+        )?));
+        Ok(Self {
+            recall_tracker: RecallTracker::new(storage.clone()),
+            storage,
+            reflector: ReflectorMVP::new(config.reflector.clone().into()),
+            curator: CuratorMVP::new(CuratorConfig::default()),
+            redactor: Redactor::new(&config.redaction)?,
+            context_config: config.context,
+            counter: TokenCounter::approximate(),
+            top_k: options.top_k,
+        })
+    }
+
+    async fn load_playbook(&self) -> Result<Playbook> {
+        self.storage.read().await.load_playbook().await
+    }
+
+    /// 重放一个回合：先检索，再学习
+    async fn replay_turn(
+        &self,
+        outcome: &TurnOutcome,
+        session_id: &str,
+        report: &mut EvalReport,
+    ) -> Result<()> {
+        let (query, _) = self.redactor.redact_text(&outcome.query);
+        let (response, _) = self
+            .redactor
+            .redact_text(outcome.response.as_deref().unwrap_or(""));
+        let success = outcome.success();
+        report.turns += 1;
+
+        // 1. 检索和注入
+        let retrieved = self
+            .storage
+            .read()
+            .await
+            .query_bullets(&outcome.query, self.top_k)
+            .await?;
+        let injected_ids = match render_context(&retrieved, &self.context_config, &self.counter) {
+            Some(rendered) => {
+                report.injected_tokens += rendered.tokens;
+                rendered.bullet_ids
+            }
+            None => Vec::new(),
+        };
+        if !injected_ids.is_empty() {
+            report.turns_with_retrieval += 1;
+            report.retrieved_bullets += injected_ids.len();
+            self.recall_tracker
+                .record_bullet_usage(injected_ids.clone(), truncate_string(&query, 100), success)
+                .await?;
+        }
+
+        // 2. 学习
+        let mut execution_result = execution_result_for(outcome, &response);
+        self.redactor.redact_execution_result(&mut execution_result);
+        let insights = self
+            .reflector
+            .analyze_conversation(&query, &response, &execution_result, session_id.to_string())
+            .await?;
+        if insights.is_empty() {
+            return Ok(());
+        }
+        let playbook = self.load_playbook().await?;
+        let mut delta = self
+            .curator
+            .process_insights_with_playbook(insights, session_id.to_string(), &playbook)
+            .await?;
+        self.redactor.redact_delta(&mut delta);
+
+        // 3. 被本回合学习结果强化的检索结果视为有用
+        let reinforced: HashSet<&str> = delta
+            .updated_bullets
+            .iter()
+            .map(|bullet| bullet.id.as_str())
+            .collect();
+        let useful = injected_ids
+            .iter()
+            .filter(|id| reinforced.contains(id.as_str()))
+            .count();
+        if useful > 0 {
+            report.turns_with_hit += 1;
+            report.useful_bullets += useful;
+        }
+
+        self.storage.read().await.merge_delta(delta).await
+    }
+}
+
+/// 重放目录中的所有 rollout 并计算检索指标
+///
+/// rollout 按路径顺序依次重放，共享同一个临时 playbook，模拟 playbook 随会话增长的过程。
+pub async fn evaluate(dir: &Path, options: &EvalOptions) -> Result<EvalReport> {
+    let rollouts = collect_rollouts(dir)?;
+    let storage_dir = tempfile::tempdir().context("Failed to create temporary playbook")?;
+    let simulator = Simulator::new(storage_dir.path(), options)?;
+    let mut report = EvalReport::default();
+
+    for path in &rollouts {
+        let history = match RolloutRecorder::get_rollout_history(path).await {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!("Skipping rollout {}: {}", path.display(), e);
+                report.skipped_rollouts += 1;
+                continue;
+            }
+        };
+        let items = history.get_rollout_items();
+        // 会话 ID 取 rollout 记录的会话元数据，没有时退回文件名
+        let session_id = items
+            .iter()
+            .find_map(|item| match item {
+                RolloutItem::SessionMeta(meta) => Some(meta.meta.id.to_string()),
+                _ => None,
+            })
+            .or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .unwrap_or_default();
+
+        for outcome in turn_outcomes(&items) {
+            simulator
+                .replay_turn(&outcome, &session_id, &mut report)
+                .await?;
+        }
+
+        report.rollouts += 1;
+        report
+            .playbook_growth
+            .push(simulator.load_playbook().await?.all_bullets().len());
+    }
+
+    let playbook = simulator.load_playbook().await?;
+    report.finish(&playbook, CuratorConfig::default().merge_threshold);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::AskForApproval;
+    use codex_protocol::protocol::RolloutLine;
+    use codex_protocol::protocol::SandboxPolicy;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::TurnContextItem;
+    use codex_protocol::protocol::TurnDiffEvent;
+    use codex_protocol::protocol::UserMessageEvent;
+
+    fn turn(turn_id: &str, query: &str, command: &str, output: &str) -> Vec<RolloutItem> {
+        vec![
+            RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                message: query.to_string(),
+                images: None,
+            })),
+            RolloutItem::TurnContext(TurnContextItem {
+                cwd: PathBuf::from("/repo"),
+                approval_policy: AskForApproval::Never,
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                model: "gpt-5".to_string(),
+                effort: None,
+                summary: Default::default(),
+                turn_id: Some(turn_id.to_string()),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: format!(r#"{{"command":["bash","-lc","{command}"]}}"#),
+                call_id: format!("call-{turn_id}"),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: format!("call-{turn_id}"),
+                output: FunctionCallOutputPayload {
+                    content: output.to_string(),
+                    ..Default::default()
+                },
+            }),
+            RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                message: format!("Ran `{command}` and it succeeded: {output}"),
+            })),
+        ]
+    }
+
+    fn write_rollout(path: &Path, turns: Vec<Vec<RolloutItem>>) {
+        let mut items = vec![RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id: ConversationId::new(),
+                ..Default::default()
+            },
+            git: None,
+        })];
+        items.extend(turns.into_iter().flatten());
+        let lines: Vec<String> = items
+            .into_iter()
+            .map(|item| {
+                serde_json::to_string(&RolloutLine {
+                    timestamp: "2025-01-01T10:00:00.000Z".to_string(),
+                    item,
+                })
+                .unwrap()
+            })
+            .collect();
+        std::fs::write(path, lines.join("\n")).unwrap();
+    }
+
+    #[test]
+    fn test_turn_outcomes_pair_tool_calls_with_outputs() {
+        let mut items = turn("1", "run the tests", "cargo test", "test result: ok");
+        items.push(RolloutItem::EventMsg(EventMsg::TurnDiff(TurnDiffEvent {
+            unified_diff: "--- a/README.md\n+++ b/README.md\n".to_string(),
+        })));
+        let outcomes = turn_outcomes(&items);
+        assert_eq!(outcomes.len(), 1);
+        let outcome = &outcomes[0];
+        assert_eq!(outcome.turn_id, "1");
+        assert_eq!(outcome.query, "run the tests");
+        assert!(outcome.success());
+        assert_eq!(outcome.tool_calls.len(), 1);
+        assert_eq!(outcome.tool_calls[0].name, "shell");
+        assert_eq!(outcome.tool_calls[0].output, "test result: ok");
+        assert_eq!(
+            outcome.diff.as_deref(),
+            Some("--- a/README.md\n+++ b/README.md\n")
+        );
+        assert_eq!(execution_result_for(outcome, "done").diff, outcome.diff);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_is_deterministic() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("2025/01/01");
+        std::fs::create_dir_all(&nested).unwrap();
+        write_rollout(
+            &nested.join("rollout-a.jsonl"),
+            vec![
+                turn("1", "run the rust tests", "cargo test", "test result: ok"),
+                turn("2", "format the rust code", "cargo fmt", "done"),
+            ],
+        );
+        write_rollout(
+            &nested.join("rollout-b.jsonl"),
+            vec![turn(
+                "1",
+                "run the rust tests again",
+                "cargo test",
+                "test result: ok",
+            )],
+        );
+        std::fs::write(nested.join("notes.txt"), "not a rollout").unwrap();
+
+        let options = EvalOptions::default();
+        let first = evaluate(dir.path(), &options).await.unwrap();
+        let second = evaluate(dir.path(), &options).await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(first.rollouts, 2);
+        assert_eq!(first.skipped_rollouts, 0);
+        assert_eq!(first.turns, 3);
+        assert_eq!(first.playbook_growth.len(), 2);
+        assert!(first.playbook_growth[0] <= first.playbook_growth[1]);
+        assert_eq!(first.final_bullets, first.playbook_growth[1]);
+        assert!(first.useful_bullets <= first.retrieved_bullets);
+        assert!((0.0..=1.0).contains(&first.hit_rate));
+        assert!((0.0..=1.0).contains(&first.duplication_ratio));
+    }
+}
@@ -0,0 +1,320 @@
+//! LLM Reflector - 基于模型结构化输出的洞察提取
+//!
+//! 将完成的回合（用户查询、工具调用、最终 diff、错误）发送给配置的
+//! `ModelClient`，要求模型按 JSON schema 返回洞察，再转换为 `RawInsight`。
+//! 请求失败或超时（例如离线）时回退到规则版的 `ReflectorMVP`。
+
+use super::reflector::Reflector;
+use super::reflector::ReflectorMVP;
+use super::types::ExecutionResult;
+use super::types::InsightCategory;
+use super::types::InsightContext;
+use super::types::RawInsight;
+use super::types::truncate_string;
+use crate::client::ModelClient;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::sandboxing::assessment::response_item_text;
+use anyhow::Context;
+use anyhow::Result;
+use askama::Template;
+use async_trait::async_trait;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// 发送给模型的 diff / 响应最大字符数（避免 prompt 过大）
+const MAX_DIFF_CHARS: usize = 6000;
+const MAX_RESPONSE_CHARS: usize = 4000;
+
+/// 单个回合最多接受的洞察数
+const MAX_INSIGHTS: usize = 5;
+
+#[derive(Template)]
+#[template(path = "ace/reflector_prompt.md", escape = "none")]
+struct ReflectorPromptTemplate<'a> {
+    outcome: &'a str,
+    user_query: &'a str,
+    tools_used: Option<&'a str>,
+    errors: Option<&'a str>,
+    diff: Option<&'a str>,
+    assistant_response: &'a str,
+}
+
+/// 模型返回的结构化输出
+#[derive(Debug, Deserialize)]
+struct ReflectorOutput {
+    insights: Vec<ReflectorOutputInsight>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReflectorOutputInsight {
+    content: String,
+    category: String,
+    importance: f32,
+}
+
+/// LLM 版 Reflector
+pub struct LlmReflector {
+    client: ModelClient,
+    fallback: Arc<ReflectorMVP>,
+    timeout: Duration,
+}
+
+impl LlmReflector {
+    /// 创建 LLM Reflector
+    ///
+    /// # 参数
+    /// - `client`: 用于请求模型的客户端
+    /// - `fallback`: 模型不可用时使用的规则版 Reflector
+    /// - `timeout`: 单次请求的超时时间
+    pub fn new(client: ModelClient, fallback: Arc<ReflectorMVP>, timeout: Duration) -> Self {
+        Self {
+            client,
+            fallback,
+            timeout,
+        }
+    }
+
+    /// 请求模型并解析洞察
+    async fn request_insights(
+        &self,
+        user_query: &str,
+        assistant_response: &str,
+        execution_result: &ExecutionResult,
+        session_id: &str,
+    ) -> Result<Vec<RawInsight>> {
+        let prompt = build_prompt(user_query, assistant_response, execution_result)?;
+
+        let raw = timeout(self.timeout, async {
+            let mut stream = self.client.stream(&prompt).await?;
+            let mut last_json: Option<String> = None;
+            while let Some(event) = stream.next().await {
+                match event? {
+                    ResponseEvent::OutputItemDone(item) => {
+                        if let Some(text) = response_item_text(&item) {
+                            last_json = Some(text);
+                        }
+                    }
+                    ResponseEvent::Completed { .. } => break,
+                    _ => continue,
+                }
+            }
+            Ok::<_, crate::error::CodexErr>(last_json)
+        })
+        .await
+        .context("LLM reflector request timed out")??
+        .context("LLM reflector response did not include any message")?;
+
+        let output: ReflectorOutput = serde_json::from_str(raw.trim())
+            .context("Failed to parse LLM reflector JSON output")?;
+
+        let insights = output
+            .insights
+            .into_iter()
+            .filter(|insight| !insight.content.trim().is_empty())
+            .take(MAX_INSIGHTS)
+            .map(|insight| RawInsight {
+                content: insight.content.trim().to_string(),
+                category: parse_category(&insight.category),
+                importance: insight.importance.clamp(0.0, 1.0),
+                context: InsightContext {
+                    user_query: user_query.to_string(),
+                    assistant_response_snippet: truncate_string(assistant_response, 200),
+                    execution_success: execution_result.success,
+                    tools_used: execution_result.tools_used.clone(),
+                    error_message: execution_result.error.clone(),
+                    session_id: session_id.to_string(),
+                },
+            })
+            .collect();
+
+        Ok(insights)
+    }
+}
+
+#[async_trait]
+impl Reflector for LlmReflector {
+    async fn analyze_conversation(
+        &self,
+        user_query: &str,
+        assistant_response: &str,
+        execution_result: &ExecutionResult,
+        session_id: String,
+    ) -> Result<Vec<RawInsight>> {
+        match self
+            .request_insights(
+                user_query,
+                assistant_response,
+                execution_result,
+                &session_id,
+            )
+            .await
+        {
+            Ok(insights) => {
+                tracing::debug!("LLM reflector extracted {} insights", insights.len());
+                Ok(insights)
+            }
+            Err(e) => {
+                tracing::warn!("LLM reflector failed, falling back to rule-based: {e:#}");
+                self.fallback
+                    .analyze_conversation(
+                        user_query,
+                        assistant_response,
+                        execution_result,
+                        session_id,
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+fn build_prompt(
+    user_query: &str,
+    assistant_response: &str,
+    execution_result: &ExecutionResult,
+) -> Result<Prompt> {
+    let tools_used =
+        (!execution_result.tools_used.is_empty()).then(|| execution_result.tools_used.join(", "));
+    let mut errors: Vec<&str> = execution_result.errors.iter().map(String::as_str).collect();
+    if let Some(error) = execution_result.error.as_deref()
+        && !errors.contains(&error)
+    {
+        errors.push(error);
+    }
+    let errors = (!errors.is_empty()).then(|| errors.join("\n"));
+    let diff = execution_result
+        .diff
+        .as_deref()
+        .filter(|diff| !diff.trim().is_empty())
+        .map(|diff| truncate_string(diff, MAX_DIFF_CHARS));
+    let assistant_response = truncate_string(assistant_response, MAX_RESPONSE_CHARS);
+
+    let rendered = ReflectorPromptTemplate {
+        outcome: if execution_result.success {
+            "success"
+        } else {
+            "failure"
+        },
+        user_query,
+        tools_used: tools_used.as_deref(),
+        errors: errors.as_deref(),
+        diff: diff.as_deref(),
+        assistant_response: &assistant_response,
+    }
+    .render()
+    .context("Failed to render LLM reflector prompt")?;
+
+    let (system_prompt, user_prompt) = rendered
+        .split_once("\n---\n")
+        .context("Rendered LLM reflector prompt missing separator")?;
+
+    Ok(Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: user_prompt.trim().to_string(),
+            }],
+        }],
+        base_instructions_override: Some(system_prompt.trim().to_string()),
+        output_schema: Some(reflector_output_schema()),
+        ..Default::default()
+    })
+}
+
+fn parse_category(category: &str) -> InsightCategory {
+    match category {
+        "tool_usage" => InsightCategory::ToolUsage,
+        "pattern" => InsightCategory::Pattern,
+        "solution" => InsightCategory::Solution,
+        "error_handling" => InsightCategory::ErrorHandling,
+        _ => InsightCategory::Knowledge,
+    }
+}
+
+fn reflector_output_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "required": ["insights"],
+        "properties": {
+            "insights": {
+                "type": "array",
+                "maxItems": MAX_INSIGHTS,
+                "items": {
+                    "type": "object",
+                    "required": ["content", "category", "importance"],
+                    "properties": {
+                        "content": {
+                            "type": "string",
+                            "minLength": 1,
+                            "maxLength": 800
+                        },
+                        "category": {
+                            "type": "string",
+                            "enum": ["tool_usage", "pattern", "solution", "knowledge", "error_handling"]
+                        },
+                        "importance": {
+                            "type": "number",
+                            "minimum": 0.0,
+                            "maximum": 1.0
+                        }
+                    },
+                    "additionalProperties": false
+                }
+            }
+        },
+        "additionalProperties": false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_build_prompt_includes_turn_details() {
+        let execution_result = ExecutionResult {
+            success: false,
+            tools_used: vec!["shell".to_string(), "apply_patch".to_string()],
+            errors: vec!["error[E0308]: mismatched types".to_string()],
+            diff: Some("--- a/src/lib.rs\n+++ b/src/lib.rs".to_string()),
+            ..Default::default()
+        };
+
+        let prompt = build_prompt("fix the build", "I changed the type", &execution_result)
+            .expect("prompt should render");
+
+        let instructions = prompt.base_instructions_override.expect("system prompt");
+        assert!(instructions.contains("Reflector"));
+        assert!(prompt.output_schema.is_some());
+
+        let ResponseItem::Message { content, .. } = &prompt.input[0] else {
+            panic!("expected user message");
+        };
+        let ContentItem::InputText { text } = &content[0] else {
+            panic!("expected input text");
+        };
+        assert!(text.contains("Turn outcome: failure"));
+        assert!(text.contains("Tool calls: shell, apply_patch"));
+        assert!(text.contains("mismatched types"));
+        assert!(text.contains("+++ b/src/lib.rs"));
+    }
+
+    #[test]
+    fn test_parse_category() {
+        assert_eq!(parse_category("tool_usage"), InsightCategory::ToolUsage);
+        assert_eq!(
+            parse_category("error_handling"),
+            InsightCategory::ErrorHandling
+        );
+        assert_eq!(parse_category("unknown"), InsightCategory::Knowledge);
+    }
+}
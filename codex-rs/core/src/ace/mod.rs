@@ -4,37 +4,80 @@
 //!
 //! 基于 Agentic Context Engineering 论文实现，采用 Bullet-based 架构。
 
-pub mod background_optimizer;
-pub mod bullet_formatter;
 pub mod cli;
-pub mod code_analyzer;
-pub mod config_loader;
-pub mod content_classifier;
 pub mod context;
-pub mod curator;
-pub mod knowledge_scope;
-pub mod lightweight_index;
-pub mod recall_tracker;
-pub mod reflector;
-pub mod similarity;
-pub mod storage;
-pub mod types;
+pub mod eval;
+pub mod llm_reflector;
 
+// Bullet 架构（类型、存储、提取、整理、检索）位于 `codex-ace` crate，
+// 这里按原模块路径重新导出，`codex_core::ace::*` 的使用方无需改动。
+pub use codex_ace::background_optimizer;
+pub use codex_ace::bullet_formatter;
+pub use codex_ace::code_analyzer;
+pub use codex_ace::config_loader;
+pub use codex_ace::content_classifier;
+pub use codex_ace::curator;
+pub use codex_ace::embedding;
+pub use codex_ace::knowledge_scope;
+pub use codex_ace::lightweight_index;
+pub use codex_ace::migration;
+pub use codex_ace::provenance;
+pub use codex_ace::recall_tracker;
+pub use codex_ace::redaction;
+pub use codex_ace::reflector;
+pub use codex_ace::similarity;
+pub use codex_ace::storage;
+pub use codex_ace::transfer;
+pub use codex_ace::types;
+
+use crate::client::ModelClient;
+use crate::git_info::collect_git_info;
+use crate::git_info::get_git_repo_root;
+use crate::hooks::ContextContribution;
 use crate::hooks::ExecutorHook;
+use crate::hooks::SessionInfo;
+use crate::hooks::TokenCounter;
+use crate::hooks::TurnOutcome;
 use anyhow::Result;
+use anyhow::bail;
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::PlaybookFeedbackRating;
+use codex_protocol::protocol::PlaybookUpdatedEvent;
+use knowledge_scope::KnowledgeScope;
+use knowledge_scope::RetrievalScope;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Once;
+use std::time::Duration;
+use tokio::sync::RwLock;
 
+pub use background_optimizer::BackgroundOptimizer;
+pub use background_optimizer::OptimizerConfig;
 pub use cli::AceCliHandler;
 pub use cli::AceCommand;
+pub use cli::AceStatus;
+pub use cli::BulletAction;
 pub use config_loader::ACEConfigLoader;
 pub use config_loader::load_ace_config;
 pub use curator::CuratorMVP;
+pub use embedding::Embedder;
+pub use embedding::HashingEmbedder;
+pub use lightweight_index::PlaybookIndex;
+pub use llm_reflector::LlmReflector;
+pub use recall_tracker::RecallTracker;
+pub use redaction::RedactionConfig;
+pub use redaction::Redactor;
+pub use reflector::Reflector;
 pub use reflector::ReflectorMVP;
 pub use storage::BulletStorage;
+pub use storage::StorageStats;
 pub use types::ACEConfig;
 pub use types::Bullet;
+pub use types::BulletProvenance;
 pub use types::BulletSection;
 pub use types::ContextConfig;
 pub use types::CuratorConfig;
@@ -42,29 +85,108 @@ pub use types::DeltaContext;
 pub use types::ExecutionResult;
 pub use types::Playbook;
 pub use types::RawInsight;
+pub use types::ReflectorMode;
+pub use types::ScopeConfig;
+
+/// 每轮检索的候选 bullet 数量上限（实际注入数量再受 token 预算限制）
+const MAX_INJECTED_BULLETS: usize = 10;
+
+/// `playbook_search` 工具按分类/标签过滤前，每个 playbook 取的候选数量相对 `limit` 的倍数
+const TOOL_SEARCH_CANDIDATE_FACTOR: usize = 4;
+
+/// 用户反馈写入 journal 时使用的会话标识
+const FEEDBACK_SESSION_ID: &str = "user-feedback";
 
 /// ACE插件 - Bullet-based 架构
 ///
 /// 数据流:
-/// 1. pre_execute: 从 Storage 检索相关 bullets，注入到 context
-/// 2. post_execute: Reflector 提取 insights → Curator 生成 delta → Storage 合并
+/// 1. pre_execute: 从常驻内存的 PlaybookIndex 检索相关 bullets，注入到 context
+/// 2. post_execute: 输入脱敏 → Reflector 提取 insights → Curator 生成 delta → delta 脱敏 → Storage 合并
+/// 3. post_execute: 记录本轮注入的 bullets 及成败，后台优化器据此衰减/降级/归档
+/// 4. 用户反馈（`Op::PlaybookFeedback`）: 对最近一轮注入的 bullets 点赞、点踩或标记
+///
+/// 通过 `with_cwd` 设置会话范围后，新 bullets 会打上项目/语言范围，检索按范围过滤和加权，
+/// 并合并仓库本地 playbook（默认 `.codeace/playbook`）中的 bullets。
 pub struct ACEPlugin {
     /// 是否启用
     enabled: bool,
 
     /// Reflector - 智能提取器（生成 RawInsights）
-    reflector: Arc<ReflectorMVP>,
+    reflector: Arc<dyn Reflector>,
+
+    /// 规则版 Reflector（LLM 模式下作为回退）
+    rule_reflector: Arc<ReflectorMVP>,
+
+    /// Reflector 配置（决定是否启用 LLM 模式）
+    reflector_config: types::ReflectorConfig,
 
     /// Curator - 组织器（RawInsights → Bullets）
     curator: Arc<CuratorMVP>,
 
     /// Storage - 存储管理（增量更新）
     storage: Arc<BulletStorage>,
+
+    /// 脱敏器（密钥、令牌等不写入 playbook）
+    redactor: Arc<Redactor>,
+
+    /// 检索索引（常驻内存，随 delta 增量更新）
+    index: Arc<PlaybookIndex>,
+
+    /// 后台优化器（同时负责记录召回）
+    optimizer: Arc<BackgroundOptimizer>,
+
+    /// 后台优化任务只在会话开始（或首次检索）时启动一次
+    optimizer_started: Once,
+
+    /// 最近一次 pre_execute 注入的 bullets，由 post_execute 消费
+    last_injection: Mutex<Option<InjectedBullets>>,
+
+    /// 最近一轮注入的个人 bullet ID（用户反馈的默认对象，不随 post_execute 清空）
+    last_injected_ids: Mutex<Vec<String>>,
+
+    /// 本轮 `playbook_search` 工具返回的个人 bullet ID，由 post_execute 与注入的 bullets 一起记录召回
+    tool_retrievals: Mutex<Vec<String>>,
+
+    /// 当前会话（on_session_start 时记录），用于标注新 bullets 的来源
+    session: Mutex<Option<SessionInfo>>,
+
+    /// 上下文注入预算
+    context_config: ContextConfig,
+
+    /// 按当前模型计算注入的 token 数
+    token_counter: Arc<TokenCounter>,
+
+    /// 范围配置
+    scope_config: ScopeConfig,
+
+    /// 当前会话的检索范围（由 `with_cwd` 设置）
+    retrieval_scope: Option<RetrievalScope>,
+
+    /// 仓库本地 playbook 的检索索引（只读，团队共享）
+    repo_index: Option<Arc<PlaybookIndex>>,
+
+    /// 会话事件通道（合并 delta 后发送 `EventMsg::PlaybookUpdated`）
+    tx_event: Option<async_channel::Sender<Event>>,
+}
+
+/// 一轮对话中注入到上下文的 bullets
+struct InjectedBullets {
+    query: String,
+    /// 来自个人 playbook 的 bullet ID（仓库 playbook 只读，不记录召回）
+    bullet_ids: Vec<String>,
 }
 
 impl ACEPlugin {
     /// 创建新的ACE插件
     pub fn new(config: ACEConfig) -> Result<Self> {
+        Self::new_with_embedder(config, None)
+    }
+
+    /// 创建新的ACE插件，`ollama_embedder` 供 `provider = "ollama"` 的向量检索使用
+    pub fn new_with_embedder(
+        config: ACEConfig,
+        ollama_embedder: Option<Arc<dyn Embedder>>,
+    ) -> Result<Self> {
         // 展开路径中的~
         let storage_path = shellexpand::tilde(&config.storage_path).to_string();
         let storage_path = PathBuf::from(storage_path);
@@ -72,25 +194,253 @@ impl ACEPlugin {
         // 创建 Storage
         let storage = Arc::new(BulletStorage::new(&storage_path, config.max_entries)?);
 
+        // 创建检索索引（可选启用向量检索）
+        let mut index = PlaybookIndex::new(Arc::clone(&storage));
+        if let Some(embedder) = embedding::embedder_from_config(&config.embedding, ollama_embedder)
+        {
+            index = index.with_embedder(
+                embedder,
+                storage_path.join("embeddings.json"),
+                &config.embedding,
+            );
+        }
+        let index = Arc::new(index);
+
+        // 创建后台优化器（召回记录和优化结果都经由索引写入）
+        let optimizer = Arc::new(
+            BackgroundOptimizer::new(
+                Arc::new(RwLock::new(storage.as_ref().clone())),
+                config.optimizer.clone(),
+            )
+            .with_index(Arc::clone(&index)),
+        );
+
         // 创建 Reflector
-        let reflector_config = reflector::ReflectorConfig {
-            extract_patterns: config.reflector.extract_patterns,
-            extract_tools: config.reflector.extract_tools,
-            extract_errors: config.reflector.extract_errors,
-        };
-        let reflector = Arc::new(ReflectorMVP::new(reflector_config));
+        // LLM 模式需要 ModelClient，通过 with_model_client() 注入；在此之前使用规则版
+        let rule_reflector = Arc::new(ReflectorMVP::new(config.reflector.clone().into()));
 
         // 创建 Curator
         let curator = Arc::new(CuratorMVP::new(CuratorConfig::default()));
 
+        // 自定义正则无效时报错，避免在未脱敏的情况下继续学习
+        let redactor = Arc::new(Redactor::new(&config.redaction)?);
+
         Ok(Self {
             enabled: config.enabled,
-            reflector,
+            reflector: Arc::clone(&rule_reflector) as Arc<dyn Reflector>,
+            rule_reflector,
+            reflector_config: config.reflector,
             curator,
             storage,
+            redactor,
+            index,
+            optimizer,
+            optimizer_started: Once::new(),
+            last_injection: Mutex::new(None),
+            last_injected_ids: Mutex::new(Vec::new()),
+            tool_retrievals: Mutex::new(Vec::new()),
+            session: Mutex::new(None),
+            context_config: config.context,
+            token_counter: Arc::new(TokenCounter::approximate()),
+            scope_config: config.scope,
+            retrieval_scope: None,
+            repo_index: None,
+            tx_event: None,
         })
     }
 
+    /// 设置会话工作目录
+    ///
+    /// 检测当前项目/语言范围（Curator 据此为新 bullets 打上范围，检索据此过滤和加权），
+    /// 并在仓库中存在本地 playbook 目录时将其作为只读层加入检索。
+    pub async fn with_cwd(mut self, cwd: &Path) -> Self {
+        if !self.scope_config.enabled {
+            return self;
+        }
+
+        let repo_root = get_git_repo_root(cwd);
+        let remote = match repo_root {
+            Some(_) => collect_git_info(cwd)
+                .await
+                .and_then(|info| info.repository_url),
+            None => None,
+        };
+        let scope = KnowledgeScope::from_repo(cwd, repo_root.as_deref(), remote.as_deref());
+        tracing::info!(
+            "ACE session scope: project={:?}, language={:?}",
+            scope.project,
+            scope.language
+        );
+
+        self.curator =
+            Arc::new(CuratorMVP::new(CuratorConfig::default()).with_scope(scope.clone()));
+        self.retrieval_scope = Some(RetrievalScope {
+            session: scope,
+            project_only: self.scope_config.project_only,
+        });
+
+        if !self.scope_config.repo_playbook_dir.is_empty() {
+            let repo_root =
+                crate::git_info::get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+            let repo_playbook = repo_root.join(&self.scope_config.repo_playbook_dir);
+            if repo_playbook.is_dir() {
+                match BulletStorage::new(&repo_playbook, usize::MAX) {
+                    Ok(storage) => {
+                        tracing::info!("ACE repo playbook layer: {}", repo_playbook.display());
+                        self.repo_index = Some(Arc::new(PlaybookIndex::new(Arc::new(storage))));
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to open repo playbook {}: {}",
+                            repo_playbook.display(),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+
+        self
+    }
+
+    /// 检索本轮要注入的 bullets
+    ///
+    /// 仓库本地 playbook 由团队维护，其结果排在前面；再用个人 playbook 补足数量。
+    /// 返回 bullets 以及其中来自个人 playbook 的 ID（仅这些参与召回统计）。
+    async fn search_bullets(&self, query: &str) -> Result<(Vec<Bullet>, Vec<String>)> {
+        let scope = self.retrieval_scope.as_ref();
+
+        let mut bullets = match &self.repo_index {
+            Some(repo_index) => repo_index
+                .search_scoped(query, MAX_INJECTED_BULLETS, scope)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to query repo playbook: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+
+        let personal = self
+            .index
+            .search_scoped(query, MAX_INJECTED_BULLETS, scope)
+            .await?;
+        let mut personal_ids = Vec::new();
+        for bullet in personal {
+            if bullets.len() >= MAX_INJECTED_BULLETS {
+                break;
+            }
+            if bullets.iter().any(|existing| existing.id == bullet.id) {
+                continue;
+            }
+            personal_ids.push(bullet.id.clone());
+            bullets.push(bullet);
+        }
+
+        Ok((bullets, personal_ids))
+    }
+
+    /// 回合中按需检索（`playbook_search` 工具）
+    ///
+    /// 与 pre_execute 使用相同的范围和排序（仓库 playbook 在前），再按分类和标签过滤：
+    /// 标签须全部包含，不区分大小写。返回的个人 bullets 计入本轮召回。
+    pub async fn search_on_demand(
+        &self,
+        query: &str,
+        section: Option<&BulletSection>,
+        tags: &[String],
+        limit: usize,
+    ) -> Result<Vec<Bullet>> {
+        if !self.enabled {
+            bail!("ACE is disabled");
+        }
+        self.start_optimizer();
+
+        let scope = self.retrieval_scope.as_ref();
+        let candidates = limit.saturating_mul(TOOL_SEARCH_CANDIDATE_FACTOR);
+        let matches = |bullet: &Bullet| {
+            section.is_none_or(|section| &bullet.section == section)
+                && tags.iter().all(|tag| {
+                    bullet
+                        .tags
+                        .iter()
+                        .any(|bullet_tag| bullet_tag.eq_ignore_ascii_case(tag))
+                })
+        };
+
+        let mut bullets: Vec<Bullet> = match &self.repo_index {
+            Some(repo_index) => repo_index
+                .search_scoped(query, candidates, scope)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to query repo playbook: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        bullets.retain(matches);
+        bullets.truncate(limit);
+
+        let personal = self.index.search_scoped(query, candidates, scope).await?;
+        let mut personal_ids = Vec::new();
+        for bullet in personal.into_iter().filter(matches) {
+            if bullets.len() >= limit {
+                break;
+            }
+            if bullets.iter().any(|existing| existing.id == bullet.id) {
+                continue;
+            }
+            personal_ids.push(bullet.id.clone());
+            bullets.push(bullet);
+        }
+
+        if let Ok(mut retrievals) = self.tool_retrievals.lock() {
+            for id in personal_ids {
+                if !retrievals.contains(&id) {
+                    retrievals.push(id);
+                }
+            }
+        }
+        Ok(bullets)
+    }
+
+    /// 是否配置了 LLM 模式的 Reflector（需要通过 `with_model_client()` 注入客户端）
+    pub fn uses_llm_reflector(&self) -> bool {
+        self.reflector_config.mode == ReflectorMode::Llm
+    }
+
+    /// 注入模型客户端
+    ///
+    /// 当 `reflector.mode = "llm"` 时，使用 `LlmReflector` 替换规则版 Reflector，
+    /// 模型请求失败时仍回退到 `ReflectorMVP`。其他模式下忽略该客户端。
+    pub fn with_model_client(mut self, client: ModelClient) -> Self {
+        if self.uses_llm_reflector() {
+            tracing::info!("ACE reflector running in LLM mode");
+            self.reflector = Arc::new(LlmReflector::new(
+                client,
+                Arc::clone(&self.rule_reflector),
+                Duration::from_secs(self.reflector_config.llm_timeout_secs),
+            ));
+        }
+        self
+    }
+
+    /// 注入 token 计数器
+    ///
+    /// 通常与 `HookManager` 共享同一个按会话模型加载的计数器；未设置时按字节估算。
+    pub fn with_token_counter(mut self, token_counter: Arc<TokenCounter>) -> Self {
+        self.token_counter = token_counter;
+        self
+    }
+
+    /// 注入会话事件通道
+    ///
+    /// 之后每次合并 delta 都会发送 `EventMsg::PlaybookUpdated`，app-server 据此通知客户端刷新。
+    pub fn with_event_sender(mut self, tx_event: async_channel::Sender<Event>) -> Self {
+        self.tx_event = Some(tx_event);
+        self
+    }
+
     /// 从配置创建（便捷方法）
     pub fn from_config(config: Option<ACEConfig>) -> Result<Option<Self>> {
         match config {
@@ -119,6 +469,15 @@ impl ACEPlugin {
     /// # 参数
     /// - `codex_home`: Codex home 目录路径
     pub async fn from_codex_home(codex_home: &Path) -> Result<Option<Self>> {
+        Self::from_codex_home_with_embedder(codex_home, None).await
+    }
+
+    /// 同 [`Self::from_codex_home`]，`ollama_embedder` 为前端创建的 Ollama embedder
+    /// （`provider = "ollama"` 时使用）
+    pub async fn from_codex_home_with_embedder(
+        codex_home: &Path,
+        ollama_embedder: Option<Arc<dyn Embedder>>,
+    ) -> Result<Option<Self>> {
         tracing::debug!("Loading ACE config from {:?}", codex_home);
 
         // 加载配置（自动创建如果不存在）
@@ -130,65 +489,135 @@ impl ACEPlugin {
             }
         };
 
+        // 旧版 SimpleStorage playbook 迁移为 bullets（只迁移一次）
+        let storage_path = PathBuf::from(shellexpand::tilde(&config.storage_path).to_string());
+        if migration::has_legacy_playbook(&storage_path) {
+            let migrated = match BulletStorage::new(&storage_path, config.max_entries) {
+                Ok(storage) => migration::migrate_simple_storage(&storage).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = migrated {
+                tracing::warn!("Failed to migrate legacy ACE playbook: {e:#}");
+            }
+        }
+
         // 根据配置创建插件
-        Self::from_config(Some(config))
+        if !config.enabled {
+            return Self::from_config(Some(config));
+        }
+        tracing::info!("Initializing ACE plugin (Bullet-based)...");
+        Self::new_with_embedder(config, ollama_embedder).map(Some)
     }
 
-    /// 格式化 bullets 为上下文字符串
-    fn format_bullets_as_context(&self, bullets: Vec<Bullet>) -> String {
-        let mut output = String::from("# 📚 ACE Playbook Context\n\n");
-        output.push_str(&format!("Found {} relevant strategies:\n\n", bullets.len()));
-
-        // 按 section 分组
-        let mut by_section: std::collections::HashMap<BulletSection, Vec<&Bullet>> =
-            std::collections::HashMap::new();
-        for bullet in &bullets {
-            by_section
-                .entry(bullet.section.clone())
-                .or_default()
-                .push(bullet);
-        }
-
-        // 格式化输出
-        for (section, bullets) in by_section {
-            output.push_str(&format!("## {}\n\n", self.section_title(&section)));
-
-            for bullet in bullets {
-                output.push_str(&format!("- {}\n", bullet.content));
-
-                // 显示相关工具
-                if !bullet.metadata.related_tools.is_empty() {
-                    output.push_str(&format!(
-                        "  - Tools: {}\n",
-                        bullet.metadata.related_tools.join(", ")
-                    ));
-                }
+    /// 启动后台优化任务（只启动一次）
+    fn start_optimizer(&self) {
+        self.optimizer_started
+            .call_once(|| Arc::clone(&self.optimizer).start());
+    }
 
-                // 显示成功率
-                let total = bullet.metadata.success_count + bullet.metadata.failure_count;
-                if total > 0 {
-                    let success_rate =
-                        (bullet.metadata.success_count as f32 / total as f32) * 100.0;
-                    output.push_str(&format!("  - Success rate: {success_rate:.0}%\n"));
-                }
+    fn set_last_injection(&self, injection: Option<InjectedBullets>) {
+        if let Ok(mut ids) = self.last_injected_ids.lock() {
+            *ids = injection
+                .as_ref()
+                .map(|injection| injection.bullet_ids.clone())
+                .unwrap_or_default();
+        }
+        if let Ok(mut last) = self.last_injection.lock() {
+            *last = injection;
+        }
+    }
 
-                output.push('\n');
-            }
+    /// 记录用户对注入 bullets 的反馈
+    ///
+    /// `bullet_ids` 为空时作用于最近一轮注入的个人 bullets（仓库 playbook 只读，不接受反馈）。
+    /// 返回更新后的 bullets。
+    pub async fn record_feedback(
+        &self,
+        rating: PlaybookFeedbackRating,
+        bullet_ids: Vec<String>,
+    ) -> Result<Vec<Bullet>> {
+        let bullet_ids = if bullet_ids.is_empty() {
+            self.last_injected_ids
+                .lock()
+                .map(|ids| ids.clone())
+                .unwrap_or_default()
+        } else {
+            bullet_ids
+        };
+        if bullet_ids.is_empty() {
+            bail!("No playbook entries were injected in the last turn");
         }
 
-        output
+        let updated = self
+            .index
+            .update_bullets_with(FEEDBACK_SESSION_ID.to_string(), bullet_ids, move |bullet| {
+                bullet.metadata.record_user_feedback(rating);
+                bullet.updated_at = chrono::Utc::now();
+            })
+            .await?;
+        if updated.is_empty() {
+            bail!("Playbook entries not found");
+        }
+
+        tracing::info!("Recorded user {} for {} bullets", rating, updated.len());
+        Ok(updated)
     }
 
-    fn section_title(&self, section: &BulletSection) -> &str {
-        match section {
-            BulletSection::StrategiesAndRules => "Strategies and Rules",
-            BulletSection::CodeSnippetsAndTemplates => "Code Snippets and Templates",
-            BulletSection::TroubleshootingAndPitfalls => "Troubleshooting and Pitfalls",
-            BulletSection::ApiUsageGuides => "API Usage Guides",
-            BulletSection::ErrorHandlingPatterns => "Error Handling Patterns",
-            BulletSection::ToolUsageTips => "Tool Usage Tips",
-            BulletSection::General => "General Knowledge",
+    /// 添加用户手写的 bullet
+    ///
+    /// 内容先脱敏，按当前会话范围打上项目/语言范围，来源记为当前会话。
+    /// `sub_id` 为触发添加的提交 ID，用作 `PlaybookUpdated` 事件的 ID。
+    pub async fn add_manual_entry(
+        &self,
+        sub_id: &str,
+        content: &str,
+        section: BulletSection,
+    ) -> Result<Bullet> {
+        let content = content.trim();
+        if content.is_empty() {
+            bail!("Playbook entry cannot be empty");
+        }
+        let (content, redacted) = self.redactor.redact_text(content);
+        if !redacted.is_empty() {
+            tracing::info!("Redacted {} secrets from manual entry", redacted.len());
         }
+
+        // 手动条目不属于某个回合
+        let provenance = self.provenance("");
+        let session_id = provenance.as_ref().map_or_else(
+            || uuid::Uuid::new_v4().to_string(),
+            |provenance| provenance.conversation_id.clone(),
+        );
+        let mut bullet = Bullet::new(section, content, session_id.clone());
+        bullet.metadata.source_type = types::SourceType::ManualEntry;
+        bullet.metadata.scope = self
+            .retrieval_scope
+            .as_ref()
+            .map(|scope| scope.session.clone());
+        bullet.metadata.provenance = provenance;
+
+        let mut delta = DeltaContext::new(session_id);
+        delta.new_bullets.push(bullet.clone());
+        merge_and_notify(&self.index, self.tx_event.as_ref(), sub_id, delta).await?;
+
+        tracing::info!(
+            "Added manual bullet {} ({})",
+            bullet.id,
+            bullet.section.title()
+        );
+        Ok(bullet)
+    }
+
+    /// 本回合学习到的 bullets 的来源
+    ///
+    /// 未经会话启动（例如直接调用 hook 的测试）时返回 None。
+    fn provenance(&self, turn_id: &str) -> Option<types::BulletProvenance> {
+        let session = self.session.lock().ok()?.clone()?;
+        Some(types::BulletProvenance {
+            conversation_id: session.conversation_id,
+            turn_id: Some(turn_id.to_string()).filter(|id| !id.is_empty()),
+            rollout_path: session.rollout_path,
+        })
     }
 
     /// Todo 完成时触发 Reflector
@@ -199,34 +628,49 @@ impl ACEPlugin {
     /// # 参数
     /// - `todo_step`: Todo 的描述
     /// - `conversation_context`: 完成该 Todo 的对话上下文
-    /// - `session_id`: 会话 ID
+    /// - `diff`: 本回合到目前为止对文件的改动（unified diff）
+    /// - `turn_id`: 完成该 Todo 的回合 ID
     pub fn on_todo_completed(
         &self,
         todo_step: String,
         conversation_context: String,
-        session_id: String,
+        diff: Option<String>,
+        turn_id: String,
     ) {
         if !self.enabled {
             return;
         }
 
+        let provenance = self.provenance(&turn_id);
+        let session_id = provenance.as_ref().map_or_else(
+            || uuid::Uuid::new_v4().to_string(),
+            |provenance| provenance.conversation_id.clone(),
+        );
+
         let reflector = Arc::clone(&self.reflector);
         let curator = Arc::clone(&self.curator);
         let storage = Arc::clone(&self.storage);
+        let index = Arc::clone(&self.index);
+        let redactor = Arc::clone(&self.redactor);
+        let tx_event = self.tx_event.clone();
 
         // 异步执行学习过程（不阻塞主流程）
         tokio::spawn(async move {
             tracing::info!("🎯 Todo completed, triggering Reflector: {}", todo_step);
+            let (todo_step, _) = redactor.redact_text(&todo_step);
+            let (conversation_context, _) = redactor.redact_text(&conversation_context);
 
             // 构造执行结果（Todo 完成场景）
-            let execution_result = ExecutionResult {
+            let mut execution_result = ExecutionResult {
                 success: true,
                 output: Some(format!("Completed todo: {todo_step}")),
                 error: None,
                 tools_used: Vec::new(),
                 errors: Vec::new(),
                 retry_success: false,
+                diff,
             };
+            redactor.redact_execution_result(&mut execution_result);
 
             // 1. Reflector 分析
             let insights = match reflector
@@ -252,14 +696,31 @@ impl ACEPlugin {
 
             tracing::info!("Extracted {} insights from todo", insights.len());
 
-            // 2. Curator 生成 delta
-            let delta = match curator.process_insights(insights, session_id).await {
+            // 2. Curator 与已有 playbook 比较，生成 delta
+            let playbook = match storage.load_playbook().await {
+                Ok(playbook) => playbook,
+                Err(e) => {
+                    tracing::error!("Failed to load playbook for todo: {}", e);
+                    return;
+                }
+            };
+            let mut delta = match curator
+                .process_insights_with_playbook(insights, session_id, &playbook)
+                .await
+            {
                 Ok(delta) => delta,
                 Err(e) => {
                     tracing::error!("Curator failed for todo: {}", e);
                     return;
                 }
             };
+            let redacted = redactor.redact_delta(&mut delta);
+            if redacted > 0 {
+                tracing::info!("Redacted {} secrets from todo delta", redacted);
+            }
+            if let Some(provenance) = &provenance {
+                delta.set_provenance(provenance);
+            }
 
             if delta.is_empty() {
                 tracing::debug!("Delta is empty for todo");
@@ -267,12 +728,14 @@ impl ACEPlugin {
             }
 
             tracing::info!(
-                "Generated {} bullets from todo completion",
-                delta.new_bullets.len()
+                "Generated delta from todo completion: {} new, {} updated, {} deleted",
+                delta.new_bullets.len(),
+                delta.updated_bullets.len(),
+                delta.deleted_bullet_ids.len()
             );
 
-            // 3. Storage 合并 delta
-            if let Err(e) = storage.merge_delta(delta).await {
+            // 3. Storage 合并 delta（同时增量更新索引）
+            if let Err(e) = merge_and_notify(&index, tx_event.as_ref(), &turn_id, delta).await {
                 tracing::error!("Failed to merge delta for todo: {}", e);
             } else {
                 tracing::info!("✅ Todo completion learning completed");
@@ -281,141 +744,268 @@ impl ACEPlugin {
     }
 }
 
+/// 合并 delta（同时增量更新索引），成功后发送 `EventMsg::PlaybookUpdated`
+async fn merge_and_notify(
+    index: &PlaybookIndex,
+    tx_event: Option<&async_channel::Sender<Event>>,
+    sub_id: &str,
+    delta: DeltaContext,
+) -> Result<()> {
+    let update = PlaybookUpdatedEvent {
+        added: delta.new_bullets.iter().map(|b| b.id.clone()).collect(),
+        updated: delta.updated_bullets.iter().map(|b| b.id.clone()).collect(),
+        deleted: delta.deleted_bullet_ids.clone(),
+    };
+    index.merge_delta(delta).await?;
+
+    if let Some(tx_event) = tx_event {
+        let event = Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::PlaybookUpdated(update),
+        };
+        // 会话结束后通道关闭，忽略即可
+        if tx_event.send(event).await.is_err() {
+            tracing::debug!("Session closed before PlaybookUpdated was delivered");
+        }
+    }
+    Ok(())
+}
+
+/// 根据实际的工具调用构造执行结果
+///
+/// `response` 为（脱敏后的）模型回复。离线评估（`eval`）重放 rollout 时使用同样的构造方式。
+pub(crate) fn execution_result_for(outcome: &TurnOutcome, response: &str) -> ExecutionResult {
+    let success = outcome.success();
+    let mut errors = outcome.errors.clone();
+    errors.extend(
+        outcome
+            .tool_calls
+            .iter()
+            .filter(|call| call.failed())
+            .map(|call| {
+                format!(
+                    "{} failed: {}",
+                    call.name,
+                    types::truncate_string(&call.output, 200)
+                )
+            }),
+    );
+    ExecutionResult {
+        success,
+        output: success.then(|| response.to_string()),
+        error: if success {
+            None
+        } else {
+            Some(
+                outcome
+                    .errors
+                    .last()
+                    .cloned()
+                    .unwrap_or_else(|| "Execution failed".to_string()),
+            )
+        },
+        tools_used: outcome.tools_used(),
+        errors,
+        retry_success: success && outcome.retried_after_failure(),
+        diff: outcome.diff.clone(),
+    }
+}
+
 /// 实现ExecutorHook trait
+#[async_trait]
 impl ExecutorHook for ACEPlugin {
+    fn name(&self) -> &str {
+        "ace"
+    }
+
+    /// 会话开始时启动后台优化任务，并记录会话用于标注 bullets 来源
+    async fn on_session_start(&self, session: &SessionInfo) {
+        if self.enabled {
+            self.start_optimizer();
+        }
+        if let Ok(mut current) = self.session.lock() {
+            *current = Some(session.clone());
+        }
+    }
+
     /// 在执行前加载相关上下文
-    fn pre_execute(&self, query: &str) -> Option<String> {
+    ///
+    /// 直接查询内存索引，不再为每轮创建线程和运行时。
+    async fn pre_execute(&self, query: &str) -> Vec<ContextContribution> {
         if !self.enabled {
-            return None;
+            return Vec::new();
         }
 
-        let storage = Arc::clone(&self.storage);
-        let query_content = query.to_string();
-
-        // 使用新的运行时来避免嵌套 block_on 的问题
-        // 这是因为 Hook trait 不是 async 的，但我们需要执行异步操作
-        let context = std::thread::spawn(move || {
-            // 创建新的运行时
-            let rt = tokio::runtime::Runtime::new().ok()?;
-            rt.block_on(async move {
+        self.start_optimizer();
+        // 新回合开始，丢弃上一轮（例如被中断的回合）未记录的工具检索
+        if let Ok(mut retrievals) = self.tool_retrievals.lock() {
+            retrievals.clear();
+        }
+
+        tracing::info!(
+            "🔍 ACE pre_execute: Querying bullets for: {}",
+            query.chars().take(50).collect::<String>()
+        );
+        let found = self
+            .search_bullets(query)
+            .await
+            .map(|(bullets, personal_ids)| {
+                let rendered =
+                    context::render_context(&bullets, &self.context_config, &self.token_counter);
+                (bullets.len(), rendered, personal_ids)
+            });
+        match found {
+            Ok((found, Some(rendered), personal_ids)) => {
                 tracing::info!(
-                    "🔍 ACE pre_execute: Querying bullets for: {}",
-                    query_content.chars().take(50).collect::<String>()
+                    "✅ ACE pre_execute: Injecting {}/{} relevant bullets ({} tokens)",
+                    rendered.bullet_ids.len(),
+                    found,
+                    rendered.tokens
                 );
-                match storage.query_bullets(&query_content, 10).await {
-                    Ok(bullets) if !bullets.is_empty() => {
-                        tracing::info!(
-                            "✅ ACE pre_execute: Found {} relevant bullets",
-                            bullets.len()
-                        );
-                        Some(bullets)
-                    }
-                    Ok(_) => {
-                        tracing::info!("⚠️ ACE pre_execute: No relevant bullets found");
-                        None
-                    }
-                    Err(e) => {
-                        tracing::warn!("❌ ACE pre_execute: Failed to query bullets: {}", e);
-                        None
-                    }
-                }
-            })
-        })
-        .join()
-        .ok()
-        .flatten();
-
-        context.map(|bullets| self.format_bullets_as_context(bullets))
+                // 只记录实际注入的个人 bullets
+                self.set_last_injection(Some(InjectedBullets {
+                    query: query.to_string(),
+                    bullet_ids: personal_ids
+                        .into_iter()
+                        .filter(|id| rendered.bullet_ids.contains(id))
+                        .collect(),
+                }));
+                vec![
+                    ContextContribution::new(self.name(), rendered.text)
+                        .with_max_tokens(self.context_config.max_context_tokens),
+                ]
+            }
+            Ok(_) => {
+                tracing::info!("⚠️ ACE pre_execute: No relevant bullets found");
+                self.set_last_injection(None);
+                Vec::new()
+            }
+            Err(e) => {
+                tracing::warn!("❌ ACE pre_execute: Failed to query bullets: {}", e);
+                self.set_last_injection(None);
+                Vec::new()
+            }
+        }
     }
 
     /// 在执行后进行学习
-    fn post_execute(&self, query: &str, response: &str, success: bool) {
+    ///
+    /// 由 `HookManager` 在独立任务中调用，这里直接 await 整个学习过程。
+    async fn post_execute(&self, outcome: &TurnOutcome) {
         if !self.enabled {
             return;
         }
 
-        // 克隆必要的组件
-        let reflector = Arc::clone(&self.reflector);
-        let curator = Arc::clone(&self.curator);
-        let storage = Arc::clone(&self.storage);
-        let query_content = query.to_string();
-        let response_content = response.to_string();
-
-        // 创建执行结果（简化版）
-        let execution_result = ExecutionResult {
-            success,
-            output: if success {
-                Some(response.to_string())
-            } else {
-                None
-            },
-            error: if !success {
-                Some("Execution failed".to_string())
-            } else {
-                None
-            },
-            tools_used: Vec::new(), // TODO: 从响应中提取
-            errors: Vec::new(),
-            retry_success: false,
-        };
+        // 先脱敏，后续的 InsightContext、bullets 和召回记录都只包含脱敏后的文本
+        let (query, _) = self.redactor.redact_text(&outcome.query);
+        let (response, _) = self
+            .redactor
+            .redact_text(outcome.response.as_deref().unwrap_or(""));
+        let query = query.as_str();
+        let response = response.as_str();
+        let success = outcome.success();
 
-        // 异步执行学习过程
-        tokio::spawn(async move {
-            tracing::debug!("Starting ACE learning process (Bullet-based)...");
+        // 记录本轮注入和工具检索到的 bullets 是否帮助完成了任务
+        let mut used_bullet_ids = self
+            .last_injection
+            .lock()
+            .ok()
+            .and_then(|mut last| last.take())
+            .filter(|injection| injection.query == outcome.query)
+            .map(|injection| injection.bullet_ids)
+            .unwrap_or_default();
+        if let Ok(mut retrievals) = self.tool_retrievals.lock() {
+            for id in retrievals.drain(..) {
+                if !used_bullet_ids.contains(&id) {
+                    used_bullet_ids.push(id);
+                }
+            }
+        }
+        self.optimizer
+            .record_call(used_bullet_ids, types::truncate_string(query, 100), success)
+            .await;
 
-            // 1. Reflector 分析
-            let session_id = uuid::Uuid::new_v4().to_string();
+        let mut execution_result = execution_result_for(outcome, response);
+        self.redactor.redact_execution_result(&mut execution_result);
 
-            let insights = match reflector
-                .analyze_conversation(
-                    &query_content,
-                    &response_content,
-                    &execution_result,
-                    session_id.clone(),
-                )
-                .await
-            {
-                Ok(insights) => insights,
-                Err(e) => {
-                    tracing::error!("Reflector failed: {}", e);
-                    return;
-                }
-            };
+        tracing::debug!("Starting ACE learning process (Bullet-based)...");
 
-            if insights.is_empty() {
-                tracing::debug!("No valuable insights extracted");
+        // 1. Reflector 分析
+        // 新 bullets 记录真实的会话 ID；没有会话时退回随机 ID
+        let provenance = self.provenance(&outcome.turn_id);
+        let session_id = provenance.as_ref().map_or_else(
+            || uuid::Uuid::new_v4().to_string(),
+            |provenance| provenance.conversation_id.clone(),
+        );
+
+        let insights = match self
+            .reflector
+            .analyze_conversation(query, response, &execution_result, session_id.clone())
+            .await
+        {
+            Ok(insights) => insights,
+            Err(e) => {
+                tracing::error!("Reflector failed: {}", e);
                 return;
             }
+        };
 
-            tracing::info!("Extracted {} insights", insights.len());
+        if insights.is_empty() {
+            tracing::debug!("No valuable insights extracted");
+            return;
+        }
 
-            // 2. Curator 生成 delta
-            let delta = match curator.process_insights(insights, session_id).await {
-                Ok(delta) => delta,
-                Err(e) => {
-                    tracing::error!("Curator failed: {}", e);
-                    return;
-                }
-            };
+        tracing::info!("Extracted {} insights", insights.len());
 
-            if delta.is_empty() {
-                tracing::debug!("Delta is empty, nothing to merge");
+        // 2. Curator 与已有 playbook 比较，生成 delta
+        let playbook = match self.storage.load_playbook().await {
+            Ok(playbook) => playbook,
+            Err(e) => {
+                tracing::error!("Failed to load playbook: {}", e);
+                return;
+            }
+        };
+        let mut delta = match self
+            .curator
+            .process_insights_with_playbook(insights, session_id, &playbook)
+            .await
+        {
+            Ok(delta) => delta,
+            Err(e) => {
+                tracing::error!("Curator failed: {}", e);
                 return;
             }
+        };
 
-            tracing::info!(
-                "Generated delta: {} new bullets, {} updated",
-                delta.new_bullets.len(),
-                delta.updated_bullets.len()
-            );
+        // LLM Reflector 可能改写出新的形式，已有 bullets 也可能在脱敏启用前写入，合并前再检查一遍
+        let redacted = self.redactor.redact_delta(&mut delta);
+        if redacted > 0 {
+            tracing::info!("Redacted {} secrets from delta", redacted);
+        }
+        if let Some(provenance) = &provenance {
+            delta.set_provenance(provenance);
+        }
 
-            // 3. Storage 合并 delta
-            if let Err(e) = storage.merge_delta(delta).await {
-                tracing::error!("Failed to merge delta: {}", e);
-            } else {
-                tracing::info!("Delta merged successfully");
-            }
-        });
+        if delta.is_empty() {
+            tracing::debug!("Delta is empty, nothing to merge");
+            return;
+        }
+
+        tracing::info!(
+            "Generated delta: {} new bullets, {} updated, {} deleted",
+            delta.new_bullets.len(),
+            delta.updated_bullets.len(),
+            delta.deleted_bullet_ids.len()
+        );
+
+        // 3. Storage 合并 delta（同时增量更新索引）
+        if let Err(e) =
+            merge_and_notify(&self.index, self.tx_event.as_ref(), &outcome.turn_id, delta).await
+        {
+            tracing::error!("Failed to merge delta: {}", e);
+        } else {
+            tracing::info!("Delta merged successfully");
+        }
     }
 }
 
@@ -447,6 +1037,202 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_plugin_scoped_retrieval_with_repo_layer() {
+        use knowledge_scope::Domain;
+        use knowledge_scope::Language;
+
+        let home = tempfile::tempdir().unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        std::fs::create_dir(repo.path().join(".git")).unwrap();
+        std::fs::write(repo.path().join("Cargo.toml"), "").unwrap();
+        let repo_playbook = repo.path().join(".codeace/playbook");
+        std::fs::create_dir_all(&repo_playbook).unwrap();
+
+        let scoped_bullet = |content: &str, language: Language| {
+            let mut bullet = Bullet::new(
+                BulletSection::ToolUsageTips,
+                content.to_string(),
+                "session-1".to_string(),
+            );
+            bullet.metadata.scope = Some(KnowledgeScope::new(Domain::Generic, language));
+            bullet
+        };
+
+        // 个人 playbook：一条 Python 经验、一条 Rust 经验
+        let personal = BulletStorage::new(home.path(), 100).unwrap();
+        let python = scoped_bullet("Run the test suite with pytest -x", Language::Python);
+        let rust = scoped_bullet("Run the test suite with cargo nextest", Language::Rust);
+        let rust_id = rust.id.clone();
+        let mut delta = DeltaContext::new("session-1".to_string());
+        delta.new_bullets.push(python);
+        delta.new_bullets.push(rust);
+        personal.merge_delta(delta).await.unwrap();
+
+        // 仓库 playbook：团队共享的经验
+        let team = Bullet::new(
+            BulletSection::ToolUsageTips,
+            "Run the test suite through just test".to_string(),
+            "team".to_string(),
+        );
+        let team_id = team.id.clone();
+        let mut delta = DeltaContext::new("team".to_string());
+        delta.new_bullets.push(team);
+        BulletStorage::new(&repo_playbook, 100)
+            .unwrap()
+            .merge_delta(delta)
+            .await
+            .unwrap();
+
+        let config = ACEConfig {
+            enabled: true,
+            storage_path: home.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let plugin = ACEPlugin::new(config).unwrap().with_cwd(repo.path()).await;
+
+        let (bullets, personal_ids) = plugin.search_bullets("run the test suite").await.unwrap();
+        let ids: Vec<_> = bullets.iter().map(|bullet| bullet.id.clone()).collect();
+        assert_eq!(ids, vec![team_id, rust_id.clone()]);
+        assert_eq!(personal_ids, vec![rust_id]);
+    }
+
+    #[tokio::test]
+    async fn test_plugin_manual_entry_and_feedback() {
+        let home = tempfile::tempdir().unwrap();
+        let config = ACEConfig {
+            enabled: true,
+            storage_path: home.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let plugin = ACEPlugin::new(config).unwrap().with_event_sender(tx_event);
+
+        let bullet = plugin
+            .add_manual_entry(
+                "sub-1",
+                "  Run the linter with cargo clippy before pushing  ",
+                BulletSection::ToolUsageTips,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            bullet.content,
+            "Run the linter with cargo clippy before pushing"
+        );
+        assert_eq!(bullet.metadata.source_type, types::SourceType::ManualEntry);
+        let event = rx_event.try_recv().unwrap();
+        assert_eq!(event.id, "sub-1");
+        let EventMsg::PlaybookUpdated(update) = event.msg else {
+            panic!("expected PlaybookUpdated, got {:?}", event.msg);
+        };
+        assert_eq!(
+            update,
+            PlaybookUpdatedEvent {
+                added: vec![bullet.id.clone()],
+                ..Default::default()
+            }
+        );
+        assert!(
+            plugin
+                .add_manual_entry("sub-2", "   ", BulletSection::General)
+                .await
+                .is_err()
+        );
+
+        // 没有注入过 bullets 时，默认对象为空
+        assert!(
+            plugin
+                .record_feedback(PlaybookFeedbackRating::Upvote, Vec::new())
+                .await
+                .is_err()
+        );
+
+        let injected = plugin.pre_execute("run the linter before pushing").await;
+        assert_eq!(injected.len(), 1);
+
+        let updated = plugin
+            .record_feedback(PlaybookFeedbackRating::Downvote, Vec::new())
+            .await
+            .unwrap();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].id, bullet.id);
+        assert_eq!(updated[0].metadata.failure_count, 1);
+
+        // 标记后不再注入
+        plugin
+            .record_feedback(PlaybookFeedbackRating::Flag, vec![bullet.id.clone()])
+            .await
+            .unwrap();
+        assert!(
+            plugin
+                .pre_execute("run the linter before pushing")
+                .await
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plugin_search_on_demand_filters_and_tracks_recall() {
+        let home = tempfile::tempdir().unwrap();
+        let storage = BulletStorage::new(home.path(), 100).unwrap();
+        let tagged = |section: BulletSection, content: &str, tags: &[&str]| {
+            let mut bullet = Bullet::new(section, content.to_string(), "session-1".to_string());
+            bullet.tags = tags.iter().map(|tag| tag.to_string()).collect();
+            bullet
+        };
+        let linker = tagged(
+            BulletSection::TroubleshootingAndPitfalls,
+            "Linker errors about missing symbols: run cargo clean first",
+            &["rust", "linker"],
+        );
+        let linker_id = linker.id.clone();
+        let mut delta = DeltaContext::new("session-1".to_string());
+        delta.new_bullets.push(linker);
+        delta.new_bullets.push(tagged(
+            BulletSection::ToolUsageTips,
+            "Linker flags go in .cargo/config.toml",
+            &["rust"],
+        ));
+        storage.merge_delta(delta).await.unwrap();
+
+        let config = ACEConfig {
+            enabled: true,
+            storage_path: home.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let plugin = ACEPlugin::new(config).unwrap();
+
+        let all = plugin
+            .search_on_demand("linker", None, &[], 5)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        let by_section = plugin
+            .search_on_demand(
+                "linker",
+                Some(&BulletSection::TroubleshootingAndPitfalls),
+                &[],
+                5,
+            )
+            .await
+            .unwrap();
+        let by_tag = plugin
+            .search_on_demand("linker", None, &["LINKER".to_string()], 5)
+            .await
+            .unwrap();
+        for found in [by_section, by_tag] {
+            let ids: Vec<_> = found.into_iter().map(|bullet| bullet.id).collect();
+            assert_eq!(ids, vec![linker_id.clone()]);
+        }
+
+        // 检索到的 bullets 去重后等待 post_execute 记录召回；新回合开始时清空
+        assert_eq!(plugin.tool_retrievals.lock().unwrap().len(), 2);
+        plugin.pre_execute("something unrelated").await;
+        assert!(plugin.tool_retrievals.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_plugin_from_config_none() {
         let result = ACEPlugin::from_config(None).unwrap();
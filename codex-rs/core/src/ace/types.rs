@@ -1,916 +0,0 @@
-//! ACE 框架的核心数据结构 - Bullet-based 架构
-//!
-//! 基于 Agentic Context Engineering 论文实现，采用细粒度的 bullet 管理。
-
-use chrono::DateTime;
-use chrono::Utc;
-use serde::Deserialize;
-use serde::Serialize;
-use std::collections::HashMap;
-use uuid::Uuid;
-
-// ============================================================================
-// Bullet 数据结构（核心单元）
-// ============================================================================
-
-/// 一条可执行的规则/策略/知识点
-///
-/// Bullet 是 ACE 系统的核心存储单元，每个 bullet 代表一条独立的、
-/// 可引用的知识点或策略。
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Bullet {
-    /// 唯一标识符（UUID）
-    pub id: String,
-
-    /// 创建时间
-    pub created_at: DateTime<Utc>,
-
-    /// 最后更新时间
-    pub updated_at: DateTime<Utc>,
-
-    /// 来源会话ID（首次创建时）
-    pub source_session_id: String,
-
-    /// 所属分类（structured sections）
-    pub section: BulletSection,
-
-    /// 具体内容（markdown 格式）
-    pub content: String,
-
-    /// 代码内容（如果包含代码）
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub code_content: Option<BulletCodeContent>,
-
-    /// 元数据（细粒度跟踪）
-    pub metadata: BulletMetadata,
-
-    /// 关联的标签（用于检索）
-    pub tags: Vec<String>,
-}
-
-/// 代码内容（分级保存）
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum BulletCodeContent {
-    /// 完整代码（用于核心代码、小文件）
-    Full {
-        /// 编程语言
-        language: String,
-        /// 完整代码
-        code: String,
-        /// 文件路径（如果有）
-        #[serde(skip_serializing_if = "Option::is_none")]
-        file_path: Option<String>,
-    },
-
-    /// 摘要+引用（用于大文件、辅助代码）
-    Summary {
-        /// 编程语言
-        language: String,
-        /// 代码摘要（函数签名、关键类型等）
-        summary: String,
-        /// 文件路径
-        file_path: String,
-        /// 关键行号范围
-        #[serde(skip_serializing_if = "Option::is_none")]
-        key_lines: Option<Vec<(usize, usize)>>,
-    },
-}
-
-/// 分类（参考论文 Figure 3）
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub enum BulletSection {
-    /// 策略和硬性规则
-    StrategiesAndRules,
-
-    /// 可用的代码片段和模板
-    CodeSnippetsAndTemplates,
-
-    /// 故障排查和陷阱
-    TroubleshootingAndPitfalls,
-
-    /// API 使用指南
-    ApiUsageGuides,
-
-    /// 错误处理模式
-    ErrorHandlingPatterns,
-
-    /// 工具使用技巧
-    ToolUsageTips,
-
-    /// 其他通用知识
-    General,
-}
-
-/// 细粒度元数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BulletMetadata {
-    /// 重要性评分（0.0 - 1.0）
-    pub importance: f32,
-
-    /// 来源类型
-    pub source_type: SourceType,
-
-    /// 适用性范围
-    pub applicability: Applicability,
-
-    /// 引用次数（被 context loader 使用）
-    pub reference_count: u32,
-
-    /// 成功应用次数
-    pub success_count: u32,
-
-    /// 失败应用次数
-    pub failure_count: u32,
-
-    /// 相关工具/语言
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub related_tools: Vec<String>,
-
-    /// 相关文件模式（glob）
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub related_file_patterns: Vec<String>,
-
-    /// 置信度（0.0 - 1.0，MVP 可固定为 1.0）
-    pub confidence: f32,
-
-    // ============ LAPS 新增字段 ============
-    /// 被召回次数
-    #[serde(default)]
-    pub recall_count: u32,
-
-    /// 最后召回时间
-    #[serde(default)]
-    pub last_recall: Option<DateTime<Utc>>,
-
-    /// 最近召回的上下文（保留最近10次）
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub recall_contexts: Vec<String>,
-
-    /// 成功率（0.0 - 1.0，基于 success_count 和 failure_count）
-    #[serde(default)]
-    pub success_rate: f32,
-}
-
-/// 来源类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum SourceType {
-    /// 从成功执行中提取
-    SuccessExecution,
-
-    /// 从错误解决中提取
-    ErrorResolution,
-
-    /// 从模式识别中提取
-    PatternRecognition,
-
-    /// 手动添加（预留）
-    ManualEntry,
-}
-
-/// 适用性范围
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct Applicability {
-    /// 适用的编程语言（空表示通用）
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub languages: Vec<String>,
-
-    /// 适用的工具
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub tools: Vec<String>,
-
-    /// 适用的操作系统
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub platforms: Vec<String>,
-
-    /// 适用的项目类型
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub project_types: Vec<String>,
-}
-
-impl Bullet {
-    /// 创建新 bullet
-    pub fn new(section: BulletSection, content: String, source_session_id: String) -> Self {
-        let now = Utc::now();
-        Self {
-            id: Uuid::new_v4().to_string(),
-            created_at: now,
-            updated_at: now,
-            source_session_id,
-            section,
-            content,
-            code_content: None,
-            metadata: BulletMetadata::default(),
-            tags: Vec::new(),
-        }
-    }
-
-    /// 增加引用计数
-    pub fn increment_reference(&mut self) {
-        self.metadata.reference_count += 1;
-        self.updated_at = Utc::now();
-    }
-
-    /// 记录成功应用
-    pub fn record_success(&mut self) {
-        self.metadata.success_count += 1;
-        self.updated_at = Utc::now();
-    }
-
-    /// 记录失败应用
-    pub fn record_failure(&mut self) {
-        self.metadata.failure_count += 1;
-        self.updated_at = Utc::now();
-    }
-
-    /// 计算成功率（0.0 - 1.0）
-    pub fn success_rate(&self) -> f32 {
-        let total = self.metadata.success_count + self.metadata.failure_count;
-        if total == 0 {
-            0.0
-        } else {
-            self.metadata.success_count as f32 / total as f32
-        }
-    }
-}
-
-impl Default for BulletMetadata {
-    fn default() -> Self {
-        Self {
-            importance: 0.5,
-            source_type: SourceType::PatternRecognition,
-            applicability: Applicability::default(),
-            reference_count: 0,
-            success_count: 0,
-            failure_count: 0,
-            related_tools: Vec::new(),
-            related_file_patterns: Vec::new(),
-            confidence: 1.0,
-            // LAPS 新增字段
-            recall_count: 0,
-            last_recall: None,
-            recall_contexts: Vec::new(),
-            success_rate: 0.0,
-        }
-    }
-}
-
-impl BulletMetadata {
-    /// 【LAPS】计算动态权重
-    ///
-    /// 基于多个因子计算 bullet 的动态权重：
-    /// - 召回频率（对数增长，避免过度偏向）
-    /// - 成功率（质量指标）
-    /// - 时效性（最近使用的更重要）
-    ///
-    /// # 返回
-    /// 动态权重值（0.0 - 无上限，通常在 0.0 - 5.0 之间）
-    pub fn calculate_dynamic_weight(&self) -> f32 {
-        // 召回频率因子（对数增长）
-        // 使用 ln(1 + recall_count) 避免对高频内容过度偏向
-        let recall_factor = (1.0 + self.recall_count as f32).ln();
-
-        // 成功率因子（0.5 - 1.0）
-        // 即使没有使用记录，也给予基础权重 0.5
-        let total = self.success_count + self.failure_count;
-        let success_rate = if total > 0 {
-            self.success_count as f32 / total as f32
-        } else {
-            0.5 // 默认50%
-        };
-        let success_factor = 0.5 + success_rate * 0.5;
-
-        // 时效性因子（0.5 - 1.0）
-        // 最近使用的内容权重更高
-        let recency_factor = if let Some(last_recall) = self.last_recall {
-            let days_since = (Utc::now() - last_recall).num_days();
-            // 使用指数衰减，7天内保持高权重
-            let decay = 1.0 / (1.0 + (days_since as f32) * 0.01);
-            0.5 + decay * 0.5
-        } else {
-            0.5 // 从未被召回，给予基础权重
-        };
-
-        // 综合权重 = 基础重要性 × 召回频率 × 成功率 × 时效性
-        self.importance * recall_factor * success_factor * recency_factor
-    }
-
-    /// 【LAPS】记录召回
-    ///
-    /// 当 bullet 被召回使用时调用此方法，更新统计信息。
-    ///
-    /// # 参数
-    /// - `context`: 召回时的上下文描述
-    /// - `success`: 是否成功应用
-    pub fn record_recall(&mut self, context: String, success: bool) {
-        // 增加召回计数
-        self.recall_count += 1;
-
-        // 更新最后召回时间
-        self.last_recall = Some(Utc::now());
-
-        // 保留最近10次上下文
-        self.recall_contexts.push(context);
-        if self.recall_contexts.len() > 10 {
-            self.recall_contexts.remove(0);
-        }
-
-        // 更新成功/失败计数
-        if success {
-            self.success_count += 1;
-        } else {
-            self.failure_count += 1;
-        }
-
-        // 重新计算成功率
-        let total = self.success_count + self.failure_count;
-        self.success_rate = if total > 0 {
-            self.success_count as f32 / total as f32
-        } else {
-            0.0
-        };
-    }
-}
-
-// ============================================================================
-// Playbook 结构（bullet 集合）
-// ============================================================================
-
-/// Playbook - bullet 的有序集合
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Playbook {
-    /// 版本号（用于追踪变更）
-    pub version: u32,
-
-    /// 最后更新时间
-    pub last_updated: DateTime<Utc>,
-
-    /// 所有 bullets（按 section 分组）
-    pub bullets: HashMap<BulletSection, Vec<Bullet>>,
-
-    /// 全局元数据
-    pub metadata: PlaybookMetadata,
-}
-
-/// Playbook 元数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlaybookMetadata {
-    /// 总 bullet 数
-    pub total_bullets: usize,
-
-    /// 按 section 统计
-    pub section_counts: HashMap<BulletSection, usize>,
-
-    /// 创建时间
-    pub created_at: DateTime<Utc>,
-
-    /// 来源会话数
-    pub total_sessions: usize,
-}
-
-impl Playbook {
-    /// 创建空 playbook
-    pub fn new() -> Self {
-        Self {
-            version: 1,
-            last_updated: Utc::now(),
-            bullets: HashMap::new(),
-            metadata: PlaybookMetadata {
-                total_bullets: 0,
-                section_counts: HashMap::new(),
-                created_at: Utc::now(),
-                total_sessions: 0,
-            },
-        }
-    }
-
-    /// 添加 bullet
-    pub fn add_bullet(&mut self, bullet: Bullet) {
-        let section = bullet.section.clone();
-        self.bullets
-            .entry(section.clone())
-            .or_default()
-            .push(bullet);
-
-        self.metadata.total_bullets += 1;
-        *self.metadata.section_counts.entry(section).or_insert(0) += 1;
-        self.version += 1;
-        self.last_updated = Utc::now();
-    }
-
-    /// 查找 bullet
-    pub fn find_bullet(&self, id: &str) -> Option<&Bullet> {
-        self.bullets.values().flatten().find(|b| b.id == id)
-    }
-
-    /// 查找 bullet（可变引用）
-    pub fn find_bullet_mut(&mut self, id: &str) -> Option<&mut Bullet> {
-        self.bullets.values_mut().flatten().find(|b| b.id == id)
-    }
-
-    /// 更新 bullet（返回是否成功）
-    pub fn update_bullet(&mut self, updated: Bullet) -> bool {
-        for bullets in self.bullets.values_mut() {
-            if let Some(pos) = bullets.iter().position(|b| b.id == updated.id) {
-                bullets[pos] = updated;
-                self.version += 1;
-                self.last_updated = Utc::now();
-                return true;
-            }
-        }
-        false
-    }
-
-    /// 删除 bullet（返回是否成功）
-    pub fn remove_bullet(&mut self, id: &str) -> bool {
-        for (section, bullets) in self.bullets.iter_mut() {
-            if let Some(pos) = bullets.iter().position(|b| b.id == id) {
-                bullets.remove(pos);
-                self.metadata.total_bullets = self.metadata.total_bullets.saturating_sub(1);
-                if let Some(count) = self.metadata.section_counts.get_mut(section) {
-                    *count = count.saturating_sub(1);
-                }
-                self.version += 1;
-                self.last_updated = Utc::now();
-                return true;
-            }
-        }
-        false
-    }
-
-    /// 获取所有 bullets（扁平化）
-    pub fn all_bullets(&self) -> Vec<&Bullet> {
-        self.bullets.values().flatten().collect()
-    }
-
-    /// 按 section 获取 bullets
-    pub fn bullets_by_section(&self, section: &BulletSection) -> Vec<&Bullet> {
-        self.bullets
-            .get(section)
-            .map(|v| v.iter().collect())
-            .unwrap_or_default()
-    }
-}
-
-impl Default for Playbook {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-// ============================================================================
-// RawInsight（Reflector 输出）
-// ============================================================================
-
-/// Reflector 的原始输出（未结构化）
-#[derive(Debug, Clone)]
-pub struct RawInsight {
-    /// 洞察内容
-    pub content: String,
-
-    /// 类别
-    pub category: InsightCategory,
-
-    /// 重要性
-    pub importance: f32,
-
-    /// 来源上下文
-    pub context: InsightContext,
-}
-
-/// 洞察上下文（帮助 Curator 生成 metadata）
-#[derive(Debug, Clone)]
-pub struct InsightContext {
-    /// 用户查询
-    pub user_query: String,
-
-    /// 助手响应片段
-    pub assistant_response_snippet: String,
-
-    /// 执行结果
-    pub execution_success: bool,
-
-    /// 使用的工具
-    pub tools_used: Vec<String>,
-
-    /// 错误信息
-    pub error_message: Option<String>,
-
-    /// 会话ID
-    pub session_id: String,
-}
-
-/// 洞察类别
-#[derive(Debug, Clone, PartialEq)]
-pub enum InsightCategory {
-    /// 工具使用
-    ToolUsage,
-
-    /// 模式识别
-    Pattern,
-
-    /// 问题解决
-    Solution,
-
-    /// 知识捕获
-    Knowledge,
-
-    /// 错误处理
-    ErrorHandling,
-}
-
-// ============================================================================
-// DeltaContext（Curator 输出）
-// ============================================================================
-
-/// 增量上下文更新（Curator 输出）
-#[derive(Debug, Clone)]
-pub struct DeltaContext {
-    /// 会话ID
-    pub session_id: String,
-
-    /// 新增的 bullets
-    pub new_bullets: Vec<Bullet>,
-
-    /// 需要更新的 bullets（仅 metadata 变化）
-    pub updated_bullets: Vec<Bullet>,
-
-    /// 生成时间
-    pub generated_at: DateTime<Utc>,
-
-    /// 元数据
-    pub metadata: DeltaMetadata,
-}
-
-/// Delta 元数据
-#[derive(Debug, Clone)]
-pub struct DeltaMetadata {
-    /// 处理的 insights 数量
-    pub insights_processed: usize,
-
-    /// 生成的新 bullets 数量
-    pub new_bullets_count: usize,
-
-    /// 更新的 bullets 数量
-    pub updated_bullets_count: usize,
-
-    /// 处理耗时（毫秒）
-    pub processing_time_ms: u64,
-}
-
-impl DeltaContext {
-    /// 创建空 delta
-    pub fn new(session_id: String) -> Self {
-        Self {
-            session_id,
-            new_bullets: Vec::new(),
-            updated_bullets: Vec::new(),
-            generated_at: Utc::now(),
-            metadata: DeltaMetadata {
-                insights_processed: 0,
-                new_bullets_count: 0,
-                updated_bullets_count: 0,
-                processing_time_ms: 0,
-            },
-        }
-    }
-
-    /// 是否为空（无变更）
-    pub fn is_empty(&self) -> bool {
-        self.new_bullets.is_empty() && self.updated_bullets.is_empty()
-    }
-}
-
-// ============================================================================
-// 执行结果
-// ============================================================================
-
-/// 执行结果
-#[derive(Debug, Clone, Default)]
-pub struct ExecutionResult {
-    /// 是否成功
-    pub success: bool,
-
-    /// 输出内容
-    pub output: Option<String>,
-
-    /// 错误信息
-    pub error: Option<String>,
-
-    /// 使用的工具
-    pub tools_used: Vec<String>,
-
-    /// 错误列表
-    pub errors: Vec<String>,
-
-    /// 是否重试成功
-    pub retry_success: bool,
-}
-
-// ============================================================================
-// 配置
-// ============================================================================
-
-/// ACE配置
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ACEConfig {
-    /// 是否启用
-    pub enabled: bool,
-
-    /// 存储路径
-    pub storage_path: String,
-
-    /// 最大条目数
-    pub max_entries: usize,
-
-    /// Reflector配置
-    pub reflector: ReflectorConfig,
-
-    /// Context配置
-    pub context: ContextConfig,
-}
-
-impl Default for ACEConfig {
-    fn default() -> Self {
-        Self {
-            enabled: false,
-            storage_path: "~/.codeACE/ace".to_string(),
-            max_entries: 500,
-            reflector: ReflectorConfig::default(),
-            context: ContextConfig::default(),
-        }
-    }
-}
-
-/// Reflector配置
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ReflectorConfig {
-    /// 提取模式
-    pub extract_patterns: bool,
-
-    /// 提取工具使用
-    pub extract_tools: bool,
-
-    /// 提取错误处理
-    pub extract_errors: bool,
-}
-
-impl Default for ReflectorConfig {
-    fn default() -> Self {
-        Self {
-            extract_patterns: true,
-            extract_tools: true,
-            extract_errors: true,
-        }
-    }
-}
-
-/// Context配置
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ContextConfig {
-    /// 最近条目数
-    pub max_recent_entries: usize,
-
-    /// 包含所有成功案例
-    pub include_all_successes: bool,
-
-    /// 最大字符数
-    pub max_context_chars: usize,
-}
-
-impl Default for ContextConfig {
-    fn default() -> Self {
-        Self {
-            max_recent_entries: 10,
-            include_all_successes: true,
-            max_context_chars: 4000,
-        }
-    }
-}
-
-/// Curator配置
-#[derive(Debug, Clone)]
-pub struct CuratorConfig {
-    /// 最小重要性阈值
-    pub min_importance: f32,
-
-    /// 是否自动分类
-    pub auto_categorize: bool,
-
-    /// 是否生成标签
-    pub generate_tags: bool,
-}
-
-impl Default for CuratorConfig {
-    fn default() -> Self {
-        Self {
-            min_importance: 0.5,
-            auto_categorize: true,
-            generate_tags: true,
-        }
-    }
-}
-
-// ============================================================================
-// 辅助函数
-// ============================================================================
-
-/// 截断字符串到指定长度（安全处理 UTF-8 边界）
-pub fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        // 找到安全的 UTF-8 字符边界
-        let mut boundary = max_len;
-        while boundary > 0 && !s.is_char_boundary(boundary) {
-            boundary -= 1;
-        }
-
-        if boundary == 0 {
-            // 如果连第一个字符都放不下，返回空字符串
-            "...".to_string()
-        } else {
-            format!("{}...", &s[..boundary])
-        }
-    }
-}
-
-// ============================================================================
-// 对话精华提取（Essence Extraction）
-// ============================================================================
-
-/// 一次对话的精华总结
-///
-/// 目标：压缩并提取精华，让历史上下文膨胀得慢一些
-/// 一次用户对话通常只生成一条精炼的 insight (200-800 字符)
-#[derive(Debug, Clone)]
-pub struct ConversationSummary {
-    /// 用户要求（原始 query）
-    pub user_request: String,
-
-    /// 任务类型
-    pub task_type: TaskType,
-
-    /// 最终状态
-    pub final_state: FinalState,
-
-    /// 提取的精华信息
-    pub essence: TaskEssence,
-}
-
-/// 任务类型
-#[derive(Debug, Clone, PartialEq)]
-pub enum TaskType {
-    /// 代码实现
-    CodeImplementation,
-
-    /// 问题修复
-    BugFix,
-
-    /// 测试运行
-    Testing,
-
-    /// 重构
-    Refactoring,
-
-    /// 配置修改
-    Configuration,
-
-    /// 文档编写
-    Documentation,
-
-    /// 其他
-    Other,
-}
-
-/// 最终状态
-#[derive(Debug, Clone)]
-pub enum FinalState {
-    /// 成功完成
-    Completed {
-        /// 简短总结（一句话）
-        summary: String,
-    },
-
-    /// 部分完成
-    PartiallyCompleted {
-        /// 完成了什么
-        completed: String,
-        /// 未完成什么
-        remaining: String,
-    },
-
-    /// 失败未解决
-    Failed {
-        /// 问题描述
-        problem: String,
-        /// 后续计划
-        next_steps: Vec<String>,
-    },
-}
-
-/// 任务精华（只保留最终结果）
-#[derive(Debug, Clone)]
-pub struct TaskEssence {
-    /// 做了什么（最终结果，不记录中间过程）
-    pub what_was_done: String,
-
-    /// 为什么这么做
-    pub why: Option<String>,
-
-    /// 最终代码（只保留最后成功的版本）
-    pub final_code: Vec<CodeBlock>,
-
-    /// 解决了什么问题
-    pub problem_solved: Option<String>,
-
-    /// 修改的文件
-    pub modified_files: Vec<String>,
-
-    /// 关键决策（只保留最后的决策）
-    pub key_decisions: Vec<String>,
-}
-
-/// 代码块（最终版本）
-#[derive(Debug, Clone)]
-pub struct CodeBlock {
-    /// 编程语言
-    pub language: String,
-
-    /// 代码内容
-    pub code: String,
-
-    /// 文件路径（如果有）
-    pub file_path: Option<String>,
-
-    /// 简短描述（如："异步函数实现，45 行"）
-    pub description: String,
-}
-
-/// 文件修改类型
-#[derive(Debug, Clone, PartialEq)]
-pub enum ModificationType {
-    /// 新建
-    Created,
-
-    /// 修改
-    Modified,
-
-    /// 删除
-    Deleted,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_truncate_string_ascii() {
-        // ASCII 字符串
-        let result = truncate_string("hello world", 5);
-        assert_eq!(result, "hello...");
-
-        let result = truncate_string("hello", 10);
-        assert_eq!(result, "hello");
-    }
-
-    #[test]
-    fn test_truncate_string_utf8() {
-        // 中文字符（每个字符 3 字节）
-        let result = truncate_string("你好世界", 6); // 正好 2 个中文字符
-        assert_eq!(result, "你好...");
-
-        // 边界不对齐的情况
-        let result = truncate_string("你好世界", 7); // 7 字节落在第 3 个字符中间
-        assert_eq!(result, "你好..."); // 应该截断到安全边界
-
-        // 混合字符
-        let result = truncate_string("Hello你好World", 10);
-        assert!(result.contains("..."));
-        assert!(!result.contains("�")); // 不应该有乱码
-    }
-
-    #[test]
-    fn test_truncate_string_emoji() {
-        // Emoji（4 字节）
-        let result = truncate_string("😀😁😂", 4);
-        assert_eq!(result, "😀...");
-
-        let result = truncate_string("😀😁😂", 5); // 落在第 2 个 emoji 中间
-        assert_eq!(result, "😀..."); // 应该截断到第 1 个
-    }
-
-    #[test]
-    fn test_truncate_string_edge_cases() {
-        // 空字符串
-        let result = truncate_string("", 10);
-        assert_eq!(result, "");
-
-        // 极小的 max_len
-        let result = truncate_string("你好", 1);
-        assert_eq!(result, "..."); // 无法容纳任何字符
-
-        // max_len 为 0
-        let result = truncate_string("hello", 0);
-        assert_eq!(result, "...");
-    }
-}
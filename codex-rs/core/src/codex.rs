@@ -55,8 +55,10 @@ use crate::ModelProviderInfo;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::command_safety::approval_rules::ProjectApprovalRules;
 use crate::config::Config;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::SandboxContainer;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::conversation_history::ConversationHistory;
 use crate::environment_context::EnvironmentContext;
@@ -64,6 +66,7 @@ use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 #[cfg(test)]
 use crate::exec::StreamOutput;
+use crate::exec_policy::ExecPolicy;
 // Removed: legacy executor wiring replaced by ToolOrchestrator flows.
 // legacy normalize_exec_result no longer used after orchestrator migration
 use crate::mcp::auth::compute_auth_statuses;
@@ -76,11 +79,15 @@ use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::CommandPattern;
+use crate::protocol::ContextSourceTokens;
+use crate::protocol::ContextTokenCountEvent;
 use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::ExecPolicyMatch;
 use crate::protocol::Op;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
@@ -135,35 +142,105 @@ use self::compact::collect_user_messages;
 
 // ACE 组件初始化辅助函数
 #[cfg(feature = "ace")]
-async fn init_ace_components(
+async fn init_ace_plugin(
     codex_home: &std::path::Path,
-) -> (
-    Option<Arc<crate::hooks::HookManager>>,
-    Option<Arc<crate::ace::ACEPlugin>>,
-) {
+    cwd: &std::path::Path,
+    ollama_embedder: Option<Arc<dyn crate::ace::Embedder>>,
+    model_client: impl FnOnce() -> ModelClient,
+    token_counter: Arc<crate::hooks::TokenCounter>,
+    tx_event: Sender<Event>,
+) -> Option<Arc<crate::ace::ACEPlugin>> {
     use crate::ace::ACEPlugin;
 
     // 从独立配置文件加载 ACE 配置（自动创建如果不存在）
     // 配置文件路径：~/.codeACE/codeACE-config.toml
-    match ACEPlugin::from_codex_home(codex_home).await {
+    match ACEPlugin::from_codex_home_with_embedder(codex_home, ollama_embedder).await {
         Ok(Some(plugin)) => {
-            let plugin = Arc::new(plugin);
-            let mut hook_manager = crate::hooks::HookManager::new();
-            hook_manager.register(Arc::clone(&plugin) as Arc<dyn crate::hooks::ExecutorHook>);
             tracing::info!("✅ ACE plugin initialized successfully");
-            (Some(Arc::new(hook_manager)), Some(plugin))
+            // 只有 LLM 模式的 Reflector 才需要单独的模型客户端
+            let plugin = if plugin.uses_llm_reflector() {
+                plugin.with_model_client(model_client())
+            } else {
+                plugin
+            };
+            Some(Arc::new(
+                plugin
+                    .with_token_counter(token_counter)
+                    .with_event_sender(tx_event)
+                    .with_cwd(cwd)
+                    .await,
+            ))
         }
         Ok(None) => {
             tracing::info!("ACE is disabled in config");
-            (None, None)
+            None
         }
         Err(e) => {
             tracing::warn!("Failed to initialize ACE plugin: {}", e);
-            (None, None)
+            None
         }
     }
 }
 
+/// 将一次工具调用及其输出转换为 hook 使用的记录
+fn tool_call_record(
+    item: &ResponseItem,
+    response: &ResponseInputItem,
+) -> Option<crate::hooks::ToolCallRecord> {
+    use codex_utils_string::take_bytes_at_char_boundary;
+
+    const MAX_OUTPUT_BYTES: usize = 2048;
+
+    let (call_id, name, arguments) = match item {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } => (call_id.clone(), name.clone(), arguments.clone()),
+        ResponseItem::CustomToolCall {
+            name,
+            input,
+            call_id,
+            ..
+        } => (call_id.clone(), name.clone(), input.clone()),
+        ResponseItem::LocalShellCall {
+            call_id,
+            id,
+            action,
+            ..
+        } => (
+            call_id.clone().or_else(|| id.clone()).unwrap_or_default(),
+            "local_shell".to_string(),
+            serde_json::to_string(action).unwrap_or_default(),
+        ),
+        _ => return None,
+    };
+
+    let (success, output) = match response {
+        ResponseInputItem::FunctionCallOutput { output, .. } => {
+            (output.success, output.content.clone())
+        }
+        ResponseInputItem::CustomToolCallOutput { output, .. } => (None, output.clone()),
+        ResponseInputItem::McpToolCallOutput { result, .. } => match result {
+            Ok(result) => {
+                let output = FunctionCallOutputPayload::from(result);
+                (output.success, output.content)
+            }
+            Err(err) => (Some(false), err.clone()),
+        },
+        _ => return None,
+    };
+
+    Some(crate::hooks::ToolCallRecord {
+        call_id,
+        name,
+        arguments,
+        success,
+        output: take_bytes_at_char_boundary(&output, MAX_OUTPUT_BYTES).to_string(),
+    })
+}
+
 /// The high-level interface to the Codex system.
 /// It operates as a queue pair where you send submissions and receive events.
 pub struct Codex {
@@ -172,6 +249,15 @@ pub struct Codex {
     pub(crate) rx_event: Receiver<Event>,
 }
 
+/// Dependencies a frontend hands to every session it spawns, next to its
+/// [`Config`]. Sub-agent sessions inherit them from their parent.
+#[derive(Clone, Default)]
+pub struct SessionExtensions {
+    /// Embedder for ACE semantic retrieval with `provider = "ollama"`.
+    #[cfg(feature = "ace")]
+    pub(crate) ace_embedder: Option<Arc<dyn crate::ace::Embedder>>,
+}
+
 /// Wrapper returned by [`Codex::spawn`] containing the spawned [`Codex`],
 /// the submission id for the initial `ConfigureSession` request and the
 /// unique session id.
@@ -190,6 +276,7 @@ impl Codex {
         auth_manager: Arc<AuthManager>,
         conversation_history: InitialHistory,
         session_source: SessionSource,
+        extensions: SessionExtensions,
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
@@ -224,6 +311,7 @@ impl Codex {
             tx_event.clone(),
             conversation_history,
             session_source_clone,
+            extensions,
         )
         .await
         .map_err(|e| {
@@ -304,6 +392,8 @@ pub(crate) struct TurnContext {
     pub(crate) user_instructions: Option<String>,
     pub(crate) approval_policy: AskForApproval,
     pub(crate) sandbox_policy: SandboxPolicy,
+    pub(crate) sandbox_container: Option<SandboxContainer>,
+    pub(crate) sandbox_report_denied_reads: bool,
     pub(crate) shell_environment_policy: ShellEnvironmentPolicy,
     pub(crate) tools_config: ToolsConfig,
     pub(crate) final_output_json_schema: Option<Value>,
@@ -460,6 +550,8 @@ impl Session {
             user_instructions: session_configuration.user_instructions.clone(),
             approval_policy: session_configuration.approval_policy,
             sandbox_policy: session_configuration.sandbox_policy.clone(),
+            sandbox_container: config.sandbox_container.clone(),
+            sandbox_report_denied_reads: config.sandbox_report_denied_reads,
             shell_environment_policy: config.shell_environment_policy.clone(),
             tools_config,
             final_output_json_schema: None,
@@ -475,6 +567,7 @@ impl Session {
         tx_event: Sender<Event>,
         initial_history: InitialHistory,
         session_source: SessionSource,
+        extensions: SessionExtensions,
     ) -> anyhow::Result<Arc<Self>> {
         debug!(
             "Configuring session: model={}; provider={:?}",
@@ -620,8 +713,85 @@ impl Session {
         // Create the mutable state for the Session.
         let state = SessionState::new(session_configuration.clone());
 
+        // 生命周期 hooks：`[hooks]` 中的外部命令先于 ACE 注册
+        // hooks 上下文按会话模型的 tokenizer 计数，ACE 共享同一个计数器
+        let token_counter = Arc::new(crate::hooks::TokenCounter::for_model(&config.model));
+        let mut hook_manager =
+            crate::hooks::HookManager::new().with_token_counter(Arc::clone(&token_counter));
+        if let Some(external_hooks) =
+            crate::hooks::external::ExternalHooks::from_config(&config, conversation_id.to_string())
+        {
+            hook_manager.register(Arc::new(external_hooks));
+        }
+
         #[cfg(feature = "ace")]
-        let (ace_hook_manager, ace_plugin) = init_ace_components(&config.codex_home).await;
+        let ace_plugin = {
+            // LLM 模式的 Reflector 使用与会话相同的模型配置
+            let reflector_client = || {
+                ModelClient::new(
+                    Arc::clone(&config),
+                    Some(Arc::clone(&auth_manager)),
+                    otel_event_manager.clone(),
+                    session_configuration.provider.clone(),
+                    config.model_reasoning_effort,
+                    config.model_reasoning_summary,
+                    conversation_id,
+                    session_configuration.session_source.clone(),
+                )
+            };
+            let ace_plugin = init_ace_plugin(
+                &config.codex_home,
+                &config.cwd,
+                extensions.ace_embedder.clone(),
+                reflector_client,
+                Arc::clone(&token_counter),
+                tx_event.clone(),
+            )
+            .await;
+            if let Some(plugin) = &ace_plugin {
+                hook_manager.register(Arc::clone(plugin) as Arc<dyn crate::hooks::ExecutorHook>);
+            }
+            ace_plugin
+        };
+
+        let hook_manager = if hook_manager.hook_count() > 0 {
+            hook_manager
+                .call_on_session_start(&crate::hooks::SessionInfo {
+                    conversation_id: conversation_id.to_string(),
+                    cwd: config.cwd.clone(),
+                    rollout_path: Some(rollout_path.clone()),
+                })
+                .await;
+            Some(Arc::new(hook_manager))
+        } else {
+            None
+        };
+
+        // Exec policy layers: repo-local and user `*.policy` files on top of the default.
+        let (exec_policy, exec_policy_errors) = ExecPolicy::load(
+            &config.codex_home,
+            &config.cwd,
+            config.active_project.is_trusted(),
+        );
+        for message in exec_policy_errors {
+            warn!("{message}");
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(crate::protocol::WarningEvent { message }),
+            });
+        }
+
+        // Command patterns the user approved for this project in earlier sessions.
+        let approval_rules = ProjectApprovalRules::load(&config.codex_home, &config.cwd)
+            .unwrap_or_else(|err| {
+                let message = format!("failed to load project approval rules: {err}");
+                warn!("{message}");
+                post_session_configured_events.push(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::Warning(crate::protocol::WarningEvent { message }),
+                });
+                ProjectApprovalRules::new(&config.codex_home, &config.cwd)
+            });
 
         let services = SessionServices {
             mcp_connection_manager,
@@ -633,8 +803,10 @@ impl Session {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
-            #[cfg(feature = "ace")]
-            hook_manager: ace_hook_manager,
+            exec_policy,
+            approval_rules: Mutex::new(approval_rules),
+            hook_manager,
+            extensions,
             #[cfg(feature = "ace")]
             ace_plugin,
             #[cfg(feature = "ace")]
@@ -722,6 +894,14 @@ impl Session {
                     self.record_into_history(&reconstructed_history).await;
                 }
 
+                // Restore mission/todo progress recorded in the rollout
+                #[cfg(feature = "ace")]
+                if let Some(mission_manager) =
+                    crate::mission::MissionManager::from_rollout_items(&rollout_items)
+                {
+                    *self.services.mission_manager.lock().await = mission_manager;
+                }
+
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
                     self.persist_rollout_items(&rollout_items).await;
@@ -870,7 +1050,10 @@ impl Session {
     ///
     /// The request is keyed by `sub_id`/`call_id` so matching responses are delivered
     /// to the correct in-flight turn. If the task is aborted, this returns the
-    /// default `ReviewDecision` (`Denied`).
+    /// default `ReviewDecision` (`Denied`). When the user answers
+    /// `ApprovedForProject`, `project_approval_pattern` is saved to the
+    /// project's approval rules.
+    #[allow(clippy::too_many_arguments)]
     pub async fn request_command_approval(
         &self,
         turn_context: &TurnContext,
@@ -879,6 +1062,8 @@ impl Session {
         cwd: PathBuf,
         reason: Option<String>,
         risk: Option<SandboxCommandAssessment>,
+        policy_match: Option<ExecPolicyMatch>,
+        project_approval_pattern: Option<CommandPattern>,
     ) -> ReviewDecision {
         let sub_id = turn_context.sub_id.clone();
         // Add the tx_approve callback to the map before sending the request.
@@ -905,10 +1090,39 @@ impl Session {
             cwd,
             reason,
             risk,
+            policy_match,
+            project_approval_pattern: project_approval_pattern.clone(),
             parsed_cmd,
         });
         self.send_event(turn_context, event).await;
-        rx_approve.await.unwrap_or_default()
+        let decision = rx_approve.await.unwrap_or_default();
+        if decision == ReviewDecision::ApprovedForProject
+            && let Some(pattern) = project_approval_pattern
+        {
+            self.save_project_approval(turn_context, pattern).await;
+        }
+        decision
+    }
+
+    async fn save_project_approval(&self, turn_context: &TurnContext, pattern: CommandPattern) {
+        let mut rules = self.services.approval_rules.lock().await;
+        let message = match rules.add(pattern.clone()) {
+            Ok(true) => format!(
+                "Commands matching `{pattern}` will run without approval in {}",
+                rules.project().display()
+            ),
+            Ok(false) => return,
+            Err(err) => {
+                warn!("failed to save project approval rule: {err}");
+                format!("Failed to save approval rule `{pattern}`: {err}")
+            }
+        };
+        drop(rules);
+        self.send_event(
+            turn_context,
+            EventMsg::BackgroundEvent(BackgroundEventEvent { message }),
+        )
+        .await;
     }
 
     pub async fn request_patch_approval(
@@ -1060,6 +1274,15 @@ impl Session {
         items
     }
 
+    /// Record the current mission state in the rollout so it survives resumes.
+    #[cfg(feature = "ace")]
+    pub(crate) async fn record_mission_state(&self) {
+        let item = self.services.mission_manager.lock().await.to_rollout_item();
+        if let Some(item) = item {
+            self.persist_rollout_items(&[item]).await;
+        }
+    }
+
     async fn persist_rollout_items(&self, items: &[RolloutItem]) {
         let recorder = {
             let guard = self.services.rollout.lock().await;
@@ -1370,6 +1593,12 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Review { review_request } => {
                 handlers::review(&sess, &config, sub.id.clone(), review_request).await;
             }
+            Op::PlaybookFeedback { rating, bullet_ids } => {
+                handlers::playbook_feedback(&sess, sub.id.clone(), rating, bullet_ids).await;
+            }
+            Op::AddPlaybookEntry { content, section } => {
+                handlers::add_playbook_entry(&sess, sub.id.clone(), content, section).await;
+            }
             _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
         }
     }
@@ -1395,9 +1624,11 @@ mod handlers {
     use codex_protocol::protocol::EventMsg;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
     use codex_protocol::protocol::Op;
+    use codex_protocol::protocol::PlaybookFeedbackRating;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
     use codex_protocol::protocol::TurnAbortReason;
+    use codex_protocol::protocol::WarningEvent;
     use codex_protocol::user_input::UserInput;
     use std::sync::Arc;
     use tracing::info;
@@ -1593,6 +1824,88 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    /// Apply user feedback to playbook entries injected by ACE.
+    pub async fn playbook_feedback(
+        sess: &Session,
+        sub_id: String,
+        rating: PlaybookFeedbackRating,
+        bullet_ids: Vec<String>,
+    ) {
+        #[cfg(feature = "ace")]
+        let result = match &sess.services.ace_plugin {
+            Some(plugin) => plugin
+                .record_feedback(rating, bullet_ids)
+                .await
+                .map(|updated| {
+                    EventMsg::PlaybookFeedbackApplied(
+                        codex_protocol::protocol::PlaybookFeedbackAppliedEvent {
+                            rating,
+                            bullet_ids: updated.into_iter().map(|bullet| bullet.id).collect(),
+                        },
+                    )
+                }),
+            None => Err(anyhow::anyhow!("ACE is not enabled for this session")),
+        };
+        #[cfg(not(feature = "ace"))]
+        let result: anyhow::Result<EventMsg> = {
+            let _ = (rating, bullet_ids);
+            Err(anyhow::anyhow!("ACE is not enabled in this build"))
+        };
+
+        let msg = result.unwrap_or_else(|err| {
+            EventMsg::Warning(WarningEvent {
+                message: format!("Failed to record playbook feedback: {err}"),
+            })
+        });
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    /// Add a user-authored entry to the ACE playbook.
+    pub async fn add_playbook_entry(
+        sess: &Session,
+        sub_id: String,
+        content: String,
+        section: Option<String>,
+    ) {
+        #[cfg(feature = "ace")]
+        let result = {
+            use crate::ace::BulletSection;
+
+            let section = match section.as_deref() {
+                Some(title) => BulletSection::from_title(title)
+                    .ok_or_else(|| anyhow::anyhow!("unknown playbook section `{title}`")),
+                None => Ok(BulletSection::General),
+            };
+            match (&sess.services.ace_plugin, section) {
+                (Some(plugin), Ok(section)) => plugin
+                    .add_manual_entry(&sub_id, &content, section)
+                    .await
+                    .map(|bullet| {
+                        EventMsg::PlaybookEntryAdded(
+                            codex_protocol::protocol::PlaybookEntryAddedEvent {
+                                section: bullet.section.title().to_string(),
+                                bullet_id: bullet.id,
+                            },
+                        )
+                    }),
+                (None, _) => Err(anyhow::anyhow!("ACE is not enabled for this session")),
+                (_, Err(err)) => Err(err),
+            }
+        };
+        #[cfg(not(feature = "ace"))]
+        let result: anyhow::Result<EventMsg> = {
+            let _ = (content, section);
+            Err(anyhow::anyhow!("ACE is not enabled in this build"))
+        };
+
+        let msg = result.unwrap_or_else(|err| {
+            EventMsg::Warning(WarningEvent {
+                message: format!("Failed to add playbook entry: {err}"),
+            })
+        });
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
     pub async fn undo(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1621,6 +1934,24 @@ mod handlers {
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
         info!("Shutting down Codex instance");
 
+        if let Some(ref hook_manager) = sess.services.hook_manager {
+            let cwd = sess.state.lock().await.session_configuration.cwd.clone();
+            let rollout_path = sess
+                .services
+                .rollout
+                .lock()
+                .await
+                .as_ref()
+                .map(|recorder| recorder.rollout_path.clone());
+            hook_manager
+                .call_on_session_end(&crate::hooks::SessionInfo {
+                    conversation_id: sess.conversation_id.to_string(),
+                    cwd,
+                    rollout_path,
+                })
+                .await;
+        }
+
         // Gracefully flush and shutdown rollout recorder on session end so tests
         // that inspect the rollout file do not race with the background writer.
         let recorder_opt = {
@@ -1736,6 +2067,8 @@ async fn spawn_review_thread(
         compact_prompt: parent_turn_context.compact_prompt.clone(),
         approval_policy: parent_turn_context.approval_policy,
         sandbox_policy: parent_turn_context.sandbox_policy.clone(),
+        sandbox_container: parent_turn_context.sandbox_container.clone(),
+        sandbox_report_denied_reads: parent_turn_context.sandbox_report_denied_reads,
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
         cwd: parent_turn_context.cwd.clone(),
         final_output_json_schema: None,
@@ -1779,7 +2112,7 @@ pub(crate) async fn run_task(
         return None;
     }
 
-    // 提取用户查询文本（用于ACE）
+    // 提取用户查询文本（用于 hooks）
     let user_query_text = input
         .iter()
         .filter_map(|item| match item {
@@ -1789,46 +2122,57 @@ pub(crate) async fn run_task(
         .collect::<Vec<_>>()
         .join(" ");
 
-    // ACE Hook: Pre-execute - 加载相关上下文
-    #[cfg(feature = "ace")]
-    let ace_context = {
-        if let Some(ref hook_manager) = sess.services.hook_manager {
-            tracing::info!(
-                "🔍 ACE: Calling pre_execute with query: {} chars",
-                user_query_text.len()
-            );
-            if let Some(context) = hook_manager.call_pre_execute(&user_query_text) {
-                tracing::info!("✅ ACE: Loaded context: {} chars", context.len());
-                Some(context)
-            } else {
-                tracing::info!("⚠️ ACE: pre_execute returned None (no relevant context found)");
-                None
-            }
-        } else {
-            tracing::info!("⚠️ ACE: hook_manager is None");
-            None
+    // Hook: Pre-execute - 加载相关上下文（如 ACE playbook）
+    let hook_context = if let Some(ref hook_manager) = sess.services.hook_manager {
+        tracing::info!(
+            "🔍 Hooks: Calling pre_execute with query: {} chars",
+            user_query_text.len()
+        );
+        let context = hook_manager.call_pre_execute(&user_query_text).await;
+        match &context {
+            Some(context) => tracing::info!(
+                "✅ Hooks: Loaded context: {} chars, {} tokens",
+                context.text.len(),
+                context.total_tokens()
+            ),
+            None => tracing::info!("⚠️ Hooks: pre_execute returned no context"),
         }
+        context
+    } else {
+        None
     };
-    #[cfg(not(feature = "ace"))]
-    let ace_context: Option<String> = None;
 
     let event = EventMsg::TaskStarted(TaskStartedEvent {
         model_context_window: turn_context.client.get_model_context_window(),
     });
     sess.send_event(&turn_context, event).await;
 
+    // 报告本回合各 hook 注入的 token 数（用于 /status）
+    if let Some(context) = &hook_context {
+        let event = EventMsg::ContextTokenCount(ContextTokenCountEvent {
+            sources: context
+                .usage
+                .iter()
+                .map(|usage| ContextSourceTokens {
+                    source: usage.source.clone(),
+                    tokens: usage.tokens as i64,
+                })
+                .collect(),
+            total_tokens: context.total_tokens() as i64,
+        });
+        sess.send_event(&turn_context, event).await;
+    }
+
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     sess.record_input_and_rollout_usermsg(turn_context.as_ref(), &initial_input_for_turn)
         .await;
 
-    // 如果有ACE上下文，将其作为系统消息添加到历史记录
-    if let Some(context) = ace_context {
+    // 如果有hook上下文（按预算合并后的各片段），将其作为系统消息添加到历史记录
+    if let Some(context) = hook_context {
         let context_item = ResponseItem::Message {
             id: None,
             role: "system".to_string(),
-            content: vec![ContentItem::InputText {
-                text: format!("📚 ACE Context (loaded from previous conversations):\n{context}"),
-            }],
+            content: vec![ContentItem::InputText { text: context.text }],
         };
         sess.record_conversation_items(&turn_context, &[context_item])
             .await;
@@ -1842,6 +2186,14 @@ pub(crate) async fn run_task(
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
     let mut auto_compact_recently_attempted = false;
 
+    // 本回合的工具调用和错误（交给 post_execute hooks）
+    let mut turn_outcome = crate::hooks::TurnOutcome {
+        turn_id: turn_context.sub_id.clone(),
+        query: user_query_text.clone(),
+        ..Default::default()
+    };
+    let mut model_request_index = 0;
+
     loop {
         // Note that pending_input would be something like a message the user
         // submitted through the UI while the model was running. Though the UI
@@ -1868,6 +2220,18 @@ pub(crate) async fn run_task(
             })
             .map(|user_message| user_message.message())
             .collect::<Vec<String>>();
+
+        if let Some(ref hook_manager) = sess.services.hook_manager {
+            hook_manager
+                .call_before_model_request(&crate::hooks::ModelRequestInfo {
+                    turn_id: turn_context.sub_id.clone(),
+                    request_index: model_request_index,
+                    input_items: turn_input.len(),
+                })
+                .await;
+            model_request_index += 1;
+        }
+
         match run_turn(
             Arc::clone(&sess),
             Arc::clone(&turn_context),
@@ -1889,6 +2253,19 @@ pub(crate) async fn run_task(
                 let total_usage_tokens = total_token_usage
                     .as_ref()
                     .map(TokenUsage::tokens_in_context_window);
+
+                // after_tool_call hooks 已在工具分发时调用，这里只记录给 post_execute
+                if sess.services.hook_manager.is_some() {
+                    turn_outcome
+                        .tool_calls
+                        .extend(processed_items.iter().filter_map(|processed| {
+                            processed
+                                .response
+                                .as_ref()
+                                .and_then(|response| tool_call_record(&processed.item, response))
+                        }));
+                }
+
                 let token_limit_reached = total_usage_tokens
                     .map(|tokens| tokens >= limit)
                     .unwrap_or(false);
@@ -1901,11 +2278,11 @@ pub(crate) async fn run_task(
                         let current_tokens = total_usage_tokens
                             .map(|tokens| tokens.to_string())
                             .unwrap_or_else(|| "unknown".to_string());
-                        let event = EventMsg::Error(ErrorEvent {
-                            message: format!(
-                                "Conversation is still above the token limit after automatic summarization (limit {limit_str}, current {current_tokens}). Please start a new session or trim your input."
-                            ),
-                        });
+                        let message = format!(
+                            "Conversation is still above the token limit after automatic summarization (limit {limit_str}, current {current_tokens}). Please start a new session or trim your input."
+                        );
+                        turn_outcome.errors.push(message.clone());
+                        let event = EventMsg::Error(ErrorEvent { message });
                         sess.send_event(&turn_context, event).await;
                         break;
                     }
@@ -1936,11 +2313,13 @@ pub(crate) async fn run_task(
                 dangling_artifacts: processed_items,
             }) => {
                 let _ = process_items(processed_items, &sess, &turn_context).await;
+                turn_outcome.aborted = true;
                 // Aborted turn is reported via a different event.
                 break;
             }
             Err(e) => {
                 info!("Turn error: {e:#}");
+                turn_outcome.errors.push(e.to_string());
                 let event = EventMsg::Error(ErrorEvent {
                     message: e.to_string(),
                 });
@@ -1951,17 +2330,19 @@ pub(crate) async fn run_task(
         }
     }
 
-    // ACE Hook: Post-execute - 学习对话内容
-    #[cfg(feature = "ace")]
-    {
-        if let Some(ref hook_manager) = sess.services.hook_manager {
-            let response = last_agent_message.as_deref().unwrap_or("");
-            let success = !response.is_empty(); // 简单判断：有响应就算成功
+    // Hook: Post-execute - ACE 学习、turn_complete 外部命令（携带实际的工具调用和结果）
+    if let Some(ref hook_manager) = sess.services.hook_manager {
+        turn_outcome.response = last_agent_message.clone();
+        turn_outcome.diff = turn_diff_tracker
+            .lock()
+            .await
+            .get_unified_diff()
+            .ok()
+            .flatten();
 
-            // 异步调用post_execute，不等待完成
-            hook_manager.call_post_execute(&user_query_text, response, success);
-            tracing::debug!("ACE post-execute hook called for learning");
-        }
+        // 异步调用post_execute，不等待完成
+        hook_manager.call_post_execute(turn_outcome);
+        tracing::debug!("Post-execute hooks called");
     }
 
     last_agent_message
@@ -2091,6 +2472,7 @@ async fn try_run_turn(
         model: turn_context.client.get_model(),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
+        turn_id: Some(turn_context.sub_id.clone()),
     });
 
     sess.persist_rollout_items(&[rollout_item]).await;
@@ -2647,8 +3029,10 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
-            #[cfg(feature = "ace")]
+            exec_policy: ExecPolicy::default(),
+            approval_rules: Mutex::new(ProjectApprovalRules::new(&config.codex_home, &config.cwd)),
             hook_manager: None,
+            extensions: SessionExtensions::default(),
             #[cfg(feature = "ace")]
             ace_plugin: None,
             #[cfg(feature = "ace")]
@@ -2729,8 +3113,10 @@ mod tests {
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
-            #[cfg(feature = "ace")]
+            exec_policy: ExecPolicy::default(),
+            approval_rules: Mutex::new(ProjectApprovalRules::new(&config.codex_home, &config.cwd)),
             hook_manager: None,
+            extensions: SessionExtensions::default(),
             #[cfg(feature = "ace")]
             ace_plugin: None,
             #[cfg(feature = "ace")]
@@ -72,6 +72,7 @@ async fn run_compact_task_inner(
         model: turn_context.client.get_model(),
         effort: turn_context.client.get_reasoning_effort(),
         summary: turn_context.client.get_reasoning_summary(),
+        turn_id: Some(turn_context.sub_id.clone()),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
@@ -46,6 +46,7 @@ pub(crate) async fn run_codex_conversation_interactive(
         auth_manager,
         initial_history.unwrap_or(InitialHistory::New),
         SessionSource::SubAgent(SubAgentSource::Review),
+        parent_session.services.extensions.clone(),
     )
     .await?;
     let codex = Arc::new(codex);
@@ -235,6 +236,8 @@ async fn handle_exec_approval(
         event.cwd,
         event.reason,
         event.risk,
+        event.policy_match,
+        event.project_approval_pattern,
     );
     let decision = await_approval_with_cancel(
         approval_fut,
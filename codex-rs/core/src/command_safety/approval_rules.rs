@@ -0,0 +1,477 @@
+//! Per-project allowlist of command patterns saved with
+//! `ReviewDecision::ApprovedForProject`.
+//!
+//! A project is the git root of the session cwd (or the cwd itself outside a
+//! repository). Each project gets one JSON file under
+//! `$CODEX_HOME/approvals/`; rules live outside the repository so a checkout
+//! cannot pre-approve its own commands.
+//!
+//! Sessions of the same project may add rules at the same time, so every
+//! change re-reads the file under an advisory lock and replaces it atomically.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use codex_protocol::protocol::CommandPattern;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use tempfile::NamedTempFile;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
+use crate::git_info::get_git_repo_root;
+
+/// Directory under `$CODEX_HOME` holding one rules file per project.
+pub const APPROVALS_DIR: &str = "approvals";
+
+/// Lock acquisition retries (mirrors the history file locking strategy).
+const LOCK_MAX_RETRIES: usize = 250;
+const LOCK_RETRY_SLEEP: Duration = Duration::from_millis(20);
+
+/// Programs whose subcommands start containers, change clusters or cloud
+/// resources, or reach other machines; their patterns are always exact.
+const EXACT_ONLY_PROGRAMS: &[&str] = &[
+    "aws",
+    "az",
+    "docker",
+    "doas",
+    "gcloud",
+    "helm",
+    "kubectl",
+    "podman",
+    "rsync",
+    "scp",
+    "ssh",
+    "sudo",
+    "systemctl",
+    "terraform",
+];
+
+/// Subcommands that delete, publish or otherwise change state; a pattern
+/// containing one of them is exact.
+const MUTATING_SUBCOMMANDS: &[&str] = &[
+    "apply",
+    "clean",
+    "delete",
+    "deploy",
+    "destroy",
+    "drop",
+    "kill",
+    "prune",
+    "publish",
+    "purge",
+    "push",
+    "remove",
+    "reset",
+    "rm",
+    "uninstall",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRule {
+    pub pattern: CommandPattern,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RulesFile {
+    project: PathBuf,
+    #[serde(default)]
+    rules: Vec<ApprovalRule>,
+}
+
+#[derive(Debug)]
+pub struct ProjectApprovalRules {
+    path: PathBuf,
+    file: RulesFile,
+}
+
+impl ProjectApprovalRules {
+    /// Empty rule set for the project containing `cwd`; nothing is read.
+    pub fn new(codex_home: &Path, cwd: &Path) -> Self {
+        let project = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        Self {
+            path: rules_path(codex_home, &project),
+            file: RulesFile {
+                project,
+                rules: Vec::new(),
+            },
+        }
+    }
+
+    /// Loads the rules saved for the project containing `cwd`.
+    pub fn load(codex_home: &Path, cwd: &Path) -> io::Result<Self> {
+        let mut rules = Self::new(codex_home, cwd);
+        rules.reload()?;
+        Ok(rules)
+    }
+
+    /// Loads the rules of every project, sorted by project path.
+    pub fn load_all(codex_home: &Path) -> io::Result<Vec<Self>> {
+        let entries = match std::fs::read_dir(codex_home.join(APPROVALS_DIR)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut all = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let file = read_rules_file(&path)?;
+            all.push(Self { path, file });
+        }
+        all.sort_by(|a, b| a.file.project.cmp(&b.file.project));
+        Ok(all)
+    }
+
+    pub fn project(&self) -> &Path {
+        &self.file.project
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn rules(&self) -> &[ApprovalRule] {
+        &self.file.rules
+    }
+
+    /// Whether a saved rule covers `command`. A plain `bash -lc` script is
+    /// covered when every command in it is.
+    pub fn matches(&self, command: &[String]) -> bool {
+        if self.file.rules.is_empty() {
+            return false;
+        }
+        let commands =
+            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        !commands.is_empty()
+            && commands.iter().all(|command| {
+                self.file
+                    .rules
+                    .iter()
+                    .any(|rule| rule.pattern.matches(command))
+            })
+    }
+
+    /// Saves `pattern`, keeping rules other sessions wrote since this set was
+    /// loaded. Returns `false` when the pattern was already saved.
+    pub fn add(&mut self, pattern: CommandPattern) -> io::Result<bool> {
+        let _lock = self.lock()?;
+        self.reload()?;
+        if self.file.rules.iter().any(|rule| rule.pattern == pattern) {
+            return Ok(false);
+        }
+        self.file.rules.push(ApprovalRule {
+            pattern,
+            created_at: Utc::now(),
+        });
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Removes the rule at `index` in `rules()`, keeping rules other
+    /// sessions wrote since this set was loaded. Returns `None` when there is
+    /// no such rule or it was already removed.
+    pub fn revoke(&mut self, index: usize) -> io::Result<Option<ApprovalRule>> {
+        let Some(pattern) = self.file.rules.get(index).map(|rule| rule.pattern.clone()) else {
+            return Ok(None);
+        };
+        let _lock = self.lock()?;
+        self.reload()?;
+        let Some(index) = self
+            .file
+            .rules
+            .iter()
+            .position(|rule| rule.pattern == pattern)
+        else {
+            return Ok(None);
+        };
+        let rule = self.file.rules.remove(index);
+        self.save()?;
+        Ok(Some(rule))
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        match read_rules_file(&self.path) {
+            Ok(file) => self.file.rules = file.rules,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => self.file.rules.clear(),
+            Err(err) => return Err(err),
+        }
+        Ok(())
+    }
+
+    /// Takes the advisory lock guarding the rules file; released when the
+    /// returned file is dropped.
+    fn lock(&self) -> io::Result<File> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let lock_file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(self.path.with_extension("json.lock"))?;
+
+        // Retry a few times to avoid indefinite blocking when contended.
+        for _ in 0..LOCK_MAX_RETRIES {
+            match lock_file.try_lock() {
+                Ok(()) => return Ok(lock_file),
+                Err(std::fs::TryLockError::WouldBlock) => std::thread::sleep(LOCK_RETRY_SLEEP),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            format!("could not acquire lock on {}", self.path.display()),
+        ))
+    }
+
+    /// Replaces the rules file; the caller holds the lock.
+    fn save(&self) -> io::Result<()> {
+        let parent = self.path.parent().unwrap_or_else(|| Path::new("."));
+        let json = serde_json::to_string_pretty(&self.file).map_err(io::Error::other)?;
+        let mut tmp = NamedTempFile::new_in(parent)?;
+        tmp.write_all(json.as_bytes())?;
+        tmp.persist(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Pattern offered for `ReviewDecision::ApprovedForProject`, or `None` when
+/// `command` is a script of several commands.
+///
+/// Leading subcommand words are kept and the remaining arguments become a
+/// wildcard, so `cargo test -p foo` is saved as `cargo test *`. Commands that
+/// would generalize to a bare program (`python3 script.py`), programs in
+/// [`EXACT_ONLY_PROGRAMS`], mutating subcommands such as `git push` and
+/// commands that might be dangerous are saved exactly.
+pub fn suggest_pattern(command: &[String]) -> Option<CommandPattern> {
+    let command = match parse_shell_lc_plain_commands(command) {
+        Some(mut commands) if commands.len() == 1 => commands.pop()?,
+        Some(_) => return None,
+        None => command.to_vec(),
+    };
+    let program = command.first()?;
+
+    let words = command
+        .iter()
+        .take_while(|arg| is_subcommand_word(arg))
+        .count();
+    let generalize = words >= 2
+        && words < command.len()
+        && !EXACT_ONLY_PROGRAMS.contains(&program.as_str())
+        && !command[1..words]
+            .iter()
+            .any(|word| MUTATING_SUBCOMMANDS.contains(&word.as_str()))
+        && !command_might_be_dangerous(&command);
+    Some(if generalize {
+        CommandPattern {
+            prefix: command[..words].to_vec(),
+            allow_extra_args: true,
+        }
+    } else {
+        CommandPattern {
+            prefix: command,
+            allow_extra_args: false,
+        }
+    })
+}
+
+fn is_subcommand_word(arg: &str) -> bool {
+    arg.starts_with(|c: char| c.is_ascii_lowercase())
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+fn read_rules_file(path: &Path) -> io::Result<RulesFile> {
+    let text = std::fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to parse {}: {err}", path.display()),
+        )
+    })
+}
+
+/// `<project dir name>-<hash of the project path>.json`
+fn rules_path(codex_home: &Path, project: &Path) -> PathBuf {
+    let name: String = project
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let digest = Sha256::digest(project.to_string_lossy().as_bytes());
+    let hex = format!("{digest:x}");
+    let hash = hex.get(..16).unwrap_or(&hex);
+    codex_home
+        .join(APPROVALS_DIR)
+        .join(format!("{name}-{hash}.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn cmd(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    fn pattern(prefix: &[&str], allow_extra_args: bool) -> CommandPattern {
+        CommandPattern {
+            prefix: cmd(prefix),
+            allow_extra_args,
+        }
+    }
+
+    #[test]
+    fn suggest_pattern_generalizes_subcommands_only() {
+        assert_eq!(
+            suggest_pattern(&cmd(&["cargo", "test", "-p", "foo"])),
+            Some(pattern(&["cargo", "test"], true))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["bash", "-lc", "npm run build -- --watch"])),
+            Some(pattern(&["npm", "run", "build"], true))
+        );
+        // Nothing to generalize, or generalizing would be too broad.
+        assert_eq!(
+            suggest_pattern(&cmd(&["npm", "run", "build"])),
+            Some(pattern(&["npm", "run", "build"], false))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["python3", "script.py"])),
+            Some(pattern(&["python3", "script.py"], false))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["git", "reset", "--hard"])),
+            Some(pattern(&["git", "reset", "--hard"], false))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["bash", "-lc", "cargo build && cargo test"])),
+            None
+        );
+    }
+
+    #[test]
+    fn suggest_pattern_keeps_mutating_commands_exact() {
+        assert_eq!(
+            suggest_pattern(&cmd(&["docker", "run", "--rm", "alpine", "true"])),
+            Some(pattern(&["docker", "run", "--rm", "alpine", "true"], false))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["kubectl", "delete", "pod", "foo"])),
+            Some(pattern(&["kubectl", "delete", "pod", "foo"], false))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["git", "push", "origin", "main"])),
+            Some(pattern(&["git", "push", "origin", "main"], false))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["npm", "uninstall", "left-pad"])),
+            Some(pattern(&["npm", "uninstall", "left-pad"], false))
+        );
+        assert_eq!(
+            suggest_pattern(&cmd(&["sudo", "apt", "install", "jq"])),
+            Some(pattern(&["sudo", "apt", "install", "jq"], false))
+        );
+    }
+
+    #[test]
+    fn matches_requires_every_command_of_a_script() {
+        let codex_home = TempDir::new().unwrap();
+        let cwd = TempDir::new().unwrap();
+        let mut rules = ProjectApprovalRules::new(codex_home.path(), cwd.path());
+        assert!(rules.add(pattern(&["cargo", "test"], true)).unwrap());
+
+        assert!(rules.matches(&cmd(&["cargo", "test", "-p", "bar"])));
+        assert!(rules.matches(&cmd(&["bash", "-lc", "cargo test && cargo test -p foo"])));
+        assert!(!rules.matches(&cmd(&["bash", "-lc", "cargo test && cargo publish"])));
+        assert!(!rules.matches(&cmd(&["cargo", "build"])));
+    }
+
+    #[test]
+    fn rules_persist_per_project_and_can_be_revoked() {
+        let codex_home = TempDir::new().unwrap();
+        let project_a = TempDir::new().unwrap();
+        let project_b = TempDir::new().unwrap();
+
+        let mut a = ProjectApprovalRules::load(codex_home.path(), project_a.path()).unwrap();
+        assert!(a.add(pattern(&["cargo", "test"], true)).unwrap());
+        assert!(!a.add(pattern(&["cargo", "test"], true)).unwrap());
+        let mut b = ProjectApprovalRules::load(codex_home.path(), project_b.path()).unwrap();
+        assert!(b.add(pattern(&["make", "check"], false)).unwrap());
+
+        let reloaded = ProjectApprovalRules::load(codex_home.path(), project_a.path()).unwrap();
+        assert_eq!(reloaded.rules(), a.rules());
+        assert!(!reloaded.matches(&cmd(&["make", "check"])));
+        assert_eq!(
+            ProjectApprovalRules::load_all(codex_home.path())
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let revoked = a.revoke(0).unwrap().unwrap();
+        assert_eq!(revoked.pattern, pattern(&["cargo", "test"], true));
+        assert_eq!(a.revoke(0).unwrap(), None);
+        let reloaded = ProjectApprovalRules::load(codex_home.path(), project_a.path()).unwrap();
+        assert!(reloaded.rules().is_empty());
+    }
+
+    #[test]
+    fn concurrent_sessions_keep_each_others_rules() {
+        let codex_home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let (codex_home, project) = (codex_home.path(), project.path());
+                scope.spawn(move || {
+                    let mut rules = ProjectApprovalRules::new(codex_home, project);
+                    let program = format!("tool{i}");
+                    assert!(
+                        rules
+                            .add(pattern(&[program.as_str(), "run"], true))
+                            .unwrap()
+                    );
+                });
+            }
+        });
+        let rules = ProjectApprovalRules::load(codex_home.path(), project.path()).unwrap();
+        assert_eq!(rules.rules().len(), 8);
+
+        // A stale view revokes its own rule without dropping newer ones.
+        let mut stale = ProjectApprovalRules::load(codex_home.path(), project.path()).unwrap();
+        let mut other = ProjectApprovalRules::new(codex_home.path(), project.path());
+        assert!(other.add(pattern(&["make", "check"], false)).unwrap());
+        let revoked = stale.revoke(0).unwrap().unwrap();
+        assert_eq!(revoked.pattern, rules.rules()[0].pattern);
+        let reloaded = ProjectApprovalRules::load(codex_home.path(), project.path()).unwrap();
+        assert_eq!(reloaded.rules().len(), 8);
+        assert!(reloaded.matches(&cmd(&["make", "check"])));
+        assert!(!reloaded.rules().contains(&revoked));
+    }
+}
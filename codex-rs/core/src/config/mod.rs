@@ -1,6 +1,7 @@
 use crate::auth::AuthCredentialsStoreMode;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
+use crate::config::types::HooksConfig;
 use crate::config::types::McpServerConfig;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
@@ -8,6 +9,7 @@ use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
 use crate::config::types::ReasoningSummaryFormat;
+use crate::config::types::SandboxContainer;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
@@ -32,6 +34,7 @@ use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::protocol::default_deny_read;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
 use codex_protocol::config_types::ForcedLoginMethod;
@@ -103,6 +106,15 @@ pub struct Config {
 
     pub sandbox_policy: SandboxPolicy,
 
+    /// Run sandboxed commands inside a container on Linux. `None` unless the
+    /// resolved `sandbox_container` table is enabled.
+    pub sandbox_container: Option<SandboxContainer>,
+
+    /// Watch the `open` calls of sandboxed commands on Linux to name the
+    /// `sandbox_deny_read` paths they were refused. Off by default: every
+    /// `open` then round-trips through a monitor process.
+    pub sandbox_report_denied_reads: bool,
+
     /// True if the user passed in an override or set a value in config.toml
     /// for either of approval_policy or sandbox_mode.
     pub did_user_set_custom_approval_policy_or_sandbox_mode: bool,
@@ -156,6 +168,10 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// External commands to run on agent lifecycle events (`[hooks]` in
+    /// config.toml). See [`HooksConfig`].
+    pub hooks: HooksConfig,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -539,10 +555,25 @@ pub struct ConfigToml {
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Paths that sandboxed commands and the built-in file tools cannot read.
+    /// Replaces the default list of credential locations when set.
+    pub sandbox_deny_read: Option<Vec<String>>,
+
+    /// Container sandbox used on Linux unless the active project sets its own.
+    pub sandbox_container: Option<SandboxContainer>,
+
+    /// Report which `sandbox_deny_read` paths a failed command tried to open
+    /// (Linux only). Defaults to `false`.
+    pub sandbox_report_denied_reads: Option<bool>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// External commands to run on agent lifecycle events.
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -694,6 +725,10 @@ impl From<ConfigToml> for UserSavedConfig {
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ProjectConfig {
     pub trust_level: Option<String>,
+
+    /// Replaces the top-level `sandbox_container` table for this project.
+    #[serde(default)]
+    pub sandbox_container: Option<SandboxContainer>,
 }
 
 impl ProjectConfig {
@@ -758,11 +793,14 @@ impl ConfigToml {
                 Some(SandboxWorkspaceWrite {
                     writable_roots,
                     network_access,
+                    network_allowlist,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
+                    network_allowlist: network_allowlist.clone(),
+                    deny_read: default_deny_read(),
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
                 },
@@ -779,6 +817,9 @@ impl ConfigToml {
             sandbox_policy = SandboxPolicy::new_read_only_policy();
             forced_auto_mode_downgraded_on_windows = true;
         }
+        if let Some(deny_read) = &self.sandbox_deny_read {
+            sandbox_policy.set_deny_read(deny_read.clone());
+        }
         SandboxPolicyResolution {
             policy: sandbox_policy,
             forced_auto_mode_downgraded_on_windows,
@@ -945,7 +986,16 @@ impl Config {
             .collect();
         let active_project = cfg
             .get_active_project(&resolved_cwd)
-            .unwrap_or(ProjectConfig { trust_level: None });
+            .unwrap_or(ProjectConfig {
+                trust_level: None,
+                sandbox_container: None,
+            });
+        let sandbox_container = active_project
+            .sandbox_container
+            .as_ref()
+            .or(cfg.sandbox_container.as_ref())
+            .filter(|container| container.enabled)
+            .cloned();
 
         let SandboxPolicyResolution {
             policy: mut sandbox_policy,
@@ -1103,10 +1153,13 @@ impl Config {
             cwd: resolved_cwd,
             approval_policy,
             sandbox_policy,
+            sandbox_container,
+            sandbox_report_denied_reads: cfg.sandbox_report_denied_reads.unwrap_or(false),
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
             notify: cfg.notify,
+            hooks: cfg.hooks.unwrap_or_default(),
             user_instructions,
             base_instructions,
             developer_instructions,
@@ -1291,6 +1344,7 @@ mod tests {
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
     use crate::config::types::HistoryPersistence;
+    use crate::config::types::HookCommandConfig;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
     use crate::features::Feature;
@@ -1346,6 +1400,44 @@ persistence = "none"
         assert_eq!(tui.notifications, Notifications::Enabled(false));
     }
 
+    #[test]
+    fn hooks_config_parses_commands_per_event() {
+        let cfg = r#"
+[[hooks.before_tool_call]]
+command = ["./audit.sh"]
+tools = ["shell"]
+
+[[hooks.turn_complete]]
+command = ["logger", "codex"]
+timeout_ms = 500
+sandbox_mode = "read-only"
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("hooks config should parse");
+        let hooks = parsed.hooks.expect("config should include hooks section");
+
+        assert_eq!(
+            hooks.before_tool_call,
+            vec![HookCommandConfig {
+                command: vec!["./audit.sh".to_string()],
+                tools: vec!["shell".to_string()],
+                timeout_ms: None,
+                sandbox_mode: None,
+            }]
+        );
+        assert_eq!(
+            hooks.turn_complete,
+            vec![HookCommandConfig {
+                command: vec!["logger".to_string(), "codex".to_string()],
+                tools: Vec::new(),
+                timeout_ms: Some(500),
+                sandbox_mode: Some(SandboxMode::ReadOnly),
+            }]
+        );
+        assert!(hooks.session_start.is_empty());
+        assert!(!hooks.is_empty());
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -1388,7 +1480,7 @@ network_access = true  # This should be ignored.
         assert_eq!(
             resolution,
             SandboxPolicyResolution {
-                policy: SandboxPolicy::ReadOnly,
+                policy: SandboxPolicy::new_read_only_policy(),
                 forced_auto_mode_downgraded_on_windows: false,
             }
         );
@@ -1416,7 +1508,7 @@ exclude_slash_tmp = true
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1427,6 +1519,8 @@ exclude_slash_tmp = true
                     policy: SandboxPolicy::WorkspaceWrite {
                         writable_roots: vec![PathBuf::from("/my/workspace")],
                         network_access: false,
+                        network_allowlist: Vec::new(),
+                        deny_read: default_deny_read(),
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
                     },
@@ -1461,7 +1555,7 @@ trust_level = "trusted"
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1472,6 +1566,8 @@ trust_level = "trusted"
                     policy: SandboxPolicy::WorkspaceWrite {
                         writable_roots: vec![PathBuf::from("/my/workspace")],
                         network_access: false,
+                        network_allowlist: Vec::new(),
+                        deny_read: default_deny_read(),
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
                     },
@@ -1481,6 +1577,95 @@ trust_level = "trusted"
         }
     }
 
+    #[test]
+    fn sandbox_deny_read_replaces_default_list() {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "read-only"
+sandbox_deny_read = ["~/.ssh", "secrets/*.pem"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let resolution = cfg.derive_sandbox_policy(None, None, &PathBuf::from("/tmp/test"));
+        assert_eq!(
+            resolution.policy,
+            SandboxPolicy::ReadOnly {
+                deny_read: vec!["~/.ssh".to_string(), "secrets/*.pem".to_string()],
+            }
+        );
+
+        let cfg = toml::from_str::<ConfigToml>("sandbox_deny_read = []")
+            .expect("TOML deserialization should succeed");
+        let resolution = cfg.derive_sandbox_policy(None, None, &PathBuf::from("/tmp/test"));
+        assert!(resolution.policy.has_full_disk_read_access());
+    }
+
+    #[test]
+    fn project_sandbox_container_replaces_top_level_table() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let project = TempDir::new()?;
+        let project_dir = canonicalize(project.path())?;
+        let cfg = toml::from_str::<ConfigToml>(&format!(
+            r#"
+[sandbox_container]
+enabled = true
+binds = ["/srv/cache"]
+
+[projects."{}".sandbox_container]
+enabled = true
+rootfs = "/var/lib/images/debian"
+"#,
+            project_dir.display()
+        ))
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg.clone(),
+            ConfigOverrides {
+                cwd: Some(project_dir.clone()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.sandbox_container,
+            Some(SandboxContainer {
+                enabled: true,
+                rootfs: Some(PathBuf::from("/var/lib/images/debian")),
+                binds: Vec::new(),
+                required: false,
+            })
+        );
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                cwd: Some(codex_home.path().to_path_buf()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.sandbox_container,
+            Some(SandboxContainer {
+                enabled: true,
+                rootfs: None,
+                binds: vec![PathBuf::from("/srv/cache")],
+                required: false,
+            })
+        );
+
+        let cfg = toml::from_str::<ConfigToml>("[sandbox_container]\nbinds = [\"/srv/cache\"]")
+            .expect("TOML deserialization should succeed");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(config.sandbox_container, None);
+        Ok(())
+    }
+
     #[test]
     fn add_dir_override_extends_workspace_writable_roots() -> std::io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -1509,7 +1694,7 @@ trust_level = "trusted"
                 "expected workspace-write request to be downgraded on Windows"
             );
             match config.sandbox_policy {
-                SandboxPolicy::ReadOnly => {}
+                SandboxPolicy::ReadOnly { .. } => {}
                 other => panic!("expected read-only policy on Windows, got {other:?}"),
             }
         } else {
@@ -1682,7 +1867,10 @@ trust_level = "trusted"
         )?;
 
         if cfg!(target_os = "windows") {
-            assert!(matches!(config.sandbox_policy, SandboxPolicy::ReadOnly));
+            assert!(matches!(
+                config.sandbox_policy,
+                SandboxPolicy::ReadOnly { .. }
+            ));
             assert!(config.forced_auto_mode_downgraded_on_windows);
         } else {
             assert!(matches!(
@@ -2878,11 +3066,14 @@ model_verbosity = "high"
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                sandbox_container: None,
+                sandbox_report_denied_reads: false,
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: HooksConfig::default(),
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: HashMap::new(),
@@ -2913,7 +3104,10 @@ model_verbosity = "high"
                 use_experimental_use_rmcp_client: false,
                 features: Features::with_defaults(),
                 active_profile: Some("o3".to_string()),
-                active_project: ProjectConfig { trust_level: None },
+                active_project: ProjectConfig {
+                    trust_level: None,
+                    sandbox_container: None,
+                },
                 windows_wsl_setup_acknowledged: false,
                 notices: Default::default(),
                 disable_paste_burst: false,
@@ -2950,11 +3144,14 @@ model_verbosity = "high"
             model_provider: fixture.openai_chat_completions_provider.clone(),
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            sandbox_container: None,
+            sandbox_report_denied_reads: false,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -2985,7 +3182,10 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("gpt3".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig {
+                trust_level: None,
+                sandbox_container: None,
+            },
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
@@ -3037,11 +3237,14 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            sandbox_container: None,
+            sandbox_report_denied_reads: false,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3072,7 +3275,10 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("zdr".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig {
+                trust_level: None,
+                sandbox_container: None,
+            },
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
@@ -3110,11 +3316,14 @@ model_verbosity = "high"
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            sandbox_container: None,
+            sandbox_report_denied_reads: false,
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: HooksConfig::default(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3145,7 +3354,10 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("gpt5".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig {
+                trust_level: None,
+                sandbox_container: None,
+            },
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
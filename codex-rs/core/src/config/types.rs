@@ -3,9 +3,11 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use codex_protocol::config_types::SandboxMode;
 use serde::Deserializer;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::Duration;
 use wildmatch::WildMatchPattern;
 
@@ -365,12 +367,78 @@ impl Notice {
     pub(crate) const TABLE_KEY: &'static str = "notice";
 }
 
+/// External commands run on agent lifecycle events, configured under `[hooks]`.
+///
+/// Each command receives a JSON payload describing the event on stdin.
+///
+/// ```toml
+/// [[hooks.before_tool_call]]
+/// command = ["./scripts/audit.sh"]
+/// tools = ["shell"]
+///
+/// [[hooks.after_tool_call]]
+/// command = ["cargo", "fmt"]
+/// tools = ["apply_patch"]
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct HooksConfig {
+    /// Run after a session is created.
+    #[serde(default)]
+    pub session_start: Vec<HookCommandConfig>,
+
+    /// Run before a tool call executes. These hooks can veto the call.
+    #[serde(default)]
+    pub before_tool_call: Vec<HookCommandConfig>,
+
+    /// Run after a tool call completes.
+    #[serde(default)]
+    pub after_tool_call: Vec<HookCommandConfig>,
+
+    /// Run when the agent finishes processing a user turn.
+    #[serde(default)]
+    pub turn_complete: Vec<HookCommandConfig>,
+
+    /// Run when the session shuts down.
+    #[serde(default)]
+    pub session_end: Vec<HookCommandConfig>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.session_start.is_empty()
+            && self.before_tool_call.is_empty()
+            && self.after_tool_call.is_empty()
+            && self.turn_complete.is_empty()
+            && self.session_end.is_empty()
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HookCommandConfig {
+    /// Program and arguments to execute.
+    pub command: Vec<String>,
+
+    /// Tool names this hook applies to. Only used by tool call events; when
+    /// empty the hook runs for every tool.
+    #[serde(default)]
+    pub tools: Vec<String>,
+
+    /// Timeout in milliseconds. Defaults to the exec timeout (10 seconds).
+    pub timeout_ms: Option<u64>,
+
+    /// Sandbox to run the command in. Defaults to the session's sandbox policy.
+    pub sandbox_mode: Option<SandboxMode>,
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SandboxWorkspaceWrite {
     #[serde(default)]
     pub writable_roots: Vec<PathBuf>,
     #[serde(default)]
     pub network_access: bool,
+    /// `host` or `host:port` entries reachable while `network_access` is off.
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
     #[serde(default)]
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
@@ -382,12 +450,41 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
         Self {
             writable_roots: sandbox_workspace_write.writable_roots,
             network_access: Some(sandbox_workspace_write.network_access),
+            network_allowlist: sandbox_workspace_write.network_allowlist,
             exclude_tmpdir_env_var: Some(sandbox_workspace_write.exclude_tmpdir_env_var),
             exclude_slash_tmp: Some(sandbox_workspace_write.exclude_slash_tmp),
         }
     }
 }
 
+/// Runs sandboxed commands inside a private mount namespace on Linux: the
+/// workspace and writable roots are bind-mounted at their own paths, while
+/// `$HOME` and `/tmp` are fresh tmpfs mounts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct SandboxContainer {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory used as `/` inside the container. Defaults to the host's
+    /// `/usr`, `/bin`, `/lib*`, `/etc` and `/opt`.
+    #[serde(default)]
+    pub rootfs: Option<PathBuf>,
+    /// Extra host paths mounted at the same path inside the container.
+    #[serde(default)]
+    pub binds: Vec<PathBuf>,
+    /// Fail commands instead of falling back to the Landlock sandbox when
+    /// namespaces are unavailable.
+    #[serde(default)]
+    pub required: bool,
+}
+
+impl FromStr for SandboxContainer {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum ShellEnvironmentPolicyInherit {
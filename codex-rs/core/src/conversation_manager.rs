@@ -3,6 +3,7 @@ use crate::CodexAuth;
 use crate::codex::Codex;
 use crate::codex::CodexSpawnOk;
 use crate::codex::INITIAL_SUBMIT_ID;
+use crate::codex::SessionExtensions;
 use crate::codex_conversation::CodexConversation;
 use crate::config::Config;
 use crate::error::CodexErr;
@@ -36,6 +37,7 @@ pub struct ConversationManager {
     conversations: Arc<RwLock<HashMap<ConversationId, Arc<CodexConversation>>>>,
     auth_manager: Arc<AuthManager>,
     session_source: SessionSource,
+    extensions: SessionExtensions,
 }
 
 impl ConversationManager {
@@ -44,9 +46,18 @@ impl ConversationManager {
             conversations: Arc::new(RwLock::new(HashMap::new())),
             auth_manager,
             session_source,
+            extensions: SessionExtensions::default(),
         }
     }
 
+    /// Hand `embedder` to the ACE plugin of every conversation this manager
+    /// spawns, for semantic retrieval with `provider = "ollama"`.
+    #[cfg(feature = "ace")]
+    pub fn with_ace_embedder(mut self, embedder: Option<Arc<dyn crate::ace::Embedder>>) -> Self {
+        self.extensions.ace_embedder = embedder;
+        self
+    }
+
     /// Construct with a dummy AuthManager containing the provided CodexAuth.
     /// Used for integration tests: should not be used by ordinary business logic.
     pub fn with_auth(auth: CodexAuth) -> Self {
@@ -74,6 +85,7 @@ impl ConversationManager {
             auth_manager,
             InitialHistory::New,
             self.session_source.clone(),
+            self.extensions.clone(),
         )
         .await?;
         self.finalize_spawn(codex, conversation_id).await
@@ -150,6 +162,7 @@ impl ConversationManager {
             auth_manager,
             initial_history,
             self.session_source.clone(),
+            self.extensions.clone(),
         )
         .await?;
         self.finalize_spawn(codex, conversation_id).await
@@ -185,7 +198,14 @@ impl ConversationManager {
         let CodexSpawnOk {
             codex,
             conversation_id,
-        } = Codex::spawn(config, auth_manager, history, self.session_source.clone()).await?;
+        } = Codex::spawn(
+            config,
+            auth_manager,
+            history,
+            self.session_source.clone(),
+            self.extensions.clone(),
+        )
+        .await?;
 
         self.finalize_spawn(codex, conversation_id).await
     }
@@ -13,6 +13,7 @@ use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
 use serde_json;
 use std::io;
+use std::path::PathBuf;
 use std::time::Duration;
 use thiserror::Error;
 use tokio::task::JoinError;
@@ -31,6 +32,21 @@ pub enum SandboxErr {
     )]
     Denied { output: Box<ExecToolCallOutput> },
 
+    /// The Linux sandbox proxy refused connections to hosts outside the
+    /// configured network allowlist.
+    #[error("sandbox blocked network access to {}", .hosts.join(", "))]
+    NetworkDenied {
+        hosts: Vec<String>,
+        output: Box<ExecToolCallOutput>,
+    },
+
+    /// The command failed to read paths on the sandbox `deny_read` list.
+    #[error("sandbox blocked reading {}", display_paths(.paths))]
+    ReadDenied {
+        paths: Vec<PathBuf>,
+        output: Box<ExecToolCallOutput>,
+    },
+
     /// Error from linux seccomp filter setup
     #[cfg(target_os = "linux")]
     #[error("seccomp setup error")]
@@ -375,6 +391,14 @@ impl CodexErr {
     }
 }
 
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| format!("`{}`", path.display()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 pub fn get_error_message_ui(e: &CodexErr) -> String {
     let message = match e {
         CodexErr::Sandbox(SandboxErr::Denied { output }) => {
@@ -395,6 +419,14 @@ pub fn get_error_message_ui(e: &CodexErr) -> String {
                 }
             }
         }
+        CodexErr::Sandbox(SandboxErr::NetworkDenied { hosts, .. }) => format!(
+            "network access to {} is not in the sandbox allowlist",
+            hosts.join(", ")
+        ),
+        CodexErr::Sandbox(SandboxErr::ReadDenied { paths, .. }) => format!(
+            "reading {} is blocked by the sandbox deny_read list",
+            display_paths(paths)
+        ),
         // Timeouts are not sandbox errors from a UX perspective; present them plainly
         CodexErr::Sandbox(SandboxErr::Timeout { output }) => {
             format!(
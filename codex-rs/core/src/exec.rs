@@ -18,6 +18,10 @@ use tokio::process::Child;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
+#[cfg(target_os = "linux")]
+use crate::landlock::DenialReport;
+use crate::landlock::network_denied_hosts;
+use crate::landlock::read_denied_paths;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
@@ -73,10 +77,24 @@ pub enum SandboxType {
     /// Only available on Linux.
     LinuxSeccomp,
 
+    /// Landlock and seccomp inside a private mount namespace. Only available
+    /// on Linux.
+    LinuxContainer,
+
     /// Only available on Windows.
     WindowsRestrictedToken,
 }
 
+impl SandboxType {
+    /// Whether commands run under the `codex-linux-sandbox` helper.
+    pub fn is_linux(self) -> bool {
+        matches!(
+            self,
+            SandboxType::LinuxSeccomp | SandboxType::LinuxContainer
+        )
+    }
+}
+
 #[derive(Clone)]
 pub struct StdoutStream {
     pub sub_id: String,
@@ -127,6 +145,8 @@ pub async fn process_exec_tool_call(
             sandbox_type,
             sandbox_cwd,
             codex_linux_sandbox_exe.as_ref(),
+            None,
+            false,
         )
         .map_err(CodexErr::from)?;
 
@@ -184,7 +204,7 @@ async fn exec_windows_sandbox(
 
     let policy_str = match sandbox_policy {
         SandboxPolicy::DangerFullAccess => "workspace-write",
-        SandboxPolicy::ReadOnly => "read-only",
+        SandboxPolicy::ReadOnly { .. } => "read-only",
         SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
     };
 
@@ -241,6 +261,7 @@ async fn exec_windows_sandbox(
         stderr,
         aggregated_output,
         timed_out: capture.timed_out,
+        denial_report: String::new(),
     })
 }
 
@@ -288,6 +309,24 @@ fn finalize_exec_result(
                 }));
             }
 
+            if sandbox_type.is_linux() && exec_output.exit_code != 0 {
+                let hosts = network_denied_hosts(&raw_output.denial_report);
+                if !hosts.is_empty() {
+                    return Err(CodexErr::Sandbox(SandboxErr::NetworkDenied {
+                        hosts,
+                        output: Box::new(exec_output),
+                    }));
+                }
+
+                let paths = read_denied_paths(&raw_output.denial_report);
+                if !paths.is_empty() {
+                    return Err(CodexErr::Sandbox(SandboxErr::ReadDenied {
+                        paths,
+                        output: Box::new(exec_output),
+                    }));
+                }
+            }
+
             if is_likely_sandbox_denied(sandbox_type, &exec_output) {
                 return Err(CodexErr::Sandbox(SandboxErr::Denied {
                     output: Box::new(exec_output),
@@ -370,9 +409,7 @@ pub(crate) fn is_likely_sandbox_denied(
     #[cfg(unix)]
     {
         const SIGSYS_CODE: i32 = libc::SIGSYS;
-        if sandbox_type == SandboxType::LinuxSeccomp
-            && exec_output.exit_code == EXIT_CODE_SIGNAL_BASE + SIGSYS_CODE
-        {
+        if sandbox_type.is_linux() && exec_output.exit_code == EXIT_CODE_SIGNAL_BASE + SIGSYS_CODE {
             return true;
         }
     }
@@ -393,6 +430,8 @@ struct RawExecToolCallOutput {
     pub stderr: StreamOutput<Vec<u8>>,
     pub aggregated_output: StreamOutput<Vec<u8>>,
     pub timed_out: bool,
+    /// What `codex-linux-sandbox` reported refusing, one line per denial.
+    pub denial_report: String,
 }
 
 impl StreamOutput<String> {
@@ -454,18 +493,49 @@ async fn exec(
             "command args are empty",
         ))
     })?;
+    // Denials are read from a pipe only the helper can write to, never from
+    // the output of the command.
+    #[cfg(target_os = "linux")]
+    let mut denial_report = sandbox.is_linux().then(DenialReport::new).transpose()?;
+    #[cfg(target_os = "linux")]
+    let (args, inherited_fd) = match &denial_report {
+        Some(report) => (
+            report
+                .helper_args()
+                .into_iter()
+                .chain(args.iter().cloned())
+                .collect(),
+            report.writer_fd(),
+        ),
+        None => (args.to_vec(), None),
+    };
+    #[cfg(not(target_os = "linux"))]
+    let (args, inherited_fd) = (args.to_vec(), None);
+
     let arg0_ref = arg0.as_deref();
     let child = spawn_child_async(
         PathBuf::from(program),
-        args.into(),
+        args,
         arg0_ref,
         cwd,
         sandbox_policy,
         StdioPolicy::RedirectForShellTool,
         env,
+        inherited_fd,
     )
     .await?;
-    consume_truncated_output(child, timeout, stdout_stream).await
+    #[cfg(target_os = "linux")]
+    if let Some(report) = denial_report.as_mut() {
+        report.close_writer();
+    }
+
+    #[allow(unused_mut)]
+    let mut raw_output = consume_truncated_output(child, timeout, stdout_stream).await?;
+    #[cfg(target_os = "linux")]
+    if let Some(report) = denial_report {
+        raw_output.denial_report = report.read_reported();
+    }
+    Ok(raw_output)
 }
 
 /// Consumes the output of a child process, truncating it so it is suitable for
@@ -546,6 +616,7 @@ async fn consume_truncated_output(
         stderr,
         aggregated_output,
         timed_out,
+        denial_report: String::new(),
     })
 }
 
@@ -0,0 +1,542 @@
+//! Layered `codex-execpolicy` rules consulted before asking to run a command.
+//!
+//! Policies are checked from most to least specific: repo-local files
+//! (`<repo root>/.codeace/policy/*.policy`), user files
+//! (`$CODEX_HOME/policy/*.policy`) and finally the built-in default policy. A
+//! `forbidden` rule in any layer wins; otherwise the first layer that matches
+//! the command decides. Commands no layer matches fall back to the
+//! hard-coded `command_safety` heuristics.
+//!
+//! Repo-local files ship with the checkout, so unless the project is trusted
+//! they only contribute `forbidden` rules; otherwise a cloned repository could
+//! auto-approve its own commands.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::Policy;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::ValidExec;
+use codex_execpolicy::get_default_policy;
+use codex_protocol::protocol::ExecPolicyDecision;
+use codex_protocol::protocol::ExecPolicyMatch;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::git_info::get_git_repo_root;
+use crate::protocol::SandboxPolicy;
+use crate::safety::is_path_writable;
+
+/// Directory under `$CODEX_HOME` holding user policy files.
+pub(crate) const USER_POLICY_DIR: &str = "policy";
+
+/// Directory under the repository root holding repo-local policy files.
+pub(crate) const REPO_POLICY_DIR: &str = ".codeace/policy";
+
+const POLICY_FILE_EXTENSION: &str = "policy";
+
+const DEFAULT_POLICY_SOURCE: &str = "#default";
+
+struct PolicyLayer {
+    source: String,
+    policy: Policy,
+    /// Only the `forbidden` rules of this layer apply.
+    forbid_only: bool,
+}
+
+/// Result of [`ExecPolicy::evaluate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PolicyEvaluation {
+    pub(crate) matched: ExecPolicyMatch,
+    /// Some matched command takes arguments that may be files it writes.
+    pub(crate) writes_files: bool,
+    /// Approving a `RequireApproval` match runs the command outside the
+    /// sandbox, because it writes outside the writable roots.
+    pub(crate) escalate_on_approval: bool,
+}
+
+impl PolicyEvaluation {
+    /// Orders matches by how much approval they need.
+    fn strength(&self) -> u8 {
+        match self.matched.decision {
+            ExecPolicyDecision::AutoApprove => 0,
+            ExecPolicyDecision::RequireApproval if !self.escalate_on_approval => 1,
+            ExecPolicyDecision::RequireApproval => 2,
+            ExecPolicyDecision::Forbidden => 3,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ExecPolicy {
+    layers: Vec<PolicyLayer>,
+}
+
+impl ExecPolicy {
+    /// Loads the repo-local, user and default layers for a session rooted at
+    /// `cwd`. Repo-local layers can only forbid commands unless
+    /// `repo_trusted`. Files that fail to read or parse are skipped; one
+    /// message per failure is returned so the caller can surface it.
+    pub(crate) fn load(codex_home: &Path, cwd: &Path, repo_trusted: bool) -> (Self, Vec<String>) {
+        let mut layers = Vec::new();
+        let mut errors = Vec::new();
+
+        let repo_root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        for (dir, forbid_only) in [
+            (repo_root.join(REPO_POLICY_DIR), !repo_trusted),
+            (codex_home.join(USER_POLICY_DIR), false),
+        ] {
+            for path in policy_files(&dir) {
+                match parse_policy_file(&path, forbid_only) {
+                    Ok(layer) => layers.push(layer),
+                    Err(err) => errors.push(err),
+                }
+            }
+        }
+
+        match get_default_policy() {
+            Ok(policy) => layers.push(PolicyLayer {
+                source: DEFAULT_POLICY_SOURCE.to_string(),
+                policy,
+                forbid_only: false,
+            }),
+            Err(err) => errors.push(format!("failed to parse the default exec policy: {err}")),
+        }
+
+        (Self { layers }, errors)
+    }
+
+    #[cfg(test)]
+    fn from_sources(sources: &[(&str, &str)]) -> Self {
+        let layers = sources
+            .iter()
+            .map(|(source, text)| PolicyLayer {
+                source: source.to_string(),
+                policy: PolicyParser::new(source, text)
+                    .parse()
+                    .unwrap_or_else(|err| panic!("invalid test policy {source}: {err}")),
+                forbid_only: false,
+            })
+            .collect();
+        Self { layers }
+    }
+
+    /// Evaluates `command` (including each command of a plain `bash -lc`
+    /// script). Returns `None` when the policy has no opinion and the caller
+    /// should fall back to its usual approval rules.
+    pub(crate) fn evaluate(
+        &self,
+        command: &[String],
+        cwd: &Path,
+        sandbox_policy: &SandboxPolicy,
+    ) -> Option<PolicyEvaluation> {
+        if self.layers.is_empty() {
+            return None;
+        }
+
+        let commands =
+            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        let mut strongest: Option<PolicyEvaluation> = None;
+        let mut writes_files = false;
+        let mut all_matched = !commands.is_empty();
+        for command in &commands {
+            let Some(evaluation) = self.evaluate_single(command, cwd, sandbox_policy) else {
+                all_matched = false;
+                continue;
+            };
+            if evaluation.matched.decision == ExecPolicyDecision::Forbidden {
+                return Some(evaluation);
+            }
+            writes_files |= evaluation.writes_files;
+            // The prompt shows the strongest match, so a command that would
+            // run outside the sandbox is the one explained.
+            if strongest
+                .as_ref()
+                .is_none_or(|s| evaluation.strength() > s.strength())
+            {
+                strongest = Some(evaluation);
+            }
+        }
+
+        // A script is only auto-approved when every command in it is.
+        match strongest {
+            Some(evaluation)
+                if all_matched
+                    || evaluation.matched.decision == ExecPolicyDecision::RequireApproval =>
+            {
+                Some(PolicyEvaluation {
+                    writes_files,
+                    ..evaluation
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn evaluate_single(
+        &self,
+        command: &[String],
+        cwd: &Path,
+        sandbox_policy: &SandboxPolicy,
+    ) -> Option<PolicyEvaluation> {
+        let (program, args) = command.split_first()?;
+        let exec_call = ExecCall {
+            program: program.clone(),
+            args: args.to_vec(),
+        };
+
+        let mut first_match: Option<(&PolicyLayer, ValidExec)> = None;
+        for layer in &self.layers {
+            match layer.policy.check(&exec_call) {
+                Ok(MatchedExec::Forbidden { reason, .. }) => {
+                    return Some(PolicyEvaluation {
+                        matched: ExecPolicyMatch {
+                            source: layer.source.clone(),
+                            program: program.clone(),
+                            decision: ExecPolicyDecision::Forbidden,
+                            reason: Some(reason),
+                        },
+                        writes_files: false,
+                        escalate_on_approval: false,
+                    });
+                }
+                Ok(MatchedExec::Match { exec }) => {
+                    if first_match.is_none() && !layer.forbid_only {
+                        first_match = Some((layer, exec));
+                    }
+                }
+                Err(_) => {}
+            }
+        }
+
+        let (layer, exec) = first_match?;
+        let writes_files = possibly_written_files(&exec).next().is_some();
+        let unwritable = unwritable_target(&exec, cwd, sandbox_policy);
+        let decision = if unwritable.is_some() {
+            ExecPolicyDecision::RequireApproval
+        } else {
+            ExecPolicyDecision::AutoApprove
+        };
+        let escalate_on_approval = unwritable
+            .as_ref()
+            .is_some_and(|target| target.outside_writable_roots);
+        Some(PolicyEvaluation {
+            matched: ExecPolicyMatch {
+                source: layer.source.clone(),
+                program: program.clone(),
+                decision,
+                reason: unwritable.map(|target| target.reason),
+            },
+            writes_files,
+            escalate_on_approval,
+        })
+    }
+}
+
+struct UnwritableTarget {
+    reason: String,
+    /// The target is known to be outside the writable roots, rather than an
+    /// argument the policy cannot classify.
+    outside_writable_roots: bool,
+}
+
+/// Arguments and options of `exec` that may be files it writes.
+fn possibly_written_files(
+    exec: &ValidExec,
+) -> impl Iterator<Item = (&codex_execpolicy::ArgType, &String)> {
+    let args = exec.args.iter().map(|arg| (&arg.r#type, &arg.value));
+    let opts = exec.opts.iter().map(|opt| (&opt.r#type, &opt.value));
+    args.chain(opts)
+        .filter(|(arg_type, _)| arg_type.might_write_file())
+}
+
+/// Describes the first file `exec` may write outside the writable roots, if
+/// any. Arguments of unknown type are treated as possible writes.
+fn unwritable_target(
+    exec: &ValidExec,
+    cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+) -> Option<UnwritableTarget> {
+    for (arg_type, value) in possibly_written_files(exec) {
+        if *arg_type == codex_execpolicy::ArgType::Unknown {
+            return Some(UnwritableTarget {
+                reason: format!("`{value}` may be a file the policy cannot classify"),
+                outside_writable_roots: false,
+            });
+        }
+        if !is_path_writable(Path::new(value), sandbox_policy, cwd) {
+            return Some(UnwritableTarget {
+                reason: format!(
+                    "writes `{value}` outside the writable roots; approving runs the command without the sandbox"
+                ),
+                outside_writable_roots: true,
+            });
+        }
+    }
+    None
+}
+
+/// `*.policy` files directly inside `dir`, sorted by name.
+fn policy_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext == POLICY_FILE_EXTENSION)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse_policy_file(path: &Path, forbid_only: bool) -> Result<PolicyLayer, String> {
+    let source = path.display().to_string();
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("failed to read exec policy {source}: {err}"))?;
+    let policy = PolicyParser::new(&source, &text)
+        .parse()
+        .map_err(|err| format!("failed to parse exec policy {source}: {err}"))?;
+    Ok(PolicyLayer {
+        source,
+        policy,
+        forbid_only,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn cmd(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    fn workspace_write() -> SandboxPolicy {
+        SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: Vec::new(),
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        }
+    }
+
+    fn default_only() -> ExecPolicy {
+        let (policy, errors) = ExecPolicy::load(Path::new("/nonexistent"), Path::new("/"), false);
+        assert_eq!(errors, Vec::<String>::new());
+        policy
+    }
+
+    #[test]
+    fn read_only_match_is_auto_approved() {
+        let cwd = TempDir::new().unwrap();
+        let matched = default_only()
+            .evaluate(
+                &cmd(&["cat", "README.md"]),
+                cwd.path(),
+                &SandboxPolicy::new_read_only_policy(),
+            )
+            .unwrap();
+        assert_eq!(
+            matched,
+            PolicyEvaluation {
+                matched: ExecPolicyMatch {
+                    source: DEFAULT_POLICY_SOURCE.to_string(),
+                    program: "cat".to_string(),
+                    decision: ExecPolicyDecision::AutoApprove,
+                    reason: None,
+                },
+                writes_files: false,
+                escalate_on_approval: false,
+            }
+        );
+    }
+
+    #[test]
+    fn writes_require_approval_only_outside_writable_roots() {
+        let cwd = TempDir::new().unwrap();
+        let policy = default_only();
+        let inside = policy
+            .evaluate(
+                &cmd(&["cp", "a.txt", "b.txt"]),
+                cwd.path(),
+                &workspace_write(),
+            )
+            .unwrap();
+        assert_eq!(inside.matched.decision, ExecPolicyDecision::AutoApprove);
+        assert!(inside.writes_files);
+
+        let outside = policy
+            .evaluate(
+                &cmd(&["cp", "a.txt", "/etc/b.txt"]),
+                cwd.path(),
+                &workspace_write(),
+            )
+            .unwrap();
+        assert_eq!(
+            outside.matched.decision,
+            ExecPolicyDecision::RequireApproval
+        );
+        assert!(outside.escalate_on_approval);
+        assert_eq!(
+            outside.matched.reason.as_deref(),
+            Some(
+                "writes `/etc/b.txt` outside the writable roots; approving runs the command without the sandbox"
+            )
+        );
+    }
+
+    #[test]
+    fn unclassified_arguments_require_approval_inside_the_sandbox() {
+        let cwd = TempDir::new().unwrap();
+        let policy = ExecPolicy::from_sources(&[(
+            "user.policy",
+            r#"
+define_program(program="cp", args=[ARG_RFILES, ARG_WFILE])
+define_program(program="tool", args=[ARG_UNVERIFIED_VARARGS])
+"#,
+        )]);
+        let evaluation = policy
+            .evaluate(
+                &cmd(&["bash", "-lc", "tool out && cp a.txt /etc/b.txt"]),
+                cwd.path(),
+                &workspace_write(),
+            )
+            .unwrap();
+        // The write outside the writable roots is the one explained.
+        assert!(evaluation.escalate_on_approval);
+
+        let evaluation = policy
+            .evaluate(&cmd(&["tool", "out"]), cwd.path(), &workspace_write())
+            .unwrap();
+        assert_eq!(
+            evaluation.matched.decision,
+            ExecPolicyDecision::RequireApproval
+        );
+        assert!(!evaluation.escalate_on_approval);
+        assert_eq!(
+            evaluation.matched.reason.as_deref(),
+            Some("`out` may be a file the policy cannot classify")
+        );
+    }
+
+    #[test]
+    fn forbidden_rule_in_any_layer_wins() {
+        let cwd = TempDir::new().unwrap();
+        let policy = ExecPolicy::from_sources(&[
+            (
+                "repo.policy",
+                r#"forbid_program_regex(regex="^rm$", reason="use trash instead")"#,
+            ),
+            (
+                "user.policy",
+                r#"define_program(program="rm", args=[ARG_RFILES])"#,
+            ),
+        ]);
+        let script = cmd(&["bash", "-lc", "ls && rm foo"]);
+        let matched = policy
+            .evaluate(&script, cwd.path(), &SandboxPolicy::DangerFullAccess)
+            .unwrap()
+            .matched;
+        assert_eq!(matched.decision, ExecPolicyDecision::Forbidden);
+        assert_eq!(matched.source, "repo.policy");
+        assert_eq!(matched.reason.as_deref(), Some("use trash instead"));
+    }
+
+    #[test]
+    fn unmatched_commands_defer_to_heuristics() {
+        let cwd = TempDir::new().unwrap();
+        let policy = default_only();
+        assert_eq!(
+            policy.evaluate(
+                &cmd(&["python3", "-c", "print(1)"]),
+                cwd.path(),
+                &workspace_write()
+            ),
+            None
+        );
+        // One unmatched command keeps the whole script out of auto-approval.
+        assert_eq!(
+            policy.evaluate(
+                &cmd(&["bash", "-lc", "ls && python3 script.py"]),
+                cwd.path(),
+                &workspace_write()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn load_reads_user_policy_files_and_reports_errors() {
+        let codex_home = TempDir::new().unwrap();
+        let cwd = TempDir::new().unwrap();
+        let dir = codex_home.path().join(USER_POLICY_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("tools.policy"),
+            r#"define_program(program="just", args=[ARG_OPAQUE_VALUE])"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("broken.policy"), "define_program(").unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let (policy, errors) = ExecPolicy::load(codex_home.path(), cwd.path(), false);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("broken.policy"));
+        let matched = policy
+            .evaluate(&cmd(&["just", "test"]), cwd.path(), &workspace_write())
+            .unwrap()
+            .matched;
+        assert_eq!(matched.decision, ExecPolicyDecision::AutoApprove);
+        assert!(matched.source.ends_with("tools.policy"));
+    }
+
+    #[test]
+    fn untrusted_repo_policy_can_only_forbid() {
+        let codex_home = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let dir = repo.path().join(REPO_POLICY_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("repo.policy"),
+            r#"
+define_program(program="curl", args=[ARG_OPAQUE_VALUE])
+forbid_program_regex(regex="^terraform$", reason="run terraform from CI")
+"#,
+        )
+        .unwrap();
+        let curl = cmd(&["curl", "https://example.com/install.sh"]);
+        let terraform = cmd(&["terraform", "apply"]);
+
+        let (untrusted, errors) = ExecPolicy::load(codex_home.path(), repo.path(), false);
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(
+            untrusted.evaluate(&curl, repo.path(), &workspace_write()),
+            None
+        );
+        assert_eq!(
+            untrusted
+                .evaluate(&terraform, repo.path(), &workspace_write())
+                .map(|evaluation| evaluation.matched.decision),
+            Some(ExecPolicyDecision::Forbidden)
+        );
+
+        let (trusted, _) = ExecPolicy::load(codex_home.path(), repo.path(), true);
+        assert_eq!(
+            trusted
+                .evaluate(&curl, repo.path(), &workspace_write())
+                .map(|evaluation| evaluation.matched.decision),
+            Some(ExecPolicyDecision::AutoApprove)
+        );
+    }
+}
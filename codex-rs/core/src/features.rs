@@ -45,6 +45,8 @@ pub enum Feature {
     GhostCommit,
     /// Enable Windows sandbox (restricted token) on Windows.
     WindowsSandbox,
+    /// Include the playbook_search tool (requires ACE).
+    PlaybookSearchTool,
 }
 
 impl Feature {
@@ -300,4 +302,10 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::PlaybookSearchTool,
+        key: "playbook_search_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
 ];
@@ -0,0 +1,536 @@
+//! 用户配置的外部 hooks
+//!
+//! `config.toml` 中的 `[hooks]` 把生命周期事件映射到外部命令。每个命令：
+//! - 从 stdin 读取描述事件的 JSON（同时通过 `CODEX_HOOK_PAYLOAD` 提供文件路径）
+//! - 经 `core::exec` 执行，复用超时和 `SandboxPolicy` 沙箱
+//! - 退出码 0：stdout 为 JSON 时按 `{"decision": "block", "reason": "...", "message": "..."}`
+//!   解析，否则非空 stdout 作为说明追加到工具输出
+//! - 退出码 2：阻止工具调用，stderr 作为原因（仅 `before_tool_call` 有效）
+//! - 其他退出码、超时或启动失败：记录警告，不影响工具调用
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use codex_protocol::config_types::SandboxMode;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config::Config;
+use crate::config::types::HookCommandConfig;
+use crate::config::types::HooksConfig;
+use crate::error::CodexErr;
+use crate::error::SandboxErr;
+use crate::exec::ExecParams;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::process_exec_tool_call;
+use crate::exec_env::create_env;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::SandboxManager;
+use crate::tools::sandboxing::SandboxablePreference;
+
+use super::ExecutorHook;
+use super::SessionInfo;
+use super::ToolCallDecision;
+use super::ToolCallRecord;
+use super::ToolCallRequest;
+use super::TurnOutcome;
+
+/// 退出码 2 表示阻止工具调用
+const BLOCK_EXIT_CODE: i32 = 2;
+
+/// 传递给 hook 命令的环境变量
+const HOOK_EVENT_ENV_VAR: &str = "CODEX_HOOK_EVENT";
+const HOOK_PAYLOAD_ENV_VAR: &str = "CODEX_HOOK_PAYLOAD";
+
+/// 可配置的生命周期事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    SessionStart,
+    BeforeToolCall,
+    AfterToolCall,
+    TurnComplete,
+    SessionEnd,
+}
+
+impl HookEvent {
+    /// 事件名称（与 `[hooks]` 中的键一致）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::SessionStart => "session_start",
+            HookEvent::BeforeToolCall => "before_tool_call",
+            HookEvent::AfterToolCall => "after_tool_call",
+            HookEvent::TurnComplete => "turn_complete",
+            HookEvent::SessionEnd => "session_end",
+        }
+    }
+}
+
+/// 单个 hook 命令的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HookResponse {
+    /// 阻止工具调用的原因
+    pub block: Option<String>,
+
+    /// 追加到工具输出中的说明
+    pub message: Option<String>,
+}
+
+/// hook 通过 stdout 返回的 JSON
+#[derive(Debug, Deserialize)]
+struct HookOutputJson {
+    #[serde(default)]
+    decision: Option<String>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+impl HookResponse {
+    /// 根据退出码和输出解析 hook 结果；无法解释的失败返回 None
+    pub fn from_output(exit_code: i32, stdout: &str, stderr: &str) -> Option<Self> {
+        let stdout = stdout.trim();
+        let stderr = stderr.trim();
+
+        match exit_code {
+            0 => {}
+            BLOCK_EXIT_CODE => {
+                let reason = [stderr, stdout]
+                    .into_iter()
+                    .find(|text| !text.is_empty())
+                    .unwrap_or("blocked by hook");
+                return Some(Self {
+                    block: Some(reason.to_string()),
+                    message: None,
+                });
+            }
+            _ => return None,
+        }
+
+        if stdout.starts_with('{')
+            && let Ok(output) = serde_json::from_str::<HookOutputJson>(stdout)
+        {
+            let block = output
+                .decision
+                .is_some_and(|decision| decision.eq_ignore_ascii_case("block"))
+                .then(|| {
+                    output
+                        .reason
+                        .clone()
+                        .unwrap_or_else(|| "blocked by hook".to_string())
+                });
+            return Some(Self {
+                block,
+                message: output.message.filter(|message| !message.trim().is_empty()),
+            });
+        }
+
+        Some(Self {
+            block: None,
+            message: (!stdout.is_empty()).then(|| stdout.to_string()),
+        })
+    }
+}
+
+/// 运行 `[hooks]` 中配置的外部命令
+pub struct ExternalHooks {
+    config: HooksConfig,
+    conversation_id: String,
+    sandbox_policy: SandboxPolicy,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    env: HashMap<String, String>,
+    cwd: PathBuf,
+}
+
+impl ExternalHooks {
+    /// 从配置创建；未配置任何 hook 时返回 None
+    pub fn from_config(config: &Config, conversation_id: impl Into<String>) -> Option<Self> {
+        if config.hooks.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            config: config.hooks.clone(),
+            conversation_id: conversation_id.into(),
+            sandbox_policy: config.sandbox_policy.clone(),
+            codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
+            env: create_env(&config.shell_environment_policy),
+            cwd: config.cwd.clone(),
+        })
+    }
+
+    fn commands(&self, event: HookEvent) -> &[HookCommandConfig] {
+        match event {
+            HookEvent::SessionStart => &self.config.session_start,
+            HookEvent::BeforeToolCall => &self.config.before_tool_call,
+            HookEvent::AfterToolCall => &self.config.after_tool_call,
+            HookEvent::TurnComplete => &self.config.turn_complete,
+            HookEvent::SessionEnd => &self.config.session_end,
+        }
+    }
+
+    /// 依次运行某个事件的所有命令；`tool` 用于匹配 `tools` 过滤条件
+    ///
+    /// 遇到阻止结果时停止，后续命令不再运行。
+    async fn run_event(
+        &self,
+        event: HookEvent,
+        tool: Option<&str>,
+        cwd: &Path,
+        payload: serde_json::Value,
+    ) -> Vec<HookResponse> {
+        let hooks: Vec<&HookCommandConfig> = self
+            .commands(event)
+            .iter()
+            .filter(|hook| match tool {
+                Some(tool) => hook.tools.is_empty() || hook.tools.iter().any(|name| name == tool),
+                None => true,
+            })
+            .collect();
+        if hooks.is_empty() {
+            return Vec::new();
+        }
+
+        let mut payload = payload;
+        payload["event"] = json!(event.as_str());
+        payload["session_id"] = json!(self.conversation_id);
+        payload["cwd"] = json!(cwd);
+        let payload = payload.to_string();
+
+        let mut responses = Vec::new();
+        for hook in hooks {
+            let Some(response) = self.run_command(hook, event, cwd, &payload).await else {
+                continue;
+            };
+            let blocked = response.block.is_some();
+            responses.push(response);
+            if blocked {
+                break;
+            }
+        }
+        responses
+    }
+
+    async fn run_command(
+        &self,
+        hook: &HookCommandConfig,
+        event: HookEvent,
+        cwd: &Path,
+        payload: &str,
+    ) -> Option<HookResponse> {
+        let label = hook.command.join(" ");
+        if hook.command.is_empty() {
+            tracing::warn!("Ignoring {} hook with an empty command", event.as_str());
+            return None;
+        }
+
+        // payload 写入临时文件，由 stdin 重定向读取
+        let payload_file = match write_payload(payload) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("Failed to write payload for hook `{label}`: {e}");
+                return None;
+            }
+        };
+
+        let mut env = self.env.clone();
+        env.insert(HOOK_EVENT_ENV_VAR.to_string(), event.as_str().to_string());
+        env.insert(
+            HOOK_PAYLOAD_ENV_VAR.to_string(),
+            payload_file.path().display().to_string(),
+        );
+
+        let sandbox_policy = self.sandbox_policy_for(hook.sandbox_mode);
+        let sandbox_type = SandboxManager::new().select_initial(
+            &sandbox_policy,
+            SandboxablePreference::Auto,
+            None,
+        );
+        let params = ExecParams {
+            command: command_with_stdin(&hook.command),
+            cwd: cwd.to_path_buf(),
+            timeout_ms: hook.timeout_ms,
+            env,
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+
+        let output = match process_exec_tool_call(
+            params,
+            sandbox_type,
+            &sandbox_policy,
+            cwd,
+            &self.codex_linux_sandbox_exe,
+            None,
+        )
+        .await
+        {
+            Ok(output) => output,
+            // 沙箱拒绝只是启发式判断，仍按退出码解释输出
+            Err(CodexErr::Sandbox(
+                SandboxErr::Denied { output }
+                | SandboxErr::NetworkDenied { output, .. }
+                | SandboxErr::ReadDenied { output, .. },
+            )) => *output,
+            Err(CodexErr::Sandbox(SandboxErr::Timeout { .. })) => {
+                tracing::warn!("Hook `{label}` timed out on {}", event.as_str());
+                return None;
+            }
+            Err(e) => {
+                tracing::warn!("Hook `{label}` failed on {}: {e}", event.as_str());
+                return None;
+            }
+        };
+
+        let response = response_from_exec_output(&output);
+        if response.is_none() {
+            tracing::warn!(
+                "Hook `{label}` exited with code {} on {}: {}",
+                output.exit_code,
+                event.as_str(),
+                output.stderr.text.trim()
+            );
+        }
+        response
+    }
+
+    /// 命令级 `sandbox_mode` 覆盖会话的沙箱策略
+    fn sandbox_policy_for(&self, mode: Option<SandboxMode>) -> SandboxPolicy {
+        match mode {
+            None => self.sandbox_policy.clone(),
+            Some(SandboxMode::ReadOnly) => SandboxPolicy::new_read_only_policy(),
+            Some(SandboxMode::WorkspaceWrite) => match &self.sandbox_policy {
+                policy @ SandboxPolicy::WorkspaceWrite { .. } => policy.clone(),
+                _ => SandboxPolicy::new_workspace_write_policy(),
+            },
+            Some(SandboxMode::DangerFullAccess) => SandboxPolicy::DangerFullAccess,
+        }
+    }
+}
+
+fn response_from_exec_output(output: &ExecToolCallOutput) -> Option<HookResponse> {
+    HookResponse::from_output(output.exit_code, &output.stdout.text, &output.stderr.text)
+}
+
+fn write_payload(payload: &str) -> std::io::Result<tempfile::NamedTempFile> {
+    let mut file = tempfile::Builder::new()
+        .prefix("codex-hook-")
+        .suffix(".json")
+        .tempfile()?;
+    file.write_all(payload.as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
+
+/// exec 不为子进程提供 stdin，这里用 shell 把 payload 文件重定向到 stdin
+#[cfg(unix)]
+fn command_with_stdin(command: &[String]) -> Vec<String> {
+    let mut wrapped = vec![
+        "/bin/sh".to_string(),
+        "-c".to_string(),
+        format!("exec \"$@\" < \"${HOOK_PAYLOAD_ENV_VAR}\""),
+        "codex-hook".to_string(),
+    ];
+    wrapped.extend(command.iter().cloned());
+    wrapped
+}
+
+/// 非 Unix 平台只能通过 `CODEX_HOOK_PAYLOAD` 读取 payload
+#[cfg(not(unix))]
+fn command_with_stdin(command: &[String]) -> Vec<String> {
+    command.to_vec()
+}
+
+fn join_messages(responses: Vec<HookResponse>) -> Option<String> {
+    let messages: Vec<String> = responses
+        .into_iter()
+        .filter_map(|response| response.message)
+        .collect();
+    (!messages.is_empty()).then(|| messages.join("\n"))
+}
+
+#[async_trait]
+impl ExecutorHook for ExternalHooks {
+    fn name(&self) -> &str {
+        "external"
+    }
+
+    async fn on_session_start(&self, session: &SessionInfo) {
+        self.run_event(HookEvent::SessionStart, None, &session.cwd, json!({}))
+            .await;
+    }
+
+    async fn before_tool_call(&self, call: &ToolCallRequest) -> ToolCallDecision {
+        let payload = json!({
+            "tool": {
+                "call_id": call.call_id,
+                "name": call.name,
+                "arguments": call.arguments,
+            },
+        });
+        let responses = self
+            .run_event(
+                HookEvent::BeforeToolCall,
+                Some(&call.name),
+                &call.cwd,
+                payload,
+            )
+            .await;
+
+        if let Some(reason) = responses.iter().find_map(|response| response.block.clone()) {
+            return ToolCallDecision::Block { reason };
+        }
+        ToolCallDecision::Proceed {
+            annotation: join_messages(responses),
+        }
+    }
+
+    async fn after_tool_call(&self, call: &ToolCallRecord) -> Option<String> {
+        let payload = json!({
+            "tool": {
+                "call_id": call.call_id,
+                "name": call.name,
+                "arguments": call.arguments,
+                "success": call.success,
+                "output": call.output,
+            },
+        });
+        let responses = self
+            .run_event(
+                HookEvent::AfterToolCall,
+                Some(&call.name),
+                &self.cwd,
+                payload,
+            )
+            .await;
+        join_messages(responses)
+    }
+
+    async fn post_execute(&self, outcome: &TurnOutcome) {
+        let payload = json!({
+            "turn": {
+                "turn_id": outcome.turn_id,
+                "query": outcome.query,
+                "response": outcome.response,
+                "success": outcome.success(),
+                "aborted": outcome.aborted,
+                "errors": outcome.errors,
+                "tools_used": outcome.tools_used(),
+            },
+        });
+        self.run_event(HookEvent::TurnComplete, None, &self.cwd, payload)
+            .await;
+    }
+
+    async fn on_session_end(&self, session: &SessionInfo) {
+        self.run_event(HookEvent::SessionEnd, None, &session.cwd, json!({}))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_hook_response_from_output() {
+        assert_eq!(
+            HookResponse::from_output(0, "", ""),
+            Some(HookResponse::default())
+        );
+        assert_eq!(
+            HookResponse::from_output(0, "lint: 2 warnings\n", ""),
+            Some(HookResponse {
+                block: None,
+                message: Some("lint: 2 warnings".to_string()),
+            })
+        );
+        assert_eq!(
+            HookResponse::from_output(0, r#"{"decision":"block","reason":"rm -rf"}"#, ""),
+            Some(HookResponse {
+                block: Some("rm -rf".to_string()),
+                message: None,
+            })
+        );
+        assert_eq!(
+            HookResponse::from_output(2, "", "not allowed on main\n"),
+            Some(HookResponse {
+                block: Some("not allowed on main".to_string()),
+                message: None,
+            })
+        );
+        assert_eq!(HookResponse::from_output(1, "", "boom"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_before_tool_call_runs_matching_commands_with_payload_on_stdin() {
+        let cwd = tempfile::tempdir().unwrap();
+        let hooks = ExternalHooks {
+            config: HooksConfig {
+                before_tool_call: vec![
+                    HookCommandConfig {
+                        command: vec![
+                            "/bin/sh".to_string(),
+                            "-c".to_string(),
+                            "printf 'payload: %s' \"$(cat)\"".to_string(),
+                        ],
+                        tools: vec!["shell".to_string()],
+                        timeout_ms: None,
+                        sandbox_mode: Some(SandboxMode::DangerFullAccess),
+                    },
+                    HookCommandConfig {
+                        command: vec![
+                            "/bin/sh".to_string(),
+                            "-c".to_string(),
+                            "echo 'blocked: apply_patch only' >&2; exit 2".to_string(),
+                        ],
+                        tools: vec!["apply_patch".to_string()],
+                        timeout_ms: None,
+                        sandbox_mode: Some(SandboxMode::DangerFullAccess),
+                    },
+                ],
+                ..Default::default()
+            },
+            conversation_id: "session-1".to_string(),
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            codex_linux_sandbox_exe: None,
+            env: std::env::vars().collect(),
+            cwd: cwd.path().to_path_buf(),
+        };
+
+        let request = ToolCallRequest {
+            call_id: "call-1".to_string(),
+            name: "shell".to_string(),
+            arguments: r#"{"command":["ls"]}"#.to_string(),
+            cwd: cwd.path().to_path_buf(),
+        };
+        let ToolCallDecision::Proceed {
+            annotation: Some(annotation),
+        } = hooks.before_tool_call(&request).await
+        else {
+            panic!("expected the shell hook to annotate the call");
+        };
+        let payload: serde_json::Value =
+            serde_json::from_str(annotation.strip_prefix("payload: ").unwrap()).unwrap();
+        assert_eq!(payload["event"], "before_tool_call");
+        assert_eq!(payload["session_id"], "session-1");
+        assert_eq!(payload["tool"]["name"], "shell");
+
+        let request = ToolCallRequest {
+            name: "apply_patch".to_string(),
+            ..request
+        };
+        assert_eq!(
+            hooks.before_tool_call(&request).await,
+            ToolCallDecision::Block {
+                reason: "blocked: apply_patch only".to_string(),
+            }
+        );
+    }
+}
@@ -0,0 +1,819 @@
+//! Hook机制，用于扩展Executor功能
+//!
+//! 这个模块提供了一个最小化的扩展点，允许ACE等插件
+//! 在不修改核心代码的情况下扩展功能。
+//!
+//! 生命周期:
+//! 1. `on_session_start`: 会话创建后
+//! 2. `pre_execute`: 用户回合开始前，各 hook 提供结构化的上下文片段
+//! 3. `before_model_request`: 每次向模型发送请求前
+//! 4. `before_tool_call`: 每个工具调用执行前，可阻止或附加说明
+//! 5. `after_tool_call`: 每个工具调用完成后，可附加说明
+//! 6. `post_execute`: 用户回合结束后，携带实际的工具调用和结果
+//! 7. `on_session_end`: 会话关闭时
+//!
+//! 用户在 `config.toml` 的 `[hooks]` 中配置的外部命令见 [`external`]。
+
+pub mod external;
+
+use async_trait::async_trait;
+use codex_utils_string::take_bytes_at_char_boundary;
+use codex_utils_tokenizer::Tokenizer;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+/// 默认的上下文 token 预算（所有 hook 的上下文合计）
+pub const DEFAULT_CONTEXT_BUDGET_TOKENS: usize = 2000;
+
+/// 估算 token 数时每个 token 对应的字节数
+const APPROX_BYTES_PER_TOKEN: usize = 4;
+
+/// 截断时附加的标记
+const TRUNCATION_MARKER: &str = "\n[...truncated]";
+
+/// Hook 提供的一段上下文
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextContribution {
+    /// 来源（通常是 hook 名称）
+    pub source: String,
+
+    /// 上下文内容
+    pub content: String,
+
+    /// 优先级，越大越先分配预算
+    pub priority: i32,
+
+    /// 该片段最多占用的 token 数（None 表示只受总预算限制）
+    pub max_tokens: Option<usize>,
+}
+
+impl ContextContribution {
+    /// 创建上下文片段
+    pub fn new(source: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            content: content.into(),
+            priority: 0,
+            max_tokens: None,
+        }
+    }
+
+    /// 设置优先级
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 设置 token 上限
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+}
+
+/// 上下文 token 计数器
+///
+/// 按模型选择本地 tokenizer（首次计数时加载），加载失败或未指定模型时按字节估算。
+#[derive(Default)]
+pub struct TokenCounter {
+    model: Option<String>,
+    tokenizer: OnceLock<Option<Tokenizer>>,
+}
+
+impl TokenCounter {
+    /// 使用指定模型的 tokenizer 计数
+    pub fn for_model(model: impl Into<String>) -> Self {
+        Self {
+            model: Some(model.into()),
+            tokenizer: OnceLock::new(),
+        }
+    }
+
+    /// 只按字节估算（不加载 tokenizer）
+    pub fn approximate() -> Self {
+        Self::default()
+    }
+
+    fn tokenizer(&self) -> Option<&Tokenizer> {
+        self.tokenizer
+            .get_or_init(|| {
+                let model = self.model.as_deref()?;
+                Tokenizer::for_model(model)
+                    .map_err(|e| {
+                        tracing::warn!(
+                            "Failed to load tokenizer for {model}: {e}, estimating tokens"
+                        );
+                    })
+                    .ok()
+            })
+            .as_ref()
+    }
+
+    /// 计算文本的 token 数
+    pub fn count(&self, text: &str) -> usize {
+        match self.tokenizer() {
+            Some(tokenizer) => usize::try_from(tokenizer.count(text)).unwrap_or(0),
+            None => approx_token_count(text),
+        }
+    }
+
+    /// 截取不超过 `max_tokens` 的前缀
+    pub fn truncate<'a>(&self, text: &'a str, max_tokens: usize) -> &'a str {
+        let Some(tokenizer) = self.tokenizer() else {
+            return take_bytes_at_char_boundary(text, max_tokens * APPROX_BYTES_PER_TOKEN);
+        };
+        let tokens = tokenizer.encode(text, false);
+        if tokens.len() <= max_tokens {
+            return text;
+        }
+        // 解码结果可能在多字节字符中间结束，回退到最近的字符边界
+        let prefix_len = tokenizer
+            .decode(&tokens[..max_tokens])
+            .map(|prefix| prefix.len())
+            .unwrap_or(max_tokens * APPROX_BYTES_PER_TOKEN);
+        take_bytes_at_char_boundary(text, prefix_len)
+    }
+}
+
+/// 合并后注入的上下文
+#[derive(Debug, Clone, PartialEq)]
+pub struct InjectedContext {
+    /// 合并后的上下文文本
+    pub text: String,
+
+    /// 每个来源实际注入的 token 数（合并、截断之后）
+    pub usage: Vec<ContextUsage>,
+}
+
+impl InjectedContext {
+    /// 所有来源合计的 token 数
+    pub fn total_tokens(&self) -> usize {
+        self.usage.iter().map(|usage| usage.tokens).sum()
+    }
+}
+
+/// 单个来源注入的 token 数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextUsage {
+    /// 来源（hook 名称）
+    pub source: String,
+
+    /// 注入的 token 数
+    pub tokens: usize,
+}
+
+/// 会话信息
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    /// 会话 ID
+    pub conversation_id: String,
+
+    /// 工作目录
+    pub cwd: PathBuf,
+
+    /// 会话 rollout 文件
+    pub rollout_path: Option<PathBuf>,
+}
+
+/// 模型请求信息
+#[derive(Debug, Clone)]
+pub struct ModelRequestInfo {
+    /// 回合 ID
+    pub turn_id: String,
+
+    /// 本回合中的第几次模型请求（从 0 开始）
+    pub request_index: usize,
+
+    /// 发送给模型的输入项数量
+    pub input_items: usize,
+}
+
+/// 即将执行的工具调用
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCallRequest {
+    /// 调用 ID
+    pub call_id: String,
+
+    /// 工具名称
+    pub name: String,
+
+    /// 调用参数（原始 JSON 或自由文本）
+    pub arguments: String,
+
+    /// 工具调用的工作目录
+    pub cwd: PathBuf,
+}
+
+/// Hook 对即将执行的工具调用的决定
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolCallDecision {
+    /// 继续执行，可附带一段追加到工具输出中的说明
+    Proceed { annotation: Option<String> },
+
+    /// 阻止执行，原因会作为工具输出返回给模型
+    Block { reason: String },
+}
+
+impl Default for ToolCallDecision {
+    fn default() -> Self {
+        Self::Proceed { annotation: None }
+    }
+}
+
+/// 一次工具调用的记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCallRecord {
+    /// 调用 ID
+    pub call_id: String,
+
+    /// 工具名称
+    pub name: String,
+
+    /// 调用参数（原始 JSON 或自由文本）
+    pub arguments: String,
+
+    /// 是否成功（工具未报告时为 None）
+    pub success: Option<bool>,
+
+    /// 工具输出（可能已截断）
+    pub output: String,
+}
+
+impl ToolCallRecord {
+    /// 工具是否明确报告失败
+    pub fn failed(&self) -> bool {
+        self.success == Some(false)
+    }
+}
+
+/// 一次用户回合的结果
+#[derive(Debug, Clone, Default)]
+pub struct TurnOutcome {
+    /// 回合 ID
+    pub turn_id: String,
+
+    /// 用户查询
+    pub query: String,
+
+    /// 模型最后一条回复
+    pub response: Option<String>,
+
+    /// 本回合的工具调用（按完成顺序）
+    pub tool_calls: Vec<ToolCallRecord>,
+
+    /// 本回合出现的错误
+    pub errors: Vec<String>,
+
+    /// 本回合对文件的改动（`TurnDiffTracker` 生成的 unified diff）
+    pub diff: Option<String>,
+
+    /// 是否被用户中断
+    pub aborted: bool,
+}
+
+impl TurnOutcome {
+    /// 回合是否成功完成（有回复、未中断、没有错误）
+    pub fn success(&self) -> bool {
+        !self.aborted
+            && self.errors.is_empty()
+            && self
+                .response
+                .as_deref()
+                .is_some_and(|response| !response.is_empty())
+    }
+
+    /// 本回合使用过的工具（去重，保持首次出现的顺序）
+    pub fn tools_used(&self) -> Vec<String> {
+        let mut tools: Vec<String> = Vec::new();
+        for call in &self.tool_calls {
+            if !tools.contains(&call.name) {
+                tools.push(call.name.clone());
+            }
+        }
+        tools
+    }
+
+    /// 是否有工具先失败、之后又成功（即通过重试解决）
+    pub fn retried_after_failure(&self) -> bool {
+        self.tool_calls
+            .iter()
+            .position(ToolCallRecord::failed)
+            .is_some_and(|first_failure| {
+                self.tool_calls[first_failure + 1..]
+                    .iter()
+                    .any(|call| call.success == Some(true))
+            })
+    }
+}
+
+/// Executor扩展Hook trait
+///
+/// 实现这个trait可以在Executor的各个生命周期点注入自定义逻辑。
+/// 所有的hook方法都是可选的，默认实现为空操作。
+/// 所有方法都在会话的异步上下文中直接 await，实现不应自行创建运行时。
+#[async_trait]
+pub trait ExecutorHook: Send + Sync {
+    /// Hook 名称（用于日志和上下文来源）
+    fn name(&self) -> &str {
+        "hook"
+    }
+
+    /// 会话创建后调用
+    async fn on_session_start(&self, _session: &SessionInfo) {}
+
+    /// 在用户回合开始前调用
+    ///
+    /// 返回的上下文片段由 `HookManager` 按优先级和 token 预算合并后，
+    /// 作为系统上下文添加到对话中。
+    async fn pre_execute(&self, _query: &str) -> Vec<ContextContribution> {
+        Vec::new()
+    }
+
+    /// 每次向模型发送请求前调用
+    async fn before_model_request(&self, _request: &ModelRequestInfo) {}
+
+    /// 每个工具调用执行前调用
+    ///
+    /// 返回 `Block` 时工具不会执行。在回合内同步 await，实现应尽快返回。
+    async fn before_tool_call(&self, _call: &ToolCallRequest) -> ToolCallDecision {
+        ToolCallDecision::default()
+    }
+
+    /// 每个工具调用完成后调用
+    ///
+    /// 返回的说明会追加到发送给模型的工具输出中。在回合内同步 await，实现应尽快返回。
+    async fn after_tool_call(&self, _call: &ToolCallRecord) -> Option<String> {
+        None
+    }
+
+    /// 在用户回合结束后调用
+    ///
+    /// 用于记录、学习或其他后处理逻辑。在独立任务中执行，不阻塞主流程。
+    async fn post_execute(&self, _outcome: &TurnOutcome) {}
+
+    /// 会话关闭时调用
+    async fn on_session_end(&self, _session: &SessionInfo) {}
+}
+
+/// Hook管理器
+///
+/// 管理和调用所有注册的hooks。
+pub struct HookManager {
+    hooks: Vec<Arc<dyn ExecutorHook>>,
+
+    /// 所有 hooks 上下文合计的 token 预算
+    context_budget_tokens: usize,
+
+    /// 计算上下文 token 数
+    token_counter: Arc<TokenCounter>,
+}
+
+impl Default for HookManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HookManager {
+    /// 创建新的Hook管理器
+    pub fn new() -> Self {
+        Self {
+            hooks: Vec::new(),
+            context_budget_tokens: DEFAULT_CONTEXT_BUDGET_TOKENS,
+            token_counter: Arc::new(TokenCounter::approximate()),
+        }
+    }
+
+    /// 设置上下文 token 预算
+    pub fn with_context_budget(mut self, tokens: usize) -> Self {
+        self.context_budget_tokens = tokens;
+        self
+    }
+
+    /// 设置 token 计数器（默认按字节估算）
+    pub fn with_token_counter(mut self, token_counter: Arc<TokenCounter>) -> Self {
+        self.token_counter = token_counter;
+        self
+    }
+
+    /// 注册一个新的hook
+    pub fn register(&mut self, hook: Arc<dyn ExecutorHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// 调用所有on_session_start hooks
+    pub async fn call_on_session_start(&self, session: &SessionInfo) {
+        for hook in &self.hooks {
+            hook.on_session_start(session).await;
+        }
+    }
+
+    /// 调用所有pre_execute hooks
+    ///
+    /// 并发收集所有 hooks 的上下文片段，按预算合并。没有任何上下文时返回None。
+    pub async fn call_pre_execute(&self, query: &str) -> Option<InjectedContext> {
+        let contributions: Vec<ContextContribution> =
+            futures::future::join_all(self.hooks.iter().map(|hook| hook.pre_execute(query)))
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+
+        merge_contributions(
+            contributions,
+            self.context_budget_tokens,
+            &self.token_counter,
+        )
+    }
+
+    /// 调用所有before_model_request hooks
+    pub async fn call_before_model_request(&self, request: &ModelRequestInfo) {
+        for hook in &self.hooks {
+            hook.before_model_request(request).await;
+        }
+    }
+
+    /// 调用所有before_tool_call hooks
+    ///
+    /// 按注册顺序依次调用，第一个 `Block` 立即生效；否则合并所有说明。
+    pub async fn call_before_tool_call(&self, call: &ToolCallRequest) -> ToolCallDecision {
+        let mut annotations = Vec::new();
+        for hook in &self.hooks {
+            match hook.before_tool_call(call).await {
+                ToolCallDecision::Proceed { annotation } => annotations.extend(annotation),
+                ToolCallDecision::Block { reason } => {
+                    tracing::info!(
+                        "Hook {} blocked tool call {}: {}",
+                        hook.name(),
+                        call.name,
+                        reason
+                    );
+                    return ToolCallDecision::Block { reason };
+                }
+            }
+        }
+
+        ToolCallDecision::Proceed {
+            annotation: join_annotations(annotations),
+        }
+    }
+
+    /// 调用所有after_tool_call hooks
+    ///
+    /// 返回所有 hooks 附加说明的合并结果。
+    pub async fn call_after_tool_call(&self, call: &ToolCallRecord) -> Option<String> {
+        let mut annotations = Vec::new();
+        for hook in &self.hooks {
+            annotations.extend(hook.after_tool_call(call).await);
+        }
+        join_annotations(annotations)
+    }
+
+    /// 调用所有post_execute hooks
+    ///
+    /// 每个hook在独立任务中执行，不等待完成。
+    pub fn call_post_execute(&self, outcome: TurnOutcome) {
+        let outcome = Arc::new(outcome);
+        for hook in &self.hooks {
+            let hook = Arc::clone(hook);
+            let outcome = Arc::clone(&outcome);
+
+            // 在新的任务中异步调用，避免阻塞
+            tokio::spawn(async move {
+                hook.post_execute(&outcome).await;
+            });
+        }
+    }
+
+    /// 调用所有on_session_end hooks
+    pub async fn call_on_session_end(&self, session: &SessionInfo) {
+        for hook in &self.hooks {
+            hook.on_session_end(session).await;
+        }
+    }
+
+    /// 获取已注册的hook数量
+    pub fn hook_count(&self) -> usize {
+        self.hooks.len()
+    }
+}
+
+/// 合并多个 hooks 的说明，忽略空白内容
+fn join_annotations(annotations: Vec<String>) -> Option<String> {
+    let annotations: Vec<String> = annotations
+        .into_iter()
+        .filter(|annotation| !annotation.trim().is_empty())
+        .collect();
+    if annotations.is_empty() {
+        None
+    } else {
+        Some(annotations.join("\n"))
+    }
+}
+
+/// 估算文本的 token 数
+fn approx_token_count(text: &str) -> usize {
+    text.len().div_ceil(APPROX_BYTES_PER_TOKEN)
+}
+
+/// 按优先级合并上下文片段
+///
+/// 优先级高的片段先分配预算（同优先级保持注册顺序），
+/// 每个片段受自身 `max_tokens` 和剩余预算限制，超出部分被截断；预算用尽后其余片段被丢弃。
+fn merge_contributions(
+    mut contributions: Vec<ContextContribution>,
+    budget_tokens: usize,
+    counter: &TokenCounter,
+) -> Option<InjectedContext> {
+    contributions.retain(|contribution| !contribution.content.trim().is_empty());
+    contributions.sort_by_key(|contribution| std::cmp::Reverse(contribution.priority));
+
+    let marker_tokens = counter.count(TRUNCATION_MARKER);
+    let mut remaining = budget_tokens;
+    let mut sections = Vec::new();
+    let mut usage: Vec<ContextUsage> = Vec::new();
+    for contribution in contributions {
+        let limit = contribution
+            .max_tokens
+            .map_or(remaining, |max_tokens| max_tokens.min(remaining));
+        if limit == 0 {
+            tracing::debug!(
+                "Dropping context from {}: token budget exhausted",
+                contribution.source
+            );
+            continue;
+        }
+
+        let tokens = counter.count(&contribution.content);
+        let (content, used) = if tokens <= limit {
+            (contribution.content, tokens)
+        } else {
+            tracing::debug!(
+                "Truncating context from {}: ~{} tokens > {} tokens",
+                contribution.source,
+                tokens,
+                limit
+            );
+            let prefix =
+                counter.truncate(&contribution.content, limit.saturating_sub(marker_tokens));
+            (format!("{prefix}{TRUNCATION_MARKER}"), limit)
+        };
+        remaining -= used;
+
+        tracing::debug!(
+            "Hook {} provided context: {} chars",
+            contribution.source,
+            content.len()
+        );
+        sections.push(content);
+        match usage
+            .iter_mut()
+            .find(|existing| existing.source == contribution.source)
+        {
+            Some(existing) => existing.tokens += used,
+            None => usage.push(ContextUsage {
+                source: contribution.source,
+                tokens: used,
+            }),
+        }
+    }
+
+    if sections.is_empty() {
+        None
+    } else {
+        Some(InjectedContext {
+            text: sections.join("\n\n"),
+            usage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct TestHook {
+        contributions: Vec<ContextContribution>,
+        outcomes: Arc<Mutex<Vec<TurnOutcome>>>,
+    }
+
+    #[async_trait]
+    impl ExecutorHook for TestHook {
+        fn name(&self) -> &str {
+            "test"
+        }
+
+        async fn pre_execute(&self, _query: &str) -> Vec<ContextContribution> {
+            self.contributions.clone()
+        }
+
+        async fn post_execute(&self, outcome: &TurnOutcome) {
+            self.outcomes.lock().unwrap().push(outcome.clone());
+        }
+    }
+
+    fn test_hook(
+        contributions: Vec<ContextContribution>,
+    ) -> (Arc<TestHook>, Arc<Mutex<Vec<TurnOutcome>>>) {
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+        let hook = Arc::new(TestHook {
+            contributions,
+            outcomes: Arc::clone(&outcomes),
+        });
+        (hook, outcomes)
+    }
+
+    #[tokio::test]
+    async fn test_hook_manager() {
+        let mut manager = HookManager::new();
+        assert_eq!(manager.hook_count(), 0);
+
+        let (hook, _) = test_hook(vec![ContextContribution::new("test", "test context")]);
+        manager.register(hook);
+        assert_eq!(manager.hook_count(), 1);
+
+        let query = "test query";
+
+        let context = manager.call_pre_execute(query).await;
+        assert_eq!(
+            context,
+            Some(InjectedContext {
+                text: "test context".to_string(),
+                usage: vec![ContextUsage {
+                    source: "test".to_string(),
+                    tokens: 3,
+                }],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hook_manager_merges_all_providers_by_priority() {
+        let mut manager = HookManager::new();
+        let (low, _) = test_hook(vec![ContextContribution::new("low", "low priority")]);
+        let (high, _) = test_hook(vec![
+            ContextContribution::new("high", "high priority").with_priority(10),
+            ContextContribution::new("empty", "  "),
+        ]);
+        manager.register(low);
+        manager.register(high);
+
+        let context = manager.call_pre_execute("query").await.unwrap();
+        assert_eq!(context.text, "high priority\n\nlow priority");
+        let sources: Vec<&str> = context
+            .usage
+            .iter()
+            .map(|usage| usage.source.as_str())
+            .collect();
+        assert_eq!(sources, vec!["high", "low"]);
+    }
+
+    #[test]
+    fn test_merge_contributions_respects_budgets() {
+        let long = "x".repeat(400); // ~100 tokens
+        let contributions = vec![
+            ContextContribution::new("first", long.clone()).with_priority(2),
+            ContextContribution::new("capped", long.clone())
+                .with_priority(1)
+                .with_max_tokens(20),
+            ContextContribution::new("dropped", long),
+        ];
+
+        let counter = TokenCounter::approximate();
+        let merged = merge_contributions(contributions, 120, &counter).unwrap();
+        let sections: Vec<&str> = merged.text.split("\n\n").collect();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].len(), 400);
+        assert!(sections[1].ends_with(TRUNCATION_MARKER));
+        assert!(sections[1].len() <= 20 * APPROX_BYTES_PER_TOKEN);
+        assert_eq!(
+            merged.usage,
+            vec![
+                ContextUsage {
+                    source: "first".to_string(),
+                    tokens: 100,
+                },
+                ContextUsage {
+                    source: "capped".to_string(),
+                    tokens: 20,
+                },
+            ]
+        );
+        assert_eq!(merged.total_tokens(), 120);
+
+        assert_eq!(merge_contributions(Vec::new(), 100, &counter), None);
+    }
+
+    struct GateHook {
+        decision: ToolCallDecision,
+    }
+
+    #[async_trait]
+    impl ExecutorHook for GateHook {
+        async fn before_tool_call(&self, _call: &ToolCallRequest) -> ToolCallDecision {
+            self.decision.clone()
+        }
+
+        async fn after_tool_call(&self, call: &ToolCallRecord) -> Option<String> {
+            Some(format!("checked {}", call.name))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_before_tool_call_block_wins_and_annotations_merge() {
+        let request = ToolCallRequest {
+            call_id: "call-1".to_string(),
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            cwd: PathBuf::from("/tmp"),
+        };
+
+        let mut manager = HookManager::new();
+        manager.register(Arc::new(GateHook {
+            decision: ToolCallDecision::Proceed {
+                annotation: Some("first".to_string()),
+            },
+        }));
+        manager.register(Arc::new(GateHook {
+            decision: ToolCallDecision::Proceed {
+                annotation: Some("second".to_string()),
+            },
+        }));
+        assert_eq!(
+            manager.call_before_tool_call(&request).await,
+            ToolCallDecision::Proceed {
+                annotation: Some("first\nsecond".to_string()),
+            }
+        );
+
+        manager.register(Arc::new(GateHook {
+            decision: ToolCallDecision::Block {
+                reason: "no shell".to_string(),
+            },
+        }));
+        assert_eq!(
+            manager.call_before_tool_call(&request).await,
+            ToolCallDecision::Block {
+                reason: "no shell".to_string(),
+            }
+        );
+
+        let record = ToolCallRecord {
+            call_id: request.call_id,
+            name: request.name,
+            arguments: request.arguments,
+            success: Some(true),
+            output: String::new(),
+        };
+        assert_eq!(
+            manager.call_after_tool_call(&record).await,
+            Some("checked shell\nchecked shell\nchecked shell".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_execute_receives_outcome() {
+        let mut manager = HookManager::new();
+        let (hook, outcomes) = test_hook(Vec::new());
+        manager.register(hook);
+
+        let outcome = TurnOutcome {
+            turn_id: "turn-1".to_string(),
+            query: "run the tests".to_string(),
+            response: Some("All tests pass".to_string()),
+            tool_calls: vec![
+                ToolCallRecord {
+                    call_id: "call-1".to_string(),
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":["cargo","test"]}"#.to_string(),
+                    success: Some(false),
+                    output: "error[E0425]".to_string(),
+                },
+                ToolCallRecord {
+                    call_id: "call-2".to_string(),
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":["cargo","test"]}"#.to_string(),
+                    success: Some(true),
+                    output: "test result: ok".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(outcome.success());
+        assert!(outcome.retried_after_failure());
+        assert_eq!(outcome.tools_used(), vec!["shell".to_string()]);
+
+        manager.call_post_execute(outcome);
+        for _ in 0..50 {
+            if !outcomes.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let outcomes = outcomes.lock().unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].tool_calls.len(), 2);
+    }
+}
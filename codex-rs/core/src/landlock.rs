@@ -1,11 +1,40 @@
+use crate::config::types::SandboxContainer;
 use crate::protocol::SandboxPolicy;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::io::Read;
+#[cfg(target_os = "linux")]
+use std::os::fd::AsRawFd;
+#[cfg(target_os = "linux")]
+use std::os::fd::FromRawFd;
+#[cfg(target_os = "linux")]
+use std::os::fd::OwnedFd;
+#[cfg(target_os = "linux")]
+use std::os::fd::RawFd;
 use std::path::Path;
 use std::path::PathBuf;
 use tokio::process::Child;
 
+/// Prefix of the line `codex-linux-sandbox` writes to its denial report for
+/// every connection its network proxy refuses; the `host:port` follows it.
+pub const NETWORK_DENIED_PREFIX: &str = "network-denied ";
+
+/// Prefix of the denial report line for every `deny_read` path the command
+/// tried to open; the absolute path follows it.
+pub const READ_DENIED_PREFIX: &str = "read-denied ";
+
+/// Option of `codex-linux-sandbox` naming the descriptor it reports denials on.
+pub const DENIAL_FD_ARG: &str = "--denial-fd";
+
+/// Option of `codex-linux-sandbox` that turns on reporting refused reads.
+pub const REPORT_DENIED_READS_ARG: &str = "--report-denied-reads";
+
+/// Upper bound on the denial report read back from the helper.
+#[cfg(target_os = "linux")]
+const MAX_DENIAL_REPORT_BYTES: usize = 64 * 1024;
+
 /// Spawn a shell tool command under the Linux Landlock+seccomp sandbox helper
 /// (codex-linux-sandbox).
 ///
@@ -25,7 +54,8 @@ pub async fn spawn_command_under_linux_sandbox<P>(
 where
     P: AsRef<Path>,
 {
-    let args = create_linux_sandbox_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args =
+        create_linux_sandbox_command_args(command, sandbox_policy, sandbox_policy_cwd, None, false);
     let arg0 = Some("codex-linux-sandbox");
     spawn_child_async(
         codex_linux_sandbox_exe.as_ref().to_path_buf(),
@@ -35,15 +65,21 @@ where
         sandbox_policy,
         stdio_policy,
         env,
+        None,
     )
     .await
 }
 
 /// Converts the sandbox policy into the CLI invocation for `codex-linux-sandbox`.
+/// With `container`, the helper runs the command inside a private mount
+/// namespace before applying the policy. With `report_denied_reads`, it
+/// watches the command's opens to report refused `deny_read` paths.
 pub(crate) fn create_linux_sandbox_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    container: Option<&SandboxContainer>,
+    report_denied_reads: bool,
 ) -> Vec<String> {
     #[expect(clippy::expect_used)]
     let sandbox_policy_cwd = sandbox_policy_cwd
@@ -60,13 +96,207 @@ pub(crate) fn create_linux_sandbox_command_args(
         sandbox_policy_cwd,
         "--sandbox-policy".to_string(),
         sandbox_policy_json,
-        // Separator so that command arguments starting with `-` are not parsed as
-        // options of the helper itself.
-        "--".to_string(),
     ];
 
+    if let Some(container) = container {
+        #[expect(clippy::expect_used)]
+        let container_json =
+            serde_json::to_string(container).expect("Failed to serialize SandboxContainer to JSON");
+        linux_cmd.push("--container".to_string());
+        linux_cmd.push(container_json);
+    }
+
+    if report_denied_reads {
+        linux_cmd.push(REPORT_DENIED_READS_ARG.to_string());
+    }
+
+    // Separator so that command arguments starting with `-` are not parsed as
+    // options of the helper itself.
+    linux_cmd.push("--".to_string());
+
     // Append the original tool command.
     linux_cmd.extend(command);
 
     linux_cmd
 }
+
+/// Pipe on which `codex-linux-sandbox` reports what it refused. Only the
+/// helper and the processes it forks before running the command hold the
+/// write end, so unlike the command's output the report cannot be forged by
+/// the command.
+#[cfg(target_os = "linux")]
+pub(crate) struct DenialReport {
+    reader: std::fs::File,
+    writer: Option<OwnedFd>,
+}
+
+#[cfg(target_os = "linux")]
+impl DenialReport {
+    pub(crate) fn new() -> std::io::Result<Self> {
+        let mut fds = [0; 2];
+        // Non-blocking on both ends: the helper drops reports rather than
+        // stall the command, and reading stops once the pipe is drained.
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC | libc::O_NONBLOCK) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let (reader, writer) = unsafe {
+            (
+                std::fs::File::from_raw_fd(fds[0]),
+                OwnedFd::from_raw_fd(fds[1]),
+            )
+        };
+        Ok(Self {
+            reader,
+            writer: Some(writer),
+        })
+    }
+
+    /// The write end, to be inherited by the helper.
+    pub(crate) fn writer_fd(&self) -> Option<RawFd> {
+        self.writer.as_ref().map(AsRawFd::as_raw_fd)
+    }
+
+    /// Helper arguments naming the write end.
+    pub(crate) fn helper_args(&self) -> Vec<String> {
+        self.writer_fd()
+            .map(|fd| vec![DENIAL_FD_ARG.to_string(), fd.to_string()])
+            .unwrap_or_default()
+    }
+
+    /// Drops our copy of the write end once the helper has been spawned.
+    pub(crate) fn close_writer(&mut self) {
+        self.writer = None;
+    }
+
+    /// Everything reported so far. The helper reports a denial before the
+    /// refused operation fails, so once the command has exited the report is
+    /// complete even if forked helper processes still hold the write end.
+    pub(crate) fn read_reported(mut self) -> String {
+        let mut report = Vec::new();
+        let mut buf = [0u8; 4096];
+        while report.len() < MAX_DENIAL_REPORT_BYTES {
+            match self.reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => report.extend_from_slice(&buf[..n]),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        String::from_utf8_lossy(&report).into_owned()
+    }
+}
+
+/// Hosts refused by the sandbox network proxy according to the helper's
+/// denial report, in order of first refusal.
+pub(crate) fn network_denied_hosts(report: &str) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+    for line in report.lines() {
+        if let Some(host) = line.trim_end().strip_prefix(NETWORK_DENIED_PREFIX)
+            && !host.is_empty()
+            && !hosts.iter().any(|seen| seen == host)
+        {
+            hosts.push(host.to_string());
+        }
+    }
+    hosts
+}
+
+/// `deny_read` paths the command tried to open according to the helper's
+/// denial report, in order of first attempt.
+pub(crate) fn read_denied_paths(report: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for line in report.lines() {
+        if let Some(path) = line.strip_prefix(READ_DENIED_PREFIX)
+            && !path.is_empty()
+            && !paths.iter().any(|seen| seen.as_os_str() == path)
+        {
+            paths.push(PathBuf::from(path));
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn network_denied_hosts_are_deduplicated() {
+        let report = format!(
+            "{NETWORK_DENIED_PREFIX}example.com:443\n\
+             {NETWORK_DENIED_PREFIX}example.com:443\n\
+             {NETWORK_DENIED_PREFIX}10.0.0.1:80\n"
+        );
+        assert_eq!(
+            network_denied_hosts(&report),
+            vec!["example.com:443".to_string(), "10.0.0.1:80".to_string()]
+        );
+        assert!(network_denied_hosts("").is_empty());
+    }
+
+    #[test]
+    fn read_denied_paths_ignore_network_denials() {
+        let report = format!(
+            "{READ_DENIED_PREFIX}/home/me/.ssh/id_rsa\n\
+             {NETWORK_DENIED_PREFIX}example.com:443\n\
+             {READ_DENIED_PREFIX}/home/me/.ssh/id_rsa\n"
+        );
+        assert_eq!(
+            read_denied_paths(&report),
+            vec![PathBuf::from("/home/me/.ssh/id_rsa")]
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn denial_report_is_read_while_writers_remain() {
+        let report = DenialReport::new().unwrap();
+        let line = format!("{NETWORK_DENIED_PREFIX}example.com:443\n");
+        let fd = report.writer_fd().unwrap();
+        let written = unsafe { libc::write(fd, line.as_ptr().cast(), line.len()) };
+        assert_eq!(written, line.len() as isize);
+        // The write end is still open, as it is in a lingering proxy process.
+        assert_eq!(report.read_reported(), line);
+    }
+
+    #[test]
+    fn container_settings_precede_the_command_separator() {
+        let container = SandboxContainer {
+            enabled: true,
+            rootfs: Some(PathBuf::from("/images/debian")),
+            binds: vec![PathBuf::from("/srv/cache")],
+            required: true,
+        };
+        let args = create_linux_sandbox_command_args(
+            vec!["--version".to_string()],
+            &SandboxPolicy::new_read_only_policy(),
+            Path::new("/work"),
+            Some(&container),
+            false,
+        );
+
+        let separator = args.iter().position(|arg| arg == "--").unwrap();
+        let flag = args.iter().position(|arg| arg == "--container").unwrap();
+        assert!(flag < separator);
+        assert_eq!(
+            args[flag + 1].parse::<SandboxContainer>().unwrap(),
+            container
+        );
+        assert_eq!(&args[separator + 1..], ["--version".to_string()]);
+
+        let args = create_linux_sandbox_command_args(
+            vec!["true".to_string()],
+            &SandboxPolicy::new_read_only_policy(),
+            Path::new("/work"),
+            None,
+            true,
+        );
+        assert!(!args.iter().any(|arg| arg == "--container"));
+        let flag = args
+            .iter()
+            .position(|arg| arg == REPORT_DENIED_READS_ARG)
+            .unwrap();
+        assert!(flag < args.iter().position(|arg| arg == "--").unwrap());
+    }
+}
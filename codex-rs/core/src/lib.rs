@@ -26,6 +26,7 @@ mod environment_context;
 pub mod error;
 pub mod exec;
 pub mod exec_env;
+mod exec_policy;
 pub mod features;
 mod flags;
 pub mod git_info;
@@ -96,6 +97,7 @@ pub mod ace;
 pub mod mission;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
+pub use command_safety::approval_rules;
 pub use command_safety::is_safe_command;
 pub use safety::get_platform_sandbox;
 pub use safety::set_windows_sandbox_enabled;
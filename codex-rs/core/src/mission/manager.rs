@@ -3,15 +3,27 @@
 //! Responsible for creating, updating and tracking Mission status
 
 use super::types::MissionContext;
+use super::types::MissionStatus;
 use super::types::TodoItem;
 use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::protocol::MissionStateItem;
+use codex_protocol::protocol::RolloutItem;
+use serde::Deserialize;
+use serde::Serialize;
 
 /// Mission Manager
 ///
-/// Manages Mission creation, updates and status tracking
+/// Manages Mission creation, updates and status tracking.
+/// Its state is recorded in the rollout so resumed sessions keep todo progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MissionManager {
     /// Currently active Mission (if any)
+    #[serde(default)]
     current_mission: Option<MissionContext>,
+
+    /// Earlier Missions of this session, oldest first
+    #[serde(default)]
+    history: Vec<MissionContext>,
 }
 
 impl MissionManager {
@@ -19,16 +31,48 @@ impl MissionManager {
     pub fn new() -> Self {
         Self {
             current_mission: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// Restore from the latest mission snapshot in the rollout (if any)
+    pub fn from_rollout_items(items: &[RolloutItem]) -> Option<Self> {
+        let state = items.iter().rev().find_map(|item| match item {
+            RolloutItem::MissionState(item) => Some(&item.state),
+            _ => None,
+        })?;
+
+        match serde_json::from_value(state.clone()) {
+            Ok(manager) => Some(manager),
+            Err(e) => {
+                tracing::warn!("Failed to restore mission state from rollout: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Snapshot of the current state, to be recorded in the rollout
+    pub fn to_rollout_item(&self) -> Option<RolloutItem> {
+        match serde_json::to_value(self) {
+            Ok(state) => Some(RolloutItem::MissionState(MissionStateItem { state })),
+            Err(e) => {
+                tracing::warn!("Failed to serialize mission state: {}", e);
+                None
+            }
         }
     }
 
     /// Start new Mission
     ///
-    /// If there's an active Mission, complete it first
+    /// If there's an active Mission, it is moved to the history first
     pub fn start_mission(&mut self, description: String, session_id: String) -> &MissionContext {
-        // If there's existing Mission, mark it as complete
-        if let Some(ref mut mission) = self.current_mission {
+        // An unfinished previous Mission is superseded by the new one
+        if let Some(mut mission) = self.current_mission.take() {
             tracing::info!("Completing previous mission: {}", mission.description);
+            if mission.status == MissionStatus::Active {
+                mission.status = MissionStatus::Cancelled;
+            }
+            self.history.push(mission);
         }
 
         // Create new Mission
@@ -89,10 +133,21 @@ impl MissionManager {
 
     /// Complete current Mission
     pub fn complete_current_mission(&mut self) {
-        if let Some(ref mission) = self.current_mission {
+        if let Some(mut mission) = self.current_mission.take() {
             tracing::info!("Completed mission: {}", mission.description);
+            mission.status = MissionStatus::Completed;
+            self.history.push(mission);
         }
-        self.current_mission = None;
+    }
+
+    /// Earlier Missions of this session, oldest first
+    pub fn history(&self) -> &[MissionContext] {
+        &self.history
+    }
+
+    /// All Missions of this session (history first, then the current one)
+    pub fn missions(&self) -> impl Iterator<Item = &MissionContext> {
+        self.history.iter().chain(self.current_mission.iter())
     }
 
     /// Get unreflected completed Todos
@@ -212,6 +267,8 @@ mod tests {
 
         manager.complete_current_mission();
         assert!(manager.current_mission().is_none());
+        assert_eq!(manager.history().len(), 1);
+        assert_eq!(manager.history()[0].status, MissionStatus::Completed);
     }
 
     #[test]
@@ -223,5 +280,31 @@ mod tests {
 
         manager.start_mission("Task2".to_string(), "session-2".to_string());
         assert_eq!(manager.current_mission().unwrap().description, "Task2");
+        assert_eq!(manager.history()[0].status, MissionStatus::Cancelled);
+        assert_eq!(manager.missions().count(), 2);
+    }
+
+    #[test]
+    fn test_rollout_roundtrip_keeps_reflected_flags() {
+        let mut manager = MissionManager::new();
+        manager.start_mission("Task1".to_string(), "session-1".to_string());
+        let steps = vec![
+            ("Step1".to_string(), StepStatus::Completed),
+            ("Step2".to_string(), StepStatus::Completed),
+        ];
+        manager.update_todos(steps, "session-1".to_string());
+        let todo_id = manager.current_mission().unwrap().todos[0].id.clone();
+        manager.mark_todo_reflected(&todo_id);
+
+        let items = vec![
+            MissionManager::new().to_rollout_item().unwrap(),
+            manager.to_rollout_item().unwrap(),
+        ];
+        let restored = MissionManager::from_rollout_items(&items).unwrap();
+
+        let unreflected = restored.get_unreflected_completed_todos();
+        assert_eq!(unreflected.len(), 1);
+        assert_eq!(unreflected[0].step, "Step2");
+        assert!(MissionManager::from_rollout_items(&[]).is_none());
     }
 }
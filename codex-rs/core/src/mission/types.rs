@@ -98,40 +98,47 @@ impl MissionContext {
         let now = Utc::now();
         self.updated_at = now;
 
-        // 记录之前已完成的 todo 的 step 描述
-        let previously_completed: std::collections::HashSet<String> = self
-            .todos
-            .iter()
-            .filter(|t| matches!(t.status, StepStatus::Completed))
-            .map(|t| t.step.clone())
-            .collect();
-
-        // 清空现有的 todos（会被新的列表替换）
-        self.todos.clear();
+        // 之前的 todos（按 step 描述匹配，保留 id、创建时间和反射标记）
+        let mut previous: std::collections::HashMap<String, TodoItem> =
+            self.todos.drain(..).map(|t| (t.step.clone(), t)).collect();
 
-        // 创建新的 todos
         let mut newly_completed = Vec::new();
 
         for (step, status) in new_steps {
-            let is_newly_completed =
-                matches!(status, StepStatus::Completed) && !previously_completed.contains(&step);
-
             let is_completed = matches!(status, StepStatus::Completed);
 
-            let todo = TodoItem {
-                id: Uuid::new_v4().to_string(),
-                step: step.clone(),
-                status,
-                created_at: now,
-                updated_at: now,
-                reflected: false,
-                completed_at: if is_completed { Some(now) } else { None },
+            let todo = match previous.remove(&step) {
+                Some(mut todo) => {
+                    let was_completed = matches!(todo.status, StepStatus::Completed);
+                    if std::mem::discriminant(&todo.status) != std::mem::discriminant(&status) {
+                        todo.updated_at = now;
+                    }
+                    todo.status = status;
+                    if is_completed && !was_completed {
+                        todo.completed_at = Some(now);
+                        newly_completed.push(todo.clone());
+                    } else if !is_completed {
+                        todo.completed_at = None;
+                    }
+                    todo
+                }
+                None => {
+                    let todo = TodoItem {
+                        id: Uuid::new_v4().to_string(),
+                        step,
+                        status,
+                        created_at: now,
+                        updated_at: now,
+                        reflected: false,
+                        completed_at: if is_completed { Some(now) } else { None },
+                    };
+                    if is_completed {
+                        newly_completed.push(todo.clone());
+                    }
+                    todo
+                }
             };
 
-            if is_newly_completed {
-                newly_completed.push(todo.clone());
-            }
-
             self.todos.push(todo);
         }
 
@@ -292,4 +299,25 @@ mod tests {
         assert_eq!(newly_completed.len(), 1);
         assert_eq!(newly_completed[0].step, "步骤2");
     }
+
+    #[test]
+    fn test_update_todos_keeps_existing_todo_state() {
+        let mut mission = MissionContext::new("测试任务".to_string(), "session-123".to_string());
+
+        mission.update_todos(vec![
+            ("步骤1".to_string(), StepStatus::Completed),
+            ("步骤2".to_string(), StepStatus::Pending),
+        ]);
+        let todo_id = mission.todos[0].id.clone();
+        mission.mark_todo_reflected(&todo_id);
+
+        mission.update_todos(vec![
+            ("步骤1".to_string(), StepStatus::Completed),
+            ("步骤2".to_string(), StepStatus::InProgress),
+        ]);
+
+        assert_eq!(mission.todos[0].id, todo_id);
+        assert!(mission.todos[0].reflected);
+        assert!(mission.get_unreflected_completed_todos().is_empty());
+    }
 }
@@ -432,7 +432,7 @@ async fn read_head_and_tail(
             RolloutItem::TurnContext(_) => {
                 // Not included in `head`; skip.
             }
-            RolloutItem::Compacted(_) => {
+            RolloutItem::Compacted(_) | RolloutItem::MissionState(_) => {
                 // Not included in `head`; skip.
             }
             RolloutItem::EventMsg(ev) => {
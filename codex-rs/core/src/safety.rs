@@ -114,6 +114,46 @@ pub fn get_platform_sandbox() -> Option<SandboxType> {
     }
 }
 
+/// Whether `path` (resolved against `cwd`) lies inside the writable roots of
+/// `sandbox_policy`. Used to decide whether exec policy matches that write
+/// files can run without asking.
+pub(crate) fn is_path_writable(path: &Path, sandbox_policy: &SandboxPolicy, cwd: &Path) -> bool {
+    match sandbox_policy {
+        SandboxPolicy::ReadOnly { .. } => false,
+        SandboxPolicy::DangerFullAccess => true,
+        SandboxPolicy::WorkspaceWrite { .. } => {
+            let abs = if path.is_absolute() {
+                path.to_path_buf()
+            } else {
+                cwd.join(path)
+            };
+            let Some(abs) = normalize(&abs) else {
+                return false;
+            };
+            sandbox_policy
+                .get_writable_roots_with_cwd(cwd)
+                .iter()
+                .any(|writable_root| writable_root.is_path_writable(&abs))
+        }
+    }
+}
+
+// Normalize a path by removing `.` and resolving `..` without touching the
+// filesystem (works even if the file does not exist).
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => { /* skip */ }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    Some(out)
+}
+
 fn is_write_patch_constrained_to_writable_paths(
     action: &ApplyPatchAction,
     sandbox_policy: &SandboxPolicy,
@@ -121,7 +161,7 @@ fn is_write_patch_constrained_to_writable_paths(
 ) -> bool {
     // Early‑exit if there are no declared writable roots.
     let writable_roots = match sandbox_policy {
-        SandboxPolicy::ReadOnly => {
+        SandboxPolicy::ReadOnly { .. } => {
             return false;
         }
         SandboxPolicy::DangerFullAccess => {
@@ -130,22 +170,6 @@ fn is_write_patch_constrained_to_writable_paths(
         SandboxPolicy::WorkspaceWrite { .. } => sandbox_policy.get_writable_roots_with_cwd(cwd),
     };
 
-    // Normalize a path by removing `.` and resolving `..` without touching the
-    // filesystem (works even if the file does not exist).
-    fn normalize(path: &Path) -> Option<PathBuf> {
-        let mut out = PathBuf::new();
-        for comp in path.components() {
-            match comp {
-                Component::ParentDir => {
-                    out.pop();
-                }
-                Component::CurDir => { /* skip */ }
-                other => out.push(other.as_os_str()),
-            }
-        }
-        Some(out)
-    }
-
     // Determine whether `path` is inside **any** writable root. Both `path`
     // and roots are converted to absolute, normalized forms before the
     // prefix check.
@@ -212,6 +236,8 @@ mod tests {
         let policy_workspace_only = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: Vec::new(),
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -233,6 +259,8 @@ mod tests {
         let policy_with_parent = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![parent],
             network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: Vec::new(),
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -195,7 +195,7 @@ pub(crate) async fn assess_command(
 fn summarize_sandbox_policy(policy: &SandboxPolicy) -> String {
     match policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-        SandboxPolicy::ReadOnly => "read-only".to_string(),
+        SandboxPolicy::ReadOnly { .. } => "read-only".to_string(),
         SandboxPolicy::WorkspaceWrite { network_access, .. } => {
             let network = if *network_access {
                 "network"
@@ -234,7 +234,7 @@ fn sandbox_assessment_schema() -> serde_json::Value {
     })
 }
 
-fn response_item_text(item: &ResponseItem) -> Option<String> {
+pub(crate) fn response_item_text(item: &ResponseItem) -> Option<String> {
     match item {
         ResponseItem::Message { content, .. } => {
             let mut buffers: Vec<&str> = Vec::new();
@@ -0,0 +1,275 @@
+//! Matching of the `deny_read` entries of a [`SandboxPolicy`].
+//!
+//! Entries are absolute, `~/`-relative or relative to the sandbox cwd, and may
+//! use `*` and `?` wildcards (`*` also matches across `/`). A path is denied
+//! when it, or one of its ancestors, matches an entry; both the path as given
+//! and its canonical form are checked so symlinks cannot be used to get
+//! around the list.
+
+use std::collections::HashSet;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use wildmatch::WildMatchPattern;
+
+use crate::protocol::SandboxPolicy;
+
+type GlobPattern = WildMatchPattern<'*', '?'>;
+
+/// How deep below the literal prefix of a wildcard entry
+/// [`DenyReadList::resolve_existing`] looks for matches.
+const MAX_GLOB_DEPTH: usize = 8;
+
+/// Upper bound on the directory entries visited per wildcard entry.
+const MAX_GLOB_VISITED_ENTRIES: usize = 10_000;
+
+#[derive(Debug)]
+enum DenyEntry {
+    Exact {
+        path: PathBuf,
+        canonical: Option<PathBuf>,
+    },
+    Glob {
+        /// Directory made of the components before the first wildcard.
+        base: PathBuf,
+        patterns: Vec<GlobPattern>,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct DenyReadList {
+    entries: Vec<DenyEntry>,
+    /// Base for relative entries and for relative paths passed to
+    /// [`DenyReadList::is_denied`].
+    cwd: PathBuf,
+}
+
+impl DenyReadList {
+    pub fn new(sandbox_policy: &SandboxPolicy, cwd: &Path) -> Self {
+        Self::from_entries(sandbox_policy.deny_read(), cwd)
+    }
+
+    pub fn from_entries(entries: &[String], cwd: &Path) -> Self {
+        let entries = entries
+            .iter()
+            .filter_map(|entry| expand_entry(entry, cwd))
+            .map(|path| {
+                if has_wildcard(&path) {
+                    let base = literal_prefix(&path);
+                    let mut patterns = vec![GlobPattern::new(&path.to_string_lossy())];
+                    if let Ok(canonical_base) = base.canonicalize()
+                        && canonical_base != base
+                        && let Ok(rest) = path.strip_prefix(&base)
+                    {
+                        patterns.push(GlobPattern::new(
+                            &canonical_base.join(rest).to_string_lossy(),
+                        ));
+                    }
+                    DenyEntry::Glob { base, patterns }
+                } else {
+                    let canonical = path.canonicalize().ok();
+                    DenyEntry::Exact { path, canonical }
+                }
+            })
+            .collect();
+        Self {
+            entries,
+            cwd: cwd.to_path_buf(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether reading `path` (relative paths are resolved against the cwd
+    /// of the list) is blocked.
+    pub fn is_denied(&self, path: &Path) -> bool {
+        if self.entries.is_empty() {
+            return false;
+        }
+        let path = normalize(&self.cwd.join(path));
+        if self.matches_self_or_ancestor(&path) {
+            return true;
+        }
+        path.canonicalize()
+            .is_ok_and(|canonical| self.matches_self_or_ancestor(&canonical))
+    }
+
+    /// Canonical paths of the existing files and directories covered by the
+    /// list. Wildcard entries are expanded with a bounded walk.
+    pub fn resolve_existing(&self) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        let mut resolved = Vec::new();
+        for entry in &self.entries {
+            let paths: Vec<PathBuf> = match entry {
+                DenyEntry::Exact { canonical, .. } => canonical.iter().cloned().collect(),
+                DenyEntry::Glob { base, patterns } => expand_glob(base, patterns),
+            };
+            for path in paths {
+                if seen.insert(path.clone()) {
+                    resolved.push(path);
+                }
+            }
+        }
+        resolved
+    }
+
+    fn matches_self_or_ancestor(&self, path: &Path) -> bool {
+        path.ancestors().any(|candidate| {
+            self.entries.iter().any(|entry| match entry {
+                DenyEntry::Exact {
+                    path: denied,
+                    canonical,
+                } => candidate == denied || canonical.as_deref() == Some(candidate),
+                DenyEntry::Glob { patterns, .. } => {
+                    let candidate = candidate.to_string_lossy();
+                    patterns.iter().any(|pattern| pattern.matches(&candidate))
+                }
+            })
+        })
+    }
+}
+
+/// Expands `~` and makes `entry` absolute. Entries under `~` are dropped when
+/// the home directory is unknown.
+fn expand_entry(entry: &str, cwd: &Path) -> Option<PathBuf> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return None;
+    }
+    let path = if entry == "~" {
+        dirs::home_dir()?
+    } else if let Some(rest) = entry.strip_prefix("~/") {
+        dirs::home_dir()?.join(rest)
+    } else {
+        cwd.join(entry)
+    };
+    Some(normalize(&path))
+}
+
+/// Removes `.` and `..` components without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+fn has_wildcard(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?'])
+}
+
+fn literal_prefix(path: &Path) -> PathBuf {
+    path.components()
+        .take_while(|component| !component.as_os_str().to_string_lossy().contains(['*', '?']))
+        .collect()
+}
+
+fn expand_glob(base: &Path, patterns: &[GlobPattern]) -> Vec<PathBuf> {
+    let Ok(base) = base.canonicalize() else {
+        return Vec::new();
+    };
+    let mut matches = Vec::new();
+    let mut visited = 0;
+    let mut stack = vec![(base, 0)];
+    while let Some((dir, depth)) = stack.pop() {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            visited += 1;
+            if visited > MAX_GLOB_VISITED_ENTRIES {
+                return matches;
+            }
+            let path = entry.path();
+            let path_str = path.to_string_lossy();
+            if patterns.iter().any(|pattern| pattern.matches(&path_str)) {
+                if let Ok(canonical) = path.canonicalize() {
+                    matches.push(canonical);
+                }
+                continue;
+            }
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            if is_dir && depth + 1 < MAX_GLOB_DEPTH {
+                stack.push((path, depth + 1));
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn entries(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn denies_entries_and_everything_below_them() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path();
+        let list = DenyReadList::from_entries(&entries(&["secrets", "keys/*.pem"]), cwd);
+
+        assert!(list.is_denied(Path::new("secrets")));
+        assert!(list.is_denied(&cwd.join("secrets/nested/token")));
+        assert!(list.is_denied(Path::new("keys/server.pem")));
+        assert!(list.is_denied(Path::new("keys/a/b.pem")));
+        assert!(!list.is_denied(Path::new("keys/server.crt")));
+        assert!(!list.is_denied(Path::new("secrets-public")));
+        assert!(!list.is_denied(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn expands_home_directory() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let list = DenyReadList::from_entries(&entries(&["~/.ssh"]), Path::new("/"));
+        assert!(list.is_denied(&home.join(".ssh/id_rsa")));
+        assert!(!list.is_denied(&home.join(".bashrc")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_into_denied_paths_are_denied() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path();
+        std::fs::create_dir(cwd.join("secrets")).unwrap();
+        std::fs::write(cwd.join("secrets/token"), "hunter2").unwrap();
+        std::os::unix::fs::symlink(cwd.join("secrets"), cwd.join("innocent")).unwrap();
+
+        let list = DenyReadList::from_entries(&entries(&["secrets"]), cwd);
+        assert!(list.is_denied(Path::new("innocent/token")));
+    }
+
+    #[test]
+    fn resolve_existing_expands_wildcards() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir_all(cwd.join("keys/old")).unwrap();
+        std::fs::write(cwd.join("keys/server.pem"), "").unwrap();
+        std::fs::write(cwd.join("keys/old/client.pem"), "").unwrap();
+        std::fs::write(cwd.join("keys/readme.md"), "").unwrap();
+
+        let list = DenyReadList::from_entries(&entries(&["keys/*.pem", "missing"]), &cwd);
+        let mut resolved = list.resolve_existing();
+        resolved.sort();
+        assert_eq!(
+            resolved,
+            vec![cwd.join("keys/old/client.pem"), cwd.join("keys/server.pem")]
+        );
+    }
+}
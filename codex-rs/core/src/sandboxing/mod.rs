@@ -7,7 +7,9 @@ ready‑to‑spawn environment.
 */
 
 pub mod assessment;
+pub mod deny_read;
 
+use crate::config::types::SandboxContainer;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
@@ -70,17 +72,18 @@ impl SandboxManager {
         &self,
         policy: &SandboxPolicy,
         pref: SandboxablePreference,
+        container: Option<&SandboxContainer>,
     ) -> SandboxType {
         match pref {
             SandboxablePreference::Forbid => SandboxType::None,
             SandboxablePreference::Require => {
                 // Require a platform sandbox when available; on Windows this
                 // respects the enable_experimental_windows_sandbox feature.
-                crate::safety::get_platform_sandbox().unwrap_or(SandboxType::None)
+                platform_sandbox(container).unwrap_or(SandboxType::None)
             }
             SandboxablePreference::Auto => match policy {
                 SandboxPolicy::DangerFullAccess => SandboxType::None,
-                _ => crate::safety::get_platform_sandbox().unwrap_or(SandboxType::None),
+                _ => platform_sandbox(container).unwrap_or(SandboxType::None),
             },
         }
     }
@@ -92,6 +95,8 @@ impl SandboxManager {
         sandbox: SandboxType,
         sandbox_policy_cwd: &Path,
         codex_linux_sandbox_exe: Option<&PathBuf>,
+        container: Option<&SandboxContainer>,
+        report_denied_reads: bool,
     ) -> Result<ExecEnv, SandboxTransformError> {
         let mut env = spec.env.clone();
         if !policy.has_full_network_access() {
@@ -117,11 +122,20 @@ impl SandboxManager {
                 full_command.append(&mut args);
                 (full_command, seatbelt_env, None)
             }
-            SandboxType::LinuxSeccomp => {
+            SandboxType::LinuxSeccomp | SandboxType::LinuxContainer => {
                 let exe = codex_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
-                let mut args =
-                    create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
+                // Commands run directly through `process_exec_tool_call` carry no
+                // container settings; they get the default host-directory layout.
+                let container = (sandbox == SandboxType::LinuxContainer)
+                    .then(|| container.cloned().unwrap_or_default());
+                let mut args = create_linux_sandbox_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    container.as_ref(),
+                    report_denied_reads,
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
                 full_command.append(&mut args);
@@ -160,6 +174,15 @@ impl SandboxManager {
     }
 }
 
+/// The platform sandbox, switched to [`SandboxType::LinuxContainer`] when a
+/// container is configured.
+fn platform_sandbox(container: Option<&SandboxContainer>) -> Option<SandboxType> {
+    crate::safety::get_platform_sandbox().map(|sandbox| match (sandbox, container) {
+        (SandboxType::LinuxSeccomp, Some(_)) => SandboxType::LinuxContainer,
+        (sandbox, _) => sandbox,
+    })
+}
+
 pub async fn execute_env(
     env: &ExecEnv,
     policy: &SandboxPolicy,
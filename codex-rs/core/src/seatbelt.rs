@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use tokio::process::Child;
 
 use crate::protocol::SandboxPolicy;
+use crate::sandboxing::deny_read::DenyReadList;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
@@ -35,6 +36,7 @@ pub async fn spawn_command_under_seatbelt(
         sandbox_policy,
         stdio_policy,
         env,
+        None,
     )
     .await
 }
@@ -44,7 +46,7 @@ pub(crate) fn create_seatbelt_command_args(
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
 ) -> Vec<String> {
-    let (file_write_policy, extra_cli_args) = {
+    let (mut file_write_policy, mut extra_cli_args) = {
         if sandbox_policy.has_full_disk_write_access() {
             // Allegedly, this is more permissive than `(allow file-write*)`.
             (
@@ -97,11 +99,27 @@ pub(crate) fn create_seatbelt_command_args(
         }
     };
 
-    let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
-    } else {
-        ""
-    };
+    let mut file_read_policy = "; allow read-only file operations\n(allow file-read*)".to_string();
+    if !sandbox_policy.has_full_disk_read_access() {
+        // Later rules take precedence, so these carve the `deny_read` paths
+        // out of the blanket read access above. Metadata stays readable so
+        // listing a parent directory keeps working.
+        let denied = DenyReadList::new(sandbox_policy, sandbox_policy_cwd).resolve_existing();
+        for (index, path) in denied.iter().enumerate() {
+            let param = format!("DENY_READ_{index}");
+            extra_cli_args.push(format!("-D{param}={}", path.to_string_lossy()));
+            file_read_policy.push_str(&format!(
+                "\n(deny file-read-data file-read-xattr (subpath (param \"{param}\")))"
+            ));
+            // Inside a writable root the path could otherwise be moved or
+            // copied somewhere readable.
+            if !file_write_policy.is_empty() {
+                file_write_policy.push_str(&format!(
+                    "\n(deny file-write* (subpath (param \"{param}\")))"
+                ));
+            }
+        }
+    }
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
     let network_policy = if sandbox_policy.has_full_network_access() {
@@ -156,6 +174,8 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![root_with_git, root_without_git],
             network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: Vec::new(),
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
         };
@@ -231,6 +251,8 @@ mod tests {
         let policy = SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: Vec::new(),
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         };
@@ -301,6 +323,86 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn create_seatbelt_args_with_deny_read() {
+        let tmp = TempDir::new().expect("tempdir");
+        let secrets = tmp.path().join("secrets");
+        fs::create_dir_all(&secrets).expect("create secrets");
+        let secrets_canon = secrets.canonicalize().expect("canonicalize secrets");
+
+        let policy = SandboxPolicy::ReadOnly {
+            deny_read: vec![secrets.to_string_lossy().to_string()],
+        };
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            tmp.path(),
+        );
+
+        let expected_policy = format!(
+            r#"{MACOS_SEATBELT_BASE_POLICY}
+; allow read-only file operations
+(allow file-read*)
+(deny file-read-data file-read-xattr (subpath (param "DENY_READ_0")))
+
+"#,
+        );
+        let expected_args = vec![
+            "-p".to_string(),
+            expected_policy,
+            format!("-DDENY_READ_0={}", secrets_canon.to_string_lossy()),
+            "--".to_string(),
+            "/bin/echo".to_string(),
+            "hello".to_string(),
+        ];
+        assert_eq!(expected_args, args);
+    }
+
+    #[test]
+    fn create_seatbelt_args_deny_writes_to_denied_paths_in_writable_roots() {
+        let tmp = TempDir::new().expect("tempdir");
+        let secrets = tmp.path().join("secrets");
+        fs::create_dir_all(&secrets).expect("create secrets");
+        let tmp_canon = tmp.path().canonicalize().expect("canonicalize tmp");
+        let secrets_canon = secrets.canonicalize().expect("canonicalize secrets");
+
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: vec!["secrets".to_string()],
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            tmp.path(),
+        );
+
+        let expected_policy = format!(
+            r#"{MACOS_SEATBELT_BASE_POLICY}
+; allow read-only file operations
+(allow file-read*)
+(deny file-read-data file-read-xattr (subpath (param "DENY_READ_0")))
+(allow file-write*
+(subpath (param "WRITABLE_ROOT_0"))
+)
+(deny file-write* (subpath (param "DENY_READ_0")))
+"#,
+        );
+        let expected_args = vec![
+            "-p".to_string(),
+            expected_policy,
+            format!("-DWRITABLE_ROOT_0={}", tmp_canon.to_string_lossy()),
+            format!("-DDENY_READ_0={}", secrets_canon.to_string_lossy()),
+            "--".to_string(),
+            "/bin/echo".to_string(),
+            "hello".to_string(),
+        ];
+        assert_eq!(expected_args, args);
+    }
+
     struct PopulatedTmp {
         root_with_git: PathBuf,
         root_without_git: PathBuf,
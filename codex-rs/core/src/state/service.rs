@@ -2,6 +2,9 @@ use std::sync::Arc;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
+use crate::codex::SessionExtensions;
+use crate::command_safety::approval_rules::ProjectApprovalRules;
+use crate::exec_policy::ExecPolicy;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
@@ -19,9 +22,12 @@ pub(crate) struct SessionServices {
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
-    #[cfg(feature = "ace")]
-    #[allow(dead_code)] // Hook功能暂未完全集成
+    pub(crate) exec_policy: ExecPolicy,
+    pub(crate) approval_rules: Mutex<ProjectApprovalRules>,
+    /// 生命周期 hooks（`[hooks]` 外部命令和 ACE 插件），没有任何 hook 时为 None
     pub(crate) hook_manager: Option<Arc<crate::hooks::HookManager>>,
+    /// 前端提供的会话依赖，子代理会话沿用
+    pub(crate) extensions: SessionExtensions,
     /// ACE Plugin 直接引用（用于 Mission/Todo 触发等高级功能）
     #[cfg(feature = "ace")]
     pub(crate) ace_plugin: Option<Arc<crate::ace::ACEPlugin>>,
@@ -12,6 +12,8 @@ use codex_protocol::models::ShellToolCallParams;
 use codex_protocol::protocol::FileChange;
 use codex_utils_string::take_bytes_at_char_boundary;
 use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::TextContent;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -92,6 +94,38 @@ impl ToolOutput {
         }
     }
 
+    /// Appends a note produced by lifecycle hooks to the output returned to the model.
+    pub fn append_annotation(&mut self, annotation: &str) {
+        match self {
+            ToolOutput::Function {
+                content,
+                content_items,
+                ..
+            } => {
+                if !content.is_empty() {
+                    content.push_str("\n\n");
+                }
+                content.push_str(annotation);
+                if let Some(items) = content_items {
+                    items.push(FunctionCallOutputContentItem::InputText {
+                        text: annotation.to_string(),
+                    });
+                }
+            }
+            ToolOutput::Mcp { result: Ok(result) } => {
+                result.content.push(ContentBlock::TextContent(TextContent {
+                    annotations: None,
+                    text: annotation.to_string(),
+                    r#type: "text".to_string(),
+                }));
+            }
+            ToolOutput::Mcp { result: Err(err) } => {
+                err.push_str("\n\n");
+                err.push_str(annotation);
+            }
+        }
+    }
+
     pub fn into_response(self, call_id: &str, payload: &ToolPayload) -> ResponseInputItem {
         match self {
             ToolOutput::Function {
@@ -212,6 +246,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hook_annotations_are_appended_to_outputs() {
+        let mut output = ToolOutput::Function {
+            content: "ok".to_string(),
+            content_items: None,
+            success: Some(true),
+        };
+        output.append_annotation("formatted 2 files");
+        match output {
+            ToolOutput::Function { content, .. } => {
+                assert_eq!(content, "ok\n\nformatted 2 files");
+            }
+            ToolOutput::Mcp { .. } => panic!("expected function output"),
+        }
+
+        let mut output = ToolOutput::Mcp {
+            result: Err("failed".to_string()),
+        };
+        output.append_annotation("see audit log");
+        match output {
+            ToolOutput::Mcp { result } => {
+                assert_eq!(result, Err("failed\n\nsee audit log".to_string()));
+            }
+            ToolOutput::Function { .. } => panic!("expected mcp output"),
+        }
+    }
+
     #[test]
     fn telemetry_preview_returns_original_within_limits() {
         let content = "short output";
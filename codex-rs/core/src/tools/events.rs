@@ -287,12 +287,29 @@ impl ToolEmitter {
                 (event, result)
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
-            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output })))
+            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::NetworkDenied {
+                output, ..
+            }))) => {
                 let response = super::format_exec_output_for_model(&output);
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
                 let result = Err(FunctionCallError::RespondToModel(response));
                 (event, result)
             }
+            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::ReadDenied { paths, output }))) => {
+                let blocked = paths
+                    .iter()
+                    .map(|path| format!("`{}`", path.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let response = format!(
+                    "sandbox deny_read list blocked reading {blocked}\n{}",
+                    super::format_exec_output_for_model(&output)
+                );
+                let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
+                let result = Err(FunctionCallError::RespondToModel(response));
+                (event, result)
+            }
             Err(ToolError::Codex(err)) => {
                 let message = format!("execution error: {err:?}");
                 let event = ToolEventStage::Failure(ToolEventFailure::Message(message.clone()));
@@ -10,9 +10,11 @@ use serde::Deserialize;
 use tokio::fs;
 
 use crate::function_tool::FunctionCallError;
+use crate::sandboxing::deny_read::DenyReadList;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::read_denied_error;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 
@@ -51,7 +53,7 @@ impl ToolHandler for ListDirHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -100,7 +102,12 @@ impl ToolHandler for ListDirHandler {
             ));
         }
 
-        let entries = list_dir_slice(&path, offset, limit, depth).await?;
+        let deny_read = DenyReadList::new(&turn.sandbox_policy, &turn.cwd);
+        if deny_read.is_denied(&path) {
+            return Err(read_denied_error(&path));
+        }
+
+        let entries = list_dir_slice(&path, offset, limit, depth, &deny_read).await?;
         let mut output = Vec::with_capacity(entries.len() + 1);
         output.push(format!("Absolute path: {}", path.display()));
         output.extend(entries);
@@ -117,9 +124,10 @@ async fn list_dir_slice(
     offset: usize,
     limit: usize,
     depth: usize,
+    deny_read: &DenyReadList,
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut entries = Vec::new();
-    collect_entries(path, Path::new(""), depth, &mut entries).await?;
+    collect_entries(path, Path::new(""), depth, deny_read, &mut entries).await?;
 
     if entries.is_empty() {
         return Ok(Vec::new());
@@ -154,6 +162,7 @@ async fn collect_entries(
     dir_path: &Path,
     relative_prefix: &Path,
     depth: usize,
+    deny_read: &DenyReadList,
     entries: &mut Vec<DirEntry>,
 ) -> Result<(), FunctionCallError> {
     let mut queue = VecDeque::new();
@@ -169,6 +178,11 @@ async fn collect_entries(
         while let Some(entry) = read_dir.next_entry().await.map_err(|err| {
             FunctionCallError::RespondToModel(format!("failed to read directory: {err}"))
         })? {
+            // Denied entries are left out of the listing altogether.
+            if deny_read.is_denied(&entry.path()) {
+                continue;
+            }
+
             let file_type = entry.file_type().await.map_err(|err| {
                 FunctionCallError::RespondToModel(format!("failed to inspect entry: {err}"))
             })?;
@@ -307,7 +321,7 @@ mod tests {
             symlink(dir_path.join("entry.txt"), &link_path).expect("create symlink");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 20, 3)
+        let entries = list_dir_slice(dir_path, 1, 20, 3, &DenyReadList::default())
             .await
             .expect("list directory");
 
@@ -341,7 +355,7 @@ mod tests {
             .await
             .expect("create sub dir");
 
-        let err = list_dir_slice(dir_path, 10, 1, 2)
+        let err = list_dir_slice(dir_path, 10, 1, 2, &DenyReadList::default())
             .await
             .expect_err("offset exceeds entries");
         assert_eq!(
@@ -368,7 +382,7 @@ mod tests {
             .await
             .expect("write deeper");
 
-        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1)
+        let entries_depth_one = list_dir_slice(dir_path, 1, 10, 1, &DenyReadList::default())
             .await
             .expect("list depth 1");
         assert_eq!(
@@ -376,7 +390,7 @@ mod tests {
             vec!["nested/".to_string(), "root.txt".to_string(),]
         );
 
-        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2)
+        let entries_depth_two = list_dir_slice(dir_path, 1, 20, 2, &DenyReadList::default())
             .await
             .expect("list depth 2");
         assert_eq!(
@@ -389,7 +403,7 @@ mod tests {
             ]
         );
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 30, 3)
+        let entries_depth_three = list_dir_slice(dir_path, 1, 30, 3, &DenyReadList::default())
             .await
             .expect("list depth 3");
         assert_eq!(
@@ -418,7 +432,7 @@ mod tests {
             .await
             .expect("write gamma");
 
-        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1)
+        let entries = list_dir_slice(dir_path, 2, usize::MAX, 1, &DenyReadList::default())
             .await
             .expect("list without overflow");
         assert_eq!(
@@ -439,7 +453,7 @@ mod tests {
                 .expect("write file");
         }
 
-        let entries = list_dir_slice(dir_path, 1, 25, 1)
+        let entries = list_dir_slice(dir_path, 1, 25, 1, &DenyReadList::default())
             .await
             .expect("list directory");
         assert_eq!(entries.len(), 26);
@@ -461,7 +475,8 @@ mod tests {
         tokio::fs::write(nested.join("child.txt"), b"child").await?;
         tokio::fs::write(deeper.join("grandchild.txt"), b"deep").await?;
 
-        let entries_depth_three = list_dir_slice(dir_path, 1, 3, 3).await?;
+        let entries_depth_three =
+            list_dir_slice(dir_path, 1, 3, 3, &DenyReadList::default()).await?;
         assert_eq!(
             entries_depth_three,
             vec![
@@ -474,4 +489,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn omits_denied_entries() -> anyhow::Result<()> {
+        let temp = tempdir()?;
+        let dir_path = temp.path();
+        let secrets = dir_path.join("secrets");
+        tokio::fs::create_dir(&secrets).await?;
+        tokio::fs::write(secrets.join("token"), b"hunter2").await?;
+        tokio::fs::write(dir_path.join("id_rsa"), b"key").await?;
+        tokio::fs::write(dir_path.join("notes.txt"), b"notes").await?;
+
+        let deny_read =
+            DenyReadList::from_entries(&["secrets".to_string(), "id_*".to_string()], dir_path);
+        let entries = list_dir_slice(dir_path, 1, 10, 2, &deny_read).await?;
+        assert_eq!(entries, vec!["notes.txt".to_string()]);
+
+        Ok(())
+    }
 }
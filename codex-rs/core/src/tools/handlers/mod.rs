@@ -4,6 +4,8 @@ mod list_dir;
 mod mcp;
 mod mcp_resource;
 mod plan;
+#[cfg(feature = "ace")]
+mod playbook_search;
 mod read_file;
 mod shell;
 mod test_sync;
@@ -18,8 +20,22 @@ pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
+#[cfg(feature = "ace")]
+pub use playbook_search::PlaybookSearchHandler;
 pub use read_file::ReadFileHandler;
 pub use shell::ShellHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
+
+use std::path::Path;
+
+use crate::function_tool::FunctionCallError;
+
+/// Error the file tools return for paths on the sandbox `deny_read` list.
+fn read_denied_error(path: &Path) -> FunctionCallError {
+    FunctionCallError::RespondToModel(format!(
+        "`{}` is blocked by the sandbox deny_read list",
+        path.display()
+    ))
+}
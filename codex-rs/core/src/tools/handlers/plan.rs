@@ -3,6 +3,7 @@ use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -69,6 +70,7 @@ impl ToolHandler for PlanHandler {
         let ToolInvocation {
             session,
             turn,
+            tracker,
             call_id,
             payload,
             ..
@@ -83,8 +85,14 @@ impl ToolHandler for PlanHandler {
             }
         };
 
-        let content =
-            handle_update_plan(session.as_ref(), turn.as_ref(), arguments, call_id).await?;
+        let content = handle_update_plan(
+            session.as_ref(),
+            turn.as_ref(),
+            &tracker,
+            arguments,
+            call_id,
+        )
+        .await?;
 
         Ok(ToolOutput::Function {
             content,
@@ -100,6 +108,7 @@ impl ToolHandler for PlanHandler {
 pub(crate) async fn handle_update_plan(
     session: &Session,
     turn_context: &TurnContext,
+    tracker: &SharedTurnDiffTracker,
     arguments: String,
     _call_id: String,
 ) -> Result<String, FunctionCallError> {
@@ -113,8 +122,10 @@ pub(crate) async fn handle_update_plan(
     // Mission/Todo handling (only when ACE feature is enabled)
     #[cfg(feature = "ace")]
     {
-        handle_mission_todos(session, turn_context, &args).await;
+        handle_mission_todos(session, turn_context, tracker, &args).await;
     }
+    #[cfg(not(feature = "ace"))]
+    let _ = tracker;
 
     Ok("Plan updated".to_string())
 }
@@ -124,10 +135,11 @@ pub(crate) async fn handle_update_plan(
 async fn handle_mission_todos(
     session: &Session,
     turn_context: &TurnContext,
+    tracker: &SharedTurnDiffTracker,
     args: &UpdatePlanArgs,
 ) {
     // 1. Update MissionManager
-    let newly_completed = {
+    let unreflected = {
         let mut mission_mgr = session.services.mission_manager.lock().await;
         let steps: Vec<(String, codex_protocol::plan_tool::StepStatus)> = args
             .plan
@@ -135,14 +147,23 @@ async fn handle_mission_todos(
             .map(|item| (item.step.clone(), item.status.clone()))
             .collect();
 
-        mission_mgr.update_todos(steps, turn_context.sub_id.clone())
+        mission_mgr.update_todos(steps, turn_context.sub_id.clone());
+
+        // Includes todos completed before a resume that were never reflected on
+        mission_mgr
+            .get_unreflected_completed_todos()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
     };
 
-    // 2. If there are newly completed Todos, trigger Reflector
-    if !newly_completed.is_empty()
+    // 2. If there are unreflected completed Todos, trigger Reflector
+    if !unreflected.is_empty()
         && let Some(ref ace_plugin) = session.services.ace_plugin
     {
-        for todo in newly_completed {
+        // Changes made so far this turn, shared by every todo completed in it
+        let diff = tracker.lock().await.get_unified_diff().ok().flatten();
+        for todo in unreflected {
             tracing::info!("✅ Todo completed: {}", todo.step);
 
             // Build conversation context (contains explanation and plan info)
@@ -152,6 +173,7 @@ async fn handle_mission_todos(
             ace_plugin.on_todo_completed(
                 todo.step.clone(),
                 conversation_context,
+                diff.clone(),
                 turn_context.sub_id.clone(),
             );
 
@@ -160,6 +182,9 @@ async fn handle_mission_todos(
             mission_mgr.mark_todo_reflected(&todo.id);
         }
     }
+
+    // 3. Record the mission state so resumed sessions keep todo progress
+    session.record_mission_state().await;
 }
 
 /// Build conversation context for completed Todo
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::ace::Bullet;
+use crate::ace::BulletSection;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct PlaybookSearchHandler;
+
+const MAX_CONTENT_CHARS: usize = 400;
+
+fn default_limit() -> usize {
+    5
+}
+
+#[derive(Deserialize)]
+struct PlaybookSearchArgs {
+    query: String,
+    #[serde(default)]
+    section: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for PlaybookSearchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "playbook_search handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: PlaybookSearchArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let section = match args.section.as_deref() {
+            Some(title) => Some(BulletSection::from_title(title).ok_or_else(|| {
+                FunctionCallError::RespondToModel(format!("unknown playbook section: {title}"))
+            })?),
+            None => None,
+        };
+
+        let Some(plugin) = session.services.ace_plugin.as_ref() else {
+            return Err(FunctionCallError::RespondToModel(
+                "the playbook is not available in this session".to_string(),
+            ));
+        };
+        let bullets = plugin
+            .search_on_demand(query, section.as_ref(), &args.tags, args.limit)
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("playbook search failed: {err:#}"))
+            })?;
+
+        Ok(ToolOutput::Function {
+            content: format_results(query, &bullets),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn format_results(query: &str, bullets: &[Bullet]) -> String {
+    if bullets.is_empty() {
+        return format!("No playbook entries match \"{query}\".");
+    }
+
+    let mut output = format!("{} playbook entries for \"{query}\":", bullets.len());
+    for bullet in bullets {
+        output.push_str(&format!("\n- [{}] ", bullet.section.title()));
+        let content = bullet.content.trim();
+        match content.char_indices().nth(MAX_CONTENT_CHARS) {
+            Some((end, _)) => {
+                output.push_str(&content[..end]);
+                output.push_str("...");
+            }
+            None => output.push_str(content),
+        }
+        if !bullet.tags.is_empty() {
+            output.push_str(&format!(" (tags: {})", bullet.tags.join(", ")));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn format_results_lists_section_content_and_tags() {
+        let mut bullet = Bullet::new(
+            BulletSection::TroubleshootingAndPitfalls,
+            "Link with -fuse-ld=lld when the default linker runs out of memory".to_string(),
+            "session".to_string(),
+        );
+        bullet.tags = vec!["rust".to_string(), "linker".to_string()];
+
+        assert_eq!(
+            format_results("linker error", &[bullet]),
+            "1 playbook entries for \"linker error\":\n- [Troubleshooting and Pitfalls] Link with \
+             -fuse-ld=lld when the default linker runs out of memory (tags: rust, linker)"
+        );
+        assert_eq!(
+            format_results("linker error", &[]),
+            "No playbook entries match \"linker error\"."
+        );
+    }
+}
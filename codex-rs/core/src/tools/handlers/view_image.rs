@@ -5,9 +5,11 @@ use tokio::fs;
 use crate::function_tool::FunctionCallError;
 use crate::protocol::EventMsg;
 use crate::protocol::ViewImageToolCallEvent;
+use crate::sandboxing::deny_read::DenyReadList;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::read_denied_error;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use codex_protocol::user_input::UserInput;
@@ -48,6 +50,9 @@ impl ToolHandler for ViewImageHandler {
         })?;
 
         let abs_path = turn.resolve_path(Some(args.path));
+        if DenyReadList::new(&turn.sandbox_policy, &turn.cwd).is_denied(&abs_path) {
+            return Err(read_denied_error(&abs_path));
+        }
 
         let metadata = fs::metadata(&abs_path).await.map_err(|error| {
             FunctionCallError::RespondToModel(format!(
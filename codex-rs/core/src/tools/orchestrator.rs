@@ -2,13 +2,12 @@
 Module: orchestrator
 
 Central place for approvals + sandbox selection + retry semantics. Drives a
-simple sequence for any ToolRuntime: approval → select sandbox → attempt →
-retry without sandbox on denial (no re‑approval thanks to caching).
+simple sequence for any ToolRuntime: exec policy → approval → select sandbox →
+attempt → retry without sandbox on denial (no re‑approval thanks to caching).
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
-use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ProvidesSandboxRetryData;
@@ -17,6 +16,7 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ExecPolicyDecision;
 use codex_protocol::protocol::ReviewDecision;
 
 pub(crate) struct ToolOrchestrator {
@@ -48,15 +48,67 @@ impl ToolOrchestrator {
         let otel_user = codex_otel::otel_event_manager::ToolDecisionSource::User;
         let otel_cfg = codex_otel::otel_event_manager::ToolDecisionSource::Config;
 
-        // 1) Approval
-        let needs_initial_approval =
-            tool.wants_initial_approval(req, approval_policy, &turn_ctx.sandbox_policy);
+        // 1) Exec policy: forbid outright, auto-approve, or insist on approval.
+        // Commands the policy does not cover use the tool's own approval rules.
+        let retry_data = req.sandbox_retry_data();
+        let policy_evaluation = retry_data.as_ref().and_then(|metadata| {
+            tool_ctx.session.services.exec_policy.evaluate(
+                &metadata.command,
+                &metadata.cwd,
+                &turn_ctx.sandbox_policy,
+            )
+        });
+        let mut escalate_first_attempt = tool.wants_escalated_first_attempt(req);
+        // Patterns saved with "always allow in this project" skip the up-front
+        // prompt, but never a retry outside the sandbox or a run that asks to
+        // skip it.
+        let approved_for_project = match retry_data.as_ref() {
+            Some(metadata) if !escalate_first_attempt => tool_ctx
+                .session
+                .services
+                .approval_rules
+                .lock()
+                .await
+                .matches(&metadata.command),
+            _ => false,
+        };
+        let policy_match = policy_evaluation
+            .as_ref()
+            .map(|evaluation| evaluation.matched.clone());
+        // Policy matches only skip the prompt for sandboxed runs, and never
+        // for writes when the user wants to approve every untrusted command.
+        let policy_auto_approves = !escalate_first_attempt
+            && !(approval_policy == AskForApproval::UnlessTrusted
+                && policy_evaluation
+                    .as_ref()
+                    .is_some_and(|evaluation| evaluation.writes_files));
+        let needs_initial_approval = match policy_match.as_ref().map(|m| m.decision) {
+            Some(ExecPolicyDecision::Forbidden) => {
+                let reason = policy_match
+                    .as_ref()
+                    .and_then(|m| m.reason.clone())
+                    .unwrap_or_default();
+                return Err(ToolError::Rejected(format!(
+                    "command forbidden by exec policy: {reason}"
+                )));
+            }
+            _ if approved_for_project => false,
+            Some(ExecPolicyDecision::AutoApprove) if policy_auto_approves => false,
+            // Approving a write outside the writable roots only helps if the
+            // command then runs outside the sandbox; policies that never ask
+            // up front keep the sandbox and rely on its denial instead.
+            Some(ExecPolicyDecision::RequireApproval) if !escalate_first_attempt => !matches!(
+                approval_policy,
+                AskForApproval::Never | AskForApproval::OnFailure
+            ),
+            _ => tool.wants_initial_approval(req, approval_policy, &turn_ctx.sandbox_policy),
+        };
         let mut already_approved = false;
 
         if needs_initial_approval {
             let mut risk = None;
 
-            if let Some(metadata) = req.sandbox_retry_data() {
+            if let Some(metadata) = retry_data.as_ref() {
                 risk = tool_ctx
                     .session
                     .assess_sandbox_command(turn_ctx, &tool_ctx.call_id, &metadata.command, None)
@@ -69,6 +121,7 @@ impl ToolOrchestrator {
                 call_id: &tool_ctx.call_id,
                 retry_reason: None,
                 risk,
+                policy_match: policy_match.clone(),
             };
             let decision = tool.start_approval_async(req, approval_ctx).await;
 
@@ -78,18 +131,31 @@ impl ToolOrchestrator {
                 ReviewDecision::Denied | ReviewDecision::Abort => {
                     return Err(ToolError::Rejected("rejected by user".to_string()));
                 }
-                ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {}
+                ReviewDecision::Approved
+                | ReviewDecision::ApprovedForSession
+                | ReviewDecision::ApprovedForProject => {}
             }
             already_approved = true;
+            // Only a write outside the writable roots needs to leave the
+            // sandbox; the prompt said so. Other matches stay sandboxed and
+            // retry on a denial as usual.
+            if policy_evaluation.as_ref().is_some_and(|evaluation| {
+                evaluation.matched.decision == ExecPolicyDecision::RequireApproval
+                    && evaluation.escalate_on_approval
+            }) {
+                escalate_first_attempt = true;
+            }
         } else {
             otel.tool_decision(otel_tn, otel_ci, ReviewDecision::Approved, otel_cfg);
         }
 
         // 2) First attempt under the selected sandbox.
-        let mut initial_sandbox = self
-            .sandbox
-            .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference());
-        if tool.wants_escalated_first_attempt(req) {
+        let mut initial_sandbox = self.sandbox.select_initial(
+            &turn_ctx.sandbox_policy,
+            tool.sandbox_preference(),
+            turn_ctx.sandbox_container.as_ref(),
+        );
+        if escalate_first_attempt {
             initial_sandbox = crate::exec::SandboxType::None;
         }
         // Platform-specific flag gating is handled by SandboxManager::select_initial
@@ -100,6 +166,8 @@ impl ToolOrchestrator {
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            container: turn_ctx.sandbox_container.as_ref(),
+            report_denied_reads: turn_ctx.sandbox_report_denied_reads,
         };
 
         match tool.run(req, &initial_attempt, tool_ctx).await {
@@ -107,29 +175,28 @@ impl ToolOrchestrator {
                 // We have a successful initial result
                 Ok(out)
             }
-            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+            Err(ToolError::Codex(CodexErr::Sandbox(
+                denial @ (SandboxErr::Denied { .. }
+                | SandboxErr::NetworkDenied { .. }
+                | SandboxErr::ReadDenied { .. }),
+            ))) => {
                 if !tool.escalate_on_failure() {
-                    return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
-                        output,
-                    })));
+                    return Err(ToolError::Codex(CodexErr::Sandbox(denial)));
                 }
                 // Under `Never` or `OnRequest`, do not retry without sandbox; surface a concise
                 // sandbox denial that preserves the original output.
                 if !tool.wants_no_sandbox_approval(approval_policy) {
-                    return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
-                        output,
-                    })));
+                    return Err(ToolError::Codex(CodexErr::Sandbox(denial)));
                 }
 
                 // Ask for approval before retrying without sandbox.
                 if !tool.should_bypass_approval(approval_policy, already_approved) {
                     let mut risk = None;
+                    let reason_msg = build_denial_reason(&denial);
+                    let denial = CodexErr::Sandbox(denial);
 
                     if let Some(metadata) = req.sandbox_retry_data() {
-                        let err = SandboxErr::Denied {
-                            output: output.clone(),
-                        };
-                        let friendly = get_error_message_ui(&CodexErr::Sandbox(err));
+                        let friendly = get_error_message_ui(&denial);
                         let failure_summary = format!("failed in sandbox: {friendly}");
 
                         risk = tool_ctx
@@ -143,13 +210,13 @@ impl ToolOrchestrator {
                             .await;
                     }
 
-                    let reason_msg = build_denial_reason_from_output(output.as_ref());
                     let approval_ctx = ApprovalCtx {
                         session: tool_ctx.session,
                         turn: turn_ctx,
                         call_id: &tool_ctx.call_id,
                         retry_reason: Some(reason_msg),
                         risk,
+                        policy_match: policy_match.clone(),
                     };
 
                     let decision = tool.start_approval_async(req, approval_ctx).await;
@@ -159,7 +226,9 @@ impl ToolOrchestrator {
                         ReviewDecision::Denied | ReviewDecision::Abort => {
                             return Err(ToolError::Rejected("rejected by user".to_string()));
                         }
-                        ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {}
+                        ReviewDecision::Approved
+                        | ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedForProject => {}
                     }
                 }
 
@@ -169,6 +238,8 @@ impl ToolOrchestrator {
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
+                    container: None,
+                    report_denied_reads: false,
                 };
 
                 // Second attempt.
@@ -179,8 +250,22 @@ impl ToolOrchestrator {
     }
 }
 
-fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
+fn build_denial_reason(denial: &SandboxErr) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
-    // output so we can evolve heuristics later without touching call sites.
-    "command failed; retry without sandbox?".to_string()
+    // error so we can evolve heuristics later without touching call sites.
+    match denial {
+        SandboxErr::NetworkDenied { hosts, .. } => format!(
+            "network access to {} was blocked; retry without sandbox?",
+            hosts.join(", ")
+        ),
+        SandboxErr::ReadDenied { paths, .. } => format!(
+            "reading {} was blocked by the sandbox deny_read list; retry without sandbox?",
+            paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        _ => "command failed; retry without sandbox?".to_string(),
+    }
 }
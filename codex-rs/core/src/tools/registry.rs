@@ -8,6 +8,9 @@ use tracing::warn;
 
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::ToolCallDecision;
+use crate::hooks::ToolCallRecord;
+use crate::hooks::ToolCallRequest;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -94,6 +97,32 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        let hook_manager = invocation.session.services.hook_manager.clone();
+        let mut annotations = Vec::new();
+        if let Some(hook_manager) = &hook_manager {
+            let request = ToolCallRequest {
+                call_id: call_id_owned.clone(),
+                name: tool_name.clone(),
+                arguments: log_payload.to_string(),
+                cwd: invocation.turn.cwd.clone(),
+            };
+            match hook_manager.call_before_tool_call(&request).await {
+                ToolCallDecision::Proceed { annotation } => annotations.extend(annotation),
+                ToolCallDecision::Block { reason } => {
+                    let message = format!("tool call blocked by hook: {reason}");
+                    otel.tool_result(
+                        tool_name.as_ref(),
+                        &call_id_owned,
+                        log_payload.as_ref(),
+                        Duration::ZERO,
+                        false,
+                        &message,
+                    );
+                    return Err(FunctionCallError::RespondToModel(message));
+                }
+            }
+        }
+
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -124,11 +153,41 @@ impl ToolRegistry {
         match result {
             Ok(_) => {
                 let mut guard = output_cell.lock().await;
-                let output = guard.take().ok_or_else(|| {
+                let mut output = guard.take().ok_or_else(|| {
                     FunctionCallError::Fatal("tool produced no output".to_string())
                 })?;
+                if let Some(hook_manager) = &hook_manager {
+                    let record = ToolCallRecord {
+                        call_id: call_id_owned.clone(),
+                        name: tool_name.clone(),
+                        arguments: log_payload.to_string(),
+                        success: Some(output.success_for_logging()),
+                        output: output.log_preview(),
+                    };
+                    annotations.extend(hook_manager.call_after_tool_call(&record).await);
+                }
+                if !annotations.is_empty() {
+                    output.append_annotation(&annotations.join("\n"));
+                }
                 Ok(output.into_response(&call_id_owned, &payload_for_response))
             }
+            Err(FunctionCallError::RespondToModel(mut message)) => {
+                if let Some(hook_manager) = &hook_manager {
+                    let record = ToolCallRecord {
+                        call_id: call_id_owned.clone(),
+                        name: tool_name.clone(),
+                        arguments: log_payload.to_string(),
+                        success: Some(false),
+                        output: message.clone(),
+                    };
+                    annotations.extend(hook_manager.call_after_tool_call(&record).await);
+                }
+                if !annotations.is_empty() {
+                    message.push_str("\n\n");
+                    message.push_str(&annotations.join("\n"));
+                }
+                Err(FunctionCallError::RespondToModel(message))
+            }
             Err(err) => Err(err),
         }
     }
@@ -127,6 +127,8 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
                             cwd,
                             Some(reason),
                             risk,
+                            None,
+                            None,
                         )
                         .await
                 } else if user_explicitly_approved {
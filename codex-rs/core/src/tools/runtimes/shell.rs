@@ -4,6 +4,7 @@ Runtime: shell
 Executes shell requests under the orchestrator: asks for approval when needed,
 builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
+use crate::command_safety::approval_rules::suggest_pattern;
 use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
 use crate::command_safety::is_safe_command::is_known_safe_command;
 use crate::exec::ExecToolCallOutput;
@@ -102,13 +103,31 @@ impl Approvable<ShellRequest> for ShellRuntime {
             .clone()
             .or_else(|| req.justification.clone());
         let risk = ctx.risk.clone();
+        let policy_match = ctx.policy_match.clone();
+        // Project rules never cover escalated runs or retries outside the
+        // sandbox, so don't offer one.
+        let project_approval_pattern =
+            if req.with_escalated_permissions.unwrap_or(false) || ctx.retry_reason.is_some() {
+                None
+            } else {
+                suggest_pattern(&command)
+            };
         let session = ctx.session;
         let turn = ctx.turn;
         let call_id = ctx.call_id.to_string();
         Box::pin(async move {
             with_cached_approval(&session.services, key, move || async move {
                 session
-                    .request_command_approval(turn, call_id, command, cwd, reason, risk)
+                    .request_command_approval(
+                        turn,
+                        call_id,
+                        command,
+                        cwd,
+                        reason,
+                        risk,
+                        policy_match,
+                        project_approval_pattern,
+                    )
                     .await
             })
             .await
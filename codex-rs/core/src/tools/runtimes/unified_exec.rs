@@ -4,6 +4,7 @@ Runtime: unified exec
 Handles approval + sandbox orchestration for unified exec requests, delegating to
 the session manager to spawn PTYs once an ExecEnv is prepared.
 */
+use crate::command_safety::approval_rules::suggest_pattern;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::tools::runtimes::build_command_spec;
@@ -97,10 +98,27 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
         let cwd = req.cwd.clone();
         let reason = ctx.retry_reason.clone();
         let risk = ctx.risk.clone();
+        let policy_match = ctx.policy_match.clone();
+        // Project rules never cover retries outside the sandbox, so don't
+        // offer one.
+        let project_approval_pattern = if reason.is_some() {
+            None
+        } else {
+            suggest_pattern(&command)
+        };
         Box::pin(async move {
             with_cached_approval(&session.services, key, || async move {
                 session
-                    .request_command_approval(turn, call_id, command, cwd, reason, risk)
+                    .request_command_approval(
+                        turn,
+                        call_id,
+                        command,
+                        cwd,
+                        reason,
+                        risk,
+                        policy_match,
+                        project_approval_pattern,
+                    )
                     .await
             })
             .await
@@ -7,6 +7,7 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
+use crate::protocol::ExecPolicyMatch;
 use crate::protocol::SandboxCommandAssessment;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
@@ -69,7 +70,10 @@ where
 
     let decision = fetch().await;
 
-    if matches!(decision, ReviewDecision::ApprovedForSession) {
+    if matches!(
+        decision,
+        ReviewDecision::ApprovedForSession | ReviewDecision::ApprovedForProject
+    ) {
         let mut store = services.tool_approvals.lock().await;
         store.put(key, ReviewDecision::ApprovedForSession);
     }
@@ -84,6 +88,7 @@ pub(crate) struct ApprovalCtx<'a> {
     pub call_id: &'a str,
     pub retry_reason: Option<String>,
     pub risk: Option<SandboxCommandAssessment>,
+    pub policy_match: Option<ExecPolicyMatch>,
 }
 
 pub(crate) trait Approvable<Req> {
@@ -191,6 +196,8 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    pub container: Option<&'a crate::config::types::SandboxContainer>,
+    pub report_denied_reads: bool,
 }
 
 impl<'a> SandboxAttempt<'a> {
@@ -204,6 +211,8 @@ impl<'a> SandboxAttempt<'a> {
             self.sandbox,
             self.sandbox_cwd,
             self.codex_linux_sandbox_exe,
+            self.container,
+            self.report_denied_reads,
         )
     }
 }
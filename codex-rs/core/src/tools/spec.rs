@@ -28,6 +28,7 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_playbook_search_tool: bool,
     pub experimental_unified_exec_tool: bool,
     pub experimental_supported_tools: Vec<String>,
 }
@@ -48,6 +49,8 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_playbook_search_tool =
+            cfg!(feature = "ace") && features.enabled(Feature::PlaybookSearchTool);
 
         let shell_type = if use_streamable_shell_tool {
             ConfigShellToolType::Streamable
@@ -74,6 +77,7 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_playbook_search_tool,
             experimental_unified_exec_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
         }
@@ -305,6 +309,55 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
+#[cfg(feature = "ace")]
+fn create_playbook_search_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "What you need guidance on, e.g. an error message or the task at hand.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "section".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional playbook section to restrict results to (e.g. \"Troubleshooting and \
+                 Pitfalls\", \"Tool Usage Tips\")."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "tags".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some("Optional tags every returned entry must carry.".to_string()),
+        },
+    );
+    properties.insert(
+        "limit".to_string(),
+        JsonSchema::Number {
+            description: Some("Maximum number of entries to return (defaults to 5).".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "playbook_search".to_string(),
+        description: "Searches the playbook of lessons learned in earlier sessions. Use it when \
+                      you hit an unfamiliar error or start a new kind of task mid-turn."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -980,6 +1033,17 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    // Only set when ACE is compiled in (see `ToolsConfig::new`).
+    if config.include_playbook_search_tool {
+        #[cfg(feature = "ace")]
+        {
+            use crate::tools::handlers::PlaybookSearchHandler;
+
+            builder.push_spec_with_parallel_support(create_playbook_search_tool(), true);
+            builder.register_handler("playbook_search", Arc::new(PlaybookSearchHandler));
+        }
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -1226,6 +1290,28 @@ mod tests {
         assert!(find_tool(&tools, "read_file").supports_parallel_tool_calls);
     }
 
+    #[cfg(feature = "ace")]
+    #[test]
+    fn test_playbook_search_tool_gated_by_feature() {
+        let model_family = find_family_for_model("gpt-5-codex")
+            .expect("gpt-5-codex should be a valid model family");
+        let mut features = Features::with_defaults();
+        let has_playbook_search = |features: &Features| {
+            let config = ToolsConfig::new(&ToolsConfigParams {
+                model_family: &model_family,
+                features,
+            });
+            let (tools, _) = build_specs(&config, None).build();
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "playbook_search")
+        };
+        assert!(!has_playbook_search(&features));
+
+        features.enable(Feature::PlaybookSearchTool);
+        assert!(has_playbook_search(&features));
+    }
+
     #[test]
     fn test_test_model_family_includes_sync_tool() {
         let model_family = find_family_for_model("test-gpt-5-codex")
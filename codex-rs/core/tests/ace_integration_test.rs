@@ -33,6 +33,7 @@ mod ace_integration_tests {
             max_entries: 500,
             reflector: ReflectorConfig::default(),
             context: ContextConfig::default(),
+            ..Default::default()
         };
 
         // 初始化 ACE Plugin
@@ -58,6 +59,7 @@ mod ace_integration_tests {
                 "用户: {}\n助手: {}",
                 scenario1.user_query, scenario1.assistant_response
             ),
+            None,
             "session-1".to_string(),
         );
 
@@ -95,6 +97,7 @@ function processData(data: Array<{value: number}>): number[] {
                 "用户: {}\n助手: {}",
                 scenario2.user_query, scenario2.assistant_response
             ),
+            None,
             "session-2".to_string(),
         );
 
@@ -118,6 +121,7 @@ function processData(data: Array<{value: number}>): number[] {
                 "用户: {}\n助手: {}",
                 scenario3.user_query, scenario3.assistant_response
             ),
+            None,
             "session-3".to_string(),
         );
 
@@ -146,6 +150,7 @@ function processData(data: Array<{value: number}>): number[] {
                 "用户: {}\n助手: {}",
                 scenario4.user_query, scenario4.assistant_response
             ),
+            None,
             "session-4".to_string(),
         );
 
@@ -187,6 +192,7 @@ async function fetchUserData(userId) {
                 "用户: {}\n助手: {}",
                 scenario5.user_query, scenario5.assistant_response
             ),
+            None,
             "session-5".to_string(),
         );
 
@@ -222,6 +228,7 @@ CMD ["node", "server.js"]
                 "用户: {}\n助手: {}",
                 scenario6.user_query, scenario6.assistant_response
             ),
+            None,
             "session-6".to_string(),
         );
 
@@ -213,6 +213,7 @@ cargo test -- --nocapture
         ace_plugin.on_todo_completed(
             "部署应用".to_string(),
             format!("用户: {}\n助手: {}", task1_query, task1_response),
+            None,
             "session-1".to_string(),
         );
 
@@ -248,17 +249,17 @@ cargo test -- --nocapture
 
         // 从Playbook检索相关知识
         use codex_core::hooks::ExecutorHook;
-        let context = ace_plugin.pre_execute(task2_query);
+        let context = ace_plugin.pre_execute(task2_query).await;
 
-        if let Some(ctx) = &context {
+        if let Some(contribution) = context.first() {
             println!("\n从Playbook检索到的上下文:");
-            println!("{}", ctx);
+            println!("{}", contribution.content);
         }
 
         // 3. 验证：应该能从Playbook获取上下文
-        assert!(context.is_some(), "应该从Playbook检索到相关上下文");
+        assert!(!context.is_empty(), "应该从Playbook检索到相关上下文");
 
-        let ctx = context.unwrap();
+        let ctx = &context[0].content;
         // 验证上下文包含相关信息
         let has_relevant_info = ctx.contains("pm2")
             || ctx.contains("nginx")
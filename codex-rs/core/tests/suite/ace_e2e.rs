@@ -7,6 +7,7 @@ use codex_core::ace::ACEPlugin;
 use codex_core::ace::AceCliHandler;
 use codex_core::ace::AceCommand;
 use codex_core::ace::BulletStorage;
+use codex_core::hooks::TurnOutcome;
 use std::sync::Arc;
 use tempfile::TempDir;
 
@@ -52,11 +53,11 @@ async fn test_hook_registration() -> Result<()> {
     hook_manager.register(Arc::new(plugin));
 
     // Post-execute应该不panic（学习过程是异步的）
-    hook_manager.call_post_execute(
-        "How to fix Rust errors?",
-        "You should check the error message",
-        true,
-    );
+    hook_manager.call_post_execute(TurnOutcome {
+        query: "How to fix Rust errors?".to_string(),
+        response: Some("You should check the error message".to_string()),
+        ..Default::default()
+    });
 
     // 给学习过程一些时间
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -5,6 +5,7 @@
 use anyhow::Result;
 use codex_core::ace::ACEPlugin;
 use codex_core::hooks::ExecutorHook;
+use codex_core::hooks::TurnOutcome;
 use tempfile::TempDir;
 use tokio;
 
@@ -32,8 +33,8 @@ async fn test_ace_complete_learning_flow() -> Result<()> {
     println!("\n步骤 2: 第一次对话测试...");
     let query1 = "How do I create an async function in Rust?";
 
-    let context_before = plugin.pre_execute(query1);
-    if context_before.is_none() {
+    let context_before = plugin.pre_execute(query1).await;
+    if context_before.is_empty() {
         println!("✅ pre_execute: 无历史上下文（符合预期）");
     } else {
         println!("ℹ️  pre_execute: 返回了上下文（可能是空的查询结果）");
@@ -54,7 +55,13 @@ Key points:
 - Can return Result for error handling"#;
 
     // 触发学习
-    plugin.post_execute(query1, response1, true);
+    plugin
+        .post_execute(&TurnOutcome {
+            query: query1.to_string(),
+            response: Some(response1.to_string()),
+            ..Default::default()
+        })
+        .await;
     println!("✅ post_execute: 学习过程已触发");
 
     // 给学习过程足够时间
@@ -93,12 +100,12 @@ Key points:
     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
     let query2 = "What is the syntax for async functions in Rust?";
-    let context_after = plugin.pre_execute(query2);
+    let context_after = plugin.pre_execute(query2).await;
 
-    if let Some(ctx) = context_after {
+    if let Some(ctx) = context_after.first() {
         println!("✅ pre_execute: 找到相关上下文！");
         println!("\n   加载的上下文内容:");
-        println!("   {}", ctx);
+        println!("   {}", ctx.content);
     } else {
         println!("ℹ️  pre_execute: 未找到相关上下文");
         println!("   (这可能因为 query_bullets 未实现或没有相关内容)");
@@ -107,9 +114,9 @@ Key points:
     // 步骤 5: 第三次对话 - 不相关的查询
     println!("\n步骤 5: 第三次对话测试（不相关查询）...");
     let query3 = "How do I configure Python virtual environments?";
-    let context_unrelated = plugin.pre_execute(query3);
+    let context_unrelated = plugin.pre_execute(query3).await;
 
-    if context_unrelated.is_none() {
+    if context_unrelated.is_empty() {
         println!("✅ pre_execute: 正确判断无相关上下文");
     } else {
         println!("ℹ️  pre_execute: 找到了上下文（可能相关性判断宽松）");
@@ -0,0 +1,190 @@
+//! LLM Reflector 集成测试
+//!
+//! 使用 wiremock 模拟 Responses 接口，验证结构化输出解析和离线回退。
+
+use codex_app_server_protocol::AuthMode;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::ace::ExecutionResult;
+use codex_core::ace::LlmReflector;
+use codex_core::ace::Reflector;
+use codex_core::ace::ReflectorMVP;
+use codex_core::ace::types::InsightCategory;
+use codex_core::protocol::SessionSource;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::ConversationId;
+use core_test_support::load_default_config_for_test;
+use core_test_support::responses;
+use core_test_support::skip_if_no_network;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+use wiremock::MockServer;
+
+fn llm_reflector_for_server(server: &MockServer, codex_home: &TempDir) -> LlmReflector {
+    let provider = ModelProviderInfo {
+        name: "mock".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: None,
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+
+    let mut config = load_default_config_for_test(codex_home);
+    config.model_provider_id = provider.name.clone();
+    config.model_provider = provider.clone();
+    let effort = config.model_reasoning_effort;
+    let summary = config.model_reasoning_summary;
+    let config = Arc::new(config);
+    let conversation_id = ConversationId::new();
+
+    let otel_event_manager = OtelEventManager::new(
+        conversation_id,
+        config.model.as_str(),
+        config.model_family.slug.as_str(),
+        None,
+        Some("test@test.com".to_string()),
+        Some(AuthMode::ChatGPT),
+        false,
+        "test".to_string(),
+    );
+
+    let client = ModelClient::new(
+        Arc::clone(&config),
+        None,
+        otel_event_manager,
+        provider,
+        effort,
+        summary,
+        conversation_id,
+        SessionSource::Exec,
+    );
+
+    LlmReflector::new(
+        client,
+        Arc::new(ReflectorMVP::new(Default::default())),
+        Duration::from_secs(5),
+    )
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn llm_reflector_parses_structured_insights() {
+    skip_if_no_network!();
+
+    let server = responses::start_mock_server().await;
+    let output = json!({
+        "insights": [
+            {
+                "content": "Run `cargo test -p codex-core` after touching core to catch snapshot drift.",
+                "category": "tool_usage",
+                "importance": 0.8
+            },
+            {
+                "content": "Mismatched types in async closures usually need an explicit `Box::pin`.",
+                "category": "error_handling",
+                "importance": 1.7
+            }
+        ]
+    });
+    let mock = responses::mount_sse_once(
+        &server,
+        responses::sse(vec![
+            responses::ev_response_created("resp-1"),
+            responses::ev_assistant_message("msg-1", &output.to_string()),
+            responses::ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let codex_home = TempDir::new().unwrap();
+    let reflector = llm_reflector_for_server(&server, &codex_home);
+
+    let execution_result = ExecutionResult {
+        success: true,
+        tools_used: vec!["shell".to_string()],
+        diff: Some("--- a/core/src/lib.rs\n+++ b/core/src/lib.rs".to_string()),
+        ..Default::default()
+    };
+    let insights = reflector
+        .analyze_conversation(
+            "fix the failing core tests",
+            "Tests pass now.",
+            &execution_result,
+            "session-llm".to_string(),
+        )
+        .await
+        .expect("reflector should succeed");
+
+    assert_eq!(insights.len(), 2);
+    assert_eq!(insights[0].category, InsightCategory::ToolUsage);
+    assert_eq!(insights[1].category, InsightCategory::ErrorHandling);
+    // importance 超出范围时应被截断到 1.0
+    assert_eq!(insights[1].importance, 1.0);
+    assert_eq!(insights[0].context.session_id, "session-llm");
+    assert_eq!(insights[0].context.tools_used, vec!["shell".to_string()]);
+
+    let body = mock.single_request().body_json();
+    assert_eq!(
+        body["text"]["format"]["type"].as_str(),
+        Some("json_schema"),
+        "request should carry the structured output schema"
+    );
+    let request_text = body["input"].to_string();
+    assert!(request_text.contains("fix the failing core tests"));
+    assert!(request_text.contains("+++ b/core/src/lib.rs"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn llm_reflector_falls_back_to_rules_when_model_unavailable() {
+    skip_if_no_network!();
+
+    // 未挂载任何 mock，请求会失败，模拟离线场景
+    let server = responses::start_mock_server().await;
+    let codex_home = TempDir::new().unwrap();
+    let reflector = llm_reflector_for_server(&server, &codex_home);
+
+    let user_query = "运行项目测试";
+    let assistant_response = "我将使用 cargo test 运行测试。所有测试都通过了，验证了新增的解析逻辑。";
+    let execution_result = ExecutionResult {
+        success: true,
+        tools_used: vec!["bash".to_string()],
+        ..Default::default()
+    };
+
+    let insights = reflector
+        .analyze_conversation(
+            user_query,
+            assistant_response,
+            &execution_result,
+            "session-offline".to_string(),
+        )
+        .await
+        .expect("fallback should succeed");
+
+    let expected = ReflectorMVP::new(Default::default())
+        .analyze_conversation(
+            user_query,
+            assistant_response,
+            &execution_result,
+            "session-offline".to_string(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(insights.len(), expected.len());
+    for (actual, expected) in insights.iter().zip(expected.iter()) {
+        assert_eq!(actual.content, expected.content);
+        assert_eq!(actual.category, expected.category);
+    }
+}
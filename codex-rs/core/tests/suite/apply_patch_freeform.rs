@@ -461,6 +461,8 @@ async fn apply_patch_cli_rejects_path_traversal_outside_workspace() -> Result<()
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access: false,
+        network_allowlist: Vec::new(),
+        deny_read: Vec::new(),
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
     };
@@ -510,6 +512,8 @@ async fn apply_patch_cli_rejects_move_path_traversal_outside_workspace() -> Resu
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: vec![],
         network_access: false,
+        network_allowlist: Vec::new(),
+        deny_read: Vec::new(),
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
     };
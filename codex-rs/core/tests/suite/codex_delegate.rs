@@ -60,7 +60,7 @@ async fn codex_delegate_forwards_exec_approval_and_proceeds_on_approval() {
     // routes ExecApprovalRequest via the parent.
     let mut builder = test_codex().with_config(|config| {
         config.approval_policy = AskForApproval::OnRequest;
-        config.sandbox_policy = SandboxPolicy::ReadOnly;
+        config.sandbox_policy = SandboxPolicy::new_read_only_policy();
     });
     let test = builder.build(&server).await.expect("build test codex");
 
@@ -135,7 +135,7 @@ async fn codex_delegate_forwards_patch_approval_and_proceeds_on_decision() {
     let mut builder = test_codex().with_config(|config| {
         config.approval_policy = AskForApproval::OnRequest;
         // Use a restricted sandbox so patch approval is required
-        config.sandbox_policy = SandboxPolicy::ReadOnly;
+        config.sandbox_policy = SandboxPolicy::new_read_only_policy();
         config.include_apply_patch_tool = true;
     });
     let test = builder.build(&server).await.expect("build test codex");
@@ -48,3 +48,5 @@ mod view_image;
 mod ace_e2e;
 #[cfg(feature = "ace")]
 mod ace_learning_test;
+#[cfg(feature = "ace")]
+mod ace_llm_reflector;
@@ -456,6 +456,8 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() {
             sandbox_policy: Some(SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                network_allowlist: Vec::new(),
+                deny_read: Vec::new(),
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             }),
@@ -589,6 +591,8 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() {
             sandbox_policy: SandboxPolicy::WorkspaceWrite {
                 writable_roots: vec![writable.path().to_path_buf()],
                 network_access: true,
+                network_allowlist: Vec::new(),
+                deny_read: Vec::new(),
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
             },
@@ -255,6 +255,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         config.cli_auth_credentials_store_mode,
     );
     let conversation_manager = ConversationManager::new(auth_manager.clone(), SessionSource::Exec);
+    #[cfg(feature = "ace")]
+    let conversation_manager =
+        conversation_manager.with_ace_embedder(codex_ollama::ace_embedder(&config).await);
 
     // Handle resume subcommand by resolving a rollout path and using explicit resume API.
     let NewConversation {
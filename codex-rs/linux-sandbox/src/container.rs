@@ -0,0 +1,371 @@
+//! Container mode behind `sandbox_container`.
+//!
+//! Works like bubblewrap: the process moves into private mount and PID
+//! namespaces (inside a user namespace when unprivileged), a new root is
+//! assembled on a tmpfs from bind mounts of the system directories (or of a
+//! configured `rootfs`), the workspace and the writable roots, and
+//! `pivot_root` makes it `/`. `$HOME` and `/tmp` are fresh tmpfs mounts, so
+//! nothing from the real home directory is visible, and `/proc` only shows the
+//! processes of the container. Landlock and seccomp are applied afterwards as
+//! usual and still decide which of the mounted paths are writable.
+
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config::types::SandboxContainer;
+use codex_core::protocol::SandboxPolicy;
+
+use crate::namespace::unshare_with_user_namespace;
+
+/// Host entries mounted at `/` when no `rootfs` is configured.
+const HOST_ROOT_ENTRIES: [&str; 9] = [
+    "/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/libx32", "/etc", "/opt",
+];
+
+/// Top-level entries of a `rootfs` replaced by mounts of the container.
+const ROOTFS_SKIPPED_ENTRIES: [&str; 7] = ["proc", "dev", "sys", "tmp", "home", "root", "run"];
+
+/// Device nodes bound from the host `/dev`.
+const DEVICES: [&str; 6] = ["null", "zero", "full", "random", "urandom", "tty"];
+
+/// Where the host root and the new root live while the latter is assembled.
+const OLD_ROOT: &str = "/oldroot";
+const NEW_ROOT: &str = "/newroot";
+
+/// Moves this process into the container described by `container`.
+///
+/// A new PID namespace only applies to children, so this forks: the original
+/// process waits for the child and exits with its status, and the child, the
+/// init process of the namespace, returns and goes on to run the command.
+///
+/// When namespaces are unavailable and the container is not `required`, a
+/// notice is printed and the command runs under Landlock and seccomp alone.
+/// Must run while the process is still single-threaded, before Landlock
+/// applies.
+pub(crate) fn enter_container(
+    container: &SandboxContainer,
+    sandbox_policy: &SandboxPolicy,
+    sandbox_policy_cwd: &Path,
+) -> io::Result<()> {
+    let cwd = std::env::current_dir()?;
+    if let Err(err) = enter_mount_namespace() {
+        if container.required {
+            return Err(io::Error::new(
+                err.kind(),
+                format!("sandbox_container is required but namespaces are unavailable: {err}"),
+            ));
+        }
+        eprintln!("codex-linux-sandbox: container unavailable ({err}); falling back to Landlock");
+        return Ok(());
+    }
+
+    let mut binds: Vec<PathBuf> = sandbox_policy
+        .get_writable_roots_with_cwd(sandbox_policy_cwd)
+        .into_iter()
+        .map(|writable_root| writable_root.root)
+        .collect();
+    binds.push(cwd.clone());
+    binds.extend(container.binds.iter().cloned());
+
+    // Once the host root is swapped out there is no way back, so failures
+    // from here on are errors even when the container is optional.
+    build_root(container.rootfs.as_deref(), &binds, container.required)?;
+    std::env::set_current_dir(&cwd)
+}
+
+fn enter_mount_namespace() -> io::Result<()> {
+    unshare_with_user_namespace(libc::CLONE_NEWNS | libc::CLONE_NEWPID)?;
+    continue_in_pid_namespace()?;
+    // Keep the mounts below from propagating back to the host.
+    mount(
+        Path::new("none"),
+        Path::new("/"),
+        None,
+        libc::MS_REC | libc::MS_PRIVATE,
+    )
+}
+
+/// Forks the first process of the new PID namespace. Only the child returns.
+fn continue_in_pid_namespace() -> io::Result<()> {
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            // Codex only knows about the parent; don't outlive it.
+            if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+        child => exit_like_child(child),
+    }
+}
+
+/// Waits for `child` and exits the same way it did.
+pub(crate) fn exit_like_child(child: libc::pid_t) -> ! {
+    let mut status = 0;
+    while unsafe { libc::waitpid(child, &mut status, 0) } == -1 {
+        if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+            std::process::exit(1);
+        }
+    }
+    if libc::WIFSIGNALED(status) {
+        let signal = libc::WTERMSIG(status);
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+        std::process::exit(128 + signal);
+    }
+    std::process::exit(libc::WEXITSTATUS(status));
+}
+
+fn build_root(rootfs: Option<&Path>, binds: &[PathBuf], required: bool) -> io::Result<()> {
+    // A tmpfs over /tmp becomes the temporary root, with the host root below
+    // OLD_ROOT and the new root being assembled in NEW_ROOT.
+    let base = Path::new("/tmp");
+    mount(
+        Path::new("tmpfs"),
+        base,
+        Some("tmpfs"),
+        libc::MS_NOSUID | libc::MS_NODEV,
+    )?;
+    std::env::set_current_dir(base)?;
+    std::fs::create_dir("oldroot")?;
+    std::fs::create_dir("newroot")?;
+    // pivot_root needs the new root to be a mount point.
+    bind(Path::new("newroot"), Path::new("newroot"))?;
+    pivot_root(Path::new("."), Path::new("oldroot"))?;
+    std::env::set_current_dir("/")?;
+
+    match rootfs {
+        Some(rootfs) => mount_rootfs(rootfs)?,
+        None => {
+            for entry in HOST_ROOT_ENTRIES {
+                let source = host_path(Path::new(entry));
+                if source.symlink_metadata().is_ok() {
+                    copy_entry(&source, &new_root_path(Path::new(entry)))?;
+                }
+            }
+        }
+    }
+    mount_proc_and_dev(required)?;
+
+    let tmp = new_root_path(Path::new("/tmp"));
+    std::fs::create_dir_all(&tmp)?;
+    mount_tmpfs(&tmp)?;
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from)
+        && home.is_absolute()
+        && home != Path::new("/")
+    {
+        let home = new_root_path(&home);
+        std::fs::create_dir_all(&home)?;
+        mount_tmpfs(&home)?;
+    }
+
+    for path in binds {
+        // Binding the host /tmp would undo the private one mounted above.
+        if !path.is_absolute() || path == Path::new("/tmp") {
+            continue;
+        }
+        let source = host_path(path);
+        let Ok(metadata) = source.metadata() else {
+            continue;
+        };
+        let target = new_root_path(path);
+        create_mount_point(&target, metadata.is_dir())?;
+        bind(&source, &target)?;
+    }
+
+    umount_detached(Path::new(OLD_ROOT))?;
+    std::env::set_current_dir(NEW_ROOT)?;
+    // Stacks the old root on top of the new one, then drops it.
+    pivot_root(Path::new("."), Path::new("."))?;
+    umount_detached(Path::new("."))?;
+    std::env::set_current_dir("/")
+}
+
+/// Mounts the top-level entries of `rootfs`, except the ones the container
+/// provides itself.
+fn mount_rootfs(rootfs: &Path) -> io::Result<()> {
+    if !rootfs.is_absolute() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "sandbox_container.rootfs must be absolute: {}",
+                rootfs.display()
+            ),
+        ));
+    }
+    for entry in std::fs::read_dir(host_path(rootfs))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if ROOTFS_SKIPPED_ENTRIES
+            .iter()
+            .any(|skipped| name.as_bytes() == skipped.as_bytes())
+        {
+            continue;
+        }
+        copy_entry(&entry.path(), &Path::new(NEW_ROOT).join(&name))?;
+    }
+    Ok(())
+}
+
+fn mount_proc_and_dev(required: bool) -> io::Result<()> {
+    // A fresh proc for the new PID namespace, never the host's. The kernel
+    // refuses it when the host /proc is partly masked, as inside Docker.
+    let proc = new_root_path(Path::new("/proc"));
+    std::fs::create_dir_all(&proc)?;
+    if let Err(err) = mount(
+        Path::new("proc"),
+        &proc,
+        Some("proc"),
+        libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+    ) {
+        if required {
+            return Err(err);
+        }
+        eprintln!("codex-linux-sandbox: {err}; running without /proc");
+    }
+
+    let dev = new_root_path(Path::new("/dev"));
+    std::fs::create_dir_all(&dev)?;
+    mount(
+        Path::new("tmpfs"),
+        &dev,
+        Some("tmpfs"),
+        libc::MS_NOSUID | libc::MS_NOEXEC,
+    )?;
+    for device in DEVICES {
+        let source = host_path(&Path::new("/dev").join(device));
+        if source.exists() {
+            let target = dev.join(device);
+            create_mount_point(&target, false)?;
+            bind(&source, &target)?;
+        }
+    }
+    let shm = dev.join("shm");
+    std::fs::create_dir(&shm)?;
+    mount_tmpfs(&shm)?;
+    symlink("/proc/self/fd", dev.join("fd"))?;
+    for (index, name) in ["stdin", "stdout", "stderr"].iter().enumerate() {
+        symlink(format!("/proc/self/fd/{index}"), dev.join(name))?;
+    }
+    Ok(())
+}
+
+/// Makes `source` available at `target`, recreating symlinks instead of
+/// following them so `/bin -> usr/bin` style layouts keep working.
+fn copy_entry(source: &Path, target: &Path) -> io::Result<()> {
+    let metadata = source.symlink_metadata()?;
+    if metadata.file_type().is_symlink() {
+        return symlink(std::fs::read_link(source)?, target);
+    }
+    create_mount_point(target, metadata.is_dir())?;
+    bind(source, target)
+}
+
+fn create_mount_point(target: &Path, is_dir: bool) -> io::Result<()> {
+    if is_dir {
+        return std::fs::create_dir_all(target);
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if !target.exists() {
+        std::fs::File::create(target)?;
+    }
+    Ok(())
+}
+
+/// `path` on the host, as seen while the host root sits below OLD_ROOT.
+fn host_path(path: &Path) -> PathBuf {
+    Path::new(OLD_ROOT).join(path.strip_prefix("/").unwrap_or(path))
+}
+
+/// `path` inside the root being assembled.
+fn new_root_path(path: &Path) -> PathBuf {
+    Path::new(NEW_ROOT).join(path.strip_prefix("/").unwrap_or(path))
+}
+
+fn bind(source: &Path, target: &Path) -> io::Result<()> {
+    mount(source, target, None, libc::MS_BIND | libc::MS_REC)
+}
+
+fn mount_tmpfs(target: &Path) -> io::Result<()> {
+    mount(
+        Path::new("tmpfs"),
+        target,
+        Some("tmpfs"),
+        libc::MS_NOSUID | libc::MS_NODEV,
+    )
+}
+
+fn mount(
+    source: &Path,
+    target: &Path,
+    fstype: Option<&str>,
+    flags: libc::c_ulong,
+) -> io::Result<()> {
+    let c_source = path_to_cstring(source)?;
+    let c_target = path_to_cstring(target)?;
+    let c_fstype = fstype
+        .map(|fstype| CString::new(fstype).map_err(io::Error::other))
+        .transpose()?;
+    let fstype_ptr = c_fstype
+        .as_ref()
+        .map_or(std::ptr::null(), |fstype| fstype.as_ptr());
+    let rc = unsafe {
+        libc::mount(
+            c_source.as_ptr(),
+            c_target.as_ptr(),
+            fstype_ptr,
+            flags,
+            std::ptr::null(),
+        )
+    };
+    if rc != 0 {
+        let err = io::Error::last_os_error();
+        return Err(io::Error::new(
+            err.kind(),
+            format!("mounting {} failed: {err}", target.display()),
+        ));
+    }
+    Ok(())
+}
+
+fn umount_detached(target: &Path) -> io::Result<()> {
+    let c_target = path_to_cstring(target)?;
+    if unsafe { libc::umount2(c_target.as_ptr(), libc::MNT_DETACH) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn pivot_root(new_root: &Path, put_old: &Path) -> io::Result<()> {
+    let c_new_root = path_to_cstring(new_root)?;
+    let c_put_old = path_to_cstring(put_old)?;
+    let rc = unsafe {
+        libc::syscall(
+            libc::SYS_pivot_root,
+            c_new_root.as_ptr(),
+            c_put_old.as_ptr(),
+        )
+    };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn path_to_cstring(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("path contains a NUL byte: {}", path.display()),
+        )
+    })
+}
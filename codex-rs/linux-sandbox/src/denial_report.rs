@@ -0,0 +1,35 @@
+//! Reports of what the sandbox refused, written to the descriptor Codex
+//! passes with `--denial-fd`. The sandboxed command never holds that
+//! descriptor, so unlike its stderr the report cannot be forged by it.
+
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::os::fd::FromRawFd;
+use std::os::fd::RawFd;
+use std::sync::OnceLock;
+
+static REPORT: OnceLock<File> = OnceLock::new();
+
+/// Takes over `fd` and marks it close-on-exec so the command does not inherit
+/// it. Processes forked before the command is executed keep it.
+pub(crate) fn init(fd: RawFd) -> io::Result<()> {
+    if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let _ = REPORT.set(unsafe { File::from_raw_fd(fd) });
+    Ok(())
+}
+
+/// Whether Codex is listening for reports.
+pub(crate) fn is_enabled() -> bool {
+    REPORT.get().is_some()
+}
+
+/// Writes one report line. Codex opens the pipe non-blocking, so the line is
+/// dropped rather than stalling the command when the pipe is full.
+pub(crate) fn report(prefix: &str, detail: &str) {
+    if let Some(mut report) = REPORT.get() {
+        let _ = report.write_all(format!("{prefix}{detail}\n").as_bytes());
+    }
+}
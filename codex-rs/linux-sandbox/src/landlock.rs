@@ -1,19 +1,26 @@
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::path::PathBuf;
 
+use codex_core::config::types::SandboxContainer;
 use codex_core::error::CodexErr;
 use codex_core::error::Result;
 use codex_core::error::SandboxErr;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::sandboxing::deny_read::DenyReadList;
 
 use landlock::ABI;
 use landlock::Access;
 use landlock::AccessFs;
+use landlock::BitFlags;
 use landlock::CompatLevel;
 use landlock::Compatible;
+use landlock::PathBeneath;
+use landlock::PathFd;
 use landlock::Ruleset;
 use landlock::RulesetAttr;
+use landlock::RulesetCreated;
 use landlock::RulesetCreatedAttr;
 use seccompiler::BpfProgram;
 use seccompiler::SeccompAction;
@@ -25,14 +32,41 @@ use seccompiler::SeccompRule;
 use seccompiler::TargetArch;
 use seccompiler::apply_filter;
 
+use crate::container::enter_container;
+use crate::denial_report;
+use crate::proxy::route_network_through_proxy;
+use crate::read_monitor::spawn_read_monitor;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NetworkSeccompMode {
+    /// No sockets other than AF_UNIX, which cannot connect anywhere.
+    Blocked,
+    /// The process sits in a private network namespace where IP sockets can
+    /// only reach the allowlist proxy, so those are allowed; AF_UNIX sockets
+    /// are not, since they could reach daemons outside the namespace.
+    ProxyOnly,
+}
+
 /// Apply sandbox policies inside this thread so only the child inherits
-/// them, not the entire CLI process.
+/// them, not the entire CLI process. With `container`, the process first
+/// moves into its own mount namespace (see [`enter_container`]). With
+/// `report_denied_reads`, when Codex listens for denial reports and some
+/// paths are denied, opens are watched from a forked monitor (see
+/// [`spawn_read_monitor`]); Landlock enforces the denial either way.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    container: Option<&SandboxContainer>,
+    report_denied_reads: bool,
 ) -> Result<()> {
-    if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+    // Namespaces have to be set up before any restriction is applied: the
+    // proxy is forked from the host view and Landlock forbids mounting.
+    let network_allowlist = sandbox_policy.network_allowlist();
+    if !network_allowlist.is_empty() {
+        route_network_through_proxy(network_allowlist)?;
+    }
+    if let Some(container) = container {
+        enter_container(container, sandbox_policy, cwd)?;
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
@@ -41,22 +75,53 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        let deny_list = if sandbox_policy.has_full_disk_read_access() {
+            DenyReadList::default()
+        } else {
+            DenyReadList::new(sandbox_policy, cwd)
+        };
+        // Resolved before Landlock applies, while everything is readable.
+        let denied_paths = deny_list.resolve_existing();
+        // The monitor has to stay outside Landlock.
+        let read_monitor =
+            if report_denied_reads && !denied_paths.is_empty() && denial_report::is_enabled() {
+                Some(spawn_read_monitor(deny_list)?)
+            } else {
+                None
+            };
+        install_filesystem_landlock_rules_on_current_thread(writable_roots, &denied_paths)?;
+        if let Some(read_monitor) = read_monitor {
+            read_monitor.attach();
+        }
     }
 
-    // TODO(ragona): Add appropriate restrictions if
-    // `sandbox_policy.has_full_disk_read_access()` is `false`.
+    // After the monitor is attached: the blocking filter also refuses the
+    // `sendmsg` that hands over its listener.
+    if !network_allowlist.is_empty() {
+        install_network_seccomp_filter_on_current_thread(NetworkSeccompMode::ProxyOnly)?;
+    } else if !sandbox_policy.has_full_network_access() {
+        install_network_seccomp_filter_on_current_thread(NetworkSeccompMode::Blocked)?;
+    }
 
     Ok(())
 }
 
 /// Installs Landlock file-system rules on the current thread allowing read
-/// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// access to the entire file-system except `denied_paths`, while restricting
+/// write access to `/dev/null` and the provided list of `writable_roots`.
+///
+/// Landlock can only grant access, so a root containing denied paths only
+/// keeps the right to list directories, and its other entries get the full
+/// rights back one by one (see [`carve_out_denied`]). Denied paths are
+/// neither readable nor writable, and nothing can be created or removed
+/// directly in the directories leading to them.
 ///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
-fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathBuf>) -> Result<()> {
+fn install_filesystem_landlock_rules_on_current_thread(
+    writable_roots: Vec<PathBuf>,
+    denied_paths: &[PathBuf],
+) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
@@ -64,13 +129,15 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
     let mut ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
-        .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
+        .create()?;
+
+    ruleset = add_carved_out_rules(ruleset, Path::new("/"), denied_paths, access_ro)?;
+    ruleset = ruleset
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
-    if !writable_roots.is_empty() {
-        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_rw))?;
+    for writable_root in &writable_roots {
+        ruleset = add_carved_out_rules(ruleset, writable_root, denied_paths, access_rw)?;
     }
 
     let status = ruleset.restrict_self()?;
@@ -82,12 +149,140 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
     Ok(())
 }
 
+/// Grants `access` beneath `root`, except on the paths of `denied_paths`
+/// inside it. Any right granted on `root` itself would also cover those, so
+/// it keeps nothing but listing directories.
+fn add_carved_out_rules(
+    ruleset: RulesetCreated,
+    root: &Path,
+    denied_paths: &[PathBuf],
+    access: BitFlags<AccessFs>,
+) -> Result<RulesetCreated> {
+    let Some(carve_out) = carve_out_denied(root, denied_paths) else {
+        return Ok(ruleset.add_rules(landlock::path_beneath_rules(&[root], access))?);
+    };
+    let mut ruleset =
+        add_existing_path_rules(ruleset, &[root.to_path_buf()], access & AccessFs::ReadDir)?;
+    ruleset = add_existing_path_rules(ruleset, &carve_out, access)?;
+    Ok(ruleset)
+}
+
+/// Like [`landlock::path_beneath_rules`], but skips paths that disappeared
+/// since they were listed.
+fn add_existing_path_rules(
+    mut ruleset: RulesetCreated,
+    paths: &[PathBuf],
+    access: BitFlags<AccessFs>,
+) -> Result<RulesetCreated> {
+    for path in paths {
+        let Ok(fd) = PathFd::new(path) else {
+            continue;
+        };
+        ruleset = ruleset.add_rule(PathBeneath::new(fd, access))?;
+    }
+    Ok(ruleset)
+}
+
+/// Entries that can keep full access under `root` when some of `denied_paths`
+/// lie beneath it: the siblings of every directory on the way from `root`
+/// down to a denied path. Returns `None` when nothing beneath `root` is
+/// denied, and an empty list when `root` itself is.
+///
+/// Symlinks are only kept when their target neither is, contains nor lives
+/// under a denied path, since a Landlock rule applies to the target.
+fn carve_out_denied(root: &Path, denied_paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    if denied_paths.iter().any(|denied| root.starts_with(denied)) {
+        return Some(Vec::new());
+    }
+
+    let mut split_dirs = BTreeSet::new();
+    for denied in denied_paths
+        .iter()
+        .filter(|denied| denied.starts_with(&root))
+    {
+        for ancestor in denied.ancestors().skip(1) {
+            if !ancestor.starts_with(&root) {
+                break;
+            }
+            split_dirs.insert(ancestor.to_path_buf());
+        }
+    }
+    if split_dirs.is_empty() {
+        return None;
+    }
+
+    let mut granted = Vec::new();
+    for dir in &split_dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if split_dirs.contains(&path) || denied_paths.contains(&path) {
+                continue;
+            }
+            if entry
+                .file_type()
+                .is_ok_and(|file_type| file_type.is_symlink())
+            {
+                let Ok(target) = path.canonicalize() else {
+                    continue;
+                };
+                if denied_paths
+                    .iter()
+                    .any(|denied| target.starts_with(denied) || denied.starts_with(&target))
+                {
+                    continue;
+                }
+            }
+            granted.push(path);
+        }
+    }
+    Some(granted)
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
-/// AF_UNIX domain sockets.
-fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+/// AF_UNIX domain sockets, or in [`NetworkSeccompMode::ProxyOnly`] restricts
+/// sockets to IP ones.
+fn install_network_seccomp_filter_on_current_thread(
+    mode: NetworkSeccompMode,
+) -> std::result::Result<(), SandboxErr> {
     // Build rule map.
     let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
 
+    if mode == NetworkSeccompMode::ProxyOnly {
+        rules.insert(libc::SYS_ptrace, vec![]);
+
+        // Deny `socket` unless the domain is AF_INET or AF_INET6.
+        let ip_only_rule = SeccompRule::new(vec![
+            SeccompCondition::new(
+                0,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::AF_INET as u64,
+            )?,
+            SeccompCondition::new(
+                0,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::AF_INET6 as u64,
+            )?,
+        ])?;
+        rules.insert(libc::SYS_socket, vec![ip_only_rule]);
+
+        // socketpair(2) never leaves the process tree; keep it for AF_UNIX.
+        let unix_only_rule = SeccompRule::new(vec![SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_UNIX as u64,
+        )?])?;
+        rules.insert(libc::SYS_socketpair, vec![unix_only_rule]);
+
+        return apply_network_seccomp_rules(rules);
+    }
+
     // Helper – insert unconditional deny rule for syscall number.
     let mut deny_syscall = |nr: i64| {
         rules.insert(nr, vec![]); // empty rule vec = unconditional match
@@ -124,6 +319,12 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    apply_network_seccomp_rules(rules)
+}
+
+fn apply_network_seccomp_rules(
+    rules: BTreeMap<i64, Vec<SeccompRule>>,
+) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -1,7 +1,17 @@
 #[cfg(target_os = "linux")]
+mod container;
+#[cfg(target_os = "linux")]
+mod denial_report;
+#[cfg(target_os = "linux")]
 mod landlock;
 #[cfg(target_os = "linux")]
 mod linux_run_main;
+#[cfg(target_os = "linux")]
+mod namespace;
+#[cfg(target_os = "linux")]
+mod proxy;
+#[cfg(target_os = "linux")]
+mod read_monitor;
 
 #[cfg(target_os = "linux")]
 pub fn run_main() -> ! {
@@ -2,6 +2,7 @@ use clap::Parser;
 use std::ffi::CString;
 use std::path::PathBuf;
 
+use crate::denial_report;
 use crate::landlock::apply_sandbox_policy_to_current_thread;
 
 #[derive(Debug, Parser)]
@@ -14,6 +15,19 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: codex_core::protocol::SandboxPolicy,
 
+    /// Run the command inside a private mount namespace with this layout.
+    #[arg(long = "container")]
+    pub container: Option<codex_core::config::types::SandboxContainer>,
+
+    /// Inherited descriptor to report refused accesses on.
+    #[arg(long = "denial-fd")]
+    pub denial_fd: Option<i32>,
+
+    /// Watch the command's opens and report refused `deny_read` paths on the
+    /// `--denial-fd` descriptor. Every open then goes through a monitor.
+    #[arg(long = "report-denied-reads")]
+    pub report_denied_reads: bool,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,10 +37,24 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        container,
+        denial_fd,
+        report_denied_reads,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    if let Some(fd) = denial_fd
+        && let Err(e) = denial_report::init(fd)
+    {
+        panic!("invalid --denial-fd {fd}: {e}");
+    }
+
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        container.as_ref(),
+        report_denied_reads,
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
@@ -0,0 +1,19 @@
+use std::io;
+
+/// Moves this process into new namespaces of the kinds in `flags`. Without
+/// `CAP_SYS_ADMIN` a user namespace mapping our own ids is created with them.
+///
+/// Must run while the process is still single-threaded.
+pub(crate) fn unshare_with_user_namespace(flags: libc::c_int) -> io::Result<()> {
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    if unsafe { libc::unshare(flags) } == 0 {
+        return Ok(());
+    }
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | flags) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1\n"))?;
+    std::fs::write("/proc/self/setgroups", "deny\n")?;
+    std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1\n"))?;
+    Ok(())
+}
@@ -0,0 +1,669 @@
+//! Filtering HTTP proxy behind `network_allowlist`.
+//!
+//! The sandboxed command runs in a private network namespace whose only
+//! interface is loopback, so it cannot open connections to the outside world
+//! on its own. Before leaving the original namespace we fork a proxy process
+//! that stays behind; it accepts connections on a listener bound to the
+//! private loopback and forwards `CONNECT` tunnels and plain `http://`
+//! requests, but only to allowlisted hosts. Every refused connection is
+//! written to the denial report with [`NETWORK_DENIED_PREFIX`] so Codex can
+//! surface it as `SandboxErr::NetworkDenied`.
+
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_core::landlock::NETWORK_DENIED_PREFIX;
+
+use crate::denial_report;
+use crate::namespace::unshare_with_user_namespace;
+
+/// Environment variables pointed at the proxy. curl only honors the
+/// lowercase `http_proxy`, other tools only the uppercase names.
+const PROXY_ENV_VARS: [&str; 6] = [
+    "HTTP_PROXY",
+    "http_proxy",
+    "HTTPS_PROXY",
+    "https_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+];
+
+/// Removed so that no host is contacted directly: direct connections cannot
+/// leave the namespace anyway and would only fail with a less helpful error.
+const NO_PROXY_ENV_VARS: [&str; 2] = ["NO_PROXY", "no_proxy"];
+
+/// Upper bound on the size of a request line plus headers.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Read and write timeout on client and upstream sockets. A tunnel is only
+/// closed once neither direction has moved data for this long.
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Connections served at once; further clients wait in the listen backlog.
+const PROXY_WORKERS: usize = 32;
+
+/// Headers that only concern the hop between the client and the proxy.
+const HOP_BY_HOP_HEADERS: [&str; 4] = [
+    "connection",
+    "keep-alive",
+    "proxy-authorization",
+    "proxy-connection",
+];
+
+/// One `network_allowlist` entry: `host`, `host:port`, `*.domain` or
+/// `*.domain:port`. IPv6 addresses with a port are written `[::1]:8080`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AllowRule {
+    host: HostPattern,
+    port: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HostPattern {
+    Exact(String),
+    /// Any subdomain of the domain; stored with its leading dot.
+    Subdomains(String),
+}
+
+impl AllowRule {
+    pub(crate) fn parse(entry: &str) -> io::Result<Self> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid network_allowlist entry `{entry}`"),
+            )
+        };
+        let (host, port) = split_host_port(entry.trim()).ok_or_else(invalid)?;
+        let host = match host.strip_prefix("*.") {
+            Some(domain) if !domain.is_empty() && !domain.contains('*') => {
+                HostPattern::Subdomains(format!(".{domain}"))
+            }
+            None if !host.contains('*') => HostPattern::Exact(host),
+            _ => return Err(invalid()),
+        };
+        Ok(Self { host, port })
+    }
+
+    fn allows(&self, host: &str, port: u16) -> bool {
+        let host_matches = match &self.host {
+            HostPattern::Exact(exact) => exact == host,
+            HostPattern::Subdomains(suffix) => host.len() > suffix.len() && host.ends_with(suffix),
+        };
+        host_matches && self.port.is_none_or(|allowed| allowed == port)
+    }
+}
+
+/// Forks the proxy, moves this process into a private network namespace and
+/// points the proxy environment variables at the proxy listener.
+///
+/// Must run while the process is still single-threaded, before any other
+/// sandbox restriction is applied.
+pub(crate) fn route_network_through_proxy(allowlist: &[String]) -> io::Result<()> {
+    let rules = allowlist
+        .iter()
+        .map(|entry| AllowRule::parse(entry))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let (control, proxy_control) = UnixStream::pair()?;
+    let parent_pid = unsafe { libc::getpid() };
+    match unsafe { libc::fork() } {
+        -1 => return Err(io::Error::last_os_error()),
+        0 => {
+            drop(control);
+            run_proxy(proxy_control, rules, parent_pid);
+        }
+        _ => drop(proxy_control),
+    }
+
+    enter_private_network_namespace().map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("network_allowlist needs a private network namespace: {err}"),
+        )
+    })?;
+
+    // Bound inside the new namespace, accepted from the old one.
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let proxy_url = format!("http://{}", listener.local_addr()?);
+    send_fd(&control, listener.as_raw_fd())?;
+    drop(listener);
+
+    for name in PROXY_ENV_VARS {
+        unsafe { std::env::set_var(name, &proxy_url) };
+    }
+    for name in NO_PROXY_ENV_VARS {
+        unsafe { std::env::remove_var(name) };
+    }
+    Ok(())
+}
+
+/// Body of the forked proxy process; never returns.
+fn run_proxy(control: UnixStream, rules: Vec<AllowRule>, parent_pid: libc::pid_t) -> ! {
+    // The proxy must not outlive the sandboxed command, which is what the
+    // parent process becomes once it calls execvp.
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+        if libc::getppid() != parent_pid {
+            libc::_exit(0);
+        }
+    }
+
+    // stdin and stdout belong to the command.
+    if let Ok(dev_null) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+    {
+        unsafe {
+            libc::dup2(dev_null.as_raw_fd(), libc::STDIN_FILENO);
+            libc::dup2(dev_null.as_raw_fd(), libc::STDOUT_FILENO);
+        }
+    }
+
+    let listener = match recv_fd(&control) {
+        Ok(fd) => TcpListener::from(fd),
+        Err(_) => std::process::exit(1),
+    };
+    drop(control);
+
+    // A fixed pool of workers accepting from the shared listener bounds the
+    // number of threads the sandboxed command can make the proxy spawn.
+    let listener = Arc::new(listener);
+    let rules = Arc::new(rules);
+    for _ in 1..PROXY_WORKERS {
+        let listener = Arc::clone(&listener);
+        let rules = Arc::clone(&rules);
+        let _ = std::thread::Builder::new().spawn(move || serve(&listener, &rules));
+    }
+    serve(&listener, &rules);
+    std::process::exit(0);
+}
+
+fn serve(listener: &TcpListener, rules: &[AllowRule]) {
+    for client in listener.incoming() {
+        let Ok(client) = client else {
+            continue;
+        };
+        let _ = handle_client(client, rules);
+    }
+}
+
+struct ProxyRequest {
+    host: String,
+    port: u16,
+    /// Request head to send upstream for plain HTTP; `None` for `CONNECT`.
+    forward_head: Option<String>,
+}
+
+fn handle_client(client: TcpStream, rules: &[AllowRule]) -> io::Result<()> {
+    set_socket_timeouts(&client)?;
+    let mut reader = BufReader::new(client.try_clone()?);
+    let head = read_request_head(&mut reader)?;
+    let Some(request) = parse_request(&head) else {
+        return respond(&client, "400 Bad Request", "unsupported proxy request\n");
+    };
+
+    let target = if request.host.contains(':') {
+        format!("[{}]:{}", request.host, request.port)
+    } else {
+        format!("{}:{}", request.host, request.port)
+    };
+    if !rules
+        .iter()
+        .any(|rule| rule.allows(&request.host, request.port))
+    {
+        denial_report::report(NETWORK_DENIED_PREFIX, &target);
+        return respond(
+            &client,
+            "403 Forbidden",
+            &format!("{target} is not in the sandbox network allowlist\n"),
+        );
+    }
+
+    let upstream = match connect_upstream(&request.host, request.port) {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            return respond(
+                &client,
+                "502 Bad Gateway",
+                &format!("failed to connect to {target}: {err}\n"),
+            );
+        }
+    };
+    match &request.forward_head {
+        Some(forward_head) => (&upstream).write_all(forward_head.as_bytes())?,
+        None => (&client).write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")?,
+    }
+    // Anything the client sent after the head is already buffered.
+    (&upstream).write_all(reader.buffer())?;
+    tunnel(client, upstream, SOCKET_TIMEOUT);
+    Ok(())
+}
+
+fn set_socket_timeouts(stream: &TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(SOCKET_TIMEOUT))?;
+    stream.set_write_timeout(Some(SOCKET_TIMEOUT))
+}
+
+fn read_request_head(reader: &mut BufReader<TcpStream>) -> io::Result<String> {
+    let mut head = Vec::new();
+    loop {
+        let read = reader.read_until(b'\n', &mut head)?;
+        if read == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if head.ends_with(b"\r\n\r\n") || head.ends_with(b"\n\n") || head == b"\r\n" {
+            break;
+        }
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+    }
+    String::from_utf8(head).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn parse_request(head: &str) -> Option<ProxyRequest> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let (method, target, version) = (
+        request_line.next()?,
+        request_line.next()?,
+        request_line.next()?,
+    );
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target)?;
+        return Some(ProxyRequest {
+            host,
+            port: port?,
+            forward_head: None,
+        });
+    }
+
+    // Plain HTTP requests carry an absolute URI; forward it in origin form.
+    let scheme_len = "http://".len();
+    if !target
+        .get(..scheme_len)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
+    {
+        return None;
+    }
+    let rest = &target[scheme_len..];
+    let (authority, path) = match rest.find(['/', '?']) {
+        Some(index) if rest[index..].starts_with('/') => {
+            (&rest[..index], rest[index..].to_string())
+        }
+        Some(index) => (&rest[..index], format!("/{}", &rest[index..])),
+        None => (rest, "/".to_string()),
+    };
+    let (host, port) = split_host_port(authority)?;
+
+    // The upstream routes on `Host`, so it is rebuilt from the checked
+    // authority instead of forwarding whatever the client sent.
+    let mut forward_head = format!("{method} {path} {version}\r\nHost: {authority}\r\n");
+    for header in lines.take_while(|line| !line.is_empty()) {
+        let name = header.split(':').next().unwrap_or_default().trim();
+        if name.eq_ignore_ascii_case("host")
+            || HOP_BY_HOP_HEADERS
+                .iter()
+                .any(|hop| name.eq_ignore_ascii_case(hop))
+        {
+            continue;
+        }
+        forward_head.push_str(header);
+        forward_head.push_str("\r\n");
+    }
+    // One request per connection keeps every request subject to the check.
+    forward_head.push_str("Connection: close\r\n\r\n");
+
+    Some(ProxyRequest {
+        host,
+        port: port.unwrap_or(80),
+        forward_head: Some(forward_head),
+    })
+}
+
+/// Splits `host`, `host:port` or `[v6]:port` into a normalized host and an
+/// optional port. Bare IPv6 addresses are returned without a port.
+fn split_host_port(authority: &str) -> Option<(String, Option<u16>)> {
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        let (host, rest) = bracketed.split_once(']')?;
+        let port = match rest {
+            "" => None,
+            rest => Some(rest.strip_prefix(':')?.parse().ok()?),
+        };
+        (host, port)
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) if !port.contains(':') => (host, Some(port.parse().ok()?)),
+            _ => (authority, None),
+        }
+    };
+
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() || host.chars().any(char::is_whitespace) {
+        return None;
+    }
+    Some((host, port))
+}
+
+fn connect_upstream(host: &str, port: u16) -> io::Result<TcpStream> {
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, "host did not resolve");
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, UPSTREAM_CONNECT_TIMEOUT) {
+            Ok(stream) => {
+                set_socket_timeouts(&stream)?;
+                return Ok(stream);
+            }
+            Err(err) => last_err = err,
+        }
+    }
+    Err(last_err)
+}
+
+fn respond(mut client: &TcpStream, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        client,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Copies bytes in both directions until each side has finished sending or
+/// neither side has sent anything for `idle_timeout`. Both sockets must have
+/// a read timeout of at most `idle_timeout`.
+fn tunnel(client: TcpStream, upstream: TcpStream, idle_timeout: Duration) {
+    let (Ok(client_reader), Ok(upstream_writer)) = (client.try_clone(), upstream.try_clone())
+    else {
+        return;
+    };
+    let last_activity = Arc::new(Mutex::new(Instant::now()));
+    let upload_activity = Arc::clone(&last_activity);
+    let upload = std::thread::Builder::new().spawn(move || {
+        pump(
+            client_reader,
+            upstream_writer,
+            idle_timeout,
+            &upload_activity,
+        );
+    });
+    let Ok(upload) = upload else {
+        return;
+    };
+
+    pump(upstream, client, idle_timeout, &last_activity);
+    let _ = upload.join();
+}
+
+/// Copies `from` into `to`, then shuts down the sending side of `to`.
+fn pump(
+    mut from: TcpStream,
+    mut to: TcpStream,
+    idle_timeout: Duration,
+    last_activity: &Mutex<Instant>,
+) {
+    let mut buf = [0u8; 16 * 1024];
+    loop {
+        match from.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => {
+                if to.write_all(&buf[..read]).is_err() {
+                    break;
+                }
+                if let Ok(mut last) = last_activity.lock() {
+                    *last = Instant::now();
+                }
+            }
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                // The other direction may still be busy, e.g. a download
+                // while the client has nothing to send.
+                if last_activity
+                    .lock()
+                    .is_ok_and(|last| last.elapsed() >= idle_timeout)
+                {
+                    break;
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(_) => break,
+        }
+    }
+    let _ = to.shutdown(Shutdown::Write);
+}
+
+/// Moves this process into a new network namespace with loopback up. Without
+/// `CAP_SYS_ADMIN` a user namespace mapping our own ids is created with it.
+fn enter_private_network_namespace() -> io::Result<()> {
+    unshare_with_user_namespace(libc::CLONE_NEWNET)?;
+    bring_up_loopback()
+}
+
+/// `struct ifreq` restricted to the `ifr_flags` member of its union.
+#[repr(C)]
+struct InterfaceFlagsRequest {
+    name: [libc::c_char; libc::IFNAMSIZ],
+    flags: libc::c_short,
+    _padding: [u8; 22],
+}
+
+fn bring_up_loopback() -> io::Result<()> {
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut request = InterfaceFlagsRequest {
+        name: [0; libc::IFNAMSIZ],
+        flags: 0,
+        _padding: [0; 22],
+    };
+    for (dst, src) in request.name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCGIFFLAGS, &raw mut request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    request.flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS, &raw mut request) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Room for one `SCM_RIGHTS` control message carrying a single descriptor.
+const FD_CONTROL_WORDS: usize = 4;
+
+pub(crate) fn send_fd(socket: &UnixStream, fd: RawFd) -> io::Result<()> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let mut control = [0u64; FD_CONTROL_WORDS];
+    let sent = unsafe {
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &raw mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = libc::CMSG_SPACE(size_of::<RawFd>() as u32) as _;
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(size_of::<RawFd>() as u32) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+        libc::sendmsg(socket.as_raw_fd(), &msg, 0)
+    };
+    if sent < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+pub(crate) fn recv_fd(socket: &UnixStream) -> io::Result<OwnedFd> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let mut control = [0u64; FD_CONTROL_WORDS];
+    unsafe {
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &raw mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = size_of_val(&control) as _;
+        let received = libc::recvmsg(socket.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC);
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        if received == 0
+            || cmsg.is_null()
+            || (*cmsg).cmsg_level != libc::SOL_SOCKET
+            || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no listener received from the sandbox",
+            ));
+        }
+        let fd = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>());
+        Ok(OwnedFd::from_raw_fd(fd))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn allowed(entry: &str, host: &str, port: u16) -> bool {
+        AllowRule::parse(entry).unwrap().allows(host, port)
+    }
+
+    #[test]
+    fn allow_rules_match_hosts_and_ports() {
+        assert!(allowed("registry.npmjs.org", "registry.npmjs.org", 443));
+        assert!(allowed("Mirror.internal:8080", "mirror.internal", 8080));
+        assert!(!allowed("mirror.internal:8080", "mirror.internal", 443));
+        assert!(allowed("*.crates.io", "static.crates.io", 443));
+        assert!(!allowed("*.crates.io", "crates.io", 443));
+        assert!(!allowed("*.crates.io", "evilcrates.io", 443));
+        assert!(allowed("[::1]:3000", "::1", 3000));
+        assert!(allowed("::1", "::1", 3000));
+
+        for invalid in ["", "host:port", "*.", "a.*.com", "[::1", "host:99999"] {
+            assert!(AllowRule::parse(invalid).is_err(), "{invalid} should fail");
+        }
+    }
+
+    #[test]
+    fn parse_request_rewrites_absolute_form() {
+        let request = parse_request(
+            "GET http://Example.com:8080/index.html?q=1 HTTP/1.1\r\n\
+             Host: example.com:8080\r\n\
+             Proxy-Connection: keep-alive\r\n\
+             Accept: */*\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.host, "example.com");
+        assert_eq!(request.port, 8080);
+        assert_eq!(
+            request.forward_head.as_deref(),
+            Some(
+                "GET /index.html?q=1 HTTP/1.1\r\n\
+                 Host: Example.com:8080\r\n\
+                 Accept: */*\r\n\
+                 Connection: close\r\n\r\n"
+            )
+        );
+
+        // A `Host` header naming another server is replaced, so an allowed
+        // authority cannot carry a request for a denied virtual host.
+        let smuggled = parse_request(
+            "GET http://allowed.example/ HTTP/1.1\r\n\
+             Host: denied.example\r\n\
+             hOsT: other.example\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(smuggled.host, "allowed.example");
+        assert_eq!(
+            smuggled.forward_head.as_deref(),
+            Some(
+                "GET / HTTP/1.1\r\n\
+                 Host: allowed.example\r\n\
+                 Connection: close\r\n\r\n"
+            )
+        );
+
+        let connect = parse_request("CONNECT [::1]:443 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!((connect.host.as_str(), connect.port), ("::1", 443));
+        assert_eq!(connect.forward_head, None);
+
+        assert!(parse_request("GET https://example.com/ HTTP/1.1\r\n\r\n").is_none());
+        assert!(parse_request("CONNECT example.com HTTP/1.1\r\n\r\n").is_none());
+    }
+
+    fn connected_pair() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let peer = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (accepted, _) = listener.accept().unwrap();
+        (peer, accepted)
+    }
+
+    #[test]
+    fn tunnel_closes_once_both_directions_are_idle() {
+        let (mut client_peer, client) = connected_pair();
+        let (mut upstream_peer, upstream) = connected_pair();
+        for stream in [&client, &upstream] {
+            stream
+                .set_read_timeout(Some(Duration::from_millis(20)))
+                .unwrap();
+        }
+        upstream_peer
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let tunnel = std::thread::spawn(move || {
+            tunnel(client, upstream, Duration::from_millis(200));
+        });
+
+        client_peer.write_all(b"ping").unwrap();
+        let mut received = [0u8; 4];
+        upstream_peer.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"ping");
+
+        // Nothing else is sent, so the tunnel gives up and closes upstream.
+        let mut rest = Vec::new();
+        upstream_peer.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, Vec::<u8>::new());
+        tunnel.join().unwrap();
+    }
+}
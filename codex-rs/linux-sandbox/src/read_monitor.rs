@@ -0,0 +1,334 @@
+//! Reports attempts to open `deny_read` paths.
+//!
+//! Landlock refuses those opens without telling anyone, so to name the paths
+//! in the denial report the helper forks before applying Landlock. The child
+//! goes on to run the command under a seccomp filter that hands its
+//! `open`-family calls to the parent as user notifications. The parent never
+//! decides anything: it reads the path from the command's memory, reports it
+//! when it is denied and lets the call continue, so Landlock still refuses
+//! it. Reading another process's memory needs ptrace access, which the
+//! parent has as the command's ancestor.
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::os::unix::ffi::OsStringExt;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use codex_core::landlock::READ_DENIED_PREFIX;
+use codex_core::sandboxing::deny_read::DenyReadList;
+
+use crate::container::exit_like_child;
+use crate::denial_report;
+use crate::proxy::recv_fd;
+use crate::proxy::send_fd;
+
+// From <linux/seccomp.h> and <linux/filter.h>.
+const SECCOMP_SET_MODE_FILTER: libc::c_ulong = 1;
+const SECCOMP_FILTER_FLAG_NEW_LISTENER: libc::c_ulong = 1 << 3;
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+const SECCOMP_RET_USER_NOTIF: u32 = 0x7fc0_0000;
+const SECCOMP_USER_NOTIF_FLAG_CONTINUE: u32 = 1;
+const SECCOMP_IOCTL_NOTIF_RECV: u64 = 0xc050_2100;
+const SECCOMP_IOCTL_NOTIF_SEND: u64 = 0xc018_2101;
+const SECCOMP_IOCTL_NOTIF_ID_VALID: u64 = 0x4008_2102;
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_JEQ_K: u16 = 0x15;
+const BPF_RET_K: u16 = 0x06;
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+#[cfg(target_arch = "x86_64")]
+const OPEN_SYSCALLS: [libc::c_long; 3] = [libc::SYS_open, libc::SYS_openat, libc::SYS_openat2];
+#[cfg(target_arch = "aarch64")]
+const OPEN_SYSCALLS: [libc::c_long; 2] = [libc::SYS_openat, libc::SYS_openat2];
+
+/// Offsets into `struct seccomp_data`.
+const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+
+/// Reads of the command's memory never cross this boundary, so a string
+/// ending just before an unmapped page is still read.
+const REMOTE_READ_CHUNK: usize = 4096;
+
+#[repr(C)]
+struct SeccompData {
+    nr: libc::c_int,
+    arch: u32,
+    instruction_pointer: u64,
+    args: [u64; 6],
+}
+
+#[repr(C)]
+struct SeccompNotif {
+    id: u64,
+    pid: u32,
+    flags: u32,
+    data: SeccompData,
+}
+
+#[repr(C)]
+struct SeccompNotifResp {
+    id: u64,
+    val: i64,
+    error: i32,
+    flags: u32,
+}
+
+/// The child side of [`spawn_read_monitor`].
+pub(crate) struct MonitoredChild {
+    socket: UnixStream,
+}
+
+impl MonitoredChild {
+    /// Installs the notifying filter and hands its listener to the monitor.
+    /// Call it once Landlock applies, so that setting up Landlock is not
+    /// monitored. Failing to attach only loses the reports, not the
+    /// restriction, so errors are ignored.
+    pub(crate) fn attach(self) {
+        if let Ok(listener) = install_notifying_filter() {
+            let _ = send_fd(&self.socket, listener.as_raw_fd());
+        }
+    }
+}
+
+/// Forks the monitor, which waits for the command and exits like it. Only
+/// the child returns.
+pub(crate) fn spawn_read_monitor(deny_list: DenyReadList) -> io::Result<MonitoredChild> {
+    let (socket, monitor_socket) = UnixStream::pair()?;
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            drop(monitor_socket);
+            // Codex only knows about the monitor; don't outlive it.
+            if unsafe { libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(MonitoredChild { socket })
+        }
+        child => {
+            drop(socket);
+            run_monitor(child, &monitor_socket, &deny_list)
+        }
+    }
+}
+
+fn install_notifying_filter() -> io::Result<OwnedFd> {
+    let mut filter = vec![
+        bpf_stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH_OFFSET),
+        bpf_jump(BPF_JEQ_K, AUDIT_ARCH, 1, 0),
+        bpf_stmt(BPF_RET_K, SECCOMP_RET_ALLOW),
+        bpf_stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR_OFFSET),
+    ];
+    for nr in OPEN_SYSCALLS {
+        filter.push(bpf_jump(BPF_JEQ_K, nr as u32, 0, 1));
+        filter.push(bpf_stmt(BPF_RET_K, SECCOMP_RET_USER_NOTIF));
+    }
+    filter.push(bpf_stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+    let program = libc::sock_fprog {
+        len: filter.len() as libc::c_ushort,
+        filter: filter.as_mut_ptr(),
+    };
+
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            SECCOMP_SET_MODE_FILTER,
+            SECCOMP_FILTER_FLAG_NEW_LISTENER,
+            &raw const program,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
+
+fn bpf_stmt(code: u16, k: u32) -> libc::sock_filter {
+    bpf_jump(code, k, 0, 0)
+}
+
+fn bpf_jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter { code, jt, jf, k }
+}
+
+/// Body of the monitor; never returns.
+fn run_monitor(child: libc::pid_t, socket: &UnixStream, deny_list: &DenyReadList) -> ! {
+    // Nothing arrives when the child could not attach.
+    let Ok(listener) = recv_fd(socket) else {
+        exit_like_child(child);
+    };
+    let child_exited = pidfd_open(child);
+
+    let mut reported = HashSet::new();
+    loop {
+        let mut fds = [
+            libc::pollfd {
+                fd: listener.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+            libc::pollfd {
+                fd: child_exited.as_ref().map_or(-1, AsRawFd::as_raw_fd),
+                events: libc::POLLIN,
+                revents: 0,
+            },
+        ];
+        if unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) } < 0 {
+            if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            break;
+        }
+        if fds[0].revents & libc::POLLIN != 0 {
+            handle_notification(&listener, deny_list, &mut reported);
+        }
+        // The listener hangs up once no process uses the filter any more.
+        if fds[0].revents & (libc::POLLHUP | libc::POLLERR | libc::POLLNVAL) != 0
+            || fds[1].revents & libc::POLLIN != 0
+        {
+            break;
+        }
+    }
+    exit_like_child(child);
+}
+
+fn pidfd_open(pid: libc::pid_t) -> Option<OwnedFd> {
+    let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+    (fd >= 0).then(|| unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+}
+
+/// Reports the path of one open call if it is denied, then lets the call
+/// continue. The call must be answered whatever happens, or the command
+/// hangs.
+fn handle_notification(
+    listener: &OwnedFd,
+    deny_list: &DenyReadList,
+    reported: &mut HashSet<PathBuf>,
+) {
+    let mut notification: SeccompNotif = unsafe { std::mem::zeroed() };
+    if unsafe {
+        libc::ioctl(
+            listener.as_raw_fd(),
+            SECCOMP_IOCTL_NOTIF_RECV as _,
+            &raw mut notification,
+        )
+    } != 0
+    {
+        // The caller died before the notification was received.
+        return;
+    }
+
+    if let Some(path) = opened_path(listener, &notification)
+        && deny_list.is_denied(&path)
+        && reported.insert(path.clone())
+    {
+        denial_report::report(READ_DENIED_PREFIX, &path.to_string_lossy());
+    }
+
+    let mut response = SeccompNotifResp {
+        id: notification.id,
+        val: 0,
+        error: 0,
+        flags: SECCOMP_USER_NOTIF_FLAG_CONTINUE,
+    };
+    unsafe {
+        libc::ioctl(
+            listener.as_raw_fd(),
+            SECCOMP_IOCTL_NOTIF_SEND as _,
+            &raw mut response,
+        );
+    }
+}
+
+/// The absolute path an open call names, unless the call cannot read it.
+fn opened_path(listener: &OwnedFd, notification: &SeccompNotif) -> Option<PathBuf> {
+    let pid = notification.pid as libc::pid_t;
+    let args = &notification.data.args;
+    let (dirfd, path, flags) = match libc::c_long::from(notification.data.nr) {
+        #[cfg(target_arch = "x86_64")]
+        libc::SYS_open => (libc::AT_FDCWD, args[0], args[1]),
+        libc::SYS_openat => (args[0] as libc::c_int, args[1], args[2]),
+        // The flags lead `struct open_how`.
+        libc::SYS_openat2 => (
+            args[0] as libc::c_int,
+            args[1],
+            read_remote_u64(pid, args[2])?,
+        ),
+        _ => return None,
+    };
+    // Landlock does not check `O_PATH` opens, so they succeed.
+    if flags & libc::O_PATH as u64 != 0 {
+        return None;
+    }
+    let path = read_remote_c_string(pid, path)?;
+    // Past this check `pid` is known to still be the caller.
+    let id = notification.id;
+    if unsafe {
+        libc::ioctl(
+            listener.as_raw_fd(),
+            SECCOMP_IOCTL_NOTIF_ID_VALID as _,
+            &raw const id,
+        )
+    } != 0
+    {
+        return None;
+    }
+    if path.is_absolute() {
+        return Some(path);
+    }
+    let base = if dirfd == libc::AT_FDCWD {
+        format!("/proc/{pid}/cwd")
+    } else {
+        format!("/proc/{pid}/fd/{dirfd}")
+    };
+    Some(std::fs::read_link(base).ok()?.join(path))
+}
+
+fn read_remote_u64(pid: libc::pid_t, address: u64) -> Option<u64> {
+    let mut bytes = [0u8; 8];
+    (read_remote(pid, address, &mut bytes)? == bytes.len()).then(|| u64::from_ne_bytes(bytes))
+}
+
+fn read_remote_c_string(pid: libc::pid_t, mut address: u64) -> Option<PathBuf> {
+    let mut bytes = Vec::new();
+    let mut chunk = [0u8; REMOTE_READ_CHUNK];
+    while bytes.len() < libc::PATH_MAX as usize {
+        let len = REMOTE_READ_CHUNK - (address as usize % REMOTE_READ_CHUNK);
+        let read = read_remote(pid, address, &mut chunk[..len])?;
+        if let Some(end) = chunk[..read].iter().position(|&byte| byte == 0) {
+            bytes.extend_from_slice(&chunk[..end]);
+            return Some(PathBuf::from(OsString::from_vec(bytes)));
+        }
+        if read < len {
+            return None;
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        address += read as u64;
+    }
+    None
+}
+
+fn read_remote(pid: libc::pid_t, address: u64, buf: &mut [u8]) -> Option<usize> {
+    let local = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    let remote = libc::iovec {
+        iov_base: address as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let read = unsafe { libc::process_vm_readv(pid, &local, 1, &remote, 1, 0) };
+    usize::try_from(read).ok()
+}
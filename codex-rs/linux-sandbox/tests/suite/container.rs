@@ -0,0 +1,113 @@
+#![cfg(target_os = "linux")]
+use codex_core::config::types::ShellEnvironmentPolicy;
+use codex_core::error::CodexErr;
+use codex_core::error::SandboxErr;
+use codex_core::exec::ExecParams;
+use codex_core::exec::ExecToolCallOutput;
+use codex_core::exec::SandboxType;
+use codex_core::exec::process_exec_tool_call;
+use codex_core::exec_env::create_env;
+use codex_core::protocol::SandboxPolicy;
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+
+const TIMEOUT_MS: u64 = 10_000;
+
+#[expect(clippy::expect_used)]
+async fn run_in_container(
+    cmd: &[&str],
+    writable_roots: Vec<PathBuf>,
+) -> Result<ExecToolCallOutput, CodexErr> {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let sandbox_cwd = cwd.clone();
+    let params = ExecParams {
+        command: cmd.iter().copied().map(str::to_owned).collect(),
+        cwd,
+        timeout_ms: Some(TIMEOUT_MS),
+        env: create_env(&ShellEnvironmentPolicy::default()),
+        with_escalated_permissions: None,
+        justification: None,
+        arg0: None,
+    };
+
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots,
+        network_access: false,
+        network_allowlist: Vec::new(),
+        deny_read: Vec::new(),
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+    };
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
+    process_exec_tool_call(
+        params,
+        SandboxType::LinuxContainer,
+        &sandbox_policy,
+        sandbox_cwd.as_path(),
+        &codex_linux_sandbox_exe,
+        None,
+    )
+    .await
+}
+
+/// Hosts without unprivileged user namespaces fall back to Landlock; treat
+/// that as an accepted skip, like the tests in `network_allowlist.rs`.
+#[expect(clippy::print_stdout)]
+fn container_unavailable(output: &ExecToolCallOutput) -> bool {
+    let unavailable = output.stderr.text.contains("container unavailable");
+    if unavailable {
+        println!("skipping: {}", output.stderr.text);
+    }
+    unavailable
+}
+
+#[tokio::test]
+async fn container_only_shows_workspace_and_writable_roots() {
+    let writable = tempfile::tempdir().unwrap();
+    let hidden = tempfile::tempdir().unwrap();
+    std::fs::write(hidden.path().join("secret"), "hunter2").unwrap();
+
+    let script = format!(
+        "echo container > {out} && test ! -e {secret} && test -f Cargo.toml",
+        out = writable.path().join("out").display(),
+        secret = hidden.path().join("secret").display(),
+    );
+    let result = run_in_container(
+        &["bash", "-c", &script],
+        vec![writable.path().to_path_buf()],
+    )
+    .await;
+    let output = match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        other => panic!("expected the command to run, got: {other:?}"),
+    };
+    if container_unavailable(&output) {
+        return;
+    }
+
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+    assert_eq!(
+        std::fs::read_to_string(writable.path().join("out")).unwrap(),
+        "container\n"
+    );
+}
+
+#[tokio::test]
+async fn container_runs_in_its_own_pid_namespace() {
+    // The command is the init process of the namespace and the host's
+    // processes, such as this test, are not in its /proc.
+    let script = format!("test $$ = 1 && test ! -e /proc/{}", std::process::id());
+    let result = run_in_container(&["bash", "-c", &script], Vec::new()).await;
+    let output = match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        other => panic!("expected the command to run, got: {other:?}"),
+    };
+    if container_unavailable(&output) {
+        return;
+    }
+
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+}
@@ -3,6 +3,7 @@ use codex_core::config::types::ShellEnvironmentPolicy;
 use codex_core::error::CodexErr;
 use codex_core::error::SandboxErr;
 use codex_core::exec::ExecParams;
+use codex_core::exec::ExecToolCallOutput;
 use codex_core::exec::SandboxType;
 use codex_core::exec::process_exec_tool_call;
 use codex_core::exec_env::create_env;
@@ -50,6 +51,8 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
     let sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: writable_roots.to_vec(),
         network_access: false,
+        network_allowlist: Vec::new(),
+        deny_read: Vec::new(),
         // Exclude tmp-related folders from writable roots because we need a
         // folder that is writable by tests but that we intentionally disallow
         // writing to in the sandbox.
@@ -165,7 +168,10 @@ async fn assert_network_blocked(cmd: &[&str]) {
 
     let output = match result {
         Ok(output) => output,
-        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        // ssh also trips over the default `deny_read` entry for `~/.ssh`.
+        Err(CodexErr::Sandbox(
+            SandboxErr::Denied { output } | SandboxErr::ReadDenied { output, .. },
+        )) => *output,
         _ => {
             panic!("expected sandbox denied error, got: {result:?}");
         }
@@ -236,3 +242,134 @@ async fn sandbox_blocks_dev_tcp_redirection() {
     // all images ship bash, so we guard against 127 as well.
     assert_network_blocked(&["bash", "-c", "echo hi > /dev/tcp/127.0.0.1/80"]).await;
 }
+
+#[expect(clippy::expect_used)]
+async fn run_with_policy(
+    cmd: &[&str],
+    sandbox_policy: SandboxPolicy,
+) -> Result<ExecToolCallOutput, CodexErr> {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let sandbox_cwd = cwd.clone();
+    let params = ExecParams {
+        command: cmd.iter().copied().map(str::to_owned).collect(),
+        cwd,
+        timeout_ms: Some(LONG_TIMEOUT_MS),
+        env: create_env_from_core_vars(),
+        with_escalated_permissions: None,
+        justification: None,
+        arg0: None,
+    };
+
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
+    process_exec_tool_call(
+        params,
+        SandboxType::LinuxSeccomp,
+        &sandbox_policy,
+        sandbox_cwd.as_path(),
+        &codex_linux_sandbox_exe,
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn sandbox_blocks_reading_denied_paths() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secrets = tmpdir.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+    std::fs::write(secrets.join("token"), "hunter2").unwrap();
+    std::fs::write(tmpdir.path().join("public"), "hello").unwrap();
+    let deny_read = vec![secrets.to_string_lossy().to_string()];
+
+    let public = tmpdir.path().join("public");
+    let output = run_with_policy(
+        &["cat", &public.to_string_lossy()],
+        SandboxPolicy::ReadOnly {
+            deny_read: deny_read.clone(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(output.stdout.text, "hello");
+
+    let token = secrets.join("token");
+    match run_with_policy(
+        &["cat", &token.to_string_lossy()],
+        SandboxPolicy::ReadOnly { deny_read },
+    )
+    .await
+    {
+        // Without `--report-denied-reads` Landlock refuses the open but no
+        // monitor names the path, so this is a plain denial.
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => {
+            assert!(!output.stdout.text.contains("hunter2"));
+        }
+        other => panic!("expected sandbox denied error, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn command_output_cannot_fake_read_denials() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secret = tmpdir.path().join("secret");
+    std::fs::write(&secret, "hunter2").unwrap();
+    let script = format!(
+        "echo 'cat: {0}: Permission denied' >&2; echo 'read-denied {0}' >&2; exit 1",
+        secret.display()
+    );
+
+    let result = run_with_policy(
+        &["bash", "-c", &script],
+        SandboxPolicy::ReadOnly {
+            deny_read: vec![secret.to_string_lossy().to_string()],
+        },
+    )
+    .await;
+    assert!(
+        !matches!(
+            result,
+            Err(CodexErr::Sandbox(SandboxErr::ReadDenied { .. }))
+        ),
+        "stderr was taken for a denial report: {result:?}"
+    );
+}
+
+#[tokio::test]
+async fn denied_paths_in_writable_roots_are_not_writable() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let root = tmpdir.path().canonicalize().unwrap();
+    let secret = root.join("secret");
+    let notes = root.join("notes");
+    std::fs::write(&secret, "hunter2").unwrap();
+    std::fs::write(&notes, "").unwrap();
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![root.clone()],
+        network_access: false,
+        network_allowlist: Vec::new(),
+        deny_read: vec![secret.to_string_lossy().to_string()],
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+    };
+
+    // Other files in the root stay writable.
+    let script = format!("echo ok > {}", notes.display());
+    let output = run_with_policy(&["bash", "-c", &script], sandbox_policy.clone())
+        .await
+        .unwrap();
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+    assert_eq!(std::fs::read_to_string(&notes).unwrap(), "ok\n");
+
+    for script in [
+        format!("echo pwned > {}", secret.display()),
+        format!("rm {}", secret.display()),
+        format!("mv {} {}", secret.display(), root.join("moved").display()),
+    ] {
+        let result = run_with_policy(&["bash", "-c", &script], sandbox_policy.clone()).await;
+        match result {
+            Err(CodexErr::Sandbox(SandboxErr::Denied { .. } | SandboxErr::ReadDenied { .. })) => {}
+            other => panic!("expected `{script}` to be denied, got: {other:?}"),
+        }
+        assert_eq!(std::fs::read_to_string(&secret).unwrap(), "hunter2");
+    }
+}
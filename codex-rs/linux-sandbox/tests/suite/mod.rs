@@ -1,2 +1,4 @@
 // Aggregates all former standalone integration tests as modules.
+mod container;
 mod landlock;
+mod network_allowlist;
@@ -0,0 +1,206 @@
+#![cfg(target_os = "linux")]
+use codex_core::config::types::ShellEnvironmentPolicy;
+use codex_core::error::CodexErr;
+use codex_core::error::SandboxErr;
+use codex_core::exec::ExecParams;
+use codex_core::exec::ExecToolCallOutput;
+use codex_core::exec::SandboxType;
+use codex_core::exec::process_exec_tool_call;
+use codex_core::exec_env::create_env;
+use codex_core::protocol::SandboxPolicy;
+use pretty_assertions::assert_eq;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::PathBuf;
+
+const TIMEOUT_MS: u64 = 10_000;
+
+const RESPONSE_BODY: &str = "hello from the mirror";
+
+/// Serves `RESPONSE_BODY` to every request on a local port and returns it.
+#[expect(clippy::expect_used)]
+fn spawn_http_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind local HTTP server");
+    let port = listener.local_addr().expect("local address").port();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{RESPONSE_BODY}",
+                RESPONSE_BODY.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    port
+}
+
+#[expect(clippy::expect_used)]
+async fn run_with_allowlist(
+    cmd: &[&str],
+    network_allowlist: Vec<String>,
+) -> Result<ExecToolCallOutput, CodexErr> {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let sandbox_cwd = cwd.clone();
+    let params = ExecParams {
+        command: cmd.iter().copied().map(str::to_owned).collect(),
+        cwd,
+        timeout_ms: Some(TIMEOUT_MS),
+        env: create_env(&ShellEnvironmentPolicy::default()),
+        with_escalated_permissions: None,
+        justification: None,
+        arg0: None,
+    };
+
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![],
+        network_access: false,
+        network_allowlist,
+        deny_read: Vec::new(),
+        exclude_tmpdir_env_var: false,
+        exclude_slash_tmp: false,
+    };
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
+    process_exec_tool_call(
+        params,
+        SandboxType::LinuxSeccomp,
+        &sandbox_policy,
+        sandbox_cwd.as_path(),
+        &codex_linux_sandbox_exe,
+        None,
+    )
+    .await
+}
+
+/// The allowlist needs unprivileged user namespaces (or root) and curl; treat
+/// hosts without either as an accepted skip, like the tests in `landlock.rs`.
+#[expect(clippy::print_stdout)]
+fn environment_unsupported(result: &Result<ExecToolCallOutput, CodexErr>) -> bool {
+    let output = match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(
+            SandboxErr::Denied { output } | SandboxErr::NetworkDenied { output, .. },
+        )) => output.as_ref(),
+        Err(_) => return false,
+    };
+    let unsupported = output.exit_code == 127
+        || output
+            .stderr
+            .text
+            .contains("needs a private network namespace");
+    if unsupported {
+        println!("skipping: {}", output.stderr.text);
+    }
+    unsupported
+}
+
+#[tokio::test]
+async fn allowlisted_host_is_reachable_through_proxy() {
+    let port = spawn_http_server();
+    let result = run_with_allowlist(
+        &[
+            "curl",
+            "-sS",
+            "--fail",
+            &format!("http://127.0.0.1:{port}/"),
+        ],
+        vec![format!("127.0.0.1:{port}")],
+    )
+    .await;
+    if environment_unsupported(&result) {
+        return;
+    }
+
+    let output = result.unwrap();
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+    assert_eq!(output.stdout.text, RESPONSE_BODY);
+}
+
+#[tokio::test]
+async fn host_outside_allowlist_is_reported_as_network_denied() {
+    let port = spawn_http_server();
+    let result = run_with_allowlist(
+        &[
+            "curl",
+            "-sS",
+            "--fail",
+            &format!("http://127.0.0.1:{port}/"),
+        ],
+        vec!["registry.npmjs.org".to_string()],
+    )
+    .await;
+    if environment_unsupported(&result) {
+        return;
+    }
+
+    match result {
+        Err(CodexErr::Sandbox(SandboxErr::NetworkDenied { hosts, output })) => {
+            assert_eq!(hosts, vec![format!("127.0.0.1:{port}")]);
+            assert_ne!(output.exit_code, 0);
+        }
+        other => panic!("expected network denied error, got: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn direct_connections_cannot_bypass_proxy() {
+    let port = spawn_http_server();
+    let result = run_with_allowlist(
+        &[
+            "bash",
+            "-c",
+            &format!("echo hi > /dev/tcp/127.0.0.1/{port}"),
+        ],
+        vec![format!("127.0.0.1:{port}")],
+    )
+    .await;
+    if environment_unsupported(&result) {
+        return;
+    }
+
+    let output = match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        other => panic!("expected the command to fail, got: {other:?}"),
+    };
+    assert_ne!(
+        output.exit_code, 0,
+        "direct connection escaped the private network namespace"
+    );
+}
+
+#[tokio::test]
+async fn command_output_cannot_fake_network_denials() {
+    let result = run_with_allowlist(
+        &[
+            "bash",
+            "-c",
+            "echo 'network-denied evil.example:443' >&2; exit 1",
+        ],
+        vec!["registry.npmjs.org".to_string()],
+    )
+    .await;
+    if environment_unsupported(&result) {
+        return;
+    }
+
+    assert!(
+        !matches!(
+            result,
+            Err(CodexErr::Sandbox(SandboxErr::NetworkDenied { .. }))
+        ),
+        "stderr was taken for a denial report: {result:?}"
+    );
+}
@@ -5,6 +5,8 @@ use crate::codex_tool_config::CodexToolCallParam;
 use crate::codex_tool_config::CodexToolCallReplyParam;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
+#[cfg(feature = "ace")]
+use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_protocol::ConversationId;
@@ -25,9 +27,11 @@ use mcp_types::JSONRPCErrorError;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
+use mcp_types::ListResourcesResult;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolRequest;
 use mcp_types::RequestId;
+use mcp_types::ServerCapabilitiesResources;
 use mcp_types::ServerCapabilitiesTools;
 use mcp_types::ServerNotification;
 use mcp_types::TextContent;
@@ -42,6 +46,8 @@ pub(crate) struct MessageProcessor {
     codex_linux_sandbox_exe: Option<PathBuf>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    #[cfg(feature = "ace")]
+    codex_home: PathBuf,
 }
 
 impl MessageProcessor {
@@ -66,6 +72,8 @@ impl MessageProcessor {
             codex_linux_sandbox_exe,
             conversation_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "ace")]
+            codex_home: config.codex_home.clone(),
         }
     }
 
@@ -90,13 +98,13 @@ impl MessageProcessor {
                 self.handle_ping(request_id, params).await;
             }
             McpClientRequest::ListResourcesRequest(params) => {
-                self.handle_list_resources(params);
+                self.handle_list_resources(request_id, params).await;
             }
             McpClientRequest::ListResourceTemplatesRequest(params) => {
                 self.handle_list_resource_templates(params);
             }
             McpClientRequest::ReadResourceRequest(params) => {
-                self.handle_read_resource(params);
+                self.handle_read_resource(request_id, params).await;
             }
             McpClientRequest::SubscribeRequest(params) => {
                 self.handle_subscribe(params);
@@ -209,7 +217,10 @@ impl MessageProcessor {
                 experimental: None,
                 logging: None,
                 prompts: None,
-                resources: None,
+                resources: cfg!(feature = "ace").then_some(ServerCapabilitiesResources {
+                    list_changed: None,
+                    subscribe: None,
+                }),
                 tools: Some(ServerCapabilitiesTools {
                     list_changed: Some(true),
                 }),
@@ -246,11 +257,22 @@ impl MessageProcessor {
             .await;
     }
 
-    fn handle_list_resources(
+    async fn handle_list_resources(
         &self,
+        id: RequestId,
         params: <mcp_types::ListResourcesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/list -> params: {:?}", params);
+        #[cfg(feature = "ace")]
+        let resources = vec![crate::playbook_tools::playbook_resource()];
+        #[cfg(not(feature = "ace"))]
+        let resources = Vec::new();
+        let result = ListResourcesResult {
+            next_cursor: None,
+            resources,
+        };
+        self.send_response::<mcp_types::ListResourcesRequest>(id, result)
+            .await;
     }
 
     fn handle_list_resource_templates(
@@ -261,11 +283,36 @@ impl MessageProcessor {
         tracing::info!("resources/templates/list -> params: {:?}", params);
     }
 
-    fn handle_read_resource(
+    async fn handle_read_resource(
         &self,
+        id: RequestId,
         params: <mcp_types::ReadResourceRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/read -> params: {:?}", params);
+        let result = match params.uri.as_str() {
+            #[cfg(feature = "ace")]
+            crate::playbook_tools::PLAYBOOK_RESOURCE_URI => {
+                crate::playbook_tools::read_playbook_resource(&self.codex_home)
+                    .await
+                    .map_err(|err| JSONRPCErrorError {
+                        code: INTERNAL_ERROR_CODE,
+                        message: format!("failed to read playbook: {err:#}"),
+                        data: None,
+                    })
+            }
+            uri => Err(JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("unknown resource: {uri}"),
+                data: None,
+            }),
+        };
+        match result {
+            Ok(result) => {
+                self.send_response::<mcp_types::ReadResourceRequest>(id, result)
+                    .await;
+            }
+            Err(error) => self.outgoing.send_error(id, error).await,
+        }
     }
 
     fn handle_subscribe(
@@ -302,11 +349,14 @@ impl MessageProcessor {
         params: <mcp_types::ListToolsRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::trace!("tools/list -> {params:?}");
+        let tools = vec![
+            create_tool_for_codex_tool_call_param(),
+            create_tool_for_codex_tool_call_reply_param(),
+        ];
+        #[cfg(feature = "ace")]
+        let tools = [tools, crate::playbook_tools::create_playbook_tools()].concat();
         let result = ListToolsResult {
-            tools: vec![
-                create_tool_for_codex_tool_call_param(),
-                create_tool_for_codex_tool_call_reply_param(),
-            ],
+            tools,
             next_cursor: None,
         };
 
@@ -328,6 +378,23 @@ impl MessageProcessor {
                 self.handle_tool_call_codex_session_reply(id, arguments)
                     .await
             }
+            #[cfg(feature = "ace")]
+            name if name.starts_with("playbook-") => {
+                let result =
+                    crate::playbook_tools::call_playbook_tool(&self.codex_home, name, arguments)
+                        .await
+                        .unwrap_or_else(|| CallToolResult {
+                            content: vec![ContentBlock::TextContent(TextContent {
+                                r#type: "text".to_string(),
+                                text: format!("Unknown tool '{name}'"),
+                                annotations: None,
+                            })],
+                            is_error: Some(true),
+                            structured_content: None,
+                        });
+                self.send_response::<mcp_types::CallToolRequest>(id, result)
+                    .await;
+            }
             _ => {
                 let result = CallToolResult {
                     content: vec![ContentBlock::TextContent(TextContent {
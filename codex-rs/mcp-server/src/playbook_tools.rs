@@ -0,0 +1,324 @@
+//! `playbook-*` tools and the `codex://playbook` resource, which expose the
+//! personal ACE playbook to MCP clients. Arguments and results reuse the v2
+//! app-server `playbook/*` types so both servers speak the same shapes.
+
+use anyhow::Context;
+use codex_ace::BulletSection;
+use codex_app_server_protocol::DeletePlaybookBulletParams;
+use codex_app_server_protocol::DeletePlaybookBulletResponse;
+use codex_app_server_protocol::ListPlaybookParams;
+use codex_app_server_protocol::ListPlaybookResponse;
+use codex_app_server_protocol::PlaybookBullet;
+use codex_app_server_protocol::SearchPlaybookParams;
+use codex_app_server_protocol::SearchPlaybookResponse;
+use codex_app_server_protocol::UpdatePlaybookBulletParams;
+use codex_app_server_protocol::UpdatePlaybookBulletResponse;
+use codex_core::ace::AceCliHandler;
+use codex_core::ace::BulletAction;
+use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use mcp_types::TextContent;
+use mcp_types::TextResourceContents;
+use mcp_types::Tool;
+use mcp_types::ToolInputSchema;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::path::Path;
+
+pub(crate) const PLAYBOOK_RESOURCE_URI: &str = "codex://playbook";
+
+const PLAYBOOK_LIST_TOOL: &str = "playbook-list";
+const PLAYBOOK_SEARCH_TOOL: &str = "playbook-search";
+const PLAYBOOK_UPDATE_TOOL: &str = "playbook-update";
+const PLAYBOOK_DELETE_TOOL: &str = "playbook-delete";
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+pub(crate) fn create_playbook_tools() -> Vec<Tool> {
+    vec![
+        create_tool::<ListPlaybookParams>(
+            PLAYBOOK_LIST_TOOL,
+            "List Playbook",
+            "List entries in the Codex playbook, optionally filtered by section.",
+        ),
+        create_tool::<SearchPlaybookParams>(
+            PLAYBOOK_SEARCH_TOOL,
+            "Search Playbook",
+            "Search the Codex playbook for entries relevant to a query.",
+        ),
+        create_tool::<UpdatePlaybookBulletParams>(
+            PLAYBOOK_UPDATE_TOOL,
+            "Update Playbook Entry",
+            "Edit the content of a playbook entry or pin/unpin it.",
+        ),
+        create_tool::<DeletePlaybookBulletParams>(
+            PLAYBOOK_DELETE_TOOL,
+            "Delete Playbook Entry",
+            "Delete a playbook entry, or archive it when `archive` is true.",
+        ),
+    ]
+}
+
+fn create_tool<T: JsonSchema>(name: &str, title: &str, description: &str) -> Tool {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    #[expect(clippy::expect_used)]
+    let schema_value =
+        serde_json::to_value(&schema).expect("playbook tool schema should serialise to JSON");
+
+    let input_schema =
+        serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
+            panic!("failed to create Tool from schema: {e}");
+        });
+
+    Tool {
+        name: name.to_string(),
+        title: Some(title.to_string()),
+        input_schema,
+        output_schema: None,
+        description: Some(description.to_string()),
+        annotations: None,
+    }
+}
+
+/// Runs a `playbook-*` tool. Returns `None` when `name` is not one of them.
+pub(crate) async fn call_playbook_tool(
+    codex_home: &Path,
+    name: &str,
+    arguments: Option<serde_json::Value>,
+) -> Option<CallToolResult> {
+    let handler = AceCliHandler::new(codex_home);
+    let result = match name {
+        PLAYBOOK_LIST_TOOL => match parse_arguments(arguments) {
+            Ok(params) => to_json(list(&handler, params).await),
+            Err(err) => Err(err),
+        },
+        PLAYBOOK_SEARCH_TOOL => match parse_arguments(arguments) {
+            Ok(params) => to_json(search(&handler, params).await),
+            Err(err) => Err(err),
+        },
+        PLAYBOOK_UPDATE_TOOL => match parse_arguments(arguments) {
+            Ok(params) => to_json(update(&handler, params).await),
+            Err(err) => Err(err),
+        },
+        PLAYBOOK_DELETE_TOOL => match parse_arguments(arguments) {
+            Ok(params) => to_json(delete(&handler, params).await),
+            Err(err) => Err(err),
+        },
+        _ => return None,
+    };
+
+    Some(match result {
+        Ok(value) => CallToolResult {
+            content: vec![text_content(value.to_string())],
+            is_error: None,
+            structured_content: Some(value),
+        },
+        Err(err) => CallToolResult {
+            content: vec![text_content(format!("{name} failed: {err:#}"))],
+            is_error: Some(true),
+            structured_content: None,
+        },
+    })
+}
+
+fn text_content(text: String) -> ContentBlock {
+    ContentBlock::TextContent(TextContent {
+        r#type: "text".to_string(),
+        text,
+        annotations: None,
+    })
+}
+
+fn parse_arguments<T: DeserializeOwned>(arguments: Option<serde_json::Value>) -> anyhow::Result<T> {
+    serde_json::from_value(arguments.unwrap_or_else(|| json!({}))).context("invalid arguments")
+}
+
+fn to_json<T: Serialize>(result: anyhow::Result<T>) -> anyhow::Result<serde_json::Value> {
+    Ok(serde_json::to_value(result?)?)
+}
+
+async fn list(
+    handler: &AceCliHandler,
+    params: ListPlaybookParams,
+) -> anyhow::Result<ListPlaybookResponse> {
+    let ListPlaybookParams {
+        section,
+        page_size,
+        cursor,
+    } = params;
+    let section = section
+        .map(|title| {
+            BulletSection::from_title(&title)
+                .with_context(|| format!("unknown playbook section: {title}"))
+        })
+        .transpose()?;
+
+    let items: Vec<PlaybookBullet> = handler
+        .list_bullets()
+        .await?
+        .iter()
+        .filter(|bullet| {
+            section
+                .as_ref()
+                .is_none_or(|section| &bullet.section == section)
+        })
+        .map(PlaybookBullet::from)
+        .collect();
+
+    let start = match cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .with_context(|| format!("invalid cursor: {cursor}"))?
+            .min(items.len()),
+        None => 0,
+    };
+    let end = match page_size {
+        Some(page_size) => start.saturating_add(page_size.max(1)).min(items.len()),
+        None => items.len(),
+    };
+    let next_cursor = (end < items.len()).then(|| end.to_string());
+
+    Ok(ListPlaybookResponse {
+        items: items[start..end].to_vec(),
+        next_cursor,
+    })
+}
+
+async fn search(
+    handler: &AceCliHandler,
+    params: SearchPlaybookParams,
+) -> anyhow::Result<SearchPlaybookResponse> {
+    let SearchPlaybookParams { query, limit } = params;
+    let bullets = handler
+        .search(&query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+        .await?;
+    Ok(SearchPlaybookResponse {
+        items: bullets.iter().map(PlaybookBullet::from).collect(),
+    })
+}
+
+async fn update(
+    handler: &AceCliHandler,
+    params: UpdatePlaybookBulletParams,
+) -> anyhow::Result<UpdatePlaybookBulletResponse> {
+    let UpdatePlaybookBulletParams {
+        id,
+        content,
+        pinned,
+    } = params;
+    let actions: Vec<BulletAction> = content
+        .map(BulletAction::Edit)
+        .into_iter()
+        .chain(pinned.map(BulletAction::SetPinned))
+        .collect();
+    if actions.is_empty() {
+        anyhow::bail!("`content` or `pinned` is required");
+    }
+
+    let mut updated = None;
+    for action in actions {
+        updated = handler.apply_bullet_action(&id, action).await?;
+    }
+    let bullet = updated.with_context(|| format!("entry {id} disappeared during update"))?;
+    Ok(UpdatePlaybookBulletResponse {
+        bullet: PlaybookBullet::from(&bullet),
+    })
+}
+
+async fn delete(
+    handler: &AceCliHandler,
+    params: DeletePlaybookBulletParams,
+) -> anyhow::Result<DeletePlaybookBulletResponse> {
+    let DeletePlaybookBulletParams { id, archive } = params;
+    let action = if archive {
+        BulletAction::Archive
+    } else {
+        BulletAction::Delete
+    };
+    handler.apply_bullet_action(&id, action).await?;
+    Ok(DeletePlaybookBulletResponse {})
+}
+
+pub(crate) fn playbook_resource() -> Resource {
+    Resource {
+        annotations: None,
+        description: Some("Entries Codex has learned or been taught, as JSON.".to_string()),
+        mime_type: Some("application/json".to_string()),
+        name: "playbook".to_string(),
+        size: None,
+        title: Some("Codex Playbook".to_string()),
+        uri: PLAYBOOK_RESOURCE_URI.to_string(),
+    }
+}
+
+pub(crate) async fn read_playbook_resource(
+    codex_home: &Path,
+) -> anyhow::Result<ReadResourceResult> {
+    let items: Vec<PlaybookBullet> = AceCliHandler::new(codex_home)
+        .list_bullets()
+        .await?
+        .iter()
+        .map(PlaybookBullet::from)
+        .collect();
+    Ok(ReadResourceResult {
+        contents: vec![ReadResourceResultContents::TextResourceContents(
+            TextResourceContents {
+                mime_type: Some("application/json".to_string()),
+                text: serde_json::to_string_pretty(&items)?,
+                uri: PLAYBOOK_RESOURCE_URI.to_string(),
+            },
+        )],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn playbook_tools_are_named_after_their_methods() {
+        let names: Vec<String> = create_playbook_tools()
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "playbook-list",
+                "playbook-search",
+                "playbook-update",
+                "playbook-delete",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn call_playbook_tool_rejects_missing_arguments() {
+        let codex_home = TempDir::new().unwrap();
+        assert_eq!(
+            call_playbook_tool(codex_home.path(), "codex", None).await,
+            None
+        );
+
+        let result = call_playbook_tool(codex_home.path(), PLAYBOOK_SEARCH_TOOL, None)
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result.structured_content, None);
+    }
+}
@@ -124,6 +124,51 @@ impl OllamaClient {
         Ok(names)
     }
 
+    /// Compute embeddings for `inputs` with `model` via the native `/api/embed`
+    /// endpoint. The returned vectors are in the same order as `inputs`.
+    pub async fn embed(&self, model: &str, inputs: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        let url = format!("{}/api/embed", self.host_root.trim_end_matches('/'));
+        let resp = self
+            .client
+            .post(url)
+            .json(&serde_json::json!({ "model": model, "input": inputs }))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "Embedding request failed: HTTP {}",
+                resp.status()
+            )));
+        }
+        let val = resp.json::<JsonValue>().await.map_err(io::Error::other)?;
+        let embeddings = val
+            .get("embeddings")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| io::Error::other("Embedding response missing `embeddings`"))?
+            .iter()
+            .map(|row| {
+                row.as_array()
+                    .map(|values| {
+                        values
+                            .iter()
+                            .filter_map(JsonValue::as_f64)
+                            .map(|v| v as f32)
+                            .collect::<Vec<f32>>()
+                    })
+                    .ok_or_else(|| io::Error::other("Embedding response row is not an array"))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        if embeddings.len() != inputs.len() {
+            return Err(io::Error::other(format!(
+                "Embedding response has {} vectors for {} inputs",
+                embeddings.len(),
+                inputs.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+
     /// Start a model pull and emit streaming events. The returned stream ends when
     /// a Success event is observed or the server closes the connection.
     pub async fn pull_model_stream(
@@ -268,6 +313,47 @@ mod tests {
         assert!(models.contains(&"mistral".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_embed_happy_path() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} is set; skipping test_embed_happy_path",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/embed"))
+            .and(wiremock::matchers::body_json(serde_json::json!({
+                "model": "nomic-embed-text",
+                "input": ["flaky test", "docker"]
+            })))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_raw(
+                    serde_json::json!({
+                        "model": "nomic-embed-text",
+                        "embeddings": [[0.1, 0.2], [0.3, 0.4]]
+                    })
+                    .to_string(),
+                    "application/json",
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let embeddings = client
+            .embed(
+                "nomic-embed-text",
+                &["flaky test".to_string(), "docker".to_string()],
+            )
+            .await
+            .expect("embed");
+        assert_eq!(embeddings, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+    }
+
     #[tokio::test]
     async fn test_probe_server_happy_path_openai_compat_and_native() {
         if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
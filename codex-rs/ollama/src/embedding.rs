@@ -0,0 +1,66 @@
+//! ACE embedding provider backed by a local Ollama server.
+
+use crate::OllamaClient;
+use async_trait::async_trait;
+use codex_ace::Embedder;
+use codex_ace::load_ace_config;
+use codex_ace::types::EmbeddingProvider;
+use codex_core::config::Config;
+use std::sync::Arc;
+
+/// [`Embedder`] that computes ACE bullet embeddings with a local Ollama model.
+pub struct OllamaEmbedder {
+    client: OllamaClient,
+    model: String,
+}
+
+impl OllamaEmbedder {
+    pub fn new(client: OllamaClient, model: impl Into<String>) -> Self {
+        Self {
+            client,
+            model: model.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+    fn id(&self) -> String {
+        format!("ollama:{}", self.model)
+    }
+
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(self.client.embed(&self.model, texts).await?)
+    }
+}
+
+/// Build an Ollama-backed embedder for ACE semantic retrieval when the ACE
+/// config asks for it (`[ace.embedding] enabled = true`, `provider = "ollama"`).
+///
+/// Pass the result to `ConversationManager::with_ace_embedder`. Failures are
+/// logged and leave ACE on keyword-only retrieval.
+pub async fn ace_embedder(config: &Config) -> Option<Arc<dyn Embedder>> {
+    let ace_config = match load_ace_config(&config.codex_home).await {
+        Ok(ace_config) => ace_config,
+        Err(err) => {
+            tracing::debug!("Skipping ACE embedder setup: {err}");
+            return None;
+        }
+    };
+    let embedding = &ace_config.embedding;
+    if !ace_config.enabled || !embedding.enabled || embedding.provider != EmbeddingProvider::Ollama
+    {
+        return None;
+    }
+
+    match OllamaClient::try_from_oss_provider(config).await {
+        Ok(client) => Some(Arc::new(OllamaEmbedder::new(
+            client,
+            embedding.model.clone(),
+        ))),
+        Err(err) => {
+            tracing::warn!("ACE semantic retrieval disabled: {err}");
+            None
+        }
+    }
+}
@@ -1,10 +1,16 @@
 mod client;
+#[cfg(feature = "ace")]
+mod embedding;
 mod parser;
 mod pull;
 mod url;
 
 pub use client::OllamaClient;
 use codex_core::config::Config;
+#[cfg(feature = "ace")]
+pub use embedding::OllamaEmbedder;
+#[cfg(feature = "ace")]
+pub use embedding::ace_embedder;
 pub use pull::CliProgressReporter;
 pub use pull::PullEvent;
 pub use pull::PullProgressReporter;
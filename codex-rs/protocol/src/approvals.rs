@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
 
 use crate::parse_command::ParsedCommand;
@@ -32,6 +33,71 @@ impl SandboxRiskLevel {
     }
 }
 
+/// Outcome of evaluating a command against the layered exec policy.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecPolicyDecision {
+    /// The command matched a rule and only touches paths the sandbox allows.
+    AutoApprove,
+    /// The command matched a rule but may write outside the writable roots.
+    RequireApproval,
+    /// The command matched a rule that forbids it.
+    Forbidden,
+}
+
+impl ExecPolicyDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AutoApprove => "auto_approve",
+            Self::RequireApproval => "require_approval",
+            Self::Forbidden => "forbidden",
+        }
+    }
+}
+
+/// The exec policy rule that decided how a command is approved.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ExecPolicyMatch {
+    /// Policy file that defined the rule (`#default` for the built-in policy).
+    pub source: String,
+    /// Program the rule was defined for.
+    pub program: String,
+    pub decision: ExecPolicyDecision,
+    /// Why the rule forbids the command or requires approval.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Generalized command that `ReviewDecision::ApprovedForProject` remembers.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+pub struct CommandPattern {
+    /// Leading argv a command must match exactly.
+    pub prefix: Vec<String>,
+    /// Whether the command may pass further arguments after `prefix`.
+    pub allow_extra_args: bool,
+}
+
+impl CommandPattern {
+    pub fn matches(&self, command: &[String]) -> bool {
+        let len_ok = if self.allow_extra_args {
+            command.len() >= self.prefix.len()
+        } else {
+            command.len() == self.prefix.len()
+        };
+        len_ok && command.starts_with(&self.prefix)
+    }
+}
+
+impl fmt::Display for CommandPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.prefix.join(" "))?;
+        if self.allow_extra_args {
+            f.write_str(" *")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated exec call, if available.
@@ -46,6 +112,13 @@ pub struct ExecApprovalRequestEvent {
     /// Optional model-provided risk assessment describing the blocked command.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub risk: Option<SandboxCommandAssessment>,
+    /// Exec policy rule that matched the command, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_match: Option<ExecPolicyMatch>,
+    /// Pattern saved for this project if the user answers
+    /// `ReviewDecision::ApprovedForProject`. Absent when that answer is not offered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_approval_pattern: Option<CommandPattern>,
     pub parsed_cmd: Vec<ParsedCommand>,
 }
 
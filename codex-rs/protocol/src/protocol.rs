@@ -35,7 +35,10 @@ use strum_macros::Display;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::CommandPattern;
 pub use crate::approvals::ExecApprovalRequestEvent;
+pub use crate::approvals::ExecPolicyDecision;
+pub use crate::approvals::ExecPolicyMatch;
 pub use crate::approvals::SandboxCommandAssessment;
 pub use crate::approvals::SandboxRiskLevel;
 
@@ -186,6 +189,28 @@ pub enum Op {
     /// Request to shut down codex instance.
     Shutdown,
 
+    /// Rate playbook entries (ACE bullets) that were injected as context.
+    ///
+    /// When `bullet_ids` is empty the rating applies to the personal playbook
+    /// entries injected into the most recent turn. Reply is delivered via
+    /// `EventMsg::PlaybookFeedbackApplied`.
+    PlaybookFeedback {
+        rating: PlaybookFeedbackRating,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        bullet_ids: Vec<String>,
+    },
+
+    /// Add a user-authored entry to the personal playbook.
+    /// Reply is delivered via `EventMsg::PlaybookEntryAdded`.
+    AddPlaybookEntry {
+        /// The entry text.
+        content: String,
+
+        /// Section title (e.g. "Tool Usage Tips"). Defaults to general knowledge.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        section: Option<String>,
+    },
+
     /// Execute a user-initiated one-off shell command (triggered by "!cmd").
     ///
     /// The command string is executed using the user's default shell and may
@@ -197,6 +222,21 @@ pub enum Op {
     },
 }
 
+/// User feedback on injected playbook entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PlaybookFeedbackRating {
+    /// The entry helped; counts as a success and raises its confidence.
+    Upvote,
+
+    /// The entry did not help; counts as a failure and lowers its confidence.
+    Downvote,
+
+    /// The entry is wrong or harmful; it is no longer injected until reviewed.
+    Flag,
+}
+
 /// Determines the conditions under which the user is consulted to approve
 /// running the command proposed by Codex.
 #[derive(
@@ -238,6 +278,32 @@ pub enum AskForApproval {
     Never,
 }
 
+/// Credential locations that stay unreadable inside the sandbox unless the
+/// `deny_read` list of the policy says otherwise.
+pub const DEFAULT_DENY_READ: &[&str] = &[
+    "~/.ssh",
+    "~/.gnupg",
+    "~/.aws",
+    "~/.azure",
+    "~/.config/gcloud",
+    "~/.config/gh",
+    "~/.kube",
+    "~/.docker/config.json",
+    "~/.git-credentials",
+    "~/.netrc",
+];
+
+pub fn default_deny_read() -> Vec<String> {
+    DEFAULT_DENY_READ.iter().map(ToString::to_string).collect()
+}
+
+fn is_default_deny_read(deny_read: &[String]) -> bool {
+    deny_read
+        .iter()
+        .map(String::as_str)
+        .eq(DEFAULT_DENY_READ.iter().copied())
+}
+
 /// Determines execution restrictions for model shell commands.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display, JsonSchema, TS)]
 #[strum(serialize_all = "kebab-case")]
@@ -247,9 +313,18 @@ pub enum SandboxPolicy {
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
 
-    /// Read-only access to the entire file-system.
+    /// Read-only access to the entire file-system, except for `deny_read`.
     #[serde(rename = "read-only")]
-    ReadOnly,
+    ReadOnly {
+        /// Paths that cannot be read from within the sandbox: absolute,
+        /// `~/`-relative or relative to the cwd, where `*` and `?` are
+        /// wildcards. Defaults to [`DEFAULT_DENY_READ`].
+        #[serde(
+            default = "default_deny_read",
+            skip_serializing_if = "is_default_deny_read"
+        )]
+        deny_read: Vec<String>,
+    },
 
     /// Same as `ReadOnly` but additionally grants write access to the current
     /// working directory ("workspace").
@@ -265,6 +340,20 @@ pub enum SandboxPolicy {
         #[serde(default)]
         network_access: bool,
 
+        /// Hosts reachable through a filtering proxy while `network_access`
+        /// is `false`, as `host` or `host:port` (`*.example.com` matches
+        /// subdomains). Only enforced by the Linux sandbox.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        network_allowlist: Vec<String>,
+
+        /// Paths that cannot be read from within the sandbox; same format
+        /// and default as for `ReadOnly`.
+        #[serde(
+            default = "default_deny_read",
+            skip_serializing_if = "is_default_deny_read"
+        )]
+        deny_read: Vec<String>,
+
         /// When set to `true`, will NOT include the per-user `TMPDIR`
         /// environment variable among the default writable roots. Defaults to
         /// `false`.
@@ -320,7 +409,9 @@ impl FromStr for SandboxPolicy {
 impl SandboxPolicy {
     /// Returns a policy with read-only disk access and no network.
     pub fn new_read_only_policy() -> Self {
-        SandboxPolicy::ReadOnly
+        SandboxPolicy::ReadOnly {
+            deny_read: default_deny_read(),
+        }
     }
 
     /// Returns a policy that can read the entire disk, but can only write to
@@ -330,20 +421,41 @@ impl SandboxPolicy {
         SandboxPolicy::WorkspaceWrite {
             writable_roots: vec![],
             network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: default_deny_read(),
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
         }
     }
 
-    /// Always returns `true`; restricting read access is not supported.
+    /// Whether the whole disk is readable, i.e. `deny_read` is empty.
     pub fn has_full_disk_read_access(&self) -> bool {
-        true
+        self.deny_read().is_empty()
+    }
+
+    /// Paths (possibly `~/`-relative, relative or with wildcards) that cannot
+    /// be read from within the sandbox.
+    pub fn deny_read(&self) -> &[String] {
+        match self {
+            SandboxPolicy::DangerFullAccess => &[],
+            SandboxPolicy::ReadOnly { deny_read } => deny_read,
+            SandboxPolicy::WorkspaceWrite { deny_read, .. } => deny_read,
+        }
+    }
+
+    /// Replaces the `deny_read` list; a no-op for `DangerFullAccess`.
+    pub fn set_deny_read(&mut self, paths: Vec<String>) {
+        match self {
+            SandboxPolicy::DangerFullAccess => {}
+            SandboxPolicy::ReadOnly { deny_read } => *deny_read = paths,
+            SandboxPolicy::WorkspaceWrite { deny_read, .. } => *deny_read = paths,
+        }
     }
 
     pub fn has_full_disk_write_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { .. } => false,
         }
     }
@@ -351,23 +463,38 @@ impl SandboxPolicy {
     pub fn has_full_network_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { network_access, .. } => *network_access,
         }
     }
 
+    /// Hosts that may still be reached through the sandbox's filtering proxy
+    /// when full network access is not granted.
+    pub fn network_allowlist(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                network_allowlist,
+                ..
+            } => network_allowlist,
+            _ => &[],
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
     pub fn get_writable_roots_with_cwd(&self, cwd: &Path) -> Vec<WritableRoot> {
         match self {
             SandboxPolicy::DangerFullAccess => Vec::new(),
-            SandboxPolicy::ReadOnly => Vec::new(),
+            SandboxPolicy::ReadOnly { .. } => Vec::new(),
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                network_allowlist: _,
+                deny_read: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -451,6 +578,10 @@ pub enum EventMsg {
     /// Optional means unknown — UIs should not display when `None`.
     TokenCount(TokenCountEvent),
 
+    /// Tokens of context injected by executor hooks (e.g. ACE) at the start of
+    /// the current turn. Only sent when some context was injected.
+    ContextTokenCount(ContextTokenCountEvent),
+
     /// Agent text output message
     AgentMessage(AgentMessageEvent),
 
@@ -532,6 +663,15 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Feedback from `Op::PlaybookFeedback` was recorded.
+    PlaybookFeedbackApplied(PlaybookFeedbackAppliedEvent),
+
+    /// Entry from `Op::AddPlaybookEntry` was added to the playbook.
+    PlaybookEntryAdded(PlaybookEntryAddedEvent),
+
+    /// Learned or manually added entries were merged into the playbook.
+    PlaybookUpdated(PlaybookUpdatedEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -773,6 +913,19 @@ pub struct TokenCountEvent {
     pub rate_limits: Option<RateLimitSnapshot>,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ContextTokenCountEvent {
+    /// Tokens injected by each hook, after budgeting and truncation.
+    pub sources: Vec<ContextSourceTokens>,
+    pub total_tokens: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ContextSourceTokens {
+    pub source: String,
+    pub tokens: i64,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
 pub struct RateLimitSnapshot {
     pub primary: Option<RateLimitWindow>,
@@ -1102,6 +1255,7 @@ pub enum RolloutItem {
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
     EventMsg(EventMsg),
+    MissionState(MissionStateItem),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
@@ -1121,6 +1275,15 @@ impl From<CompactedItem> for ResponseItem {
     }
 }
 
+/// Snapshot of the session's mission tracker (missions, their todos and which
+/// completed todos were already reflected on). Recorded whenever it changes so
+/// resumed sessions pick up where they left off. The payload format is owned by
+/// `codex_core::mission`.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
+pub struct MissionStateItem {
+    pub state: Value,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
 pub struct TurnContextItem {
     pub cwd: PathBuf,
@@ -1130,6 +1293,9 @@ pub struct TurnContextItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effort: Option<ReasoningEffortConfig>,
     pub summary: ReasoningSummaryConfig,
+    /// Id of the turn (submission) this context was recorded for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, JsonSchema)]
@@ -1380,6 +1546,33 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::PlaybookFeedback`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PlaybookFeedbackAppliedEvent {
+    pub rating: PlaybookFeedbackRating,
+    /// Ids of the entries that were updated.
+    pub bullet_ids: Vec<String>,
+}
+
+/// Response payload for `Op::AddPlaybookEntry`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PlaybookEntryAddedEvent {
+    pub bullet_id: String,
+    /// Title of the section the entry was filed under.
+    pub section: String,
+}
+
+/// Emitted after the playbook merges a batch of entries.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PlaybookUpdatedEvent {
+    /// Ids of the entries that were added.
+    pub added: Vec<String>,
+    /// Ids of the existing entries that were updated.
+    pub updated: Vec<String>,
+    /// Ids of the entries that were merged into others and removed.
+    pub deleted: Vec<String>,
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SessionConfiguredEvent {
     /// Name left as session_id instead of conversation_id for backwards compatibility.
@@ -1420,6 +1613,11 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this command and wants to automatically approve any
+    /// future command matching the request's `project_approval_pattern` in
+    /// this project, across sessions.
+    ApprovedForProject,
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
@@ -1557,4 +1755,29 @@ mod tests {
         assert_eq!(deserialized, event);
         Ok(())
     }
+
+    #[test]
+    fn sandbox_policy_without_deny_read_round_trips() -> Result<()> {
+        let read_only: SandboxPolicy = serde_json::from_str(r#"{"mode":"read-only"}"#)?;
+        assert_eq!(read_only, SandboxPolicy::new_read_only_policy());
+        assert_eq!(
+            serde_json::to_string(&read_only)?,
+            r#"{"mode":"read-only"}"#
+        );
+
+        let workspace_write: SandboxPolicy =
+            serde_json::from_str(r#"{"mode":"workspace-write","network_access":false}"#)?;
+        assert_eq!(workspace_write, SandboxPolicy::new_workspace_write_policy());
+
+        let custom = SandboxPolicy::ReadOnly {
+            deny_read: vec!["secrets".to_string()],
+        };
+        let serialized = serde_json::to_string(&custom)?;
+        assert_eq!(
+            serialized,
+            r#"{"mode":"read-only","deny_read":["secrets"]}"#
+        );
+        assert_eq!(serde_json::from_str::<SandboxPolicy>(&serialized)?, custom);
+        Ok(())
+    }
 }
@@ -8,6 +8,8 @@ use crate::exec_command::strip_bash_lc_and_escape;
 use crate::file_search::FileSearchManager;
 use crate::history_cell::HistoryCell;
 use crate::pager_overlay::Overlay;
+#[cfg(feature = "ace")]
+use crate::playbook_browser::PlaybookBrowser;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::resume_picker::ResumeSelection;
 use crate::tui;
@@ -16,6 +18,8 @@ use crate::updates::UpdateAction;
 use codex_ansi_escape::ansi_escape_line;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
+#[cfg(feature = "ace")]
+use codex_core::ace::AceCliHandler;
 use codex_core::config::Config;
 use codex_core::config::edit::ConfigEditsBuilder;
 use codex_core::model_family::find_family_for_model;
@@ -96,10 +100,12 @@ impl App {
         let (app_event_tx, mut app_event_rx) = unbounded_channel();
         let app_event_tx = AppEventSender::new(app_event_tx);
 
-        let conversation_manager = Arc::new(ConversationManager::new(
-            auth_manager.clone(),
-            SessionSource::Cli,
-        ));
+        let conversation_manager =
+            ConversationManager::new(auth_manager.clone(), SessionSource::Cli);
+        #[cfg(feature = "ace")]
+        let conversation_manager =
+            conversation_manager.with_ace_embedder(codex_ollama::ace_embedder(&config).await);
+        let conversation_manager = Arc::new(conversation_manager);
 
         let enhanced_keys_supported = tui.enhanced_keys_supported();
 
@@ -368,6 +374,33 @@ impl App {
             AppEvent::OpenFeedbackConsent { category } => {
                 self.chat_widget.open_feedback_consent(category);
             }
+            #[cfg(feature = "ace")]
+            AppEvent::OpenPlaybookBrowser { bullets, search } => {
+                let _ = tui.enter_alt_screen();
+                self.overlay = Some(Overlay::new_playbook(PlaybookBrowser::new(
+                    bullets,
+                    search,
+                    self.app_event_tx.clone(),
+                )));
+                tui.frame_requester().schedule_frame();
+            }
+            #[cfg(feature = "ace")]
+            AppEvent::PlaybookBulletAction { id, action } => {
+                let handler = AceCliHandler::new(&self.config.codex_home);
+                if let Err(err) = handler.apply_bullet_action(&id, action).await {
+                    let message = format!("Failed to update playbook entry: {err}");
+                    if let Some(Overlay::Playbook(browser)) = &mut self.overlay {
+                        browser.set_error(message);
+                        tui.frame_requester().schedule_frame();
+                    } else {
+                        self.chat_widget.add_error_message(message);
+                    }
+                }
+            }
+            #[cfg(feature = "ace")]
+            AppEvent::OpenPlaybookAddPrompt { section } => {
+                self.chat_widget.show_playbook_add_prompt(section);
+            }
             AppEvent::ShowWindowsAutoModeInstructions => {
                 self.chat_widget.open_windows_auto_mode_instructions();
             }
@@ -40,6 +40,12 @@ impl App {
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
+        // The playbook browser handles Esc itself (clear search, cancel edit, close).
+        #[cfg(feature = "ace")]
+        if matches!(self.overlay, Some(Overlay::Playbook(_))) {
+            self.overlay_forward_event(tui, event)?;
+            return Ok(true);
+        }
         if self.backtrack.overlay_preview_active {
             match event {
                 TuiEvent::Key(KeyEvent {
@@ -115,6 +115,27 @@ pub(crate) enum AppEvent {
     OpenFeedbackConsent {
         category: FeedbackCategory,
     },
+
+    /// Open the full-screen playbook browser with the loaded bullets.
+    #[cfg(feature = "ace")]
+    OpenPlaybookBrowser {
+        bullets: Vec<codex_ace::Bullet>,
+        /// Start with the search field focused.
+        search: bool,
+    },
+
+    /// Persist an edit made in the playbook browser.
+    #[cfg(feature = "ace")]
+    PlaybookBulletAction {
+        id: String,
+        action: codex_core::ace::BulletAction,
+    },
+
+    /// Open the text prompt for a new playbook entry in the selected section.
+    #[cfg(feature = "ace")]
+    OpenPlaybookAddPrompt {
+        section: codex_ace::BulletSection,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
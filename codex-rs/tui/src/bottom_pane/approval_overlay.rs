@@ -16,6 +16,8 @@ use crate::key_hint::KeyBinding;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use codex_core::protocol::CommandPattern;
+use codex_core::protocol::ExecPolicyMatch;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
@@ -41,6 +43,8 @@ pub(crate) enum ApprovalRequest {
         command: Vec<String>,
         reason: Option<String>,
         risk: Option<SandboxCommandAssessment>,
+        policy_match: Option<ExecPolicyMatch>,
+        project_approval_pattern: Option<CommandPattern>,
     },
     ApplyPatch {
         id: String,
@@ -97,8 +101,11 @@ impl ApprovalOverlay {
         header: Box<dyn Renderable>,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
-            ApprovalVariant::Exec { .. } => (
-                exec_options(),
+            ApprovalVariant::Exec {
+                project_approval_pattern,
+                ..
+            } => (
+                exec_options(project_approval_pattern.as_ref()),
                 "Would you like to run the following command?".to_string(),
             ),
             ApprovalVariant::ApplyPatch { .. } => (
@@ -148,7 +155,7 @@ impl ApprovalOverlay {
         };
         if let Some(variant) = self.current_variant.as_ref() {
             match (&variant, option.decision) {
-                (ApprovalVariant::Exec { id, command }, decision) => {
+                (ApprovalVariant::Exec { id, command, .. }, decision) => {
                     self.handle_exec_decision(id, command, decision);
                 }
                 (ApprovalVariant::ApplyPatch { id, .. }, decision) => {
@@ -236,7 +243,7 @@ impl BottomPaneView for ApprovalOverlay {
             && let Some(variant) = self.current_variant.as_ref()
         {
             match &variant {
-                ApprovalVariant::Exec { id, command } => {
+                ApprovalVariant::Exec { id, command, .. } => {
                     self.handle_exec_decision(id, command, ReviewDecision::Abort);
                 }
                 ApprovalVariant::ApplyPatch { id, .. } => {
@@ -289,13 +296,18 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 command,
                 reason,
                 risk,
+                policy_match,
+                project_approval_pattern,
             } => {
                 let reason = reason.filter(|item| !item.is_empty());
-                let has_reason = reason.is_some();
+                let has_reason = reason.is_some() || policy_match.is_some();
                 let mut header: Vec<Line<'static>> = Vec::new();
                 if let Some(reason) = reason {
                     header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
                 }
+                if let Some(policy_match) = policy_match.as_ref() {
+                    header.push(render_policy_match_line(policy_match));
+                }
                 if let Some(risk) = risk.as_ref() {
                     header.extend(render_risk_lines(risk));
                 } else if has_reason {
@@ -308,7 +320,11 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 }
                 header.extend(full_cmd_lines);
                 Self {
-                    variant: ApprovalVariant::Exec { id, command },
+                    variant: ApprovalVariant::Exec {
+                        id,
+                        command,
+                        project_approval_pattern,
+                    },
                     header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
                 }
             }
@@ -338,6 +354,19 @@ impl From<ApprovalRequest> for ApprovalRequestState {
     }
 }
 
+fn render_policy_match_line(policy_match: &ExecPolicyMatch) -> Line<'static> {
+    let mut spans = vec![
+        "Policy: ".into(),
+        format!("`{}`", policy_match.program).into(),
+        format!(" rule in {}", policy_match.source).dim(),
+    ];
+    if let Some(reason) = policy_match.reason.as_ref() {
+        spans.push(": ".dim());
+        spans.push(reason.clone().italic());
+    }
+    Line::from(spans)
+}
+
 fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
     let level_span = match risk.risk_level {
         SandboxRiskLevel::Low => "LOW".green().bold(),
@@ -362,8 +391,14 @@ fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
 
 #[derive(Clone)]
 enum ApprovalVariant {
-    Exec { id: String, command: Vec<String> },
-    ApplyPatch { id: String },
+    Exec {
+        id: String,
+        command: Vec<String>,
+        project_approval_pattern: Option<CommandPattern>,
+    },
+    ApplyPatch {
+        id: String,
+    },
 }
 
 #[derive(Clone)]
@@ -382,8 +417,8 @@ impl ApprovalOption {
     }
 }
 
-fn exec_options() -> Vec<ApprovalOption> {
-    vec![
+fn exec_options(project_approval_pattern: Option<&CommandPattern>) -> Vec<ApprovalOption> {
+    let mut options = vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
             decision: ReviewDecision::Approved,
@@ -396,13 +431,22 @@ fn exec_options() -> Vec<ApprovalOption> {
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
-        ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
-            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-        },
-    ]
+    ];
+    if let Some(pattern) = project_approval_pattern {
+        options.push(ApprovalOption {
+            label: format!("Yes, and always allow `{pattern}` in this project"),
+            decision: ReviewDecision::ApprovedForProject,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+        });
+    }
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        decision: ReviewDecision::Abort,
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+    });
+    options
 }
 
 fn patch_options() -> Vec<ApprovalOption> {
@@ -435,6 +479,8 @@ mod tests {
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
             risk: None,
+            policy_match: None,
+            project_approval_pattern: None,
         }
     }
 
@@ -477,6 +523,8 @@ mod tests {
             command,
             reason: None,
             risk: None,
+            policy_match: None,
+            project_approval_pattern: None,
         };
 
         let view = ApprovalOverlay::new(exec_request, tx);
@@ -498,6 +546,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn header_includes_policy_match() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["cp".into(), "a.txt".into(), "/etc/b.txt".into()],
+            reason: None,
+            risk: None,
+            policy_match: Some(ExecPolicyMatch {
+                source: "#default".into(),
+                program: "cp".into(),
+                decision: codex_core::protocol::ExecPolicyDecision::RequireApproval,
+                reason: Some("writes `/etc/b.txt` outside the writable roots".into()),
+            }),
+            project_approval_pattern: None,
+        };
+
+        let view = ApprovalOverlay::new(exec_request, tx);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 100, view.desired_height(100)));
+        view.render(Rect::new(0, 0, 100, view.desired_height(100)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered.iter().any(|line| line.contains(
+                "Policy: `cp` rule in #default: writes `/etc/b.txt` outside the writable roots"
+            )),
+            "expected header to include the policy match, got {rendered:?}"
+        );
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -525,6 +610,46 @@ mod tests {
         assert_eq!(rendered, expected);
     }
 
+    #[test]
+    fn project_option_is_offered_only_with_a_pattern() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["cargo".into(), "test".into(), "-p".into(), "foo".into()],
+            reason: None,
+            risk: None,
+            policy_match: None,
+            project_approval_pattern: Some(CommandPattern {
+                prefix: vec!["cargo".into(), "test".into()],
+                allow_extra_args: true,
+            }),
+        };
+        let mut view = ApprovalOverlay::new(exec_request, tx.clone());
+        assert!(
+            view.options
+                .iter()
+                .any(|opt| opt.label == "Yes, and always allow `cargo test *` in this project")
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ExecApproval { decision: d, .. }) = ev {
+                decision = Some(d);
+                break;
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::ApprovedForProject));
+
+        let view = ApprovalOverlay::new(make_exec_request(), tx);
+        assert!(
+            !view
+                .options
+                .iter()
+                .any(|opt| opt.decision == ReviewDecision::ApprovedForProject)
+        );
+    }
+
     #[test]
     fn enter_sets_last_selected_index_without_dismissing() {
         let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
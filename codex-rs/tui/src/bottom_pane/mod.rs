@@ -609,6 +609,8 @@ mod tests {
             command: vec!["echo".into(), "ok".into()],
             reason: None,
             risk: None,
+            policy_match: None,
+            project_approval_pattern: None,
         }
     }
 
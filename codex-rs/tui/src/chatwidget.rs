@@ -3,6 +3,8 @@ use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+#[cfg(feature = "ace")]
+use codex_core::ace::AceCliHandler;
 use codex_core::config::Config;
 use codex_core::config::types::Notifications;
 use codex_core::git_info::current_branch_name;
@@ -16,6 +18,7 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ContextTokenCountEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
@@ -30,6 +33,9 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PlaybookEntryAddedEvent;
+use codex_core::protocol::PlaybookFeedbackAppliedEvent;
+use codex_core::protocol::PlaybookFeedbackRating;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::StreamErrorEvent;
@@ -96,6 +102,7 @@ use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::status::StatusHookContextUsage;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
 mod interrupts;
@@ -240,6 +247,7 @@ pub(crate) struct ChatWidget {
     session_header: SessionHeader,
     initial_user_message: Option<UserMessage>,
     token_info: Option<TokenUsageInfo>,
+    hook_context_usage: Option<StatusHookContextUsage>,
     rate_limit_snapshot: Option<RateLimitSnapshotDisplay>,
     rate_limit_warnings: RateLimitWarningState,
     // Stream lifecycle controller
@@ -437,6 +445,9 @@ impl ChatWidget {
         self.set_status_header(String::from("Working"));
         self.full_reasoning_buffer.clear();
         self.reasoning_buffer.clear();
+        if let Some(usage) = self.hook_context_usage.as_mut() {
+            usage.start_turn();
+        }
         self.request_redraw();
     }
 
@@ -470,6 +481,12 @@ impl ChatWidget {
         }
     }
 
+    fn on_context_token_count(&mut self, event: ContextTokenCountEvent) {
+        self.hook_context_usage
+            .get_or_insert_with(StatusHookContextUsage::default)
+            .record(event);
+    }
+
     fn on_rate_limit_snapshot(&mut self, snapshot: Option<RateLimitSnapshot>) {
         if let Some(snapshot) = snapshot {
             let warnings = self.rate_limit_warnings.take_warnings(
@@ -849,6 +866,8 @@ impl ChatWidget {
             command: ev.command,
             reason: ev.reason,
             risk: ev.risk,
+            policy_match: ev.policy_match,
+            project_approval_pattern: ev.project_approval_pattern,
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
@@ -1011,6 +1030,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            hook_context_usage: None,
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -1077,6 +1097,7 @@ impl ChatWidget {
                 initial_images,
             ),
             token_info: None,
+            hook_context_usage: None,
             rate_limit_snapshot: None,
             rate_limit_warnings: RateLimitWarningState::default(),
             stream_controller: None,
@@ -1300,9 +1321,16 @@ impl ChatWidget {
                 self.handle_playbook_clear();
             }
             SlashCommand::PlaybookSearch => {
-                // 搜索 playbook
-                // TODO: 需要从命令中提取搜索参数
-                self.handle_playbook_search(None);
+                // 打开浏览器并聚焦搜索框
+                self.handle_playbook_search();
+            }
+            SlashCommand::PlaybookFeedback => {
+                // 评价上一轮注入的条目
+                self.open_playbook_feedback_popup();
+            }
+            SlashCommand::PlaybookAdd => {
+                // 选择分类后输入条目内容
+                self.open_playbook_add_popup();
             }
             SlashCommand::TestApproval => {
                 use codex_core::protocol::EventMsg;
@@ -1501,6 +1529,7 @@ impl ChatWidget {
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
+            EventMsg::ContextTokenCount(ev) => self.on_context_token_count(ev),
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
             EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
             EventMsg::TurnAborted(ev) => match ev.reason {
@@ -1535,6 +1564,8 @@ impl ChatWidget {
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::PlaybookFeedbackApplied(ev) => self.on_playbook_feedback_applied(ev),
+            EventMsg::PlaybookEntryAdded(ev) => self.on_playbook_entry_added(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
@@ -1554,6 +1585,7 @@ impl ChatWidget {
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
             EventMsg::RawResponseItem(_)
+            | EventMsg::PlaybookUpdated(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
@@ -1696,6 +1728,7 @@ impl ChatWidget {
             context_usage,
             &self.conversation_id,
             self.rate_limit_snapshot.as_ref(),
+            self.hook_context_usage.as_ref(),
             Local::now(),
         ));
     }
@@ -2382,18 +2415,20 @@ impl ChatWidget {
 
     // ACE Playbook 命令处理方法
     fn handle_playbook_status(&mut self) {
-        // 获取并显示 playbook 状态
+        // 后台读取统计信息，完成后插入历史记录
         #[cfg(feature = "ace")]
         {
-            // 由于 AceCliHandler 会直接打印输出，我们暂时显示一条提示消息
-            // 未来可以重构 AceCliHandler 使其返回字符串而不是直接打印
-            self.add_info_message(
-                "ACE playbook status is displayed in the terminal output.".to_string(),
-                Some("Run 'codex ace status' in terminal for details.".to_string()),
-            );
-
-            // 这里可以调用 handler，但输出会到终端而不是 TUI
-            // TODO: 重构 AceCliHandler 以支持返回字符串
+            let handler = AceCliHandler::new(&self.config.codex_home);
+            let tx = self.app_event_tx.clone();
+            tokio::spawn(async move {
+                let cell = match handler.status().await {
+                    Ok(status) => history_cell::new_playbook_status(&status),
+                    Err(e) => history_cell::new_error_event(format!(
+                        "Failed to load ACE playbook status: {e}"
+                    )),
+                };
+                tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+            });
         }
 
         #[cfg(not(feature = "ace"))]
@@ -2405,14 +2440,105 @@ impl ChatWidget {
     }
 
     fn handle_playbook_show(&mut self) {
-        // 显示最近的学习条目
+        self.open_playbook_browser(false);
+    }
+
+    fn handle_playbook_search(&mut self) {
+        self.open_playbook_browser(true);
+    }
+
+    /// 加载全部 bullets 并打开全屏 playbook 浏览器
+    fn open_playbook_browser(&mut self, search: bool) {
         #[cfg(feature = "ace")]
         {
-            self.add_info_message(
-                "Recent ACE playbook entries are displayed in the terminal output.".to_string(),
-                Some("Run 'codex ace show' in terminal for details.".to_string()),
+            let handler = AceCliHandler::new(&self.config.codex_home);
+            let tx = self.app_event_tx.clone();
+            tokio::spawn(async move {
+                match handler.list_bullets().await {
+                    Ok(bullets) => tx.send(AppEvent::OpenPlaybookBrowser { bullets, search }),
+                    Err(e) => tx.send(AppEvent::InsertHistoryCell(Box::new(
+                        history_cell::new_error_event(format!("Failed to load ACE playbook: {e}")),
+                    ))),
+                }
+            });
+        }
+
+        #[cfg(not(feature = "ace"))]
+        {
+            let _ = search;
+            self.add_error_message(
+                "ACE feature is not enabled. Rebuild with --features ace".to_string(),
             );
-            // TODO: 重构 AceCliHandler 以支持返回字符串
+        }
+    }
+
+    /// 选择对上一轮注入条目的评价，由会话中的 ACE 插件记录
+    fn open_playbook_feedback_popup(&mut self) {
+        let options = [
+            (
+                PlaybookFeedbackRating::Upvote,
+                "Helpful",
+                "Count as a success and raise confidence",
+            ),
+            (
+                PlaybookFeedbackRating::Downvote,
+                "Not helpful",
+                "Count as a failure and lower confidence",
+            ),
+            (
+                PlaybookFeedbackRating::Flag,
+                "Wrong or harmful",
+                "Stop injecting until reviewed in /playbook-show",
+            ),
+        ];
+        let items = options
+            .into_iter()
+            .map(|(rating, name, description)| SelectionItem {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::CodexOp(Op::PlaybookFeedback {
+                        rating,
+                        bullet_ids: Vec::new(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Rate the playbook entries used in the last turn".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// 选择新条目的分类
+    fn open_playbook_add_popup(&mut self) {
+        #[cfg(feature = "ace")]
+        {
+            let items = codex_ace::BulletSection::ALL
+                .into_iter()
+                .map(|section| SelectionItem {
+                    name: section.title().to_string(),
+                    actions: vec![Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::OpenPlaybookAddPrompt {
+                            section: section.clone(),
+                        });
+                    })],
+                    dismiss_on_select: false,
+                    ..Default::default()
+                })
+                .collect();
+
+            self.bottom_pane.show_selection_view(SelectionViewParams {
+                title: Some("Select a section for the new playbook entry".to_string()),
+                footer_hint: Some(standard_popup_hint_line()),
+                items,
+                ..Default::default()
+            });
         }
 
         #[cfg(not(feature = "ace"))]
@@ -2423,6 +2549,47 @@ impl ChatWidget {
         }
     }
 
+    #[cfg(feature = "ace")]
+    pub(crate) fn show_playbook_add_prompt(&mut self, section: codex_ace::BulletSection) {
+        let tx = self.app_event_tx.clone();
+        let title = section.title().to_string();
+        let view = CustomPromptView::new(
+            "New playbook entry".to_string(),
+            "Type the entry and press Enter".to_string(),
+            Some(title.clone()),
+            Box::new(move |content: String| {
+                let content = content.trim().to_string();
+                if content.is_empty() {
+                    return;
+                }
+                tx.send(AppEvent::CodexOp(Op::AddPlaybookEntry {
+                    content,
+                    section: Some(title.clone()),
+                }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    fn on_playbook_feedback_applied(&mut self, event: PlaybookFeedbackAppliedEvent) {
+        let verb = match event.rating {
+            PlaybookFeedbackRating::Upvote => "Marked as helpful",
+            PlaybookFeedbackRating::Downvote => "Marked as not helpful",
+            PlaybookFeedbackRating::Flag => "Flagged for review",
+        };
+        let count = event.bullet_ids.len();
+        let noun = if count == 1 { "entry" } else { "entries" };
+        self.add_info_message(format!("{verb}: {count} playbook {noun}"), None);
+    }
+
+    fn on_playbook_entry_added(&mut self, event: PlaybookEntryAddedEvent) {
+        let short_id: String = event.bullet_id.chars().take(8).collect();
+        self.add_info_message(
+            format!("Added playbook entry {short_id} to {}", event.section),
+            None,
+        );
+    }
+
     fn handle_playbook_clear(&mut self) {
         // 清空 playbook（需要确认）
         self.add_info_message(
@@ -2432,32 +2599,6 @@ impl ChatWidget {
 
         // TODO: 实现确认对话框或参数解析
     }
-
-    fn handle_playbook_search(&mut self, query: Option<String>) {
-        // 搜索 playbook
-        if let Some(q) = query {
-            #[cfg(feature = "ace")]
-            {
-                self.add_info_message(
-                    format!("Searching playbook for '{}'...", q),
-                    Some(format!(
-                        "Run 'codex ace search {}' in terminal for details.",
-                        q
-                    )),
-                );
-                // TODO: 重构 AceCliHandler 以支持返回字符串
-            }
-
-            #[cfg(not(feature = "ace"))]
-            {
-                self.add_error_message(
-                    "ACE feature is not enabled. Rebuild with --features ace".to_string(),
-                );
-            }
-        } else {
-            self.add_info_message("Usage: /playbook-search <query>".to_string(), None);
-        }
-    }
 }
 
 impl WidgetRef for &ChatWidget {
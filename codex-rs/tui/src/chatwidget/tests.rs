@@ -283,6 +283,7 @@ fn make_chatwidget_manual() -> (
         session_header: SessionHeader::new(cfg.model),
         initial_user_message: None,
         token_info: None,
+        hook_context_usage: None,
         rate_limit_snapshot: None,
         rate_limit_warnings: RateLimitWarningState::default(),
         stream_controller: None,
@@ -408,6 +409,8 @@ fn exec_approval_emits_proposed_command_and_decision_history() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        policy_match: None,
+        project_approval_pattern: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -451,6 +454,8 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        policy_match: None,
+        project_approval_pattern: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -500,6 +505,8 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         risk: None,
+        policy_match: None,
+        project_approval_pattern: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -1685,6 +1692,8 @@ fn approval_modal_exec_snapshot() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        policy_match: None,
+        project_approval_pattern: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -1730,6 +1739,8 @@ fn approval_modal_exec_without_reason_snapshot() {
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         risk: None,
+        policy_match: None,
+        project_approval_pattern: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
@@ -1941,6 +1952,8 @@ fn status_widget_and_approval_modal_snapshot() {
             "this is a test reason such as one that would be produced by the model".into(),
         ),
         risk: None,
+        policy_match: None,
+        project_approval_pattern: None,
         parsed_cmd: vec![],
     };
     chat.handle_codex_event(Event {
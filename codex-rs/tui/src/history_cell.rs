@@ -403,6 +403,19 @@ pub fn new_approval_decision_cell(
                 ],
             )
         }
+        ApprovedForProject => {
+            let snippet = Span::from(exec_snippet(&command)).dim();
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to run ".into(),
+                    snippet,
+                    " every time in this project".bold(),
+                ],
+            )
+        }
         Denied => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
@@ -1056,6 +1069,84 @@ pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
 
+/// Render the `/playbook` summary of ACE status and statistics.
+#[cfg(feature = "ace")]
+pub(crate) fn new_playbook_status(status: &codex_core::ace::AceStatus) -> PlainHistoryCell {
+    let stats = &status.stats;
+    let enabled = |on: bool| {
+        if on {
+            "enabled".green()
+        } else {
+            "disabled".red()
+        }
+    };
+
+    let mut lines: Vec<Line<'static>> = vec![
+        "/playbook".magenta().into(),
+        "".into(),
+        vec!["📚  ".into(), "ACE Playbook".bold()].into(),
+        "".into(),
+        vec!["  • Learning: ".into(), enabled(status.enabled)].into(),
+        vec!["  • Storage: ".into(), status.storage_path.clone().dim()].into(),
+        format!(
+            "  • Entries: {} / {} from {} sessions",
+            stats.total_bullets, status.max_entries, stats.total_sessions
+        )
+        .into(),
+        format!(
+            "  • Recalled: {} entries, {} recalls total",
+            stats.recalled_bullets, stats.total_recalls
+        )
+        .into(),
+    ];
+    if stats.total_bullets > 0 {
+        lines.push(
+            format!(
+                "  • Success rate: {:.1}%",
+                stats.overall_success_rate * 100.0
+            )
+            .into(),
+        );
+    }
+
+    let mut sections: Vec<_> = stats.bullets_by_section.iter().collect();
+    sections.sort();
+    for (section, count) in sections {
+        lines.push(
+            vec![
+                format!("    - {}: ", section.title()).dim(),
+                count.to_string().into(),
+            ]
+            .into(),
+        );
+    }
+
+    let mut optimizer = vec!["  • Optimizer: ".into(), enabled(status.optimizer_enabled)];
+    match &status.last_optimization {
+        Some(report) => optimizer.push(
+            format!(
+                ", last run {} (deduplicated {}, decayed {}, demoted {}, archived {})",
+                report.finished_at.format("%Y-%m-%d %H:%M"),
+                report.deduplicated,
+                report.decayed,
+                report.demoted,
+                report.archived
+            )
+            .dim(),
+        ),
+        None => optimizer.push(", never run".dim()),
+    }
+    lines.push(optimizer.into());
+    lines.push("".into());
+    lines.push(
+        "  Use /playbook-show to browse and edit entries."
+            .dim()
+            .into(),
+    );
+
+    PlainHistoryCell { lines }
+}
+
 /// Render MCP tools grouped by connection using the fully-qualified tool names.
 pub(crate) fn new_mcp_tools_output(
     config: &Config,
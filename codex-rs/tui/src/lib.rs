@@ -56,6 +56,8 @@ mod markdown_render;
 mod markdown_stream;
 pub mod onboarding;
 mod pager_overlay;
+#[cfg(feature = "ace")]
+mod playbook_browser;
 pub mod public_widgets;
 mod render;
 mod resume_picker;
@@ -579,7 +581,10 @@ mod tests {
             temp_dir.path().to_path_buf(),
         )?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig {
+            trust_level: None,
+            sandbox_container: None,
+        };
         set_windows_sandbox_enabled(false);
 
         let should_show = should_show_trust_screen(&config);
@@ -606,7 +611,10 @@ mod tests {
             temp_dir.path().to_path_buf(),
         )?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig {
+            trust_level: None,
+            sandbox_container: None,
+        };
         set_windows_sandbox_enabled(true);
 
         let should_show = should_show_trust_screen(&config);
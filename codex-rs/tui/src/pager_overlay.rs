@@ -6,6 +6,8 @@ use crate::history_cell::HistoryCell;
 use crate::history_cell::UserHistoryCell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+#[cfg(feature = "ace")]
+use crate::playbook_browser::PlaybookBrowser;
 use crate::render::Insets;
 use crate::render::renderable::InsetRenderable;
 use crate::render::renderable::Renderable;
@@ -31,6 +33,8 @@ use ratatui::widgets::Wrap;
 pub(crate) enum Overlay {
     Transcript(TranscriptOverlay),
     Static(StaticOverlay),
+    #[cfg(feature = "ace")]
+    Playbook(PlaybookBrowser),
 }
 
 impl Overlay {
@@ -49,10 +53,17 @@ impl Overlay {
         Self::Static(StaticOverlay::with_renderables(renderables, title))
     }
 
+    #[cfg(feature = "ace")]
+    pub(crate) fn new_playbook(browser: PlaybookBrowser) -> Self {
+        Self::Playbook(browser)
+    }
+
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match self {
             Overlay::Transcript(o) => o.handle_event(tui, event),
             Overlay::Static(o) => o.handle_event(tui, event),
+            #[cfg(feature = "ace")]
+            Overlay::Playbook(o) => o.handle_event(tui, event),
         }
     }
 
@@ -60,6 +71,8 @@ impl Overlay {
         match self {
             Overlay::Transcript(o) => o.is_done(),
             Overlay::Static(o) => o.is_done(),
+            #[cfg(feature = "ace")]
+            Overlay::Playbook(o) => o.is_done(),
         }
     }
 }
@@ -0,0 +1,758 @@
+//! Full-screen browser for the ACE playbook (`/playbook-show`, `/playbook-search`).
+//!
+//! Lists bullets grouped by [`BulletSection`], filters them with fuzzy search and
+//! lets the user pin, edit, delete or archive the selected bullet. Edits are
+//! applied to the local copy right away and sent to the app as
+//! [`AppEvent::PlaybookBulletAction`] to be persisted.
+
+use std::io::Result;
+use std::time::Duration;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::text_formatting::truncate_text;
+use crate::tui;
+use crate::tui::TuiEvent;
+use chrono::Utc;
+use codex_ace::Bullet;
+use codex_ace::BulletSection;
+use codex_common::fuzzy_match::fuzzy_match;
+use codex_core::ace::BulletAction;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
+
+/// Height of the detail pane below the list.
+const DETAIL_HEIGHT: u16 = 10;
+
+enum Mode {
+    Browse,
+    Search,
+    Edit { buffer: String },
+    ConfirmDelete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    Section {
+        section: BulletSection,
+        count: usize,
+    },
+    /// Index into `PlaybookBrowser::bullets`.
+    Bullet(usize),
+}
+
+pub(crate) struct PlaybookBrowser {
+    bullets: Vec<Bullet>,
+    query: String,
+    mode: Mode,
+    rows: Vec<Row>,
+    /// Index into `rows`; always points at a `Row::Bullet` when set.
+    selected: Option<usize>,
+    scroll_top: usize,
+    list_height: usize,
+    status: Option<Line<'static>>,
+    app_event_tx: AppEventSender,
+    is_done: bool,
+}
+
+impl PlaybookBrowser {
+    pub(crate) fn new(bullets: Vec<Bullet>, search: bool, app_event_tx: AppEventSender) -> Self {
+        let mut browser = Self {
+            bullets,
+            query: String::new(),
+            mode: if search { Mode::Search } else { Mode::Browse },
+            rows: Vec::new(),
+            selected: None,
+            scroll_top: 0,
+            list_height: 10,
+            status: None,
+            app_event_tx,
+            is_done: false,
+        };
+        browser.rebuild_rows(None);
+        browser
+    }
+
+    /// Show an error reported by the app (e.g. a failed write).
+    pub(crate) fn set_error(&mut self, message: String) {
+        self.status = Some(Line::from(format!("■ {message}").red()));
+    }
+
+    pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(key_event) => {
+                self.handle_key(key_event);
+                tui.frame_requester()
+                    .schedule_frame_in(Duration::from_millis(16));
+                Ok(())
+            }
+            TuiEvent::Paste(pasted) => {
+                if let Mode::Edit { buffer } = &mut self.mode {
+                    buffer.push_str(&pasted.replace('\r', "\n"));
+                } else if matches!(self.mode, Mode::Search) {
+                    let query = format!("{}{}", self.query, pasted.trim());
+                    self.set_query(query);
+                }
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
+            TuiEvent::Draw => {
+                tui.draw(u16::MAX, |frame| {
+                    self.render(frame.area(), frame.buffer);
+                })?;
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.kind == KeyEventKind::Release {
+            return;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.is_done = true;
+            return;
+        }
+
+        match self.mode {
+            Mode::Browse => self.handle_browse_key(key),
+            Mode::Search => self.handle_search_key(key),
+            Mode::Edit { .. } => self.handle_edit_key(key),
+            Mode::ConfirmDelete => {
+                self.mode = Mode::Browse;
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    self.remove_selected(BulletAction::Delete);
+                } else {
+                    self.status = Some("Delete cancelled".dim().into());
+                }
+            }
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) {
+        if self.handle_navigation_key(key.code) {
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') => self.is_done = true,
+            KeyCode::Esc => {
+                if self.query.is_empty() {
+                    self.is_done = true;
+                } else {
+                    self.set_query(String::new());
+                }
+            }
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Char('p') => self.toggle_pin(),
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(idx) = self.selected_bullet() {
+                    self.mode = Mode::Edit {
+                        buffer: self.bullets[idx].content.clone(),
+                    };
+                    self.status = None;
+                }
+            }
+            KeyCode::Char('d') => {
+                if self.selected_bullet().is_some() {
+                    self.mode = Mode::ConfirmDelete;
+                }
+            }
+            KeyCode::Char('a') => self.remove_selected(BulletAction::Archive),
+            _ => {}
+        }
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        if self.handle_navigation_key(key.code) {
+            return;
+        }
+        match key.code {
+            KeyCode::Enter => self.mode = Mode::Browse,
+            KeyCode::Esc => {
+                self.mode = Mode::Browse;
+                self.set_query(String::new());
+            }
+            KeyCode::Backspace => {
+                let mut query = self.query.clone();
+                query.pop();
+                self.set_query(query);
+            }
+            KeyCode::Char(c)
+                if !key.modifiers.contains(KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(KeyModifiers::ALT) =>
+            {
+                let mut query = self.query.clone();
+                query.push(c);
+                self.set_query(query);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent) {
+        let Mode::Edit { buffer } = &mut self.mode else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let content = std::mem::take(buffer);
+                self.mode = Mode::Browse;
+                self.save_edit(content);
+            }
+            KeyCode::Esc => {
+                self.mode = Mode::Browse;
+                self.status = Some("Edit cancelled".dim().into());
+            }
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Char(c)
+                if !key.modifiers.contains(KeyModifiers::CONTROL)
+                    && !key.modifiers.contains(KeyModifiers::ALT) =>
+            {
+                buffer.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns true if the key moved the selection.
+    fn handle_navigation_key(&mut self, code: KeyCode) -> bool {
+        let page = self.list_height.max(1) as isize;
+        match code {
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-page),
+            KeyCode::PageDown => self.move_selection(page),
+            KeyCode::Home => self.move_selection(isize::MIN / 2),
+            KeyCode::End => self.move_selection(isize::MAX / 2),
+            KeyCode::Char('k') if matches!(self.mode, Mode::Browse) => self.move_selection(-1),
+            KeyCode::Char('j') if matches!(self.mode, Mode::Browse) => self.move_selection(1),
+            _ => return false,
+        }
+        true
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let bullet_rows: Vec<usize> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| matches!(row, Row::Bullet(_)))
+            .map(|(pos, _)| pos)
+            .collect();
+        if bullet_rows.is_empty() {
+            self.selected = None;
+            return;
+        }
+        let current = bullet_rows
+            .iter()
+            .position(|pos| Some(*pos) == self.selected)
+            .unwrap_or(0) as isize;
+        let next = current
+            .saturating_add(delta)
+            .clamp(0, bullet_rows.len() as isize - 1);
+        self.selected = Some(bullet_rows[next as usize]);
+    }
+
+    fn selected_bullet(&self) -> Option<usize> {
+        match self.selected.and_then(|pos| self.rows.get(pos)) {
+            Some(Row::Bullet(idx)) => Some(*idx),
+            _ => None,
+        }
+    }
+
+    fn set_query(&mut self, query: String) {
+        if self.query == query {
+            return;
+        }
+        self.query = query;
+        self.selected = None;
+        self.scroll_top = 0;
+        self.rebuild_rows(None);
+    }
+
+    /// Recompute the visible rows, keeping `keep` (a bullet index) selected if it
+    /// is still visible, else the row closest to the previous selection.
+    fn rebuild_rows(&mut self, keep: Option<usize>) {
+        let mut matches: Vec<(usize, i32)> = self
+            .bullets
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, bullet)| {
+                if self.query.is_empty() {
+                    return Some((idx, 0));
+                }
+                fuzzy_match(&searchable_text(bullet), &self.query).map(|(_, score)| (idx, score))
+            })
+            .collect();
+        // Stable sort: within a section, ties keep the incoming (pinned, recent) order.
+        matches.sort_by(|a, b| {
+            self.bullets[a.0]
+                .section
+                .cmp(&self.bullets[b.0].section)
+                .then(a.1.cmp(&b.1))
+        });
+
+        let previous = self.selected;
+        self.rows.clear();
+        for (pos, (idx, _)) in matches.iter().enumerate() {
+            let section = &self.bullets[*idx].section;
+            if pos == 0 || self.bullets[matches[pos - 1].0].section != *section {
+                let count = matches
+                    .iter()
+                    .filter(|(other, _)| self.bullets[*other].section == *section)
+                    .count();
+                self.rows.push(Row::Section {
+                    section: section.clone(),
+                    count,
+                });
+            }
+            self.rows.push(Row::Bullet(*idx));
+        }
+
+        self.selected = keep
+            .and_then(|idx| self.rows.iter().position(|row| *row == Row::Bullet(idx)))
+            .or_else(|| self.nearest_bullet_row(previous.unwrap_or(0)));
+    }
+
+    fn nearest_bullet_row(&self, from: usize) -> Option<usize> {
+        let from = from.min(self.rows.len().saturating_sub(1));
+        let is_bullet = |pos: &usize| matches!(self.rows[*pos], Row::Bullet(_));
+        (from..self.rows.len())
+            .find(is_bullet)
+            .or_else(|| (0..from).rev().find(is_bullet))
+    }
+
+    fn toggle_pin(&mut self) {
+        let Some(idx) = self.selected_bullet() else {
+            return;
+        };
+        let bullet = &mut self.bullets[idx];
+        bullet.metadata.pinned = !bullet.metadata.pinned;
+        let pinned = bullet.metadata.pinned;
+        bullet.metadata.flagged &= !pinned;
+        let id = bullet.id.clone();
+        self.send_action(id, BulletAction::SetPinned(pinned));
+        let message = if pinned {
+            "Pinned entry"
+        } else {
+            "Unpinned entry"
+        };
+        self.status = Some(message.green().into());
+    }
+
+    fn save_edit(&mut self, content: String) {
+        let Some(idx) = self.selected_bullet() else {
+            return;
+        };
+        let content = content.trim().to_string();
+        if content.is_empty() {
+            self.set_error("Entry content cannot be empty".to_string());
+            return;
+        }
+        let bullet = &mut self.bullets[idx];
+        if bullet.content == content {
+            self.status = Some("No changes".dim().into());
+            return;
+        }
+        bullet.content = content.clone();
+        bullet.metadata.flagged = false;
+        bullet.updated_at = Utc::now();
+        let id = bullet.id.clone();
+        self.send_action(id, BulletAction::Edit(content));
+        self.rebuild_rows(Some(idx));
+        self.status = Some("Saved entry".green().into());
+    }
+
+    fn remove_selected(&mut self, action: BulletAction) {
+        let Some(idx) = self.selected_bullet() else {
+            return;
+        };
+        let bullet = self.bullets.remove(idx);
+        self.status = Some(match action {
+            BulletAction::Archive => "Archived entry".green().into(),
+            _ => "Deleted entry".green().into(),
+        });
+        self.send_action(bullet.id, action);
+        self.rebuild_rows(None);
+    }
+
+    fn send_action(&self, id: String, action: BulletAction) {
+        self.app_event_tx
+            .send(AppEvent::PlaybookBulletAction { id, action });
+    }
+
+    fn ensure_selected_visible(&mut self) {
+        let height = self.list_height.max(1);
+        let Some(selected) = self.selected else {
+            self.scroll_top = 0;
+            return;
+        };
+        // Keep the section header directly above the first bullet in view.
+        let top = if selected > 0 && matches!(self.rows[selected - 1], Row::Section { .. }) {
+            selected - 1
+        } else {
+            selected
+        };
+        if top < self.scroll_top {
+            self.scroll_top = top;
+        } else if selected >= self.scroll_top + height {
+            self.scroll_top = selected + 1 - height;
+        }
+    }
+
+    pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let [header, search, list, detail, status, hints] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(DETAIL_HEIGHT),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+
+        let shown = self
+            .rows
+            .iter()
+            .filter(|row| matches!(row, Row::Bullet(_)))
+            .count();
+        Line::from(vec![
+            "ACE playbook".bold().magenta(),
+            format!("  {shown} of {} entries", self.bullets.len()).dim(),
+        ])
+        .render_ref(header, buf);
+
+        let search_line: Line = match self.mode {
+            Mode::Search => vec!["Search: ".cyan(), self.query.clone().into(), "▌".cyan()].into(),
+            _ if !self.query.is_empty() => vec!["Search: ".dim(), self.query.clone().into()].into(),
+            _ => "Press / to search".dim().into(),
+        };
+        search_line.render_ref(search, buf);
+
+        self.list_height = list.height as usize;
+        self.ensure_selected_visible();
+        self.render_list(list, buf);
+        self.render_detail(detail, buf);
+
+        let status_line = match self.mode {
+            Mode::ConfirmDelete => Some(Line::from(vec![
+                "Delete this entry permanently? ".red(),
+                "y".bold(),
+                " to confirm, any other key to cancel".dim(),
+            ])),
+            _ => self.status.clone(),
+        };
+        if let Some(line) = status_line {
+            line.render_ref(status, buf);
+        }
+        self.hint_line().render_ref(hints, buf);
+    }
+
+    fn render_list(&self, area: Rect, buf: &mut Buffer) {
+        if self.rows.is_empty() {
+            let message = if self.bullets.is_empty() {
+                "No playbook entries yet"
+            } else {
+                "No entries match your search"
+            };
+            Line::from(message.italic().dim()).render_ref(area, buf);
+            return;
+        }
+
+        let end = self.rows.len().min(self.scroll_top + area.height as usize);
+        for (offset, row) in self.rows[self.scroll_top..end].iter().enumerate() {
+            let pos = self.scroll_top + offset;
+            let line: Line = match row {
+                Row::Section { section, count } => {
+                    vec![section.title().bold(), format!(" ({count})").dim()].into()
+                }
+                Row::Bullet(idx) => {
+                    let bullet = &self.bullets[*idx];
+                    let is_selected = Some(pos) == self.selected;
+                    let marker: Span = if is_selected {
+                        "› ".cyan().bold()
+                    } else {
+                        "  ".into()
+                    };
+                    let pin: Span = if bullet.metadata.pinned {
+                        "📌 ".into()
+                    } else if bullet.metadata.flagged {
+                        "🚩 ".into()
+                    } else {
+                        "   ".into()
+                    };
+                    let first_line = bullet.content.lines().next().unwrap_or_default();
+                    let width = (area.width as usize).saturating_sub(5);
+                    let content = truncate_text(first_line, width);
+                    let content: Span = if is_selected {
+                        content.bold()
+                    } else {
+                        content.into()
+                    };
+                    vec![marker, pin, content].into()
+                }
+            };
+            let rect = Rect::new(area.x, area.y + offset as u16, area.width, 1);
+            line.render_ref(rect, buf);
+        }
+    }
+
+    fn render_detail(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines: Vec<Line<'static>> = vec!["─".repeat(area.width as usize).dim().into()];
+        if let Some(idx) = self.selected_bullet() {
+            let bullet = &self.bullets[idx];
+            lines.extend(metadata_lines(bullet));
+            lines.push(Line::from(""));
+            match &self.mode {
+                Mode::Edit { buffer } => {
+                    lines.push(vec!["Edit: ".cyan(), buffer.clone().into(), "▌".cyan()].into());
+                }
+                _ => lines.extend(bullet.content.lines().map(|l| Line::from(l.to_string()))),
+            }
+        }
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+
+    fn hint_line(&self) -> Line<'static> {
+        let pairs: Vec<(Span<'static>, &str)> = match self.mode {
+            Mode::Browse => vec![
+                (key_hint::plain(KeyCode::Char('/')).into(), "search"),
+                (key_hint::plain(KeyCode::Char('p')).into(), "pin"),
+                (key_hint::plain(KeyCode::Char('e')).into(), "edit"),
+                (key_hint::plain(KeyCode::Char('d')).into(), "delete"),
+                (key_hint::plain(KeyCode::Char('a')).into(), "archive"),
+                (key_hint::plain(KeyCode::Char('q')).into(), "quit"),
+            ],
+            Mode::Search => vec![
+                (key_hint::plain(KeyCode::Enter).into(), "done"),
+                (key_hint::plain(KeyCode::Esc).into(), "clear"),
+            ],
+            Mode::Edit { .. } => vec![
+                (key_hint::plain(KeyCode::Enter).into(), "save"),
+                (key_hint::plain(KeyCode::Esc).into(), "cancel"),
+            ],
+            Mode::ConfirmDelete => Vec::new(),
+        };
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (key, desc) in pairs {
+            if !spans.is_empty() {
+                spans.push("   ".into());
+            }
+            spans.push(key);
+            spans.push(format!(" {desc}").dim());
+        }
+        spans.into()
+    }
+}
+
+/// Text matched by the fuzzy search: content plus tags and related tools.
+fn searchable_text(bullet: &Bullet) -> String {
+    let mut text = bullet.content.clone();
+    for extra in bullet
+        .tags
+        .iter()
+        .chain(bullet.metadata.related_tools.iter())
+    {
+        text.push(' ');
+        text.push_str(extra);
+    }
+    text
+}
+
+fn metadata_lines(bullet: &Bullet) -> Vec<Line<'static>> {
+    let metadata = &bullet.metadata;
+    let total = metadata.success_count + metadata.failure_count;
+    let success = if total > 0 {
+        format!(
+            "{:.0}% ({}/{})",
+            metadata.success_count as f32 / total as f32 * 100.0,
+            metadata.success_count,
+            total
+        )
+    } else {
+        "no outcomes yet".to_string()
+    };
+    let recalls = match metadata.last_recall {
+        Some(last) => format!(
+            "{}× (last {})",
+            metadata.recall_count,
+            last.format("%Y-%m-%d %H:%M")
+        ),
+        None => format!("{}×", metadata.recall_count),
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            "Success ".dim(),
+            success.into(),
+            "  Recalled ".dim(),
+            recalls.into(),
+            "  Importance ".dim(),
+            format!("{:.2}", metadata.importance).into(),
+        ]),
+        Line::from(vec![
+            "Source session ".dim(),
+            bullet.source_session_id.clone().into(),
+            "  Updated ".dim(),
+            bullet
+                .updated_at
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+                .into(),
+        ]),
+    ];
+    if !metadata.related_tools.is_empty() || !bullet.tags.is_empty() {
+        lines.push(Line::from(vec![
+            "Tools ".dim(),
+            metadata.related_tools.join(", ").into(),
+            "  Tags ".dim(),
+            bullet.tags.join(", ").into(),
+        ]));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::UnboundedReceiver;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn bullet(section: BulletSection, content: &str) -> Bullet {
+        Bullet::new(section, content.to_string(), "session-1".to_string())
+    }
+
+    fn browser(bullets: Vec<Bullet>) -> (PlaybookBrowser, UnboundedReceiver<AppEvent>) {
+        let (tx, rx) = unbounded_channel();
+        (
+            PlaybookBrowser::new(bullets, false, AppEventSender::new(tx)),
+            rx,
+        )
+    }
+
+    fn press(browser: &mut PlaybookBrowser, code: KeyCode) {
+        browser.handle_key(KeyEvent::from(code));
+    }
+
+    fn visible_contents(browser: &PlaybookBrowser) -> Vec<String> {
+        browser
+            .rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Bullet(idx) => Some(browser.bullets[*idx].content.clone()),
+                Row::Section { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_by_section_and_filters_fuzzily() {
+        let (mut browser, _rx) = browser(vec![
+            bullet(BulletSection::ToolUsageTips, "Prefer rg over grep"),
+            bullet(BulletSection::StrategiesAndRules, "Run cargo fmt first"),
+            bullet(BulletSection::ToolUsageTips, "Use cargo nextest"),
+        ]);
+        assert_eq!(
+            browser.rows[0],
+            Row::Section {
+                section: BulletSection::StrategiesAndRules,
+                count: 1
+            }
+        );
+        assert_eq!(browser.selected, Some(1));
+
+        press(&mut browser, KeyCode::Char('/'));
+        for c in "crgo".chars() {
+            press(&mut browser, KeyCode::Char(c));
+        }
+        assert_eq!(
+            visible_contents(&browser),
+            vec!["Run cargo fmt first", "Use cargo nextest"]
+        );
+
+        press(&mut browser, KeyCode::Esc);
+        assert_eq!(visible_contents(&browser).len(), 3);
+    }
+
+    #[test]
+    fn pin_and_edit_send_actions() {
+        let (mut browser, mut rx) = browser(vec![bullet(BulletSection::General, "Old text")]);
+        let id = browser.bullets[0].id.clone();
+
+        press(&mut browser, KeyCode::Char('p'));
+        assert!(browser.bullets[0].metadata.pinned);
+        match rx.try_recv() {
+            Ok(AppEvent::PlaybookBulletAction { id: sent, action }) => {
+                assert_eq!(sent, id);
+                assert_eq!(action, BulletAction::SetPinned(true));
+            }
+            other => panic!("expected pin action, got {other:?}"),
+        }
+
+        press(&mut browser, KeyCode::Char('e'));
+        for _ in 0.."text".len() {
+            press(&mut browser, KeyCode::Backspace);
+        }
+        for c in "note".chars() {
+            press(&mut browser, KeyCode::Char(c));
+        }
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(browser.bullets[0].content, "Old note");
+        match rx.try_recv() {
+            Ok(AppEvent::PlaybookBulletAction { action, .. }) => {
+                assert_eq!(action, BulletAction::Edit("Old note".to_string()));
+            }
+            other => panic!("expected edit action, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn delete_requires_confirmation() {
+        let (mut browser, mut rx) = browser(vec![
+            bullet(BulletSection::General, "First"),
+            bullet(BulletSection::General, "Second"),
+        ]);
+
+        press(&mut browser, KeyCode::Char('d'));
+        press(&mut browser, KeyCode::Char('n'));
+        assert_eq!(browser.bullets.len(), 2);
+        assert!(rx.try_recv().is_err());
+
+        press(&mut browser, KeyCode::Char('d'));
+        press(&mut browser, KeyCode::Char('y'));
+        assert_eq!(visible_contents(&browser), vec!["Second"]);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::PlaybookBulletAction {
+                action: BulletAction::Delete,
+                ..
+            })
+        ));
+        assert_eq!(browser.selected, Some(1));
+    }
+}
@@ -24,10 +24,12 @@ pub enum SlashCommand {
     Status,
     Mcp,
     // ACE Playbook commands - 管理学习记录
-    Playbook,       // 显示 playbook 状态 (别名: /pb)
-    PlaybookShow,   // 显示最近学习条目 (别名: /pbs)
-    PlaybookClear,  // 清空 playbook (别名: /pbc)
-    PlaybookSearch, // 搜索 playbook (别名: /pbsearch, /pbq)
+    Playbook,         // 显示 playbook 状态 (别名: /pb)
+    PlaybookShow,     // 显示最近学习条目 (别名: /pbs)
+    PlaybookClear,    // 清空 playbook (别名: /pbc)
+    PlaybookSearch,   // 搜索 playbook (别名: /pbsearch, /pbq)
+    PlaybookFeedback, // 评价上一轮注入的条目 (别名: /pbf)
+    PlaybookAdd,      // 手动添加条目 (别名: /pba)
     Logout,
     Quit,
     Exit,
@@ -55,9 +57,11 @@ impl SlashCommand {
             SlashCommand::Mcp => "list configured MCP tools",
             // ACE Playbook 命令
             SlashCommand::Playbook => "show ACE playbook status and statistics",
-            SlashCommand::PlaybookShow => "display recent learning entries",
+            SlashCommand::PlaybookShow => "browse, pin, edit and archive learning entries",
             SlashCommand::PlaybookClear => "clear all playbook entries (with archive)",
-            SlashCommand::PlaybookSearch => "search playbook for specific content",
+            SlashCommand::PlaybookSearch => "fuzzy search playbook entries",
+            SlashCommand::PlaybookFeedback => "rate the playbook entries used in the last turn",
+            SlashCommand::PlaybookAdd => "add your own entry to the playbook",
             SlashCommand::Logout => "log out of Codex",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
@@ -89,6 +93,8 @@ impl SlashCommand {
             | SlashCommand::Playbook       // 查看状态可以在任务中执行
             | SlashCommand::PlaybookShow   // 显示条目可以在任务中执行
             | SlashCommand::PlaybookSearch // 搜索可以在任务中执行
+            | SlashCommand::PlaybookFeedback
+            | SlashCommand::PlaybookAdd
             | SlashCommand::Feedback
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
@@ -121,6 +127,8 @@ pub fn resolve_command_alias(alias: &str) -> Option<&'static str> {
         "pbs" => Some("playbook-show"),
         "pbc" => Some("playbook-clear"),
         "pbsearch" | "pbq" => Some("playbook-search"),
+        "pbf" => Some("playbook-feedback"),
+        "pba" => Some("playbook-add"),
         // 其他常用别名
         "q" => Some("quit"),
         "e" => Some("exit"),
@@ -7,6 +7,7 @@ use chrono::DateTime;
 use chrono::Local;
 use codex_common::create_config_summary_entries;
 use codex_core::config::Config;
+use codex_core::protocol::ContextTokenCountEvent;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::ConversationId;
@@ -49,6 +50,26 @@ pub(crate) struct StatusTokenUsageData {
     context_window: Option<StatusContextWindowData>,
 }
 
+/// Context injected by executor hooks (e.g. the ACE playbook) during this session.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatusHookContextUsage {
+    /// Tokens injected at the start of the current or most recent turn, if any.
+    last_turn: Option<ContextTokenCountEvent>,
+    session_total: i64,
+}
+
+impl StatusHookContextUsage {
+    pub(crate) fn record(&mut self, event: ContextTokenCountEvent) {
+        self.session_total += event.total_tokens;
+        self.last_turn = Some(event);
+    }
+
+    /// Called when a new turn starts; hooks only report turns that injected context.
+    pub(crate) fn start_turn(&mut self) {
+        self.last_turn = None;
+    }
+}
+
 #[derive(Debug)]
 struct StatusHistoryCell {
     model_name: String,
@@ -60,6 +81,7 @@ struct StatusHistoryCell {
     account: Option<StatusAccountDisplay>,
     session_id: Option<String>,
     token_usage: StatusTokenUsageData,
+    hook_context: Option<StatusHookContextUsage>,
     rate_limits: StatusRateLimitData,
 }
 
@@ -69,6 +91,7 @@ pub(crate) fn new_status_output(
     context_usage: Option<&TokenUsage>,
     session_id: &Option<ConversationId>,
     rate_limits: Option<&RateLimitSnapshotDisplay>,
+    hook_context: Option<&StatusHookContextUsage>,
     now: DateTime<Local>,
 ) -> CompositeHistoryCell {
     let command = PlainHistoryCell::new(vec!["/status".magenta().into()]);
@@ -78,6 +101,7 @@ pub(crate) fn new_status_output(
         context_usage,
         session_id,
         rate_limits,
+        hook_context,
         now,
     );
 
@@ -91,6 +115,7 @@ impl StatusHistoryCell {
         context_usage: Option<&TokenUsage>,
         session_id: &Option<ConversationId>,
         rate_limits: Option<&RateLimitSnapshotDisplay>,
+        hook_context: Option<&StatusHookContextUsage>,
         now: DateTime<Local>,
     ) -> Self {
         let config_entries = create_config_summary_entries(config);
@@ -102,7 +127,7 @@ impl StatusHistoryCell {
             .unwrap_or_else(|| "<unknown>".to_string());
         let sandbox = match &config.sandbox_policy {
             SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-            SandboxPolicy::ReadOnly => "read-only".to_string(),
+            SandboxPolicy::ReadOnly { .. } => "read-only".to_string(),
             SandboxPolicy::WorkspaceWrite { .. } => "workspace-write".to_string(),
         };
         let agents_summary = compose_agents_summary(config);
@@ -134,6 +159,7 @@ impl StatusHistoryCell {
             account,
             session_id,
             token_usage,
+            hook_context: hook_context.cloned(),
             rate_limits,
         }
     }
@@ -172,6 +198,32 @@ impl StatusHistoryCell {
         ])
     }
 
+    fn hook_context_spans(&self) -> Option<Vec<Span<'static>>> {
+        let usage = self.hook_context.as_ref()?;
+        let mut spans = match &usage.last_turn {
+            Some(last_turn) => {
+                let sources = last_turn
+                    .sources
+                    .iter()
+                    .map(|source| {
+                        format!("{} {}", source.source, format_tokens_compact(source.tokens))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                vec![
+                    Span::from(format_tokens_compact(last_turn.total_tokens)),
+                    Span::from(" last turn"),
+                    Span::from(format!(" ({sources})")).dim(),
+                ]
+            }
+            None => vec![Span::from("none last turn")],
+        };
+        spans.push(Span::from(" · ").dim());
+        spans.push(Span::from(format_tokens_compact(usage.session_total)).dim());
+        spans.push(Span::from(" session").dim());
+        Some(spans)
+    }
+
     fn rate_limit_lines(
         &self,
         available_inner_width: usize,
@@ -306,6 +358,9 @@ impl HistoryCell for StatusHistoryCell {
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
+        if self.hook_context.is_some() {
+            push_label(&mut labels, &mut seen, "Hook context");
+        }
         self.collect_rate_limit_labels(&mut seen, &mut labels);
 
         let formatter = FieldFormatter::from_labels(labels.iter().map(String::as_str));
@@ -361,6 +416,10 @@ impl HistoryCell for StatusHistoryCell {
             lines.push(formatter.line("Context window", spans));
         }
 
+        if let Some(spans) = self.hook_context_spans() {
+            lines.push(formatter.line("Hook context", spans));
+        }
+
         lines.extend(self.rate_limit_lines(available_inner_width, &formatter));
 
         let content_width = lines.iter().map(line_display_width).max().unwrap_or(0);
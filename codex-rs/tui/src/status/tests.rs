@@ -1,3 +1,4 @@
+use super::StatusHookContextUsage;
 use super::new_status_output;
 use super::rate_limit_snapshot_display;
 use crate::history_cell::HistoryCell;
@@ -7,6 +8,8 @@ use chrono::Utc;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
+use codex_core::protocol::ContextSourceTokens;
+use codex_core::protocol::ContextTokenCountEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::SandboxPolicy;
@@ -79,6 +82,8 @@ fn status_snapshot_includes_reasoning_details() {
     config.sandbox_policy = SandboxPolicy::WorkspaceWrite {
         writable_roots: Vec::new(),
         network_access: false,
+        network_allowlist: Vec::new(),
+        deny_read: Vec::new(),
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
     };
@@ -117,6 +122,7 @@ fn status_snapshot_includes_reasoning_details() {
         Some(&usage),
         &None,
         Some(&rate_display),
+        None,
         captured_at,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
@@ -165,6 +171,7 @@ fn status_snapshot_includes_monthly_limit() {
         Some(&usage),
         &None,
         Some(&rate_display),
+        None,
         captured_at,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
@@ -197,7 +204,7 @@ fn status_card_token_usage_excludes_cached_tokens() {
         .single()
         .expect("timestamp");
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, None, now);
+    let composite = new_status_output(&config, &usage, Some(&usage), &None, None, None, now);
     let rendered = render_lines(&composite.display_lines(120));
 
     assert!(
@@ -244,6 +251,7 @@ fn status_snapshot_truncates_in_narrow_terminal() {
         Some(&usage),
         &None,
         Some(&rate_display),
+        None,
         captured_at,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(46));
@@ -277,7 +285,7 @@ fn status_snapshot_shows_missing_limits_message() {
         .single()
         .expect("timestamp");
 
-    let composite = new_status_output(&config, &usage, Some(&usage), &None, None, now);
+    let composite = new_status_output(&config, &usage, Some(&usage), &None, None, None, now);
     let mut rendered_lines = render_lines(&composite.display_lines(80));
     if cfg!(windows) {
         for line in &mut rendered_lines {
@@ -319,6 +327,7 @@ fn status_snapshot_shows_empty_limits_message() {
         Some(&usage),
         &None,
         Some(&rate_display),
+        None,
         captured_at,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
@@ -371,6 +380,7 @@ fn status_snapshot_shows_stale_limits_message() {
         Some(&usage),
         &None,
         Some(&rate_display),
+        None,
         now,
     );
     let mut rendered_lines = render_lines(&composite.display_lines(80));
@@ -409,7 +419,15 @@ fn status_context_window_uses_last_usage() {
         .single()
         .expect("timestamp");
 
-    let composite = new_status_output(&config, &total_usage, Some(&last_usage), &None, None, now);
+    let composite = new_status_output(
+        &config,
+        &total_usage,
+        Some(&last_usage),
+        &None,
+        None,
+        None,
+        now,
+    );
     let rendered_lines = render_lines(&composite.display_lines(80));
     let context_line = rendered_lines
         .into_iter()
@@ -425,3 +443,74 @@ fn status_context_window_uses_last_usage() {
         "context line should not use total aggregated tokens, got: {context_line}"
     );
 }
+
+#[test]
+fn status_hook_context_shows_last_turn_and_session_tokens() {
+    let temp_home = TempDir::new().expect("temp home");
+    let config = test_config(&temp_home);
+    let usage = TokenUsage::default();
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 6, 1, 12, 0, 0)
+        .single()
+        .expect("timestamp");
+
+    let mut hook_context = StatusHookContextUsage::default();
+    hook_context.record(ContextTokenCountEvent {
+        sources: vec![ContextSourceTokens {
+            source: "ace".to_string(),
+            tokens: 4_000,
+        }],
+        total_tokens: 4_000,
+    });
+    hook_context.start_turn();
+    hook_context.record(ContextTokenCountEvent {
+        sources: vec![
+            ContextSourceTokens {
+                source: "ace".to_string(),
+                tokens: 300,
+            },
+            ContextSourceTokens {
+                source: "external".to_string(),
+                tokens: 12,
+            },
+        ],
+        total_tokens: 312,
+    });
+
+    let composite = new_status_output(
+        &config,
+        &usage,
+        Some(&usage),
+        &None,
+        None,
+        Some(&hook_context),
+        now,
+    );
+    let hook_line = render_lines(&composite.display_lines(100))
+        .into_iter()
+        .find(|line| line.contains("Hook context"))
+        .expect("hook context line");
+    assert!(
+        hook_line.contains("312 last turn (ace 300, external 12) · 4.31K session"),
+        "unexpected hook context line: {hook_line}"
+    );
+
+    hook_context.start_turn();
+    let composite = new_status_output(
+        &config,
+        &usage,
+        Some(&usage),
+        &None,
+        None,
+        Some(&hook_context),
+        now,
+    );
+    let hook_line = render_lines(&composite.display_lines(100))
+        .into_iter()
+        .find(|line| line.contains("Hook context"))
+        .expect("hook context line");
+    assert!(
+        hook_line.contains("none last turn · 4.31K session"),
+        "unexpected hook context line: {hook_line}"
+    );
+}